@@ -58,12 +58,9 @@ impl GrpcMethod {
         let method = quote! {
             #doc_hash #doc_group
             pub #signature {
-                let mut client = #grpc_client_struct :: with_interceptor(
-                    self.grpc_channel.clone(),
-                    self.auth_interceptor.clone(),
-                );
-                let request = ::tonic::Request::new(( #( #params ),* ).into_parameter());
-                let response = client. #grpc_method_name (request).await;
+                let mut client = service_client!(self, #grpc_client_struct);
+                let request = self.new_request(( #( #params ),* ).into_parameter());
+                let response = self.with_deadline_applied(client. #grpc_method_name (request)).await;
                 response?.into_inner().try_from_response()
             }
         };
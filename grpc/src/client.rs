@@ -1,25 +1,97 @@
+use std::future::Future;
+use std::ops::Range;
+use std::time::Duration;
+
+use k256::ecdsa::{signature::Signer, Signature};
 use prost::Message;
+use tokio::task::JoinSet;
+use tonic::codec::CompressionEncoding;
 use tonic::service::Interceptor;
 use tonic::transport::Channel;
+use tonic::{Code, Status};
 
 use celestia_grpc_macros::grpc_method;
 use celestia_proto::celestia::blob::v1::query_client::QueryClient as BlobQueryClient;
+use celestia_proto::celestia::core::v1::proof::ShareProof as RawShareProof;
 use celestia_proto::cosmos::auth::v1beta1::query_client::QueryClient as AuthQueryClient;
+use celestia_proto::cosmos::bank::v1beta1::query_client::QueryClient as BankQueryClient;
 use celestia_proto::cosmos::base::node::v1beta1::service_client::ServiceClient as ConfigServiceClient;
 use celestia_proto::cosmos::base::tendermint::v1beta1::service_client::ServiceClient as TendermintServiceClient;
+use celestia_proto::cosmos::base::tendermint::v1beta1::AbciQueryRequest;
+use celestia_proto::cosmos::distribution::v1beta1::query_client::QueryClient as DistributionQueryClient;
+use celestia_proto::cosmos::staking::v1beta1::query_client::QueryClient as StakingQueryClient;
 use celestia_proto::cosmos::tx::v1beta1::service_client::ServiceClient as TxServiceClient;
 use celestia_proto::cosmos::tx::v1beta1::Tx as RawTx;
 use celestia_types::blob::{Blob, BlobParams, RawBlobTx};
 use celestia_types::block::Block;
 use celestia_types::state::auth::AuthParams;
 use celestia_types::state::{Address, TxResponse};
+use celestia_types::ShareProof;
 
+use crate::concurrency::ConcurrencyLimit;
+use crate::interceptor::NoAuth;
+use crate::retry::RetryBudget;
 use crate::types::auth::Account;
-use crate::types::tx::GetTxResponse;
+use crate::types::blob::BlobModuleParams;
+use crate::types::coin::{Coin, GasPrice, UTIA_DENOM};
+use crate::types::distribution::DelegationReward;
+use crate::types::pagination::{Paginated, Pagination};
+use crate::types::staking::Delegation;
+use crate::types::tx::{
+    compute_tx_hash, encode_blob_tx, BroadcastOutcome, GasInfo, GetTxResponse, TxWithProof,
+    UnsignedTx, BLOB_TX_TYPE_ID,
+};
 use crate::types::{FromGrpcResponse, IntoGrpcParam};
 use crate::Error;
 
-pub use celestia_proto::cosmos::tx::v1beta1::BroadcastMode;
+pub use crate::types::tx::BroadcastMode;
+
+// From https://github.com/celestiaorg/cosmos-sdk/blob/v1.25.0-sdk-v0.46.16/types/errors/errors.go#L65
+const OUT_OF_GAS_CODESPACE: &str = "sdk";
+const OUT_OF_GAS_CODE: u32 = 11;
+
+/// Interval between queries for the transaction status while waiting for its confirmation.
+const TX_CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Metadata key selecting the height of the state read by the query.
+const BLOCK_HEIGHT_METADATA_KEY: &str = "x-cosmos-block-height";
+
+/// Metadata key of the delay asked for by the server rate limiting the calls, in seconds.
+const RETRY_AFTER_METADATA_KEY: &str = "retry-after";
+
+/// Maximum number of the retries of a broadcast rate limited by the server.
+const MAX_RATE_LIMITED_RETRIES: u32 = 5;
+
+/// Longest delay waited for before retrying a broadcast rate limited by the server,
+/// longer ones asked for by the server are clamped to it.
+const MAX_RATE_LIMITED_DELAY: Duration = Duration::from_secs(30);
+
+/// Maximum number of the blocks fetched by [`GrpcClient::get_blocks_in_range`] at once.
+const MAX_BLOCK_RANGE_LEN: i64 = 1000;
+
+/// Maximum size of the decoded messages if it's not configured, the default of tonic.
+const DEFAULT_MAX_DECODING_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Create the client of the gRPC service, with the message size limits of the
+/// [`GrpcClient`] applied.
+macro_rules! service_client {
+    ($self:ident, $($client:ident)::+) => {{
+        let mut client = $($client)::+::with_interceptor(
+            $self.grpc_channel.clone(),
+            $self.auth_interceptor.clone(),
+        );
+        if let Some(limit) = $self.max_decoding_message_size {
+            client = client.max_decoding_message_size(limit);
+        }
+        if let Some(limit) = $self.max_encoding_message_size {
+            client = client.max_encoding_message_size(limit);
+        }
+        if let Some(encoding) = $self.compression {
+            client = client.accept_compressed(encoding);
+        }
+        client
+    }};
+}
 
 /// Struct wrapping all the tonic types and doing type conversion behind the scenes.
 pub struct GrpcClient<I>
@@ -28,6 +100,21 @@ where
 {
     grpc_channel: Channel,
     auth_interceptor: I,
+    deadline: Option<Duration>,
+    block_height: Option<i64>,
+    max_decoding_message_size: Option<usize>,
+    max_encoding_message_size: Option<usize>,
+    retry_budget: Option<RetryBudget>,
+    compression: Option<CompressionEncoding>,
+    concurrency_limit: Option<ConcurrencyLimit>,
+}
+
+impl GrpcClient<NoAuth> {
+    /// Create a new client out of channel, for the public endpoints which don't require
+    /// any authentication.
+    pub fn new_unauthenticated(grpc_channel: Channel) -> Self {
+        Self::new(grpc_channel, NoAuth)
+    }
 }
 
 impl<I> GrpcClient<I>
@@ -39,12 +126,216 @@ where
         Self {
             grpc_channel,
             auth_interceptor,
+            deadline: None,
+            block_height: None,
+            max_decoding_message_size: None,
+            max_encoding_message_size: None,
+            retry_budget: None,
+            compression: None,
+            concurrency_limit: None,
+        }
+    }
+
+    /// Limit the size of the decoded responses, e.g. to raise the default of 4MiB so that
+    /// large blocks can be received. Larger responses fail with [`Error::ResponseTooLarge`].
+    pub fn with_max_decoding_message_size(mut self, bytes: usize) -> Self {
+        self.max_decoding_message_size = Some(bytes);
+        self
+    }
+
+    /// Limit the size of the encoded requests. Default is no limit.
+    pub fn with_max_encoding_message_size(mut self, bytes: usize) -> Self {
+        self.max_encoding_message_size = Some(bytes);
+        self
+    }
+
+    /// Accept the responses compressed with the encoding, e.g. to save the bandwidth
+    /// of large blocks on the mobile networks.
+    ///
+    /// The encoding is only advertised to the server, which keeps sending uncompressed
+    /// responses if it doesn't support it. Requests are always sent uncompressed, as not
+    /// every server accepts compressed ones.
+    pub fn with_compression(mut self, encoding: CompressionEncoding) -> Self {
+        self.compression = Some(encoding);
+        self
+    }
+
+    /// Cap the retries made by the client with the budget, which can be shared with other
+    /// clients to cap their retries in aggregate. Default is no limit.
+    pub fn with_retry_budget(mut self, budget: RetryBudget) -> Self {
+        self.retry_budget = Some(budget);
+        self
+    }
+
+    /// Make at most `max_in_flight` calls at once, queueing the rest until the calls in flight
+    /// finish, e.g. to stay within the rate limits of the server. Default is no limit.
+    ///
+    /// The limit is shared with the clients derived from this one, e.g. with
+    /// [`GrpcClient::with_deadline`]. Time spent in the queue counts towards the deadline.
+    pub fn with_max_concurrency(mut self, max_in_flight: usize) -> Self {
+        self.concurrency_limit = Some(ConcurrencyLimit::new(max_in_flight));
+        self
+    }
+
+    /// Number of the calls in flight, counted only if the concurrency is limited
+    /// with [`GrpcClient::with_max_concurrency`].
+    pub fn in_flight_calls(&self) -> usize {
+        self.concurrency_limit
+            .as_ref()
+            .map_or(0, ConcurrencyLimit::in_flight)
+    }
+
+    /// Number of the calls waiting for the calls in flight to finish, see
+    /// [`GrpcClient::with_max_concurrency`].
+    pub fn queued_calls(&self) -> usize {
+        self.concurrency_limit
+            .as_ref()
+            .map_or(0, ConcurrencyLimit::queued)
+    }
+
+    /// Set the deadline of each call made by the client, or `None` to wait as
+    /// long as the channel allows.
+    pub fn set_deadline(&mut self, deadline: Option<Duration>) {
+        self.deadline = deadline;
+    }
+
+    /// Get a client sharing the channel of this one, with the deadline of its calls
+    /// overridden, e.g. to allow a single slow call.
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use celestia_grpc::{Error, GrpcClient};
+    /// # async fn example<I: tonic::service::Interceptor + Clone>(client: GrpcClient<I>) -> Result<(), Error> {
+    /// let params = client
+    ///     .with_deadline(Duration::from_secs(30))
+    ///     .get_blob_params()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_deadline(&self, deadline: Duration) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self.duplicate()
+        }
+    }
+
+    /// Get a client sharing the channel of this one, with its queries reading the state
+    /// at the given height instead of the latest one.
+    ///
+    /// Queries of the state already pruned by the node fail with [`Error::HeightPruned`].
+    ///
+    /// ```no_run
+    /// # use celestia_grpc::{Error, GrpcClient};
+    /// # use celestia_types::state::Address;
+    /// # async fn example<I: tonic::service::Interceptor + Clone>(client: GrpcClient<I>, address: Address) -> Result<(), Error> {
+    /// let balance = client
+    ///     .at_height(1000)
+    ///     .get_balance(&address, "utia")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn at_height(&self, height: i64) -> Self {
+        Self {
+            block_height: Some(height),
+            ..self.duplicate()
+        }
+    }
+
+    /// Get a client sharing the channel, the settings and the limits of this one.
+    fn duplicate(&self) -> Self {
+        Self {
+            grpc_channel: self.grpc_channel.clone(),
+            auth_interceptor: self.auth_interceptor.clone(),
+            deadline: self.deadline,
+            block_height: self.block_height,
+            max_decoding_message_size: self.max_decoding_message_size,
+            max_encoding_message_size: self.max_encoding_message_size,
+            retry_budget: self.retry_budget.clone(),
+            compression: self.compression,
+            concurrency_limit: self.concurrency_limit.clone(),
+        }
+    }
+
+    /// Take a retry from the [`RetryBudget`] of the client, if it has one.
+    fn take_retry(&self) -> Result<(), Error> {
+        match &self.retry_budget {
+            Some(budget) if !budget.try_acquire() => Err(Error::RetryBudgetExhausted),
+            _ => Ok(()),
+        }
+    }
+
+    /// Create a request, telling the server about the deadline of the call and
+    /// the height of the state to read.
+    fn new_request<T>(&self, message: T) -> tonic::Request<T> {
+        let mut request = tonic::Request::new(message);
+        if let Some(deadline) = self.deadline {
+            request.set_timeout(deadline);
+        }
+        if let Some(height) = self.block_height {
+            request
+                .metadata_mut()
+                .insert(BLOCK_HEIGHT_METADATA_KEY, height.into());
+        }
+        request
+    }
+
+    /// Wait for the response of the call, failing with [`Error::Timeout`] once the
+    /// deadline is exceeded.
+    async fn with_deadline_applied<T>(
+        &self,
+        call: impl Future<Output = Result<T, Status>>,
+    ) -> Result<T, Error> {
+        let call = async {
+            let _permit = match &self.concurrency_limit {
+                Some(limit) => Some(limit.acquire().await),
+                None => None,
+            };
+            call.await
+        };
+
+        let response = match self.deadline {
+            Some(deadline) => tokio::time::timeout(deadline, call)
+                .await
+                .map_err(|_| Error::Timeout(deadline))?,
+            None => call.await,
+        };
+
+        match (response, self.deadline, self.block_height) {
+            (Err(status), Some(deadline), _) if is_deadline_exceeded(&status) => {
+                Err(Error::Timeout(deadline))
+            }
+            (Err(status), _, _) if is_response_too_large(&status) => Err(Error::ResponseTooLarge(
+                self.max_decoding_message_size
+                    .unwrap_or(DEFAULT_MAX_DECODING_MESSAGE_SIZE),
+            )),
+            // only the state queried at the height of the client is historical
+            (Err(status), _, Some(height)) if is_state_pruned(&status) => {
+                Err(Error::HeightPruned(height))
+            }
+            (Err(status), _, _) if status.code() == Code::ResourceExhausted => {
+                Err(Error::RateLimited {
+                    retry_after: retry_after(&status),
+                })
+            }
+            (response, _, _) => Ok(response?),
         }
     }
 
-    /// Get Minimum Gas price
+    /// Get Minimum Gas prices in all the accepted denominations
     #[grpc_method(ConfigServiceClient::config)]
-    async fn get_min_gas_price(&mut self) -> Result<f64, Error>;
+    async fn get_min_gas_prices(&mut self) -> Result<Vec<GasPrice>, Error>;
+
+    /// Get Minimum Gas price in `utia`
+    pub async fn get_min_gas_price(&mut self) -> Result<f64, Error> {
+        self.get_min_gas_prices()
+            .await?
+            .into_iter()
+            .find(|gas_price| gas_price.denom == UTIA_DENOM)
+            .map(|gas_price| gas_price.price)
+            .ok_or(Error::FailedToParseResponse)
+    }
 
     /// Get latest block
     #[grpc_method(TendermintServiceClient::get_latest_block)]
@@ -58,6 +349,10 @@ where
     #[grpc_method(BlobQueryClient::params)]
     async fn get_blob_params(&mut self) -> Result<BlobParams, Error>;
 
+    /// Get blob module params, with the gas costs derived from them
+    #[grpc_method(BlobQueryClient::params)]
+    async fn get_blob_module_params(&mut self) -> Result<BlobModuleParams, Error>;
+
     /// Get auth params
     #[grpc_method(AuthQueryClient::params)]
     async fn get_auth_params(&mut self) -> Result<AuthParams, Error>;
@@ -66,11 +361,71 @@ where
     #[grpc_method(AuthQueryClient::account)]
     async fn get_account(&mut self, account: &Address) -> Result<Account, Error>;
 
+    /// Get account number and sequence of the account, as `(account_number, sequence)`
+    #[grpc_method(AuthQueryClient::account)]
+    async fn get_account_sequence(&mut self, account: &Address) -> Result<(u64, u64), Error>;
+
     // TODO: pagination?
     /// Get accounts
     #[grpc_method(AuthQueryClient::accounts)]
     async fn get_accounts(&mut self) -> Result<Vec<Account>, Error>;
 
+    /// Get the total supply of all the coins, on the page selected with `pagination`
+    #[grpc_method(BankQueryClient::total_supply)]
+    async fn get_total_supply(
+        &mut self,
+        pagination: Option<Pagination>,
+    ) -> Result<Paginated<Coin>, Error>;
+
+    /// Get the total supply of the coin with the given denomination
+    #[grpc_method(BankQueryClient::supply_of)]
+    async fn get_supply_of(&mut self, denom: &str) -> Result<Coin, Error>;
+
+    /// Get the balance of the address in the given denomination
+    #[grpc_method(BankQueryClient::balance)]
+    async fn get_balance(&mut self, address: &Address, denom: &str) -> Result<Coin, Error>;
+
+    /// Get the delegations of the delegator, on the page selected with `pagination`
+    #[grpc_method(StakingQueryClient::delegator_delegations)]
+    async fn get_delegations(
+        &mut self,
+        delegator: &Address,
+        pagination: Option<Pagination>,
+    ) -> Result<Paginated<Delegation>, Error>;
+
+    /// Get the pending rewards of each delegation of the delegator
+    #[grpc_method(DistributionQueryClient::delegation_total_rewards)]
+    async fn get_rewards(&mut self, delegator: &Address) -> Result<Vec<DelegationReward>, Error>;
+
+    /// Check whether the balance of the address covers the fee.
+    ///
+    /// Fee may consist of coins of multiple denominations, in which case the balance
+    /// in each of them has to cover the amounts of that denomination.
+    pub async fn check_fee_affordable(
+        &mut self,
+        address: &Address,
+        fee: &[Coin],
+    ) -> Result<bool, Error> {
+        let mut required: Vec<Coin> = Vec::new();
+
+        for coin in fee {
+            match required.iter_mut().find(|c| c.denom == coin.denom) {
+                Some(total) => total.amount = total.amount.saturating_add(coin.amount),
+                None => required.push(coin.clone()),
+            }
+        }
+
+        for coin in required.iter().filter(|coin| coin.amount > 0) {
+            let balance = self.get_balance(address, &coin.denom).await?;
+
+            if balance.amount < coin.amount {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
     /// Broadcast prepared and serialised transaction
     #[grpc_method(TxServiceClient::broadcast_tx)]
     async fn broadcast_tx(
@@ -79,6 +434,25 @@ where
         mode: BroadcastMode,
     ) -> Result<TxResponse, Error>;
 
+    /// Simulate signed and serialised transaction without broadcasting it
+    #[grpc_method(TxServiceClient::simulate)]
+    async fn simulate(&mut self, tx_bytes: Vec<u8>) -> Result<GasInfo, Error>;
+
+    /// Broadcast prepared and serialised transaction in [`BroadcastMode::Sync`] and report
+    /// whether it entered the mempool.
+    ///
+    /// Only [`BroadcastOutcome::AcceptedToMempool`] transactions are worth polling for with
+    /// [`get_tx`], as the rejected ones will never be included in a block.
+    ///
+    /// [`get_tx`]: GrpcClient::get_tx
+    pub async fn broadcast_tx_sync(
+        &mut self,
+        tx_bytes: Vec<u8>,
+    ) -> Result<BroadcastOutcome, Error> {
+        let response = self.broadcast_tx(tx_bytes, BroadcastMode::Sync).await?;
+        Ok(response.into())
+    }
+
     /// Broadcast blob transaction
     pub async fn broadcast_blob_tx(
         &mut self,
@@ -86,24 +460,1998 @@ where
         blobs: Vec<Blob>,
         mode: BroadcastMode,
     ) -> Result<TxResponse, Error> {
-        // From https://github.com/celestiaorg/celestia-core/blob/v1.43.0-tm-v0.34.35/pkg/consts/consts.go#L19
-        const BLOB_TX_TYPE_ID: &str = "BLOB";
-
         if blobs.is_empty() {
             return Err(Error::TxEmptyBlobList);
         }
+        // the chain would reject it anyway, with a less helpful error
+        ensure_signed(&tx)?;
 
-        let blobs = blobs.into_iter().map(Into::into).collect();
-        let blob_tx = RawBlobTx {
-            tx: tx.encode_to_vec(),
-            blobs,
-            type_id: BLOB_TX_TYPE_ID.to_string(),
-        };
+        self.broadcast_tx(encode_blob_tx(tx, blobs), mode).await
+    }
 
-        self.broadcast_tx(blob_tx.encode_to_vec(), mode).await
+    /// Broadcast blob transaction, unless it was already included in a block.
+    ///
+    /// Before broadcasting, the node is queried for the transaction with the same hash.
+    /// If it's found, its response is returned instead of re-submitting it, which makes
+    /// it safe to retry after e.g. a timeout of the previous attempt.
+    pub async fn broadcast_blob_tx_idempotent(
+        &mut self,
+        tx: RawTx,
+        blobs: Vec<Blob>,
+        mode: BroadcastMode,
+    ) -> Result<TxResponse, Error> {
+        if let Some(existing) = self.get_tx(tx_hash(&tx)).await? {
+            return Ok(existing.tx_response);
+        }
+
+        self.broadcast_blob_tx(tx, blobs, mode).await
+    }
+
+    /// Broadcast blob transaction, re-signing it with more gas if it runs out of gas.
+    ///
+    /// Whenever the transaction is rejected as out of gas, its gas limit is multiplied
+    /// by `bump_factor`, raising the fee accordingly, and it's broadcasted again, up to
+    /// `max_attempts` times in total. Any other response is returned immediately, as is
+    /// the response of the last attempt.
+    ///
+    /// Broadcasts rejected with [`Error::RateLimited`] are retried after the delay asked for
+    /// by the server, without counting as an attempt, up to 5 times. Delays longer than
+    /// 30 seconds are clamped to it. Without the delay, with one longer than the deadline
+    /// of the client, or once the retries are exhausted, the error is returned.
+    ///
+    /// Each retry takes from the [`RetryBudget`] of the client, if it has one, and fails
+    /// with [`Error::RetryBudgetExhausted`] once it's depleted.
+    ///
+    /// Returns [`Error::InvalidTxParams`] if `max_attempts` is zero or `bump_factor`
+    /// doesn't raise the gas limit, i.e. isn't greater than 1.
+    pub async fn broadcast_blob_tx_with_gas_bump<S>(
+        &mut self,
+        tx: &UnsignedTx<S>,
+        blobs: Vec<Blob>,
+        mode: BroadcastMode,
+        max_attempts: u32,
+        bump_factor: f64,
+    ) -> Result<TxResponse, Error>
+    where
+        S: Signer<Signature> + Clone,
+    {
+        if max_attempts == 0 {
+            return Err(Error::InvalidTxParams(
+                "max_attempts must be at least 1".to_owned(),
+            ));
+        }
+        if bump_factor.is_nan() || bump_factor <= 1.0 {
+            return Err(Error::InvalidTxParams(format!(
+                "bump_factor must be greater than 1, got {bump_factor}"
+            )));
+        }
+
+        let mut gas_limit = tx.gas_limit;
+        let mut attempt = 1;
+        let mut rate_limited_retries = 0;
+
+        loop {
+            let signed = tx.sign_with_gas_limit(gas_limit);
+            let response = match self.broadcast_blob_tx(signed, blobs.clone(), mode).await {
+                Err(Error::RateLimited {
+                    retry_after: Some(delay),
+                }) if rate_limited_retries < MAX_RATE_LIMITED_RETRIES => {
+                    let Some(delay) = rate_limited_delay(delay, self.deadline) else {
+                        return Err(Error::RateLimited {
+                            retry_after: Some(delay),
+                        });
+                    };
+                    self.take_retry()?;
+                    rate_limited_retries += 1;
+                    tokio::time::sleep(delay).await;
+                    continue;
+                }
+                response => response?,
+            };
+
+            if !is_out_of_gas(&response) || attempt >= max_attempts {
+                return Ok(response);
+            }
+
+            self.take_retry()?;
+
+            attempt += 1;
+            // always raise the limit, even if the factor is too small to make a difference
+            gas_limit = ((gas_limit as f64 * bump_factor).ceil() as u64).max(gas_limit + 1);
+        }
     }
 
     /// Get Tx
-    #[grpc_method(TxServiceClient::get_tx)]
-    async fn get_tx(&mut self, hash: String) -> Result<GetTxResponse, Error>;
+    ///
+    /// Returns `None` if the node doesn't know a transaction with the given hash.
+    pub async fn get_tx(&mut self, hash: String) -> Result<Option<GetTxResponse>, Error> {
+        let mut client = service_client!(self, TxServiceClient);
+        let request = self.new_request(hash.into_parameter());
+
+        match self.with_deadline_applied(client.get_tx(request)).await {
+            Ok(response) => response.into_inner().try_from_response().map(Some),
+            Err(Error::TonicError(status)) if status.code() == Code::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Get Tx at the position in the block at the given height, e.g. to walk through
+    /// all the transactions of the block.
+    ///
+    /// For blob transactions, the wrapped transaction paying for the blobs is returned.
+    /// Returns [`Error::TxNotFound`] if the block has no transaction at the index.
+    pub async fn get_tx_by_block_index(
+        &mut self,
+        height: i64,
+        index: u32,
+    ) -> Result<GetTxResponse, Error> {
+        let block = self.get_block_by_height(height).await?;
+        let not_found = || Error::TxNotFound(format!("#{index} in block {height}"));
+
+        let tx = block
+            .data
+            .txs
+            .get(index as usize)
+            .map(|tx| unwrap_blob_tx(tx))
+            .ok_or_else(not_found)?;
+
+        self.get_tx(compute_tx_hash(&tx))
+            .await?
+            .ok_or_else(not_found)
+    }
+
+    /// Get the blocks at the heights `from..=to`, ordered by height, making at most
+    /// `max_concurrency` calls at once, e.g. to backfill the history faster than block
+    /// by block.
+    ///
+    /// The range can span at most 1000 blocks. If any of the blocks can't be fetched, the
+    /// call fails with [`Error::BlocksFailed`] listing the error of each of them.
+    pub async fn get_blocks_in_range(
+        &mut self,
+        from: i64,
+        to: i64,
+        max_concurrency: usize,
+    ) -> Result<Vec<Block>, Error>
+    where
+        I: Send + Sync + 'static,
+    {
+        if from < 1 || to < from {
+            return Err(Error::InvalidBlockRange(format!("{from}..={to}")));
+        }
+        if to - from >= MAX_BLOCK_RANGE_LEN {
+            return Err(Error::InvalidBlockRange(format!(
+                "{from}..={to} spans more than {MAX_BLOCK_RANGE_LEN} blocks"
+            )));
+        }
+
+        let mut heights = from..=to;
+        let mut blocks: Vec<Option<Block>> = heights.clone().map(|_| None).collect();
+        let mut failed = Vec::new();
+        let mut tasks = JoinSet::new();
+
+        loop {
+            while tasks.len() < max_concurrency.max(1) {
+                let Some(height) = heights.next() else {
+                    break;
+                };
+                let mut client = self.duplicate();
+                tasks.spawn(async move { (height, client.get_block_by_height(height).await) });
+            }
+
+            let Some(joined) = tasks.join_next().await else {
+                break;
+            };
+            match joined {
+                Ok((height, Ok(block))) => blocks[(height - from) as usize] = Some(block),
+                Ok((height, Err(e))) => failed.push((height, e)),
+                Err(e) => std::panic::resume_unwind(e.into_panic()),
+            }
+        }
+
+        if !failed.is_empty() {
+            failed.sort_by_key(|(height, _)| *height);
+            return Err(Error::BlocksFailed(failed));
+        }
+
+        Ok(blocks.into_iter().flatten().collect())
+    }
+
+    /// Get Tx together with the proof of its inclusion in the block
+    ///
+    /// Besides the transaction, its block is fetched to find the position of the
+    /// transaction in it, and the proof is requested with the `txInclusionProof`
+    /// ABCI query of the application. Use [`TxWithProof::verify`] to check the proof
+    /// against the header of the block.
+    pub async fn get_tx_with_proof(&mut self, hash: String) -> Result<TxWithProof, Error> {
+        let tx = self
+            .get_tx(hash.clone())
+            .await?
+            .ok_or_else(|| Error::TxNotFound(hash.clone()))?;
+        let height = tx.tx_response.height.value() as i64;
+        let block = self.get_block_by_height(height).await?;
+
+        let (index, tx_bytes) = block
+            .data
+            .txs
+            .iter()
+            .enumerate()
+            .map(|(index, tx)| (index, unwrap_blob_tx(tx)))
+            .find(|(_, tx)| compute_tx_hash(tx) == hash)
+            .ok_or(Error::TxNotFound(hash))?;
+
+        let proof = self
+            .query_share_proof(format!("custom/txInclusionProof/{index}"), height)
+            .await?;
+
+        Ok(TxWithProof {
+            tx,
+            header: block.header,
+            tx_bytes,
+            proof,
+        })
+    }
+
+    /// Get the proof of inclusion of the shares of the block's data square in its data root
+    ///
+    /// Shares are indexed in the original data square, row by row, and `shares` has to
+    /// be a non-empty range of a single namespace. The proof is requested with the
+    /// `shareInclusionProof` ABCI query of the application.
+    pub async fn get_share_proof(
+        &mut self,
+        height: i64,
+        shares: Range<u64>,
+    ) -> Result<ShareProof, Error> {
+        self.query_share_proof(
+            format!("custom/shareInclusionProof/{}/{}", shares.start, shares.end),
+            height,
+        )
+        .await
+    }
+
+    /// Run the ABCI query of the proof of shares at the height.
+    async fn query_share_proof(&mut self, path: String, height: i64) -> Result<ShareProof, Error> {
+        let mut client = service_client!(self, TendermintServiceClient);
+        let request = self.new_request(AbciQueryRequest {
+            data: Vec::new(),
+            path,
+            height,
+            prove: false,
+        });
+        let response = self
+            .with_deadline_applied(client.abci_query(request))
+            .await?
+            .into_inner();
+
+        if response.code != 0 {
+            return Err(Error::AbciQueryFailed(response.code, response.log));
+        }
+
+        Ok(RawShareProof::decode(&*response.value)
+            .map_err(|_| Error::FailedToParseResponse)?
+            .try_into()?)
+    }
+
+    /// Broadcast prepared and serialised transaction and wait until it's committed.
+    ///
+    /// Transaction is broadcasted in [`BroadcastMode::Sync`] and then polled with
+    /// [`get_tx`] until it's included in a block. If the transaction fails the
+    /// checks done before entering the mempool, the response of the broadcast is
+    /// returned. If it's not committed within `timeout`, [`Error::ConfirmationTimeout`]
+    /// is returned.
+    ///
+    /// [`get_tx`]: GrpcClient::get_tx
+    pub async fn broadcast_tx_commit(
+        &mut self,
+        tx_bytes: Vec<u8>,
+        timeout: Duration,
+    ) -> Result<TxResponse, Error> {
+        let response = self.broadcast_tx(tx_bytes, BroadcastMode::Sync).await?;
+
+        if response.code != 0 {
+            return Ok(response);
+        }
+
+        self.wait_tx_committed(response.txhash, timeout).await
+    }
+
+    /// Poll the transaction with the given hash until it's committed or `timeout` elapses.
+    async fn wait_tx_committed(
+        &mut self,
+        hash: String,
+        timeout: Duration,
+    ) -> Result<TxResponse, Error> {
+        let poll = async {
+            loop {
+                match self.get_tx(hash.clone()).await? {
+                    Some(tx) => return Ok(tx.tx_response),
+                    None => tokio::time::sleep(TX_CONFIRMATION_POLL_INTERVAL).await,
+                }
+            }
+        };
+
+        tokio::time::timeout(timeout, poll)
+            .await
+            .map_err(|_| Error::ConfirmationTimeout(hash))?
+    }
+}
+
+/// Compute the hash of the transaction, as reported by the node.
+///
+/// For blob transactions, the hash covers only the inner transaction, without the blobs.
+fn tx_hash(tx: &RawTx) -> String {
+    compute_tx_hash(&tx.encode_to_vec())
+}
+
+/// Fails with [`Error::UnsignedTx`] unless the transaction carries a non-empty signature
+/// for each of its signers.
+fn ensure_signed(tx: &RawTx) -> Result<(), Error> {
+    let signers = tx
+        .auth_info
+        .as_ref()
+        .map_or(0, |auth_info| auth_info.signer_infos.len());
+
+    if tx.signatures.is_empty()
+        || tx.signatures.len() != signers
+        || tx.signatures.iter().any(|signature| signature.is_empty())
+    {
+        return Err(Error::UnsignedTx);
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if the transaction was rejected for running out of gas.
+fn is_out_of_gas(response: &TxResponse) -> bool {
+    response.codespace == OUT_OF_GAS_CODESPACE && response.code == OUT_OF_GAS_CODE
+}
+
+/// Strip the blobs from the blob transaction, leaving only the inner transaction.
+/// Other transactions are returned unchanged.
+fn unwrap_blob_tx(tx: &[u8]) -> Vec<u8> {
+    match RawBlobTx::decode(tx) {
+        Ok(blob_tx) if blob_tx.type_id == BLOB_TX_TYPE_ID => blob_tx.tx,
+        _ => tx.to_vec(),
+    }
+}
+
+/// Checks if the response was rejected by tonic for exceeding the maximum decoding message size.
+fn is_response_too_large(status: &Status) -> bool {
+    status.code() == Code::OutOfRange
+        && status
+            .message()
+            .starts_with("Error, decoded message length too large")
+}
+
+/// Checks if the query of the historical state failed, because the node no longer has it.
+fn is_state_pruned(status: &Status) -> bool {
+    // error of the cosmos-sdk baseapp when creating the query context at the height
+    status
+        .message()
+        .starts_with("failed to load state at height")
+}
+
+/// Delay asked for by the server rate limiting the call, if it sent a valid one.
+fn retry_after(status: &Status) -> Option<Duration> {
+    let value = status
+        .metadata()
+        .get(RETRY_AFTER_METADATA_KEY)?
+        .to_str()
+        .ok()?;
+    let secs = value.trim().parse().ok()?;
+    Duration::try_from_secs_f64(secs).ok()
+}
+
+/// Delay to wait for before retrying a rate limited call, clamped to
+/// [`MAX_RATE_LIMITED_DELAY`], or `None` if it's longer than the deadline of the calls.
+fn rate_limited_delay(retry_after: Duration, deadline: Option<Duration>) -> Option<Duration> {
+    match deadline {
+        Some(deadline) if retry_after > deadline => None,
+        _ => Some(retry_after.min(MAX_RATE_LIMITED_DELAY)),
+    }
+}
+
+/// Checks if the call failed because of its deadline, either on the server
+/// or when the channel timed out waiting for the response.
+fn is_deadline_exceeded(status: &Status) -> bool {
+    if status.code() == Code::DeadlineExceeded {
+        return true;
+    }
+
+    let mut source = std::error::Error::source(status);
+    while let Some(err) = source {
+        if err.is::<tonic::TimeoutExpired>() {
+            return true;
+        }
+        source = err.source();
+    }
+
+    false
+}
+
+#[cfg(test)]
+#[allow(clippy::result_large_err)] // mock handlers return tonic::Status
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    use super::*;
+    use celestia_proto::cosmos::bank::v1beta1::{
+        QueryBalanceRequest, QueryBalanceResponse, QuerySupplyOfRequest, QuerySupplyOfResponse,
+    };
+    use celestia_proto::cosmos::base::node::v1beta1::{ConfigRequest, ConfigResponse};
+    use celestia_proto::cosmos::base::tendermint::v1beta1::{
+        AbciQueryResponse, GetBlockByHeightRequest, GetBlockByHeightResponse,
+    };
+    use celestia_proto::cosmos::base::v1beta1::Coin as RawCoin;
+    use celestia_proto::cosmos::tx::v1beta1::{
+        BroadcastTxRequest, BroadcastTxResponse, GetTxRequest, GetTxResponse as RawGetTxResponse,
+    };
+    use celestia_types::block::Data;
+    use celestia_types::consts::appconsts::{AppVersion, COMPACT_SHARE_RESERVED_BYTES, SHARE_SIZE};
+    use celestia_types::nmt::{Namespace, NS_SIZE};
+    use celestia_types::state::auth::BaseAccount;
+    use celestia_types::state::{AuthInfo, Fee, RawTxBody, RawTxResponse};
+    use celestia_types::test_utils::ExtendedHeaderGenerator;
+    use celestia_types::{DataAvailabilityHeader, ExtendedDataSquare};
+    use k256::ecdsa::SigningKey;
+    use tonic::metadata::MetadataMap;
+
+    use crate::test_utils::{MockGrpcServer, MockGrpcServerHandle, Recorder};
+    use crate::types::tx::{build_blob_tx_offline, decode_blob_tx, OfflineTxParams};
+
+    const CONFIG: &str = "/cosmos.base.node.v1beta1.Service/Config";
+    const BROADCAST_TX: &str = "/cosmos.tx.v1beta1.Service/BroadcastTx";
+    const GET_TX: &str = "/cosmos.tx.v1beta1.Service/GetTx";
+    const GET_BLOCK_BY_HEIGHT: &str = "/cosmos.base.tendermint.v1beta1.Service/GetBlockByHeight";
+    const ABCI_QUERY: &str = "/cosmos.base.tendermint.v1beta1.Service/ABCIQuery";
+    const SUPPLY_OF: &str = "/cosmos.bank.v1beta1.Query/SupplyOf";
+    const BALANCE: &str = "/cosmos.bank.v1beta1.Query/Balance";
+
+    fn raw_tx_response(hash: &str, height: i64) -> RawTxResponse {
+        RawTxResponse {
+            height,
+            txhash: hash.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    fn raw_tx(memo: &str) -> RawTx {
+        RawTx {
+            body: Some(RawTxBody {
+                memo: memo.to_owned(),
+                ..Default::default()
+            }),
+            auth_info: Some(
+                AuthInfo {
+                    signer_infos: Vec::new(),
+                    fee: Fee::new(0, 0),
+                }
+                .into(),
+            ),
+            signatures: Vec::new(),
+        }
+    }
+
+    fn committed_tx(hash: &str, height: i64) -> RawGetTxResponse {
+        RawGetTxResponse {
+            tx: Some(raw_tx("")),
+            tx_response: Some(raw_tx_response(hash, height)),
+        }
+    }
+
+    /// Single compact share of the transaction namespace, holding the transaction.
+    fn tx_share(namespace: Namespace, tx_bytes: &[u8]) -> Vec<u8> {
+        let mut unit = Vec::new();
+        prost::encode_length_delimiter(tx_bytes.len(), &mut unit).unwrap();
+        unit.extend_from_slice(tx_bytes);
+
+        let mut share = namespace.as_bytes().to_vec();
+        // info byte of share version 0, starting the sequence
+        share.push(1);
+        share.extend_from_slice(&(unit.len() as u32).to_be_bytes());
+        // offset of the first transaction in the share
+        share.extend_from_slice(
+            &((NS_SIZE + 1 + 4 + COMPACT_SHARE_RESERVED_BYTES) as u32).to_be_bytes(),
+        );
+        share.extend_from_slice(&unit);
+        share.resize(SHARE_SIZE, 0);
+        share
+    }
+
+    /// Block with the single transaction, along with the proof of its inclusion.
+    fn block_with_tx_proof(tx_bytes: &[u8]) -> (Block, RawShareProof) {
+        block_with_tx_proof_in(Namespace::TRANSACTION, tx_bytes)
+    }
+
+    /// Block with the single transaction laid out as compact shares of the namespace.
+    fn block_with_tx_proof_in(namespace: Namespace, tx_bytes: &[u8]) -> (Block, RawShareProof) {
+        let eds = ExtendedDataSquare::from_ods(vec![tx_share(namespace, tx_bytes)], AppVersion::V2)
+            .unwrap();
+        let dah = DataAvailabilityHeader::from_eds(&eds);
+        let header = ExtendedHeaderGenerator::new().next_with_dah(dah.clone());
+
+        let rows = eds
+            .get_namespace_data(namespace, &dah, header.height().value())
+            .unwrap();
+        let proof = RawShareProof {
+            data: rows
+                .iter()
+                .flat_map(|(_, row)| &row.shares)
+                .map(|share| share.data().to_vec())
+                .collect(),
+            namespace_id: namespace.id().to_vec(),
+            namespace_version: namespace.version().into(),
+            share_proofs: rows.into_iter().map(|(_, row)| row.proof.into()).collect(),
+            row_proof: Some(dah.row_proof(0..=0).unwrap().into()),
+        };
+
+        let data = Data {
+            txs: vec![tx_bytes.to_vec()],
+            square_size: 1,
+            hash: dah.hash().as_bytes().to_vec(),
+        };
+        let block = Block::new(header.header, data, Default::default(), None);
+
+        (block, proof)
+    }
+
+    #[tokio::test]
+    async fn get_tx_by_block_index() {
+        let txs: Vec<_> = ["first", "middle", "last"].map(raw_tx).into();
+        let tx_bytes: Vec<_> = txs.iter().map(|tx| tx.encode_to_vec()).collect();
+        let header = ExtendedHeaderGenerator::new().next().header;
+        let height = header.height.value() as i64;
+        let data = Data {
+            txs: tx_bytes.clone(),
+            square_size: 1,
+            hash: Vec::new(),
+        };
+        let block = Block::new(header, data, Default::default(), None);
+        let queried = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(
+                GET_BLOCK_BY_HEIGHT,
+                move |_: tonic::Request<GetBlockByHeightRequest>| {
+                    Ok(GetBlockByHeightResponse {
+                        block_id: None,
+                        block: Some(block.clone().into()),
+                        sdk_block: None,
+                    })
+                },
+            )
+            .unary(GET_TX, {
+                let queried = queried.clone();
+                move |req: tonic::Request<GetTxRequest>| {
+                    let hash = req.into_inner().hash;
+                    queried.record(hash.clone());
+                    let tx = txs
+                        .iter()
+                        .find(|tx| compute_tx_hash(&tx.encode_to_vec()) == hash)
+                        .ok_or_else(|| Status::not_found("tx not found"))?;
+                    Ok(RawGetTxResponse {
+                        tx: Some(tx.clone()),
+                        tx_response: Some(raw_tx_response(&hash, height)),
+                    })
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let tx = client.get_tx_by_block_index(height, 1).await.unwrap();
+        assert_eq!(tx.tx.body.memo, "middle");
+        assert_eq!(tx.tx_response.txhash, compute_tx_hash(&tx_bytes[1]));
+        assert_eq!(queried.take(), [compute_tx_hash(&tx_bytes[1])]);
+
+        let err = client.get_tx_by_block_index(height, 5).await.unwrap_err();
+        assert!(matches!(err, Error::TxNotFound(_)), "{err}");
+        // out of range index is detected from the block alone
+        assert_eq!(queried.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn get_blocks_in_range_bounds_concurrency() {
+        let blocks: Vec<_> = ExtendedHeaderGenerator::new()
+            .next_many(8)
+            .into_iter()
+            .map(|header| {
+                let data = Data {
+                    txs: Vec::new(),
+                    square_size: 1,
+                    hash: Vec::new(),
+                };
+                Block::new(header.header, data, Default::default(), None)
+            })
+            .collect();
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+
+        let server = MockGrpcServer::new()
+            .unary_async(GET_BLOCK_BY_HEIGHT, {
+                let blocks = blocks.clone();
+                let in_flight = in_flight.clone();
+                let max_in_flight = max_in_flight.clone();
+                move |req: tonic::Request<GetBlockByHeightRequest>| {
+                    let height = req.into_inner().height;
+                    let block = blocks.get(height as usize - 1).cloned();
+                    let in_flight = in_flight.clone();
+                    let max_in_flight = max_in_flight.clone();
+                    async move {
+                        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_in_flight.fetch_max(now, Ordering::SeqCst);
+                        // later heights answer sooner, so they complete out of order
+                        tokio::time::sleep(Duration::from_millis(20 * (10 - height as u64))).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                        let block = block.ok_or_else(|| Status::not_found("no block"))?;
+                        Ok(GetBlockByHeightResponse {
+                            block_id: None,
+                            block: Some(block.into()),
+                            sdk_block: None,
+                        })
+                    }
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let fetched = client.get_blocks_in_range(2, 7, 3).await.unwrap();
+        assert_eq!(fetched, blocks[1..7]);
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 3);
+
+        let err = client.get_blocks_in_range(7, 10, 2).await.unwrap_err();
+        let Error::BlocksFailed(failed) = err else {
+            panic!("unexpected error: {err}");
+        };
+        let failed_heights: Vec<_> = failed.iter().map(|(height, _)| *height).collect();
+        assert_eq!(failed_heights, [9, 10]);
+
+        let err = client.get_blocks_in_range(1, 1001, 2).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidBlockRange(_)), "{err}");
+        let err = client.get_blocks_in_range(5, 4, 2).await.unwrap_err();
+        assert!(matches!(err, Error::InvalidBlockRange(_)), "{err}");
+    }
+
+    async fn tx_with_proof_server(
+        proof: RawShareProof,
+    ) -> (MockGrpcServerHandle, Recorder<AbciQueryRequest>) {
+        let tx = raw_tx("proven");
+        let tx_bytes = tx.encode_to_vec();
+        let hash = compute_tx_hash(&tx_bytes);
+        let (block, _) = block_with_tx_proof(&tx_bytes);
+        let height = block.header.height.value() as i64;
+        let queries = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(GET_TX, move |_: tonic::Request<GetTxRequest>| {
+                Ok(RawGetTxResponse {
+                    tx: Some(tx.clone()),
+                    tx_response: Some(raw_tx_response(&hash, height)),
+                })
+            })
+            .unary(
+                GET_BLOCK_BY_HEIGHT,
+                move |_: tonic::Request<GetBlockByHeightRequest>| {
+                    Ok(GetBlockByHeightResponse {
+                        block_id: None,
+                        block: Some(block.clone().into()),
+                        sdk_block: None,
+                    })
+                },
+            )
+            .unary(ABCI_QUERY, {
+                let queries = queries.clone();
+                move |req: tonic::Request<AbciQueryRequest>| {
+                    queries.record(req.into_inner());
+                    Ok(AbciQueryResponse {
+                        value: proof.encode_to_vec(),
+                        ..Default::default()
+                    })
+                }
+            })
+            .start()
+            .await;
+
+        (server, queries)
+    }
+
+    fn blob() -> Blob {
+        let ns = celestia_types::nmt::Namespace::new_v0(&[1]).unwrap();
+        Blob::new(ns, vec![1, 2, 3], celestia_types::AppVersion::V2).unwrap()
+    }
+
+    fn unsigned_tx() -> UnsignedTx<SigningKey> {
+        let signer = SigningKey::from_slice(&[1; 32]).unwrap();
+
+        UnsignedTx {
+            tx_body: raw_tx("gas").body.unwrap(),
+            chain_id: "private".to_owned(),
+            base_account: BaseAccount {
+                address: "celestia1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5wgawu3"
+                    .parse()
+                    .unwrap(),
+                pub_key: None,
+                account_number: 1,
+                sequence: 0,
+            },
+            verifying_key: *signer.verifying_key(),
+            signer,
+            gas_limit: 100_000,
+            gas_price: 0.002,
+        }
+    }
+
+    /// Fee of the blob transaction submitted in the broadcast request.
+    fn broadcasted_fee(request: &BroadcastTxRequest) -> Fee {
+        let blob_tx = RawBlobTx::decode(&*request.tx_bytes).unwrap();
+        let tx = RawTx::decode(&*blob_tx.tx).unwrap();
+        AuthInfo::try_from(tx.auth_info.unwrap()).unwrap().fee
+    }
+
+    #[tokio::test]
+    async fn broadcast_blob_tx_bumps_gas_when_out_of_gas() {
+        let broadcasts = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, {
+                let broadcasts = broadcasts.clone();
+                move |req: tonic::Request<BroadcastTxRequest>| {
+                    broadcasts.record(req.into_inner());
+                    let mut response = raw_tx_response("HASH", 0);
+                    if broadcasts.len() == 1 {
+                        response.codespace = "sdk".to_owned();
+                        response.code = 11;
+                    }
+                    Ok(BroadcastTxResponse {
+                        tx_response: Some(response),
+                    })
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let response = client
+            .broadcast_blob_tx_with_gas_bump(
+                &unsigned_tx(),
+                vec![blob()],
+                BroadcastMode::Sync,
+                3,
+                1.5,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.code, 0);
+
+        let fees: Vec<_> = broadcasts.take().iter().map(broadcasted_fee).collect();
+        assert_eq!(fees.len(), 2);
+        assert_eq!(fees[0], Fee::new(200, 100_000));
+        assert_eq!(fees[1], Fee::new(300, 150_000));
+    }
+
+    #[tokio::test]
+    async fn rate_limited_broadcast_waits_retry_after() {
+        let broadcasts = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, {
+                let broadcasts = broadcasts.clone();
+                move |req: tonic::Request<BroadcastTxRequest>| {
+                    broadcasts.record(Instant::now());
+                    if broadcasts.len() == 1 {
+                        let mut metadata = MetadataMap::new();
+                        metadata.insert(RETRY_AFTER_METADATA_KEY, "0.3".parse().unwrap());
+                        return Err(Status::with_metadata(
+                            Code::ResourceExhausted,
+                            "rate limit exceeded",
+                            metadata,
+                        ));
+                    }
+                    assert!(!req.into_inner().tx_bytes.is_empty());
+                    Ok(BroadcastTxResponse {
+                        tx_response: Some(raw_tx_response("HASH", 0)),
+                    })
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let err = client
+            .broadcast_blob_tx(unsigned_tx().sign(), vec![blob()], BroadcastMode::Sync)
+            .await
+            .unwrap_err();
+        match err {
+            Error::RateLimited { retry_after } => {
+                assert_eq!(retry_after, Some(Duration::from_millis(300)))
+            }
+            err => panic!("expected RateLimited, got {err:?}"),
+        }
+        broadcasts.take();
+
+        let response = client
+            .broadcast_blob_tx_with_gas_bump(
+                &unsigned_tx(),
+                vec![blob()],
+                BroadcastMode::Sync,
+                1,
+                1.5,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.code, 0);
+
+        let [limited, retried] = broadcasts.take().try_into().unwrap();
+        assert!(retried - limited >= Duration::from_millis(300));
+    }
+
+    #[tokio::test]
+    async fn rate_limited_broadcast_retries_are_capped() {
+        let broadcasts = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, {
+                let broadcasts = broadcasts.clone();
+                move |_: tonic::Request<BroadcastTxRequest>| {
+                    broadcasts.record(());
+                    let mut metadata = MetadataMap::new();
+                    metadata.insert(RETRY_AFTER_METADATA_KEY, "0.01".parse().unwrap());
+                    Err::<BroadcastTxResponse, _>(Status::with_metadata(
+                        Code::ResourceExhausted,
+                        "rate limit exceeded",
+                        metadata,
+                    ))
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let err = client
+            .broadcast_blob_tx_with_gas_bump(
+                &unsigned_tx(),
+                vec![blob()],
+                BroadcastMode::Sync,
+                3,
+                1.5,
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::RateLimited { .. }), "{err:?}");
+        assert_eq!(broadcasts.len(), MAX_RATE_LIMITED_RETRIES as usize + 1);
+    }
+
+    #[tokio::test]
+    async fn rate_limited_broadcast_not_retried_past_deadline() {
+        let broadcasts = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, {
+                let broadcasts = broadcasts.clone();
+                move |_: tonic::Request<BroadcastTxRequest>| {
+                    broadcasts.record(());
+                    let mut metadata = MetadataMap::new();
+                    metadata.insert(RETRY_AFTER_METADATA_KEY, "3600".parse().unwrap());
+                    Err::<BroadcastTxResponse, _>(Status::with_metadata(
+                        Code::ResourceExhausted,
+                        "rate limit exceeded",
+                        metadata,
+                    ))
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await.with_deadline(Duration::from_secs(5));
+
+        let err = client
+            .broadcast_blob_tx_with_gas_bump(
+                &unsigned_tx(),
+                vec![blob()],
+                BroadcastMode::Sync,
+                3,
+                1.5,
+            )
+            .await
+            .unwrap_err();
+
+        match err {
+            Error::RateLimited { retry_after } => {
+                assert_eq!(retry_after, Some(Duration::from_secs(3600)))
+            }
+            err => panic!("expected RateLimited, got {err:?}"),
+        }
+        assert_eq!(broadcasts.len(), 1);
+    }
+
+    #[test]
+    fn rate_limited_delay_is_clamped() {
+        let secs = Duration::from_secs;
+
+        assert_eq!(rate_limited_delay(secs(2), None), Some(secs(2)));
+        assert_eq!(
+            rate_limited_delay(secs(3600), None),
+            Some(MAX_RATE_LIMITED_DELAY)
+        );
+        assert_eq!(
+            rate_limited_delay(secs(3600), Some(secs(7200))),
+            Some(MAX_RATE_LIMITED_DELAY)
+        );
+        assert_eq!(rate_limited_delay(secs(2), Some(secs(5))), Some(secs(2)));
+        assert_eq!(rate_limited_delay(secs(10), Some(secs(5))), None);
+    }
+
+    #[tokio::test]
+    async fn broadcast_blob_tx_gas_bump_stops_on_other_errors() {
+        let broadcasts = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, {
+                let broadcasts = broadcasts.clone();
+                move |req: tonic::Request<BroadcastTxRequest>| {
+                    broadcasts.record(req.into_inner());
+                    let mut response = raw_tx_response("HASH", 0);
+                    // insufficient fee
+                    response.codespace = "sdk".to_owned();
+                    response.code = 13;
+                    Ok(BroadcastTxResponse {
+                        tx_response: Some(response),
+                    })
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let response = client
+            .broadcast_blob_tx_with_gas_bump(
+                &unsigned_tx(),
+                vec![blob()],
+                BroadcastMode::Sync,
+                3,
+                1.5,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.code, 13);
+        assert_eq!(broadcasts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn broadcast_blob_tx_gas_bump_rejects_invalid_params() {
+        let server = MockGrpcServer::new().start().await;
+        let mut client = server.client().await;
+
+        for (max_attempts, bump_factor) in [(0, 1.5), (3, 1.0), (3, 0.5), (3, f64::NAN)] {
+            let err = client
+                .broadcast_blob_tx_with_gas_bump(
+                    &unsigned_tx(),
+                    vec![blob()],
+                    BroadcastMode::Sync,
+                    max_attempts,
+                    bump_factor,
+                )
+                .await
+                .unwrap_err();
+            assert!(
+                matches!(err, Error::InvalidTxParams(_)),
+                "{max_attempts}, {bump_factor}: {err:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn gas_bump_retries_share_budget() {
+        let broadcasts = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, {
+                let broadcasts = broadcasts.clone();
+                move |req: tonic::Request<BroadcastTxRequest>| {
+                    broadcasts.record(req.into_inner());
+                    let mut response = raw_tx_response("HASH", 0);
+                    response.codespace = "sdk".to_owned();
+                    response.code = 11;
+                    Ok(BroadcastTxResponse {
+                        tx_response: Some(response),
+                    })
+                }
+            })
+            .start()
+            .await;
+        let budget = RetryBudget::new(2, Duration::from_secs(3600));
+        let mut first = server.client().await.with_retry_budget(budget.clone());
+        let mut second = server.client().await.with_retry_budget(budget.clone());
+
+        // the first call takes the whole budget
+        let response = first
+            .broadcast_blob_tx_with_gas_bump(
+                &unsigned_tx(),
+                vec![blob()],
+                BroadcastMode::Sync,
+                3,
+                1.5,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.code, 11);
+        assert_eq!(broadcasts.take().len(), 3);
+        assert_eq!(budget.remaining(), 0);
+
+        // so the next ones fail on their first retry
+        for client in [&mut second, &mut first] {
+            let err = client
+                .broadcast_blob_tx_with_gas_bump(
+                    &unsigned_tx(),
+                    vec![blob()],
+                    BroadcastMode::Sync,
+                    3,
+                    1.5,
+                )
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::RetryBudgetExhausted));
+            assert_eq!(broadcasts.take().len(), 1);
+        }
+    }
+
+    fn offline_tx_params() -> OfflineTxParams<SigningKey> {
+        let signer = SigningKey::from_slice(&[1; 32]).unwrap();
+
+        OfflineTxParams {
+            chain_id: "private".to_owned(),
+            address: "celestia1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5wgawu3"
+                .parse()
+                .unwrap(),
+            account_number: 7,
+            sequence: 3,
+            verifying_key: *signer.verifying_key(),
+            signer,
+            gas_limit: 100_000,
+            fee: 200,
+            app_version: AppVersion::V2,
+            fee_granter: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn broadcast_offline_blob_tx() {
+        let broadcasts = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, {
+                let broadcasts = broadcasts.clone();
+                move |req: tonic::Request<BroadcastTxRequest>| {
+                    broadcasts.record(req.into_inner());
+                    Ok(BroadcastTxResponse {
+                        tx_response: Some(raw_tx_response("HASH", 0)),
+                    })
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let tx_bytes = build_blob_tx_offline(offline_tx_params(), vec![blob()]).unwrap();
+        let response = client
+            .broadcast_tx(tx_bytes.clone(), BroadcastMode::Sync)
+            .await
+            .unwrap();
+        assert_eq!(response.code, 0);
+
+        let broadcasts = broadcasts.take();
+        assert_eq!(broadcasts.len(), 1);
+        assert_eq!(broadcasts[0].tx_bytes, tx_bytes);
+
+        let blob_tx = RawBlobTx::decode(&*tx_bytes).unwrap();
+        assert_eq!(blob_tx.type_id, BLOB_TX_TYPE_ID);
+        assert_eq!(blob_tx.blobs, vec![blob().into()]);
+
+        let tx = RawTx::decode(&*blob_tx.tx).unwrap();
+        let auth_info = AuthInfo::try_from(tx.auth_info.unwrap()).unwrap();
+        assert_eq!(auth_info.fee, Fee::new(200, 100_000));
+        assert_eq!(auth_info.signer_infos[0].sequence, 3);
+        assert_eq!(tx.signatures.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn decode_broadcasted_blob_tx() {
+        let broadcasts = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, {
+                let broadcasts = broadcasts.clone();
+                move |req: tonic::Request<BroadcastTxRequest>| {
+                    broadcasts.record(req.into_inner());
+                    Ok(BroadcastTxResponse {
+                        tx_response: Some(raw_tx_response("HASH", 0)),
+                    })
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let tx = unsigned_tx().sign();
+        client
+            .broadcast_blob_tx(tx.clone(), vec![blob()], BroadcastMode::Sync)
+            .await
+            .unwrap();
+
+        let [request] = broadcasts.take().try_into().unwrap();
+        let (decoded_tx, blobs) = decode_blob_tx(&request.tx_bytes, AppVersion::V2).unwrap();
+        assert_eq!(decoded_tx, tx);
+        assert_eq!(blobs, vec![blob()]);
+
+        let wrong_type = RawBlobTx {
+            type_id: "TX".to_owned(),
+            ..RawBlobTx::decode(&*request.tx_bytes).unwrap()
+        };
+        assert!(matches!(
+            decode_blob_tx(&wrong_type.encode_to_vec(), AppVersion::V2),
+            Err(Error::InvalidBlobTx(_))
+        ));
+        assert!(matches!(
+            decode_blob_tx(&request.tx_bytes[1..], AppVersion::V2),
+            Err(Error::InvalidBlobTx(_))
+        ));
+    }
+
+    #[test]
+    fn offline_blob_tx_with_fee_granter() {
+        let granter: Address = "celestia1zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3shxjgz"
+            .parse()
+            .unwrap();
+        let mut params = offline_tx_params();
+        params.fee_granter = Some(granter.clone());
+
+        let tx_bytes = build_blob_tx_offline(params, vec![blob()]).unwrap();
+
+        let blob_tx = RawBlobTx::decode(&*tx_bytes).unwrap();
+        let tx = RawTx::decode(&*blob_tx.tx).unwrap();
+        let auth_info = AuthInfo::try_from(tx.auth_info.unwrap()).unwrap();
+        assert_eq!(auth_info.fee.granter, granter.to_string());
+        assert!(auth_info.fee.payer.is_empty());
+        assert_eq!(auth_info.fee.gas_limit, 100_000);
+    }
+
+    #[test]
+    fn offline_blob_tx_validation() {
+        assert!(matches!(
+            build_blob_tx_offline(offline_tx_params(), Vec::new()).unwrap_err(),
+            Error::TxEmptyBlobList
+        ));
+
+        let mut params = offline_tx_params();
+        params.chain_id.clear();
+        assert!(matches!(
+            build_blob_tx_offline(params, vec![blob()]).unwrap_err(),
+            Error::InvalidTxParams(_)
+        ));
+
+        let mut params = offline_tx_params();
+        params.gas_limit = 0;
+        assert!(matches!(
+            build_blob_tx_offline(params, vec![blob()]).unwrap_err(),
+            Error::InvalidTxParams(_)
+        ));
+
+        let mut params = offline_tx_params();
+        params.fee_granter = Some(params.address.clone());
+        assert!(matches!(
+            build_blob_tx_offline(params, vec![blob()]).unwrap_err(),
+            Error::InvalidTxParams(_)
+        ));
+
+        let mut params = offline_tx_params();
+        params.fee_granter = Some(
+            "celestiavaloper1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5thlh2h"
+                .parse()
+                .unwrap(),
+        );
+        assert!(matches!(
+            build_blob_tx_offline(params, vec![blob()]).unwrap_err(),
+            Error::InvalidTxParams(_)
+        ));
+
+        let mut params = offline_tx_params();
+        params.address = "celestiavaloper1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5thlh2h"
+            .parse()
+            .unwrap();
+        params.fee_granter = Some(
+            "celestia1zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3shxjgz"
+                .parse()
+                .unwrap(),
+        );
+        assert!(matches!(
+            build_blob_tx_offline(params, vec![blob()]).unwrap_err(),
+            Error::InvalidTxParams(_)
+        ));
+
+        let mut invalid_blob = blob();
+        invalid_blob.data.push(4);
+        assert!(matches!(
+            build_blob_tx_offline(offline_tx_params(), vec![invalid_blob]).unwrap_err(),
+            Error::CelestiaTypesError(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn broadcast_tx_commit_waits_for_inclusion() {
+        let broadcasts = Recorder::new();
+        let queries = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, {
+                let broadcasts = broadcasts.clone();
+                move |req: tonic::Request<BroadcastTxRequest>| {
+                    broadcasts.record(req.into_inner());
+                    Ok(BroadcastTxResponse {
+                        tx_response: Some(raw_tx_response("HASH", 0)),
+                    })
+                }
+            })
+            .unary(GET_TX, {
+                let queries = queries.clone();
+                move |req: tonic::Request<GetTxRequest>| {
+                    queries.record(req.into_inner().hash);
+                    if queries.len() == 1 {
+                        Err(Status::not_found("tx not found"))
+                    } else {
+                        Ok(committed_tx("HASH", 42))
+                    }
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let response = client
+            .broadcast_tx_commit(vec![1, 2, 3], Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        assert_eq!(response.height.value(), 42);
+        assert_eq!(response.txhash, "HASH");
+
+        let broadcasts = broadcasts.take();
+        assert_eq!(broadcasts.len(), 1);
+        assert_eq!(broadcasts[0].tx_bytes, vec![1, 2, 3]);
+        assert_eq!(broadcasts[0].mode(), BroadcastMode::Sync.into());
+        assert_eq!(queries.take(), ["HASH", "HASH"]);
+    }
+
+    #[tokio::test]
+    async fn broadcast_tx_commit_timeout() {
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, |_: tonic::Request<BroadcastTxRequest>| {
+                Ok(BroadcastTxResponse {
+                    tx_response: Some(raw_tx_response("HASH", 0)),
+                })
+            })
+            .unary(GET_TX, |_: tonic::Request<GetTxRequest>| {
+                Err::<RawGetTxResponse, _>(Status::not_found("tx not found"))
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let err = client
+            .broadcast_tx_commit(vec![1, 2, 3], Duration::from_millis(700))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::ConfirmationTimeout(hash) if hash == "HASH"));
+    }
+
+    #[tokio::test]
+    async fn broadcast_tx_commit_check_failed() {
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, |_: tonic::Request<BroadcastTxRequest>| {
+                Ok(BroadcastTxResponse {
+                    tx_response: Some(RawTxResponse {
+                        code: 13,
+                        ..raw_tx_response("HASH", 0)
+                    }),
+                })
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let response = client
+            .broadcast_tx_commit(vec![1, 2, 3], Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        assert_eq!(response.code, 13);
+    }
+
+    #[tokio::test]
+    async fn broadcast_tx_sync_accepted() {
+        let broadcasts = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, {
+                let broadcasts = broadcasts.clone();
+                move |req: tonic::Request<BroadcastTxRequest>| {
+                    broadcasts.record(req.into_inner());
+                    Ok(BroadcastTxResponse {
+                        tx_response: Some(raw_tx_response("HASH", 0)),
+                    })
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let outcome = client.broadcast_tx_sync(vec![1, 2, 3]).await.unwrap();
+
+        assert_eq!(
+            outcome,
+            BroadcastOutcome::AcceptedToMempool {
+                hash: "HASH".to_owned()
+            }
+        );
+        let broadcasts = broadcasts.take();
+        assert_eq!(broadcasts.len(), 1);
+        assert_eq!(broadcasts[0].mode(), BroadcastMode::Sync.into());
+    }
+
+    #[tokio::test]
+    async fn broadcast_tx_sync_rejected() {
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, |_: tonic::Request<BroadcastTxRequest>| {
+                Ok(BroadcastTxResponse {
+                    tx_response: Some(RawTxResponse {
+                        code: 13,
+                        raw_log: "insufficient fee".to_owned(),
+                        ..raw_tx_response("HASH", 0)
+                    }),
+                })
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let outcome = client.broadcast_tx_sync(vec![1, 2, 3]).await.unwrap();
+
+        assert_eq!(
+            outcome,
+            BroadcastOutcome::CheckTxRejected {
+                code: 13,
+                log: "insufficient fee".to_owned()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn idempotent_blob_tx_already_included() {
+        let tx = raw_tx("already included");
+        let hash = tx_hash(&tx);
+        let broadcasts = Recorder::new();
+        let queries = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, {
+                let broadcasts = broadcasts.clone();
+                move |req: tonic::Request<BroadcastTxRequest>| {
+                    broadcasts.record(req.into_inner());
+                    Ok(BroadcastTxResponse {
+                        tx_response: Some(raw_tx_response("OTHER", 0)),
+                    })
+                }
+            })
+            .unary(GET_TX, {
+                let queries = queries.clone();
+                move |req: tonic::Request<GetTxRequest>| {
+                    let hash = req.into_inner().hash;
+                    queries.record(hash.clone());
+                    Ok(committed_tx(&hash, 7))
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let response = client
+            .broadcast_blob_tx_idempotent(tx, vec![blob()], BroadcastMode::Sync)
+            .await
+            .unwrap();
+
+        assert_eq!(response.txhash, hash);
+        assert_eq!(response.height.value(), 7);
+        assert_eq!(queries.take(), [hash]);
+        assert_eq!(broadcasts.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn idempotent_blob_tx_not_included() {
+        let tx = unsigned_tx().sign_with_gas_limit(100_000);
+        let broadcasts = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, {
+                let broadcasts = broadcasts.clone();
+                move |req: tonic::Request<BroadcastTxRequest>| {
+                    broadcasts.record(req.into_inner());
+                    Ok(BroadcastTxResponse {
+                        tx_response: Some(raw_tx_response("HASH", 0)),
+                    })
+                }
+            })
+            .unary(GET_TX, |_: tonic::Request<GetTxRequest>| {
+                Err::<RawGetTxResponse, _>(Status::not_found("tx not found"))
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let response = client
+            .broadcast_blob_tx_idempotent(tx.clone(), vec![blob()], BroadcastMode::Sync)
+            .await
+            .unwrap();
+
+        assert_eq!(response.txhash, "HASH");
+
+        let broadcasts = broadcasts.take();
+        assert_eq!(broadcasts.len(), 1);
+        let blob_tx = RawBlobTx::decode(&broadcasts[0].tx_bytes[..]).unwrap();
+        assert_eq!(blob_tx.tx, tx.encode_to_vec());
+    }
+
+    #[tokio::test]
+    async fn broadcast_unsigned_blob_tx() {
+        let broadcasts = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(BROADCAST_TX, {
+                let broadcasts = broadcasts.clone();
+                move |req: tonic::Request<BroadcastTxRequest>| {
+                    broadcasts.record(req.into_inner());
+                    Ok(BroadcastTxResponse {
+                        tx_response: Some(raw_tx_response("HASH", 0)),
+                    })
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let signed = unsigned_tx().sign_with_gas_limit(100_000);
+        let mut missing_signer_info = signed.clone();
+        missing_signer_info
+            .auth_info
+            .as_mut()
+            .unwrap()
+            .signer_infos
+            .clear();
+        let mut empty_signature = signed.clone();
+        empty_signature.signatures[0].clear();
+
+        for tx in [raw_tx("unsigned"), missing_signer_info, empty_signature] {
+            let err = client
+                .broadcast_blob_tx(tx, vec![blob()], BroadcastMode::Sync)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, Error::UnsignedTx), "{err}");
+        }
+        assert_eq!(broadcasts.len(), 0);
+
+        client
+            .broadcast_blob_tx(signed, vec![blob()], BroadcastMode::Sync)
+            .await
+            .unwrap();
+        assert_eq!(broadcasts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn min_gas_prices_multi_denom() {
+        let server = MockGrpcServer::new()
+            .unary(CONFIG, |_: tonic::Request<ConfigRequest>| {
+                Ok(ConfigResponse {
+                    minimum_gas_price: "0.002000000000000000utia,0.5uatom".to_owned(),
+                })
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let gas_prices = client.get_min_gas_prices().await.unwrap();
+        assert_eq!(
+            gas_prices,
+            [
+                GasPrice {
+                    denom: "utia".to_owned(),
+                    price: 0.002,
+                },
+                GasPrice {
+                    denom: "uatom".to_owned(),
+                    price: 0.5,
+                },
+            ]
+        );
+
+        let utia_price = client.get_min_gas_price().await.unwrap();
+        assert_eq!(utia_price, 0.002);
+    }
+
+    #[tokio::test]
+    async fn max_concurrency_bounds_calls_in_flight() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+
+        let server = MockGrpcServer::new()
+            .unary_async(CONFIG, {
+                let in_flight = in_flight.clone();
+                let max_in_flight = max_in_flight.clone();
+                move |_: tonic::Request<ConfigRequest>| {
+                    let in_flight = in_flight.clone();
+                    let max_in_flight = max_in_flight.clone();
+                    async move {
+                        let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                        max_in_flight.fetch_max(now, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                        Ok(ConfigResponse {
+                            minimum_gas_price: "0.002utia".to_owned(),
+                        })
+                    }
+                }
+            })
+            .start()
+            .await;
+        let client = server.client().await.with_max_concurrency(2);
+
+        let calls: Vec<_> = (0..6)
+            .map(|_| {
+                let mut client = client.with_deadline(Duration::from_secs(5));
+                tokio::spawn(async move { client.get_min_gas_price().await })
+            })
+            .collect();
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(client.in_flight_calls(), 2);
+        assert_eq!(client.queued_calls(), 4);
+
+        for call in calls {
+            assert_eq!(call.await.unwrap().unwrap(), 0.002);
+        }
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+        assert_eq!(client.in_flight_calls(), 0);
+        assert_eq!(client.queued_calls(), 0);
+    }
+
+    #[tokio::test]
+    async fn compressed_responses() {
+        let accepted_encodings = Recorder::new();
+        // long enough for the compression to matter
+        let gas_prices = vec!["0.002utia"; 1000].join(",");
+
+        let server = MockGrpcServer::new()
+            .with_compression(CompressionEncoding::Gzip)
+            .unary(CONFIG, {
+                let accepted_encodings = accepted_encodings.clone();
+                move |req: tonic::Request<ConfigRequest>| {
+                    let accepted = req
+                        .metadata()
+                        .get("grpc-accept-encoding")
+                        .map(|value| value.to_str().unwrap().to_owned());
+                    accepted_encodings.record(accepted);
+                    Ok(ConfigResponse {
+                        minimum_gas_price: gas_prices.clone(),
+                    })
+                }
+            })
+            .start()
+            .await;
+
+        let mut client = server
+            .client()
+            .await
+            .with_compression(CompressionEncoding::Gzip);
+        let prices = client.get_min_gas_prices().await.unwrap();
+        assert_eq!(prices.len(), 1000);
+        // clients derived from it keep the compression
+        client
+            .with_deadline(Duration::from_secs(5))
+            .get_min_gas_prices()
+            .await
+            .unwrap();
+
+        // and the ones without it get the uncompressed responses
+        let mut plain_client = server.client().await;
+        assert_eq!(plain_client.get_min_gas_prices().await.unwrap().len(), 1000);
+
+        let [compressed, derived, plain] = accepted_encodings.take().try_into().unwrap();
+        assert!(compressed.unwrap().contains("gzip"));
+        assert!(derived.unwrap().contains("gzip"));
+        assert_eq!(plain, None);
+    }
+
+    #[tokio::test]
+    async fn compression_with_uncompressed_server() {
+        let server = MockGrpcServer::new()
+            .unary(CONFIG, |_: tonic::Request<ConfigRequest>| {
+                Ok(ConfigResponse {
+                    minimum_gas_price: "0.002utia".to_owned(),
+                })
+            })
+            .start()
+            .await;
+        let mut client = server
+            .client()
+            .await
+            .with_compression(CompressionEncoding::Gzip);
+
+        assert_eq!(client.get_min_gas_price().await.unwrap(), 0.002);
+    }
+
+    #[tokio::test]
+    async fn min_gas_prices_malformed() {
+        let server = MockGrpcServer::new()
+            .unary(CONFIG, |_: tonic::Request<ConfigRequest>| {
+                Ok(ConfigResponse {
+                    minimum_gas_price: "0.002".to_owned(),
+                })
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let err = client.get_min_gas_prices().await.unwrap_err();
+        assert!(matches!(err, Error::FailedToParseResponse));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn per_call_deadline() {
+        let server = MockGrpcServer::new()
+            .unary(CONFIG, |_: tonic::Request<ConfigRequest>| {
+                // let other workers drive the timers while this one is blocked
+                tokio::task::block_in_place(|| std::thread::sleep(Duration::from_secs(2)));
+                Ok(ConfigResponse {
+                    minimum_gas_price: "0.002utia".to_owned(),
+                })
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+        client.set_deadline(Some(Duration::from_secs(10)));
+
+        let started = std::time::Instant::now();
+        let err = client
+            .with_deadline(Duration::from_millis(100))
+            .get_min_gas_prices()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Timeout(deadline) if deadline == Duration::from_millis(100)));
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        // the default of the client is kept for other calls
+        let gas_prices = client.get_min_gas_prices().await.unwrap();
+        assert_eq!(gas_prices.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn get_tx_not_found() {
+        let server = MockGrpcServer::new()
+            .unary(GET_TX, |_: tonic::Request<GetTxRequest>| {
+                Err::<RawGetTxResponse, _>(Status::not_found("tx not found"))
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let tx = client.get_tx("HASH".to_owned()).await.unwrap();
+        assert!(tx.is_none());
+    }
+
+    #[tokio::test]
+    async fn get_tx_error() {
+        let server = MockGrpcServer::new()
+            .unary(GET_TX, |_: tonic::Request<GetTxRequest>| {
+                Err::<RawGetTxResponse, _>(Status::unavailable("node is down"))
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let err = client.get_tx("HASH".to_owned()).await.unwrap_err();
+        assert!(matches!(err, Error::TonicError(status) if status.code() == Code::Unavailable));
+    }
+
+    #[tokio::test]
+    async fn get_tx_found() {
+        let server = MockGrpcServer::new()
+            .unary(GET_TX, |req: tonic::Request<GetTxRequest>| {
+                Ok(committed_tx(&req.into_inner().hash, 3))
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let tx = client.get_tx("HASH".to_owned()).await.unwrap().unwrap();
+        assert_eq!(tx.tx_response.txhash, "HASH");
+        assert_eq!(tx.tx_response.height.value(), 3);
+    }
+
+    #[tokio::test]
+    async fn get_supply_of_utia() {
+        let queries = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(SUPPLY_OF, {
+                let queries = queries.clone();
+                move |req: tonic::Request<QuerySupplyOfRequest>| {
+                    queries.record(req.into_inner().denom);
+                    Ok(QuerySupplyOfResponse {
+                        amount: Some(RawCoin {
+                            denom: UTIA_DENOM.to_owned(),
+                            amount: "1074751234567890".to_owned(),
+                        }),
+                    })
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let supply = client.get_supply_of(UTIA_DENOM).await.unwrap();
+
+        assert_eq!(supply, Coin::utia(1_074_751_234_567_890));
+        assert_eq!(queries.take(), [UTIA_DENOM]);
+    }
+
+    #[tokio::test]
+    async fn response_exceeding_max_decoding_message_size() {
+        let server = MockGrpcServer::new()
+            .unary(GET_TX, |_: tonic::Request<GetTxRequest>| {
+                Ok(RawGetTxResponse {
+                    tx: Some(raw_tx(&"a".repeat(4096))),
+                    tx_response: Some(raw_tx_response("HASH", 5)),
+                })
+            })
+            .start()
+            .await;
+
+        let mut client = server.client().await.with_max_decoding_message_size(1024);
+        let err = client.get_tx("HASH".to_owned()).await.unwrap_err();
+        assert!(matches!(err, Error::ResponseTooLarge(1024)), "{err}");
+
+        // limit is kept when the deadline is overridden
+        let err = client
+            .with_deadline(Duration::from_secs(5))
+            .get_tx("HASH".to_owned())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ResponseTooLarge(1024)), "{err}");
+
+        let mut client = client.with_max_decoding_message_size(8192);
+        let tx = client.get_tx("HASH".to_owned()).await.unwrap().unwrap();
+        assert_eq!(tx.tx.body.memo.len(), 4096);
+    }
+
+    #[tokio::test]
+    async fn query_at_height() {
+        let address: Address = "celestia1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5wgawu3"
+            .parse()
+            .unwrap();
+        let heights = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(BALANCE, {
+                let heights = heights.clone();
+                move |req: tonic::Request<QueryBalanceRequest>| {
+                    let height = req
+                        .metadata()
+                        .get(BLOCK_HEIGHT_METADATA_KEY)
+                        .map(|height| height.to_str().unwrap().parse::<i64>().unwrap());
+                    heights.record(height);
+
+                    if height.is_some_and(|height| height < 50) {
+                        return Err(Status::invalid_argument(format!(
+                            "failed to load state at height {}; version does not exist \
+                             (latest height: 100)",
+                            height.unwrap()
+                        )));
+                    }
+                    Ok(QueryBalanceResponse {
+                        balance: Some(RawCoin {
+                            denom: req.into_inner().denom,
+                            amount: "1000".to_owned(),
+                        }),
+                    })
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        client.get_balance(&address, UTIA_DENOM).await.unwrap();
+        let balance = client
+            .at_height(70)
+            .get_balance(&address, UTIA_DENOM)
+            .await
+            .unwrap();
+        assert_eq!(balance, Coin::utia(1000));
+        assert_eq!(heights.take(), [None, Some(70)]);
+
+        let err = client
+            .at_height(20)
+            .get_balance(&address, UTIA_DENOM)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::HeightPruned(20)), "{err}");
+    }
+
+    #[tokio::test]
+    async fn state_pruned_message_without_height() {
+        let address: Address = "celestia1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5wgawu3"
+            .parse()
+            .unwrap();
+        let server = MockGrpcServer::new()
+            .unary(BALANCE, |_: tonic::Request<QueryBalanceRequest>| {
+                Err::<QueryBalanceResponse, _>(Status::invalid_argument(
+                    "failed to load state at height 20; version does not exist",
+                ))
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let err = client.get_balance(&address, UTIA_DENOM).await.unwrap_err();
+        assert!(
+            matches!(&err, Error::TonicError(status) if status.code() == Code::InvalidArgument),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn server_deadline_without_client_deadline() {
+        let server = MockGrpcServer::new()
+            .unary(CONFIG, |_: tonic::Request<ConfigRequest>| {
+                Err::<ConfigResponse, _>(Status::deadline_exceeded("deadline set by the server"))
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        let err = client.get_min_gas_prices().await.unwrap_err();
+        assert!(
+            matches!(&err, Error::TonicError(status) if status.code() == Code::DeadlineExceeded),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn check_fee_affordable() {
+        let address: Address = "celestia1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5wgawu3"
+            .parse()
+            .unwrap();
+        let queries = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(BALANCE, {
+                let queries = queries.clone();
+                move |req: tonic::Request<QueryBalanceRequest>| {
+                    let req = req.into_inner();
+                    let amount = match req.denom.as_str() {
+                        UTIA_DENOM => "1000",
+                        "uother" => "50",
+                        _ => "0",
+                    };
+                    queries.record((req.address, req.denom.clone()));
+                    Ok(QueryBalanceResponse {
+                        balance: Some(RawCoin {
+                            denom: req.denom,
+                            amount: amount.to_owned(),
+                        }),
+                    })
+                }
+            })
+            .start()
+            .await;
+        let mut client = server.client().await;
+
+        assert!(client
+            .check_fee_affordable(&address, &[Coin::utia(1000)])
+            .await
+            .unwrap());
+        assert_eq!(
+            queries.take(),
+            [(address.to_string(), UTIA_DENOM.to_owned())]
+        );
+        assert!(!client
+            .check_fee_affordable(&address, &[Coin::utia(1001)])
+            .await
+            .unwrap());
+
+        // amounts of the same denomination are summed up
+        assert!(!client
+            .check_fee_affordable(&address, &[Coin::utia(600), Coin::utia(401)])
+            .await
+            .unwrap());
+        assert_eq!(queries.take().len(), 2);
+
+        // every denomination has to be covered
+        let fee = [Coin::utia(100), Coin::new("uother", 50)];
+        assert!(client.check_fee_affordable(&address, &fee).await.unwrap());
+        let fee = [Coin::utia(100), Coin::new("uother", 51)];
+        assert!(!client.check_fee_affordable(&address, &fee).await.unwrap());
+        let fee = [Coin::utia(100), Coin::new("unknown", 1)];
+        assert!(!client.check_fee_affordable(&address, &fee).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn get_tx_with_valid_proof() {
+        let tx_bytes = raw_tx("proven").encode_to_vec();
+        let (block, proof) = block_with_tx_proof(&tx_bytes);
+        let hash = compute_tx_hash(&tx_bytes);
+
+        let (server, queries) = tx_with_proof_server(proof).await;
+        let mut client = server.client().await;
+
+        let tx = client.get_tx_with_proof(hash).await.unwrap();
+
+        // headers are generated with random validators, so only compare what's proven
+        assert_eq!(tx.header.data_hash, block.header.data_hash);
+        assert_eq!(tx.header.height, block.header.height);
+        assert_eq!(tx.tx_bytes, tx_bytes);
+        assert!(tx.verify().unwrap());
+
+        let queries = queries.take();
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0].path, "custom/txInclusionProof/0");
+        assert_eq!(queries[0].height, block.header.height.value() as i64);
+    }
+
+    #[tokio::test]
+    async fn get_tx_with_tampered_proof() {
+        let tx_bytes = raw_tx("proven").encode_to_vec();
+        let (_, mut proof) = block_with_tx_proof(&tx_bytes);
+        let hash = compute_tx_hash(&tx_bytes);
+        // proof of a different transaction
+        let last = proof.data[0].len() - 1;
+        proof.data[0][last] ^= 0xff;
+
+        let (server, _) = tx_with_proof_server(proof).await;
+        let mut client = server.client().await;
+
+        let mut tx = client.get_tx_with_proof(hash).await.unwrap();
+        assert!(!tx.verify().unwrap());
+
+        // transaction not matching its hash
+        let (_, proof) = block_with_tx_proof(&tx_bytes);
+        tx.proof = proof.try_into().unwrap();
+        assert!(tx.verify().unwrap());
+        tx.tx_bytes = raw_tx("other").encode_to_vec();
+        assert!(!tx.verify().unwrap());
+    }
+
+    #[tokio::test]
+    async fn get_tx_with_proof_of_blob_namespace() {
+        let tx_bytes = raw_tx("proven").encode_to_vec();
+        let (_, proof) = block_with_tx_proof(&tx_bytes);
+        let hash = compute_tx_hash(&tx_bytes);
+
+        let (server, _) = tx_with_proof_server(proof).await;
+        let mut client = server.client().await;
+        let mut tx = client.get_tx_with_proof(hash).await.unwrap();
+        assert!(tx.verify().unwrap());
+
+        // the same bytes, proven in the shares of a blob
+        let namespace = Namespace::new_v0(b"blob").unwrap();
+        let (block, proof) = block_with_tx_proof_in(namespace, &tx_bytes);
+        tx.header.data_hash = block.header.data_hash;
+        tx.proof = proof.try_into().unwrap();
+
+        assert!(tx.proof.verify(tx.header.data_hash.unwrap()).is_ok());
+        assert!(!tx.verify().unwrap());
+    }
 }
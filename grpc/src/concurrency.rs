@@ -0,0 +1,63 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// Limit of the calls in flight at once, shared by the clones of the clients it's given to.
+///
+/// Calls over the limit wait in the queue for one of the calls in flight to finish.
+#[derive(Debug, Clone)]
+pub(crate) struct ConcurrencyLimit {
+    max_in_flight: usize,
+    semaphore: Arc<Semaphore>,
+    queued: Arc<AtomicUsize>,
+}
+
+impl ConcurrencyLimit {
+    /// Create the limit allowing up to `max_in_flight` calls at once, at least one.
+    pub(crate) fn new(max_in_flight: usize) -> Self {
+        let max_in_flight = max_in_flight.max(1);
+
+        ConcurrencyLimit {
+            max_in_flight,
+            semaphore: Arc::new(Semaphore::new(max_in_flight)),
+            queued: Arc::default(),
+        }
+    }
+
+    /// Wait until the call can be made, holding its place until the permit is dropped.
+    pub(crate) async fn acquire(&self) -> SemaphorePermit<'_> {
+        let _queued = Queued::new(&self.queued);
+
+        self.semaphore
+            .acquire()
+            .await
+            .expect("semaphore is never closed")
+    }
+
+    /// Number of the calls in flight.
+    pub(crate) fn in_flight(&self) -> usize {
+        self.max_in_flight - self.semaphore.available_permits()
+    }
+
+    /// Number of the calls waiting for the calls in flight to finish.
+    pub(crate) fn queued(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+}
+
+/// Guard counting the call as queued until it's dropped, also if the call is cancelled.
+struct Queued<'a>(&'a AtomicUsize);
+
+impl<'a> Queued<'a> {
+    fn new(queued: &'a AtomicUsize) -> Self {
+        queued.fetch_add(1, Ordering::Relaxed);
+        Queued(queued)
+    }
+}
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
@@ -37,4 +37,97 @@ pub enum Error {
     /// Empty blob submission list
     #[error("Attempted to submit blob transaction with empty blob list")]
     TxEmptyBlobList,
+
+    /// Transaction doesn't carry the signatures of its signers
+    #[error("Attempted to submit transaction without signatures matching its signers")]
+    UnsignedTx,
+
+    /// Transaction was signed for a different chain than the one of the node
+    #[error("Transaction was signed for chain {actual}, but the node is on chain {expected}")]
+    ChainIdMismatch {
+        /// Chain id of the node
+        expected: String,
+        /// Chain id the transaction was signed for
+        actual: String,
+    },
+
+    /// Parameters of the transaction are invalid
+    #[error("Invalid transaction parameters: {0}")]
+    InvalidTxParams(String),
+
+    /// Call didn't complete before its deadline
+    #[error("Call exceeded its deadline of {0:?}")]
+    Timeout(std::time::Duration),
+
+    /// Transaction wasn't confirmed within the given time
+    #[error("Transaction {0} wasn't confirmed in time")]
+    ConfirmationTimeout(String),
+
+    /// Transaction wasn't found, neither by the node nor in the block it reported
+    #[error("Transaction {0} not found")]
+    TxNotFound(String),
+
+    /// ABCI query returned an error code
+    #[error("ABCI query failed with code {0}: {1}")]
+    AbciQueryFailed(u32, String),
+
+    /// Malformed inclusion proof
+    #[error("Invalid proof: {0}")]
+    InvalidProof(String),
+
+    /// Response didn't fit in the maximum decoding message size
+    #[error("Response exceeded the maximum message size of {0} bytes")]
+    ResponseTooLarge(usize),
+
+    /// State at the queried height was pruned by the node
+    #[error("State at height {0} was pruned")]
+    HeightPruned(i64),
+
+    /// Malformed or overflowing coin amount
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+
+    /// Broadcast mode is deprecated or unspecified
+    #[error("Unsupported broadcast mode: {0}")]
+    UnsupportedBroadcastMode(String),
+
+    /// Call wasn't retried, because the retry budget of the client is depleted
+    #[error("Retry budget exhausted")]
+    RetryBudgetExhausted,
+
+    /// Call was rejected by the server rate limiting the calls, optionally with the delay
+    /// after which it can be retried
+    #[error("Rate limited by the server, retry after {retry_after:?}")]
+    RateLimited {
+        /// Delay asked for by the server before retrying
+        retry_after: Option<std::time::Duration>,
+    },
+
+    /// Bytes aren't a valid blob transaction
+    #[error("Invalid blob transaction: {0}")]
+    InvalidBlobTx(String),
+
+    /// Range of the blocks is empty or too long
+    #[error("Invalid block range: {0}")]
+    InvalidBlockRange(String),
+
+    /// Some of the blocks of the range couldn't be fetched, with the error of each of them
+    #[error("Failed to get {} of the blocks, first at height {}: {}", .0.len(), .0[0].0, .0[0].1)]
+    BlocksFailed(Vec<(i64, Error)>),
+
+    /// Transaction was committed, but its execution failed
+    #[error("Transaction failed with code {0}: {1}")]
+    TxFailed(u32, String),
+
+    /// Blob was submitted, but its shares couldn't be found in the block including it
+    #[error(
+        "Blob with commitment {} not found in the block at height {height}",
+        hex::encode(commitment.hash())
+    )]
+    BlobNotFound {
+        /// Height of the block including the transaction paying for the blob
+        height: u64,
+        /// Commitment of the blob
+        commitment: celestia_types::blob::Commitment,
+    },
 }
@@ -0,0 +1,159 @@
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+
+/// [`Interceptor`] leaving the requests untouched, for the public endpoints which
+/// don't require any authentication.
+///
+/// See [`GrpcClient::new_unauthenticated`].
+///
+/// [`GrpcClient::new_unauthenticated`]: crate::GrpcClient::new_unauthenticated
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoAuth;
+
+impl Interceptor for NoAuth {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        Ok(request)
+    }
+}
+
+/// [`Interceptor`] applying two interceptors in sequence.
+///
+/// The request is passed to the `first` interceptor and its output to the `second` one.
+/// If any of them fails, the request is aborted with its [`Status`].
+///
+/// # Example
+///
+/// ```
+/// use celestia_grpc::ChainedInterceptor;
+/// use tonic::{Request, Status};
+///
+/// let auth = |mut req: Request<()>| -> Result<Request<()>, Status> {
+///     req.metadata_mut().insert("authorization", "Bearer token".parse().unwrap());
+///     Ok(req)
+/// };
+/// let app = |mut req: Request<()>| -> Result<Request<()>, Status> {
+///     req.metadata_mut().insert("x-app", "lumina".parse().unwrap());
+///     Ok(req)
+/// };
+///
+/// let interceptor = ChainedInterceptor::new(auth, app);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChainedInterceptor<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> ChainedInterceptor<A, B>
+where
+    A: Interceptor,
+    B: Interceptor,
+{
+    /// Create a new [`ChainedInterceptor`] calling `first` and then `second`.
+    pub fn new(first: A, second: B) -> Self {
+        ChainedInterceptor { first, second }
+    }
+}
+
+impl<A, B> Interceptor for ChainedInterceptor<A, B>
+where
+    A: Interceptor,
+    B: Interceptor,
+{
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let request = self.first.call(request)?;
+        self.second.call(request)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::result_large_err)] // interceptors return tonic::Status
+mod tests {
+    use celestia_proto::cosmos::base::node::v1beta1::{ConfigRequest, ConfigResponse};
+    use tonic::metadata::MetadataMap;
+
+    use super::*;
+    use crate::test_utils::{MockGrpcServer, Recorder};
+    use crate::GrpcClient;
+
+    const CONFIG: &str = "/cosmos.base.node.v1beta1.Service/Config";
+
+    fn auth(mut request: Request<()>) -> Result<Request<()>, Status> {
+        let token = "Bearer token".parse().unwrap();
+        request.metadata_mut().insert("authorization", token);
+        Ok(request)
+    }
+
+    fn app(mut request: Request<()>) -> Result<Request<()>, Status> {
+        request
+            .metadata_mut()
+            .insert("x-app", "lumina".parse().unwrap());
+        Ok(request)
+    }
+
+    fn reject(_: Request<()>) -> Result<Request<()>, Status> {
+        Err(Status::unauthenticated("rejected"))
+    }
+
+    #[tokio::test]
+    async fn both_interceptors_applied() {
+        let requests = Recorder::<MetadataMap>::new();
+
+        let server = MockGrpcServer::new()
+            .unary(CONFIG, {
+                let requests = requests.clone();
+                move |req: Request<ConfigRequest>| {
+                    requests.record(req.metadata().clone());
+                    Ok(ConfigResponse {
+                        minimum_gas_price: "0.002utia".to_owned(),
+                    })
+                }
+            })
+            .start()
+            .await;
+
+        let interceptor = ChainedInterceptor::new(auth, app);
+        let mut client = GrpcClient::new(server.channel().await, interceptor);
+        client.get_min_gas_price().await.unwrap();
+
+        let requests = requests.take();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].get("authorization").unwrap(), "Bearer token");
+        assert_eq!(requests[0].get("x-app").unwrap(), "lumina");
+    }
+
+    #[tokio::test]
+    async fn unauthenticated_client() {
+        let requests = Recorder::<MetadataMap>::new();
+
+        let server = MockGrpcServer::new()
+            .unary(CONFIG, {
+                let requests = requests.clone();
+                move |req: Request<ConfigRequest>| {
+                    requests.record(req.metadata().clone());
+                    Ok(ConfigResponse {
+                        minimum_gas_price: "0.002utia".to_owned(),
+                    })
+                }
+            })
+            .start()
+            .await;
+
+        let mut client = GrpcClient::new_unauthenticated(server.channel().await);
+        assert_eq!(client.get_min_gas_price().await.unwrap(), 0.002);
+
+        let requests = requests.take();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].get("authorization").is_none());
+    }
+
+    #[test]
+    fn failing_interceptor_aborts_chain() {
+        let mut interceptor = ChainedInterceptor::new(reject, app);
+        let status = interceptor.call(Request::new(())).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+        let mut interceptor = ChainedInterceptor::new(app, reject);
+        assert!(interceptor.call(Request::new(())).is_err());
+    }
+}
@@ -2,8 +2,19 @@
 #![cfg(not(target_arch = "wasm32"))]
 
 mod client;
+mod concurrency;
 mod error;
+mod interceptor;
+mod retry;
 pub mod types;
+mod wallet;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
 
 pub use crate::client::GrpcClient;
 pub use crate::error::{Error, Result};
+pub use crate::interceptor::{ChainedInterceptor, NoAuth};
+pub use crate::retry::RetryBudget;
+pub use crate::wallet::{SubmitOptions, SubmitOutcome, SubmitResult, Wallet};
+pub use tonic::codec::CompressionEncoding;
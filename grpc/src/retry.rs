@@ -0,0 +1,110 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Budget of the retries shared by all the calls of the clients it's given to, capping
+/// how many retries are made in aggregate within a time window.
+///
+/// The budget is a token bucket holding up to `max_retries` tokens, refilled at the rate
+/// of `max_retries` per `window`. Each retry takes one token, and once there are none left
+/// the call fails with [`Error::RetryBudgetExhausted`] instead of retrying. Clones of the
+/// budget share the same bucket.
+///
+/// [`Error::RetryBudgetExhausted`]: crate::Error::RetryBudgetExhausted
+#[derive(Debug, Clone)]
+pub struct RetryBudget {
+    max_retries: u32,
+    window: Duration,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RetryBudget {
+    /// Create the budget allowing up to `max_retries` retries per `window`, starting full.
+    pub fn new(max_retries: u32, window: Duration) -> Self {
+        RetryBudget {
+            max_retries,
+            window,
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: f64::from(max_retries),
+                refilled_at: Instant::now(),
+            })),
+        }
+    }
+
+    /// Take a token for a retry, returning `false` if the budget is exhausted.
+    pub(crate) fn try_acquire(&self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    /// Number of the retries left in the budget, without taking any.
+    pub fn remaining(&self) -> u32 {
+        self.remaining_at(Instant::now())
+    }
+
+    fn remaining_at(&self, now: Instant) -> u32 {
+        let bucket = self.bucket.lock().expect("poisoned lock");
+        self.tokens_at(&bucket, now) as u32
+    }
+
+    fn try_acquire_at(&self, now: Instant) -> bool {
+        let mut bucket = self.bucket.lock().expect("poisoned lock");
+
+        bucket.tokens = self.tokens_at(&bucket, now);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Tokens in the bucket at the time, refilled since it was last refilled.
+    fn tokens_at(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let max = f64::from(self.max_retries);
+
+        if self.window.is_zero() {
+            return bucket.tokens;
+        }
+
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        let refill = elapsed.as_secs_f64() / self.window.as_secs_f64() * max;
+        (bucket.tokens + refill).min(max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budget_refills_over_window() {
+        let budget = RetryBudget::new(2, Duration::from_secs(10));
+        let start = Instant::now();
+
+        assert!(budget.try_acquire_at(start));
+        assert!(budget.clone().try_acquire_at(start));
+        assert!(!budget.try_acquire_at(start));
+
+        // half of the window refills one token
+        let later = start + Duration::from_secs(5);
+        // reading the remaining retries doesn't take them
+        assert_eq!(budget.remaining_at(later), 1);
+        assert_eq!(budget.remaining_at(later), 1);
+        assert!(budget.try_acquire_at(later));
+        assert!(!budget.try_acquire_at(later));
+
+        // never refilled above the maximum
+        let much_later = later + Duration::from_secs(100);
+        assert_eq!(budget.remaining_at(much_later), 2);
+        assert!(budget.try_acquire_at(much_later));
+        assert!(budget.try_acquire_at(much_later));
+        assert!(!budget.try_acquire_at(much_later));
+    }
+}
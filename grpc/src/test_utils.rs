@@ -0,0 +1,389 @@
+//! Utilities for writing tests.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fmt::Debug;
+use std::future::{ready, Future};
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use celestia_proto::celestia::blob::v1::{
+    Params as RawBlobParams, QueryParamsRequest as QueryBlobParamsRequest,
+    QueryParamsResponse as QueryBlobParamsResponse,
+};
+use celestia_proto::cosmos::auth::v1beta1::{
+    BaseAccount as RawBaseAccount, Params as RawAuthParams, QueryAccountRequest,
+    QueryAccountResponse, QueryParamsRequest as QueryAuthParamsRequest,
+    QueryParamsResponse as QueryAuthParamsResponse,
+};
+use celestia_proto::cosmos::base::node::v1beta1::{ConfigRequest, ConfigResponse};
+use celestia_proto::cosmos::base::tendermint::v1beta1::{
+    GetLatestBlockRequest, GetLatestBlockResponse,
+};
+use celestia_proto::cosmos::tx::v1beta1::{
+    BroadcastTxRequest, BroadcastTxResponse, GetTxRequest, GetTxResponse as RawGetTxResponse,
+    Tx as RawTx,
+};
+use celestia_types::blob::RawBlobTx;
+use celestia_types::block::{Block, Data};
+use celestia_types::state::RawTxResponse;
+use celestia_types::test_utils::ExtendedHeaderGenerator;
+use hyper::body::Incoming;
+use hyper::service::service_fn;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use prost::Message;
+use tendermint_proto::google::protobuf::Any;
+use tokio::net::TcpListener;
+use tonic::body::BoxBody;
+use tonic::codec::{CompressionEncoding, ProstCodec};
+use tonic::server::{Grpc, UnaryService};
+use tonic::transport::Channel;
+use tonic::{Request, Response, Status};
+
+use crate::types::tx::compute_tx_hash;
+#[cfg(test)]
+use crate::types::FromGrpcResponse;
+#[cfg(test)]
+use crate::Error;
+use crate::{GrpcClient, NoAuth};
+
+pub const GET_LATEST_BLOCK: &str = "/cosmos.base.tendermint.v1beta1.Service/GetLatestBlock";
+pub const ACCOUNT: &str = "/cosmos.auth.v1beta1.Query/Account";
+pub const AUTH_PARAMS: &str = "/cosmos.auth.v1beta1.Query/Params";
+pub const BLOB_PARAMS: &str = "/celestia.blob.v1.Query/Params";
+pub const CONFIG: &str = "/cosmos.base.node.v1beta1.Service/Config";
+pub const BROADCAST_TX: &str = "/cosmos.tx.v1beta1.Service/BroadcastTx";
+pub const GET_TX: &str = "/cosmos.tx.v1beta1.Service/GetTx";
+
+/// Secret key of the account known to [`MockGrpcServer::with_chain`].
+pub const MOCK_ACCOUNT_KEY: [u8; 32] = [7; 32];
+/// Address of the account of [`MOCK_ACCOUNT_KEY`].
+pub const MOCK_ACCOUNT_ADDRESS: &str = "celestia150rtrmj2f8vl9tem8qpfw36ylw5jg9j2zfmer5";
+
+type HandlerFuture = Pin<Box<dyn Future<Output = http::Response<BoxBody>> + Send>>;
+type Handler = Arc<dyn Fn(http::Request<Incoming>) -> HandlerFuture + Send + Sync>;
+
+/// Assert that the raw gRPC response is converted into the expected value.
+#[cfg(test)]
+#[track_caller]
+pub(crate) fn assert_response_conversion<R, T>(raw: R, expected: T)
+where
+    R: FromGrpcResponse<T>,
+    T: PartialEq + Debug,
+{
+    assert_eq!(raw.try_from_response().unwrap(), expected);
+}
+
+/// Assert that the conversion of the malformed raw gRPC response fails
+/// with [`Error::FailedToParseResponse`].
+#[cfg(test)]
+#[track_caller]
+pub(crate) fn assert_response_parse_failure<R, T>(raw: R)
+where
+    R: FromGrpcResponse<T>,
+    T: Debug,
+{
+    match raw.try_from_response() {
+        Err(Error::FailedToParseResponse) => {}
+        other => panic!("expected FailedToParseResponse, got {other:?}"),
+    }
+}
+
+/// gRPC server answering unary calls with registered handlers.
+///
+/// Calls to methods without a handler are answered with [`Status::unimplemented`].
+#[derive(Default)]
+pub struct MockGrpcServer {
+    handlers: HashMap<&'static str, Handler>,
+    compression: Option<CompressionEncoding>,
+}
+
+impl MockGrpcServer {
+    pub fn new() -> Self {
+        MockGrpcServer::default()
+    }
+
+    /// Compress the responses of the handlers registered after it, for the clients
+    /// accepting the encoding.
+    pub fn with_compression(mut self, encoding: CompressionEncoding) -> Self {
+        self.compression = Some(encoding);
+        self
+    }
+
+    /// Register a handler of the unary method with a given path,
+    /// e.g. `/cosmos.tx.v1beta1.Service/GetTx`.
+    pub fn unary<Req, Resp, F>(self, path: &'static str, handler: F) -> Self
+    where
+        Req: Message + Default + Send + 'static,
+        Resp: Message + Send + 'static,
+        F: Fn(Request<Req>) -> Result<Resp, Status> + Send + Sync + 'static,
+    {
+        self.unary_async(path, move |req| ready(handler(req)))
+    }
+
+    /// Register a handler of the unary method with a given path, answering the call
+    /// once the returned future completes.
+    pub fn unary_async<Req, Resp, F, Fut>(mut self, path: &'static str, handler: F) -> Self
+    where
+        Req: Message + Default + Send + 'static,
+        Resp: Message + Send + 'static,
+        F: Fn(Request<Req>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Resp, Status>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        let compression = self.compression;
+
+        let handler: Handler = Arc::new(move |req| {
+            let service = UnaryHandler(handler.clone());
+
+            Box::pin(async move {
+                let mut grpc = Grpc::new(ProstCodec::<Resp, Req>::default());
+                if let Some(encoding) = compression {
+                    grpc = grpc.send_compressed(encoding);
+                }
+                grpc.unary(service, req).await
+            })
+        });
+
+        self.handlers.insert(path, handler);
+        self
+    }
+
+    /// Answer the queries the [`Wallet`] signing the transactions of the account of
+    /// [`MOCK_ACCOUNT_KEY`] makes: the latest block of app v2 of the chain `chain_id`,
+    /// the account, the parameters of the blob and auth modules and the minimum gas
+    /// price of `0.002utia`.
+    ///
+    /// [`Wallet`]: crate::Wallet
+    pub fn with_chain(self, chain_id: &'static str) -> Self {
+        self.unary(
+            GET_LATEST_BLOCK,
+            move |_: Request<GetLatestBlockRequest>| Ok(latest_block(chain_id)),
+        )
+        .unary(ACCOUNT, account)
+        .unary(BLOB_PARAMS, |_: Request<QueryBlobParamsRequest>| {
+            Ok(QueryBlobParamsResponse {
+                params: Some(RawBlobParams {
+                    gas_per_blob_byte: 8,
+                    gov_max_square_size: 64,
+                }),
+            })
+        })
+        .unary(AUTH_PARAMS, |_: Request<QueryAuthParamsRequest>| {
+            Ok(QueryAuthParamsResponse {
+                params: Some(RawAuthParams {
+                    tx_size_cost_per_byte: 10,
+                    ..Default::default()
+                }),
+            })
+        })
+        .unary(CONFIG, config)
+    }
+
+    /// Commit the broadcasted blob transactions at `height`, recording them, and answer
+    /// the queries of the committed transactions.
+    pub fn committing_blob_txs(self, height: i64, broadcasted: Recorder<RawBlobTx>) -> Self {
+        let committed = Arc::new(Mutex::new(HashMap::new()));
+
+        self.unary(BROADCAST_TX, {
+            let committed = committed.clone();
+            move |req: Request<BroadcastTxRequest>| {
+                let blob_tx = RawBlobTx::decode(&*req.into_inner().tx_bytes)
+                    .map_err(|e| Status::invalid_argument(e.to_string()))?;
+                let txhash = compute_tx_hash(&blob_tx.tx);
+                let tx = RawTx::decode(&*blob_tx.tx)
+                    .map_err(|e| Status::invalid_argument(e.to_string()))?;
+                committed.lock().unwrap().insert(txhash.clone(), tx);
+                broadcasted.record(blob_tx);
+
+                Ok(BroadcastTxResponse {
+                    tx_response: Some(RawTxResponse {
+                        txhash,
+                        ..Default::default()
+                    }),
+                })
+            }
+        })
+        .unary(GET_TX, move |req: Request<GetTxRequest>| {
+            let hash = req.into_inner().hash;
+            match committed.lock().unwrap().get(&hash) {
+                Some(tx) => Ok(RawGetTxResponse {
+                    tx: Some(tx.clone()),
+                    tx_response: Some(RawTxResponse {
+                        height,
+                        txhash: hash,
+                        ..Default::default()
+                    }),
+                }),
+                None => Err(Status::not_found(hash)),
+            }
+        })
+    }
+
+    /// Start serving on a random local port.
+    pub async fn start(self) -> MockGrpcServerHandle {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handlers = Arc::new(self.handlers);
+
+        let join_handle = tokio::spawn(async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    break;
+                };
+                let handlers = handlers.clone();
+
+                tokio::spawn(async move {
+                    let service = service_fn(move |req: http::Request<Incoming>| {
+                        let handler = handlers.get(req.uri().path()).cloned();
+
+                        async move {
+                            let response = match handler {
+                                Some(handler) => handler(req).await,
+                                None => Status::unimplemented(req.uri().path()).into_http(),
+                            };
+                            Ok::<_, Infallible>(response)
+                        }
+                    });
+
+                    let _ = hyper::server::conn::http2::Builder::new(TokioExecutor::new())
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        });
+
+        MockGrpcServerHandle { addr, join_handle }
+    }
+}
+
+/// Handle to the running [`MockGrpcServer`], which stops it when dropped.
+pub struct MockGrpcServerHandle {
+    addr: SocketAddr,
+    join_handle: tokio::task::JoinHandle<()>,
+}
+
+impl MockGrpcServerHandle {
+    /// URL of the server.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Create a [`Channel`] connected to the server.
+    pub async fn channel(&self) -> Channel {
+        Channel::from_shared(self.url())
+            .unwrap()
+            .connect()
+            .await
+            .unwrap()
+    }
+
+    /// Create a [`GrpcClient`] connected to the server.
+    pub async fn client(&self) -> GrpcClient<NoAuth> {
+        GrpcClient::new_unauthenticated(self.channel().await)
+    }
+}
+
+impl Drop for MockGrpcServerHandle {
+    fn drop(&mut self) {
+        self.join_handle.abort();
+    }
+}
+
+/// Records values passed to it, e.g. requests received by the handler.
+#[derive(Debug)]
+pub struct Recorder<T>(Arc<Mutex<Vec<T>>>);
+
+impl<T> Default for Recorder<T> {
+    fn default() -> Self {
+        Recorder::new()
+    }
+}
+
+impl<T> Recorder<T> {
+    pub fn new() -> Self {
+        Recorder(Arc::new(Mutex::new(Vec::new())))
+    }
+
+    pub fn record(&self, value: T) {
+        self.0.lock().unwrap().push(value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn take(&self) -> Vec<T> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+impl<T> Clone for Recorder<T> {
+    fn clone(&self) -> Self {
+        Recorder(self.0.clone())
+    }
+}
+
+/// Latest block of the chain, with the blobs committed to for app v2.
+pub fn latest_block(chain_id: &str) -> GetLatestBlockResponse {
+    let mut header = ExtendedHeaderGenerator::new().next().header;
+    header.version.app = 2;
+    header.chain_id = chain_id.parse().unwrap();
+    let data = Data {
+        txs: Vec::new(),
+        square_size: 1,
+        hash: Vec::new(),
+    };
+
+    GetLatestBlockResponse {
+        block_id: None,
+        block: Some(Block::new(header, data, Default::default(), None).into()),
+        sdk_block: None,
+    }
+}
+
+/// Answer the query of the account of [`MOCK_ACCOUNT_ADDRESS`].
+pub fn account(req: Request<QueryAccountRequest>) -> Result<QueryAccountResponse, Status> {
+    assert_eq!(req.into_inner().address, MOCK_ACCOUNT_ADDRESS);
+    let account = RawBaseAccount {
+        address: MOCK_ACCOUNT_ADDRESS.to_owned(),
+        pub_key: None,
+        account_number: 4,
+        sequence: 9,
+    };
+
+    Ok(QueryAccountResponse {
+        account: Some(Any {
+            type_url: "/cosmos.auth.v1beta1.BaseAccount".to_owned(),
+            value: account.encode_to_vec(),
+        }),
+    })
+}
+
+/// Answer the query of the config of the node with the minimum gas price of `0.002utia`.
+pub fn config(_: Request<ConfigRequest>) -> Result<ConfigResponse, Status> {
+    Ok(ConfigResponse {
+        minimum_gas_price: "0.002utia".to_owned(),
+    })
+}
+
+struct UnaryHandler<F>(Arc<F>);
+
+impl<Req, Resp, F, Fut> UnaryService<Req> for UnaryHandler<F>
+where
+    F: Fn(Request<Req>) -> Fut,
+    Fut: Future<Output = Result<Resp, Status>> + Send + 'static,
+{
+    type Response = Resp;
+    type Future = Pin<Box<dyn Future<Output = Result<Response<Resp>, Status>> + Send>>;
+
+    fn call(&mut self, request: Request<Req>) -> Self::Future {
+        let response = (self.0)(request);
+        Box::pin(async move { response.await.map(Response::new) })
+    }
+}
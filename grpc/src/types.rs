@@ -3,7 +3,7 @@
 use celestia_proto::celestia::blob::v1::{
     QueryParamsRequest as QueryBlobParamsRequest, QueryParamsResponse as QueryBlobParamsResponse,
 };
-use celestia_proto::cosmos::base::node::v1beta1::{ConfigRequest, ConfigResponse};
+use celestia_proto::cosmos::base::node::v1beta1::ConfigRequest;
 use celestia_proto::cosmos::base::tendermint::v1beta1::{
     GetBlockByHeightRequest, GetBlockByHeightResponse, GetLatestBlockRequest,
     GetLatestBlockResponse,
@@ -15,6 +15,18 @@ use crate::Error;
 
 /// types related to authorisation
 pub mod auth;
+/// types related to the bank module
+pub mod bank;
+/// types related to blobs
+pub mod blob;
+/// types related to coins and their amounts
+pub mod coin;
+/// types related to the distribution module
+pub mod distribution;
+/// types related to paginated queries
+pub mod pagination;
+/// types related to the staking module
+pub mod staking;
 /// types related to transaction querying and submission
 pub mod tx;
 
@@ -60,22 +72,6 @@ impl FromGrpcResponse<Block> for GetLatestBlockResponse {
     }
 }
 
-impl FromGrpcResponse<f64> for ConfigResponse {
-    fn try_from_response(self) -> Result<f64, Error> {
-        const UNITS_SUFFIX: &str = "utia";
-
-        let min_gas_price_with_suffix = self.minimum_gas_price;
-        let min_gas_price_str = min_gas_price_with_suffix
-            .strip_suffix(UNITS_SUFFIX)
-            .ok_or(Error::FailedToParseResponse)?;
-        let min_gas_price = min_gas_price_str
-            .parse::<f64>()
-            .map_err(|_| Error::FailedToParseResponse)?;
-
-        Ok(min_gas_price)
-    }
-}
-
 impl IntoGrpcParam<GetBlockByHeightRequest> for i64 {
     fn into_parameter(self) -> GetBlockByHeightRequest {
         GetBlockByHeightRequest { height: self }
@@ -85,3 +81,35 @@ impl IntoGrpcParam<GetBlockByHeightRequest> for i64 {
 make_empty_params!(GetLatestBlockRequest);
 make_empty_params!(ConfigRequest);
 make_empty_params!(QueryBlobParamsRequest);
+
+#[cfg(test)]
+mod tests {
+    use celestia_proto::celestia::blob::v1::Params as RawBlobParams;
+
+    use super::*;
+    use crate::test_utils::{assert_response_conversion, assert_response_parse_failure};
+
+    #[test]
+    fn blob_params_conversion() {
+        let raw = QueryBlobParamsResponse {
+            params: Some(RawBlobParams {
+                gas_per_blob_byte: 8,
+                gov_max_square_size: 64,
+            }),
+        };
+
+        assert_response_conversion(
+            raw,
+            BlobParams {
+                gas_per_blob_byte: 8,
+                gov_max_square_size: 64,
+            },
+        );
+    }
+
+    #[test]
+    fn blob_params_missing() {
+        let raw = QueryBlobParamsResponse { params: None };
+        assert_response_parse_failure::<_, BlobParams>(raw);
+    }
+}
@@ -4,23 +4,41 @@ use celestia_proto::cosmos::auth::v1beta1::{
     QueryAccountRequest, QueryAccountResponse, QueryAccountsRequest, QueryAccountsResponse,
     QueryParamsRequest as QueryAuthParamsRequest, QueryParamsResponse as QueryAuthParamsResponse,
 };
+use celestia_proto::cosmos::crypto::multisig::LegacyAminoPubKey;
 use celestia_types::state::auth::{
-    AuthParams, BaseAccount, ModuleAccount, RawBaseAccount, RawModuleAccount,
+    AuthParams, BaseAccount, ModuleAccount, MultisigAccount, RawBaseAccount, RawModuleAccount,
 };
 use celestia_types::state::Address;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use tendermint_proto::google::protobuf::Any;
 
 use crate::types::make_empty_params;
 use crate::types::{FromGrpcResponse, IntoGrpcParam};
 use crate::Error;
 
+/// Type urls of the vesting accounts, which all start with the `BaseVestingAccount`.
+const VESTING_ACCOUNT_TYPE_URLS: [&str; 4] = [
+    "/cosmos.vesting.v1beta1.ContinuousVestingAccount",
+    "/cosmos.vesting.v1beta1.DelayedVestingAccount",
+    "/cosmos.vesting.v1beta1.PeriodicVestingAccount",
+    "/cosmos.vesting.v1beta1.PermanentLockedAccount",
+];
+
 /// Enum representing different types of account
 #[derive(Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
 pub enum Account {
     /// Base account type
     Base(BaseAccount),
     /// Account for modules that holds coins on a pool
     Module(ModuleAccount),
+    /// Base account with a multisig public key
+    Multisig(MultisigAccount),
 }
 
 impl Account {
@@ -29,6 +47,7 @@ impl Account {
         match self {
             Account::Base(acct) => Some(acct),
             Account::Module(acct) => acct.base_account.as_ref(),
+            Account::Multisig(_) => None,
         }
     }
 }
@@ -52,6 +71,12 @@ impl FromGrpcResponse<Account> for QueryAccountResponse {
     }
 }
 
+impl FromGrpcResponse<(u64, u64)> for QueryAccountResponse {
+    fn try_from_response(self) -> Result<(u64, u64), Error> {
+        account_sequence_from_any(self.account.ok_or(Error::FailedToParseResponse)?)
+    }
+}
+
 impl FromGrpcResponse<Vec<Account>> for QueryAccountsResponse {
     fn try_from_response(self) -> Result<Vec<Account>, Error> {
         self.accounts.into_iter().map(account_from_any).collect()
@@ -78,7 +103,16 @@ fn account_from_any(any: Any) -> Result<Account, Error> {
     let account = if any.type_url == RawBaseAccount::type_url() {
         let base_account =
             RawBaseAccount::decode(&*any.value).map_err(|_| Error::FailedToParseResponse)?;
-        Account::Base(base_account.try_into()?)
+        let multisig = base_account
+            .pub_key
+            .as_ref()
+            .is_some_and(|key| key.type_url == LegacyAminoPubKey::type_url());
+
+        if multisig {
+            Account::Multisig(base_account.try_into()?)
+        } else {
+            Account::Base(base_account.try_into()?)
+        }
     } else if any.type_url == RawModuleAccount::type_url() {
         let module_account =
             RawModuleAccount::decode(&*any.value).map_err(|_| Error::FailedToParseResponse)?;
@@ -89,3 +123,305 @@ fn account_from_any(any: Any) -> Result<Account, Error> {
 
     Ok(account)
 }
+
+/// Prefix of the vesting accounts, up to their [`RawBaseAccount`].
+///
+/// Only the first field of each message is declared, the rest is skipped when decoding.
+#[derive(Clone, PartialEq, Message)]
+struct RawVestingAccount {
+    #[prost(message, optional, tag = "1")]
+    base_vesting_account: Option<RawBaseVestingAccount>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct RawBaseVestingAccount {
+    #[prost(message, optional, tag = "1")]
+    base_account: Option<RawBaseAccount>,
+}
+
+/// Account number and sequence of the account, without parsing the rest of it.
+fn account_sequence_from_any(any: Any) -> Result<(u64, u64), Error> {
+    let base_account = if any.type_url == RawBaseAccount::type_url() {
+        Some(RawBaseAccount::decode(&*any.value).map_err(|_| Error::FailedToParseResponse)?)
+    } else if any.type_url == RawModuleAccount::type_url() {
+        RawModuleAccount::decode(&*any.value)
+            .map_err(|_| Error::FailedToParseResponse)?
+            .base_account
+    } else if VESTING_ACCOUNT_TYPE_URLS.contains(&any.type_url.as_str()) {
+        RawVestingAccount::decode(&*any.value)
+            .map_err(|_| Error::FailedToParseResponse)?
+            .base_vesting_account
+            .and_then(|vesting| vesting.base_account)
+    } else {
+        return Err(Error::UnexpectedResponseType(any.type_url));
+    };
+
+    let base_account = base_account.ok_or(Error::FailedToParseResponse)?;
+    Ok((base_account.account_number, base_account.sequence))
+}
+
+#[cfg(test)]
+mod tests {
+    use celestia_types::state::auth::MultisigPubKey;
+
+    use super::*;
+    use crate::test_utils::{assert_response_conversion, assert_response_parse_failure};
+
+    const ADDRESS: &str = "celestia1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5wgawu3";
+
+    fn base_account() -> BaseAccount {
+        BaseAccount {
+            address: ADDRESS.parse().unwrap(),
+            pub_key: None,
+            account_number: 3,
+            sequence: 12,
+        }
+    }
+
+    fn any_account<T: Name>(account: T) -> Any {
+        Any {
+            type_url: T::type_url(),
+            value: account.encode_to_vec(),
+        }
+    }
+
+    #[test]
+    fn auth_params_conversion() {
+        let params = AuthParams {
+            max_memo_characters: 256,
+            tx_sig_limit: 7,
+            tx_size_cost_per_byte: 10,
+            sig_verify_cost_ed25519: 590,
+            sig_verify_cost_secp256k1: 1000,
+        };
+        let raw = QueryAuthParamsResponse {
+            params: Some(params),
+        };
+
+        assert_response_conversion(raw, params);
+    }
+
+    #[test]
+    fn auth_params_missing() {
+        let raw = QueryAuthParamsResponse { params: None };
+        assert_response_parse_failure::<_, AuthParams>(raw);
+    }
+
+    #[test]
+    fn account_conversion() {
+        let raw = QueryAccountResponse {
+            account: Some(any_account(RawBaseAccount::from(base_account()))),
+        };
+        assert_response_conversion(raw, Account::Base(base_account()));
+
+        let module_account = ModuleAccount {
+            base_account: Some(base_account()),
+            name: "distribution".to_owned(),
+            permissions: vec!["minter".to_owned()],
+        };
+        let raw = QueryAccountResponse {
+            account: Some(any_account(RawModuleAccount::from(module_account.clone()))),
+        };
+        assert_response_conversion(raw, Account::Module(module_account));
+    }
+
+    #[test]
+    fn multisig_account_conversion() {
+        let members = [1, 2, 3].map(|seed| {
+            let signing_key = k256::ecdsa::SigningKey::from_slice(&[seed; 32]).unwrap();
+            tendermint::PublicKey::from(*signing_key.verifying_key())
+        });
+        let account = MultisigAccount {
+            address: ADDRESS.parse().unwrap(),
+            pub_key: MultisigPubKey {
+                threshold: 2,
+                public_keys: members.to_vec(),
+            },
+            account_number: 3,
+            sequence: 12,
+        };
+        let raw = QueryAccountResponse {
+            account: Some(any_account(RawBaseAccount::from(account.clone()))),
+        };
+
+        let decoded = FromGrpcResponse::<Account>::try_from_response(raw.clone()).unwrap();
+        let Account::Multisig(decoded) = decoded else {
+            panic!("multisig account decoded as {decoded:?}");
+        };
+        assert_eq!(decoded.pub_key.threshold, 2);
+        assert_eq!(decoded.pub_key.public_keys.len(), 3);
+        assert_eq!(decoded, account);
+        assert_response_conversion(raw, (3, 12));
+    }
+
+    #[test]
+    fn accounts_conversion() {
+        let raw = QueryAccountsResponse {
+            accounts: vec![any_account(RawBaseAccount::from(base_account()))],
+            pagination: None,
+        };
+        assert_response_conversion(raw, vec![Account::Base(base_account())]);
+    }
+
+    #[test]
+    fn account_malformed() {
+        let raw = QueryAccountResponse { account: None };
+        assert_response_parse_failure::<_, Account>(raw);
+
+        let raw = QueryAccountResponse {
+            account: Some(Any {
+                type_url: RawBaseAccount::type_url(),
+                value: vec![0xff; 8],
+            }),
+        };
+        assert_response_parse_failure::<_, Account>(raw);
+
+        let raw = QueryAccountsResponse {
+            accounts: vec![Any {
+                type_url: RawModuleAccount::type_url(),
+                value: vec![0xff; 8],
+            }],
+            pagination: None,
+        };
+        assert_response_parse_failure::<_, Vec<Account>>(raw);
+    }
+
+    #[test]
+    fn account_sequence_conversion() {
+        let raw = QueryAccountResponse {
+            account: Some(any_account(RawBaseAccount::from(base_account()))),
+        };
+        assert_response_conversion(raw, (3, 12));
+
+        let module_account = RawModuleAccount {
+            base_account: Some(base_account().into()),
+            name: "distribution".to_owned(),
+            permissions: Vec::new(),
+        };
+        let raw = QueryAccountResponse {
+            account: Some(any_account(module_account)),
+        };
+        assert_response_conversion(raw, (3, 12));
+
+        // ContinuousVestingAccount, with `end_time` in the `BaseVestingAccount`
+        // and `start_time` after it
+        let mut base_vesting_account = RawBaseVestingAccount {
+            base_account: Some(base_account().into()),
+        }
+        .encode_to_vec();
+        base_vesting_account.extend([0x28, 0x64]);
+        let mut value = vec![0x0a, base_vesting_account.len() as u8];
+        value.extend(base_vesting_account);
+        value.extend([0x10, 0x05]);
+        let raw = QueryAccountResponse {
+            account: Some(Any {
+                type_url: VESTING_ACCOUNT_TYPE_URLS[0].to_owned(),
+                value,
+            }),
+        };
+        assert_response_conversion(raw, (3, 12));
+    }
+
+    #[test]
+    fn account_sequence_missing() {
+        let raw = QueryAccountResponse { account: None };
+        assert_response_parse_failure::<_, (u64, u64)>(raw);
+
+        let module_account = RawModuleAccount {
+            base_account: None,
+            name: "distribution".to_owned(),
+            permissions: Vec::new(),
+        };
+        let raw = QueryAccountResponse {
+            account: Some(any_account(module_account)),
+        };
+        assert_response_parse_failure::<_, (u64, u64)>(raw);
+
+        let raw = QueryAccountResponse {
+            account: Some(Any {
+                type_url: "/cosmos.auth.v1beta1.Unknown".to_owned(),
+                value: Vec::new(),
+            }),
+        };
+        let err = FromGrpcResponse::<(u64, u64)>::try_from_response(raw).unwrap_err();
+        assert!(matches!(err, Error::UnexpectedResponseType(_)));
+    }
+
+    #[test]
+    fn account_unknown_type() {
+        let raw = QueryAccountResponse {
+            account: Some(Any {
+                type_url: "/cosmos.auth.v1beta1.Unknown".to_owned(),
+                value: Vec::new(),
+            }),
+        };
+
+        let err = FromGrpcResponse::<Account>::try_from_response(raw).unwrap_err();
+        assert!(matches!(err, Error::UnexpectedResponseType(_)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn account_serde_roundtrip() {
+        use k256::ecdsa::SigningKey;
+        use tendermint::PublicKey;
+
+        let signing_key = SigningKey::from_slice(&[7; 32]).unwrap();
+        let pub_key = PublicKey::from(*signing_key.verifying_key());
+        let account = Account::Base(BaseAccount {
+            pub_key: Some(pub_key),
+            ..base_account()
+        });
+
+        let json = serde_json::to_value(&account).unwrap();
+        assert_eq!(json["type"], "base");
+        assert_eq!(json["address"], ADDRESS);
+        assert_eq!(json["pub_key"]["type"], "secp256k1");
+        assert_eq!(
+            json["pub_key"]["key"],
+            hex::encode_upper(pub_key.to_bytes())
+        );
+        assert_eq!(serde_json::from_value::<Account>(json).unwrap(), account);
+
+        let account = Account::Module(ModuleAccount {
+            base_account: Some(base_account()),
+            name: "distribution".to_owned(),
+            permissions: vec!["minter".to_owned()],
+        });
+
+        let json = serde_json::to_value(&account).unwrap();
+        assert_eq!(json["type"], "module");
+        assert_eq!(json["base_account"]["pub_key"], serde_json::Value::Null);
+        assert_eq!(serde_json::from_value::<Account>(json).unwrap(), account);
+
+        let account = Account::Multisig(MultisigAccount {
+            address: ADDRESS.parse().unwrap(),
+            pub_key: MultisigPubKey {
+                threshold: 1,
+                public_keys: vec![pub_key],
+            },
+            account_number: 3,
+            sequence: 12,
+        });
+
+        let json = serde_json::to_value(&account).unwrap();
+        assert_eq!(json["type"], "multisig");
+        assert_eq!(json["pub_key"]["public_keys"][0]["type"], "secp256k1");
+        assert_eq!(serde_json::from_value::<Account>(json).unwrap(), account);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn auth_params_serde_roundtrip() {
+        let params = AuthParams {
+            max_memo_characters: 256,
+            tx_sig_limit: 7,
+            tx_size_cost_per_byte: 10,
+            sig_verify_cost_ed25519: 590,
+            sig_verify_cost_secp256k1: 1000,
+        };
+
+        let json = serde_json::to_string(&params).unwrap();
+        assert_eq!(serde_json::from_str::<AuthParams>(&json).unwrap(), params);
+    }
+}
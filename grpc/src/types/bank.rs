@@ -0,0 +1,123 @@
+use celestia_proto::cosmos::bank::v1beta1::{
+    QueryBalanceRequest, QueryBalanceResponse, QuerySupplyOfRequest, QuerySupplyOfResponse,
+    QueryTotalSupplyRequest, QueryTotalSupplyResponse,
+};
+use celestia_types::state::Address;
+
+use crate::types::coin::Coin;
+use crate::types::pagination::{Paginated, Pagination};
+use crate::types::{FromGrpcResponse, IntoGrpcParam};
+use crate::Error;
+
+impl FromGrpcResponse<Paginated<Coin>> for QueryTotalSupplyResponse {
+    fn try_from_response(self) -> Result<Paginated<Coin>, Error> {
+        let supply = self
+            .supply
+            .into_iter()
+            .map(Coin::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Paginated::new(supply, self.pagination))
+    }
+}
+
+impl FromGrpcResponse<Coin> for QuerySupplyOfResponse {
+    fn try_from_response(self) -> Result<Coin, Error> {
+        self.amount.ok_or(Error::FailedToParseResponse)?.try_into()
+    }
+}
+
+impl FromGrpcResponse<Coin> for QueryBalanceResponse {
+    fn try_from_response(self) -> Result<Coin, Error> {
+        self.balance.ok_or(Error::FailedToParseResponse)?.try_into()
+    }
+}
+
+impl IntoGrpcParam<QueryTotalSupplyRequest> for Option<Pagination> {
+    fn into_parameter(self) -> QueryTotalSupplyRequest {
+        QueryTotalSupplyRequest {
+            pagination: self.map(Into::into),
+        }
+    }
+}
+
+impl IntoGrpcParam<QuerySupplyOfRequest> for &str {
+    fn into_parameter(self) -> QuerySupplyOfRequest {
+        QuerySupplyOfRequest {
+            denom: self.to_owned(),
+        }
+    }
+}
+
+impl IntoGrpcParam<QueryBalanceRequest> for (&Address, &str) {
+    fn into_parameter(self) -> QueryBalanceRequest {
+        let (address, denom) = self;
+
+        QueryBalanceRequest {
+            address: address.to_string(),
+            denom: denom.to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use celestia_proto::cosmos::base::query::v1beta1::PageResponse;
+    use celestia_proto::cosmos::base::v1beta1::Coin as RawCoin;
+
+    use super::*;
+    use crate::test_utils::{assert_response_conversion, assert_response_parse_failure};
+    use crate::types::coin::UTIA_DENOM;
+
+    fn raw_utia(amount: &str) -> RawCoin {
+        RawCoin {
+            denom: UTIA_DENOM.to_owned(),
+            amount: amount.to_owned(),
+        }
+    }
+
+    #[test]
+    fn total_supply_conversion() {
+        let raw = QueryTotalSupplyResponse {
+            supply: vec![raw_utia("1000000000000000")],
+            pagination: Some(PageResponse {
+                next_key: vec![1],
+                total: 2,
+            }),
+        };
+
+        assert_response_conversion(
+            raw,
+            Paginated {
+                items: vec![Coin::utia(1_000_000_000_000_000)],
+                next_key: Some(vec![1]),
+                total: Some(2),
+            },
+        );
+    }
+
+    #[test]
+    fn supply_of_malformed() {
+        let raw = QuerySupplyOfResponse { amount: None };
+        assert_response_parse_failure::<_, Coin>(raw);
+
+        let raw = QuerySupplyOfResponse {
+            amount: Some(raw_utia("-1")),
+        };
+        assert!(matches!(
+            FromGrpcResponse::<Coin>::try_from_response(raw).unwrap_err(),
+            Error::InvalidAmount(_)
+        ));
+    }
+
+    #[test]
+    fn balance_conversion() {
+        let raw = QueryBalanceResponse {
+            balance: Some(raw_utia("42")),
+        };
+        assert_response_conversion(raw, Coin::utia(42));
+
+        let raw = QueryBalanceResponse { balance: None };
+        assert_response_parse_failure::<_, Coin>(raw);
+    }
+}
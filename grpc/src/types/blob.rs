@@ -0,0 +1,173 @@
+use celestia_proto::celestia::blob::v1::QueryParamsResponse as QueryBlobParamsResponse;
+use celestia_types::consts::appconsts::SHARE_SIZE;
+use celestia_types::hash::Hash;
+use celestia_types::{Blob, ShareProof};
+
+use crate::types::FromGrpcResponse;
+use crate::Error;
+
+pub use celestia_types::blob::BlobBuilder;
+
+/// Governance controlled parameters of the blob module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlobModuleParams {
+    /// Gas charged for each byte of the blob data
+    pub gas_per_blob_byte: u32,
+    /// Gas charged for each share occupied by the blob
+    pub gas_per_share: u64,
+    /// Maximum width of the original data square
+    pub max_square_size: u64,
+}
+
+impl FromGrpcResponse<BlobModuleParams> for QueryBlobParamsResponse {
+    fn try_from_response(self) -> Result<BlobModuleParams, Error> {
+        let params = self.params.ok_or(Error::FailedToParseResponse)?;
+
+        Ok(BlobModuleParams {
+            gas_per_blob_byte: params.gas_per_blob_byte,
+            // whole shares are paid for, regardless of how much data they hold
+            gas_per_share: u64::from(params.gas_per_blob_byte) * SHARE_SIZE as u64,
+            max_square_size: params.gov_max_square_size,
+        })
+    }
+}
+
+/// Verify that the [`Blob`] is included in the block with the given data root.
+///
+/// The proof needs to cover the exact shares of the blob. A [`ShareProof`] is used,
+/// as besides the [`NamespaceProof`]s of the shares in each row, it carries the proof
+/// of inclusion of those rows in the data root.
+///
+/// Returns `Ok(false)` if the proof is well-formed, but doesn't prove the inclusion
+/// of the blob under the `data_root`.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidProof`] if the proof is malformed, e.g. the number of
+/// row roots doesn't match the number of namespace proofs, or it is a proof of absence.
+///
+/// [`NamespaceProof`]: celestia_types::nmt::NamespaceProof
+pub fn verify_blob_inclusion(
+    blob: &Blob,
+    proof: &ShareProof,
+    data_root: &Hash,
+) -> Result<bool, Error> {
+    match proof.verify(*data_root) {
+        Ok(()) => {}
+        Err(celestia_types::Error::RootMismatch | celestia_types::Error::RangeProofError(_)) => {
+            return Ok(false)
+        }
+        Err(e) => return Err(Error::InvalidProof(e.to_string())),
+    }
+
+    let blob_shares = blob.to_shares()?;
+    let proven_shares = proof.shares();
+
+    Ok(blob_shares.len() == proven_shares.len()
+        && blob_shares
+            .iter()
+            .zip(proven_shares)
+            .all(|(blob_share, proven_share)| blob_share.data() == proven_share))
+}
+
+#[cfg(test)]
+mod tests {
+    use celestia_proto::celestia::blob::v1::Params as RawBlobParams;
+    use celestia_proto::celestia::core::v1::proof::ShareProof as RawShareProof;
+    use celestia_types::nmt::Namespace;
+    use celestia_types::{AppVersion, DataAvailabilityHeader, ExtendedDataSquare};
+
+    use super::*;
+    use crate::test_utils::{assert_response_conversion, assert_response_parse_failure};
+
+    fn blob_with_proof() -> (Blob, RawShareProof, Hash) {
+        let namespace = Namespace::new_v0(&[1, 2, 3]).unwrap();
+        let blob = Blob::new(namespace, b"lumina".to_vec(), AppVersion::V2).unwrap();
+
+        let ods = blob
+            .to_shares()
+            .unwrap()
+            .into_iter()
+            .map(|share| share.data().to_vec())
+            .collect();
+        let eds = ExtendedDataSquare::from_ods(ods, AppVersion::V2).unwrap();
+        let dah = DataAvailabilityHeader::from_eds(&eds);
+
+        let rows = eds.get_namespace_data(namespace, &dah, 1).unwrap();
+        let row_proof = dah.row_proof(0..=0).unwrap();
+
+        let raw_proof = RawShareProof {
+            data: rows
+                .iter()
+                .flat_map(|(_, row)| &row.shares)
+                .map(|share| share.data().to_vec())
+                .collect(),
+            namespace_id: namespace.id().to_vec(),
+            namespace_version: namespace.version().into(),
+            share_proofs: rows.into_iter().map(|(_, row)| row.proof.into()).collect(),
+            row_proof: Some(row_proof.into()),
+        };
+
+        (blob, raw_proof, dah.hash())
+    }
+
+    #[test]
+    fn valid_inclusion_proof() {
+        let (blob, raw_proof, data_root) = blob_with_proof();
+        let proof = raw_proof.try_into().unwrap();
+
+        assert!(verify_blob_inclusion(&blob, &proof, &data_root).unwrap());
+    }
+
+    #[test]
+    fn tampered_data_root() {
+        let (blob, raw_proof, _) = blob_with_proof();
+        let proof = raw_proof.try_into().unwrap();
+        let data_root = Hash::Sha256([0xab; 32]);
+
+        assert!(!verify_blob_inclusion(&blob, &proof, &data_root).unwrap());
+    }
+
+    #[test]
+    fn proof_of_different_blob() {
+        let (blob, raw_proof, data_root) = blob_with_proof();
+        let proof = raw_proof.try_into().unwrap();
+        let other_blob = Blob::new(blob.namespace, b"other".to_vec(), AppVersion::V2).unwrap();
+
+        assert!(!verify_blob_inclusion(&other_blob, &proof, &data_root).unwrap());
+    }
+
+    #[test]
+    fn malformed_proof() {
+        let (blob, mut raw_proof, data_root) = blob_with_proof();
+        raw_proof.share_proofs.clear();
+        let proof = raw_proof.try_into().unwrap();
+
+        assert!(matches!(
+            verify_blob_inclusion(&blob, &proof, &data_root).unwrap_err(),
+            Error::InvalidProof(_)
+        ));
+    }
+
+    #[test]
+    fn blob_module_params_conversion() {
+        let raw = QueryBlobParamsResponse {
+            params: Some(RawBlobParams {
+                gas_per_blob_byte: 8,
+                gov_max_square_size: 64,
+            }),
+        };
+
+        assert_response_conversion(
+            raw,
+            BlobModuleParams {
+                gas_per_blob_byte: 8,
+                gas_per_share: 8 * 512,
+                max_square_size: 64,
+            },
+        );
+
+        let raw = QueryBlobParamsResponse { params: None };
+        assert_response_parse_failure::<_, BlobModuleParams>(raw);
+    }
+}
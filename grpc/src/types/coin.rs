@@ -0,0 +1,297 @@
+use celestia_proto::cosmos::base::node::v1beta1::ConfigResponse;
+use celestia_proto::cosmos::base::v1beta1::{Coin as RawCoin, DecCoin as RawDecCoin};
+
+use crate::types::FromGrpcResponse;
+use crate::Error;
+
+/// Denomination of the base unit of the Celestia token.
+pub const UTIA_DENOM: &str = "utia";
+
+/// Number of decimal places between `utia` and `TIA`.
+pub const TIA_DECIMALS: u32 = 6;
+
+/// Number of decimal places of the decimal amounts of the cosmos-sdk, included in their
+/// integer representation used in the protobuf messages.
+const DEC_PRECISION: u32 = 18;
+
+/// Amount of tokens of the given denomination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coin {
+    /// Coin denomination
+    pub denom: String,
+    /// Coin amount in base units of the denomination
+    pub amount: u128,
+}
+
+impl Coin {
+    /// Create a new [`Coin`] with the given denomination and amount of base units.
+    pub fn new(denom: impl Into<String>, amount: u128) -> Self {
+        Coin {
+            denom: denom.into(),
+            amount,
+        }
+    }
+
+    /// Create a new [`Coin`] with the given amount of `utia`.
+    pub fn utia(amount: u128) -> Self {
+        Coin::new(UTIA_DENOM, amount)
+    }
+
+    /// Format the amount as a decimal number, with `decimals` digits belonging to the fractional part.
+    ///
+    /// Trailing zeros of the fractional part are omitted, e.g. `1500000utia` with
+    /// [`TIA_DECIMALS`] is displayed as `1.5`.
+    pub fn to_display(&self, decimals: u32) -> String {
+        let digits = self.amount.to_string();
+        let decimals = decimals as usize;
+
+        let (integer, fraction) = if digits.len() > decimals {
+            let (integer, fraction) = digits.split_at(digits.len() - decimals);
+            (integer.to_owned(), fraction.to_owned())
+        } else {
+            ("0".to_owned(), format!("{digits:0>decimals$}"))
+        };
+
+        let fraction = fraction.trim_end_matches('0');
+
+        if fraction.is_empty() {
+            integer
+        } else {
+            format!("{integer}.{fraction}")
+        }
+    }
+
+    /// Parse a decimal number with at most `decimals` fractional digits into the [`Coin`]
+    /// holding the corresponding amount of base units, e.g. `1.5` with [`TIA_DECIMALS`]
+    /// is parsed as `1500000`.
+    pub fn from_display(
+        denom: impl Into<String>,
+        amount: &str,
+        decimals: u32,
+    ) -> Result<Self, Error> {
+        let invalid_amount = || Error::InvalidAmount(amount.to_owned());
+
+        let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+
+        if integer.is_empty() || fraction.len() > decimals as usize {
+            return Err(invalid_amount());
+        }
+
+        if amount.contains('.') && fraction.is_empty() {
+            return Err(invalid_amount());
+        }
+
+        let multiplier = 10u128.checked_pow(decimals).ok_or_else(invalid_amount)?;
+        let fraction_multiplier = 10u128
+            .checked_pow(decimals - fraction.len() as u32)
+            .ok_or_else(invalid_amount)?;
+
+        let integer = parse_digits(integer).ok_or_else(invalid_amount)?;
+        let fraction = if fraction.is_empty() {
+            0
+        } else {
+            parse_digits(fraction).ok_or_else(invalid_amount)?
+        };
+
+        let amount = integer
+            .checked_mul(multiplier)
+            .and_then(|base| base.checked_add(fraction * fraction_multiplier))
+            .ok_or_else(invalid_amount)?;
+
+        Ok(Coin::new(denom, amount))
+    }
+}
+
+/// Price of a unit of gas in the given denomination.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GasPrice {
+    /// Denomination in which the price is quoted
+    pub denom: String,
+    /// Price of a unit of gas
+    pub price: f64,
+}
+
+impl FromGrpcResponse<Vec<GasPrice>> for ConfigResponse {
+    fn try_from_response(self) -> Result<Vec<GasPrice>, Error> {
+        let mut gas_prices = Vec::new();
+
+        // gas prices are encoded as comma separated decimal coins, e.g. `0.002utia,0.1uatom`
+        for price_with_denom in self.minimum_gas_price.split(',') {
+            if price_with_denom.is_empty() {
+                continue;
+            }
+
+            let denom_start = price_with_denom
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .ok_or(Error::FailedToParseResponse)?;
+            let (price, denom) = price_with_denom.split_at(denom_start);
+            let price = price
+                .parse::<f64>()
+                .map_err(|_| Error::FailedToParseResponse)?;
+
+            gas_prices.push(GasPrice {
+                denom: denom.to_owned(),
+                price,
+            });
+        }
+
+        Ok(gas_prices)
+    }
+}
+
+impl TryFrom<RawCoin> for Coin {
+    type Error = Error;
+
+    fn try_from(value: RawCoin) -> Result<Self, Self::Error> {
+        let amount = parse_digits(&value.amount).ok_or(Error::InvalidAmount(value.amount))?;
+
+        Ok(Coin {
+            denom: value.denom,
+            amount,
+        })
+    }
+}
+
+/// Decimal amounts are truncated to the whole base units, as only those can be withdrawn.
+impl TryFrom<RawDecCoin> for Coin {
+    type Error = Error;
+
+    fn try_from(value: RawDecCoin) -> Result<Self, Self::Error> {
+        let amount = match value.amount.split_once('.') {
+            Some((integer, fraction)) => parse_digits(fraction).and(parse_digits(integer)),
+            // without the decimal point, the amount is scaled by the precision
+            None => parse_digits(&value.amount).map(|amount| amount / 10u128.pow(DEC_PRECISION)),
+        };
+        let amount = amount.ok_or(Error::InvalidAmount(value.amount))?;
+
+        Ok(Coin {
+            denom: value.denom,
+            amount,
+        })
+    }
+}
+
+impl From<Coin> for RawCoin {
+    fn from(value: Coin) -> Self {
+        RawCoin {
+            denom: value.denom,
+            amount: value.amount.to_string(),
+        }
+    }
+}
+
+/// Parse non-empty string of ascii digits, returning `None` on any other input or an overflow.
+fn parse_digits(digits: &str) -> Option<u128> {
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_display() {
+        assert_eq!(Coin::utia(1_500_000).to_display(TIA_DECIMALS), "1.5");
+        assert_eq!(Coin::utia(2_000_000).to_display(TIA_DECIMALS), "2");
+        assert_eq!(Coin::utia(1).to_display(TIA_DECIMALS), "0.000001");
+        assert_eq!(Coin::utia(0).to_display(TIA_DECIMALS), "0");
+        assert_eq!(Coin::utia(123).to_display(0), "123");
+    }
+
+    #[test]
+    fn from_display() {
+        let coin = Coin::from_display(UTIA_DENOM, "1.5", TIA_DECIMALS).unwrap();
+        assert_eq!(coin, Coin::utia(1_500_000));
+
+        let coin = Coin::from_display(UTIA_DENOM, "0.000001", TIA_DECIMALS).unwrap();
+        assert_eq!(coin, Coin::utia(1));
+
+        let coin = Coin::from_display(UTIA_DENOM, "42", TIA_DECIMALS).unwrap();
+        assert_eq!(coin, Coin::utia(42_000_000));
+    }
+
+    #[test]
+    fn display_round_trip() {
+        for amount in [0, 1, 10, 999_999, 1_000_000, 123_456_789, u128::MAX] {
+            let coin = Coin::utia(amount);
+            let displayed = coin.to_display(TIA_DECIMALS);
+            let parsed = Coin::from_display(UTIA_DENOM, &displayed, TIA_DECIMALS).unwrap();
+            assert_eq!(parsed, coin);
+        }
+    }
+
+    #[test]
+    fn from_display_malformed() {
+        for amount in [
+            "",
+            ".",
+            "1.",
+            ".5",
+            "-1",
+            "+1",
+            "1.0000001",
+            "1,5",
+            "1.2.3",
+            " 1",
+            "abc",
+        ] {
+            let err = Coin::from_display(UTIA_DENOM, amount, TIA_DECIMALS).unwrap_err();
+            assert!(matches!(err, Error::InvalidAmount(_)), "{amount:?}");
+        }
+    }
+
+    #[test]
+    fn from_display_overflow() {
+        let max = u128::MAX.to_string();
+        let err = Coin::from_display(UTIA_DENOM, &max, TIA_DECIMALS).unwrap_err();
+        assert!(matches!(err, Error::InvalidAmount(_)));
+
+        let too_many_digits = format!("{max}0");
+        let err = Coin::from_display(UTIA_DENOM, &too_many_digits, 0).unwrap_err();
+        assert!(matches!(err, Error::InvalidAmount(_)));
+    }
+
+    #[test]
+    fn raw_coin_conversion() {
+        let raw = RawCoin {
+            denom: UTIA_DENOM.to_owned(),
+            amount: "340282366920938463463374607431768211455".to_owned(),
+        };
+        let coin = Coin::try_from(raw.clone()).unwrap();
+        assert_eq!(coin, Coin::utia(u128::MAX));
+        assert_eq!(RawCoin::from(coin), raw);
+
+        let raw = RawCoin {
+            denom: UTIA_DENOM.to_owned(),
+            amount: "12utia".to_owned(),
+        };
+        assert!(matches!(
+            Coin::try_from(raw).unwrap_err(),
+            Error::InvalidAmount(_)
+        ));
+    }
+
+    #[test]
+    fn raw_dec_coin_conversion() {
+        let raw_dec = |amount: &str| RawDecCoin {
+            denom: UTIA_DENOM.to_owned(),
+            amount: amount.to_owned(),
+        };
+
+        let coin = Coin::try_from(raw_dec("1500999999999999999999")).unwrap();
+        assert_eq!(coin, Coin::utia(1500));
+        let coin = Coin::try_from(raw_dec("1500.999999999999999999")).unwrap();
+        assert_eq!(coin, Coin::utia(1500));
+        let coin = Coin::try_from(raw_dec("999999999999999999")).unwrap();
+        assert_eq!(coin, Coin::utia(0));
+
+        for amount in ["", "1.", ".5", "-1", "1.5utia"] {
+            let err = Coin::try_from(raw_dec(amount)).unwrap_err();
+            assert!(matches!(err, Error::InvalidAmount(_)), "{amount:?}");
+        }
+    }
+}
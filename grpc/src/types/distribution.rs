@@ -0,0 +1,107 @@
+use celestia_proto::cosmos::distribution::v1beta1::{
+    QueryDelegationTotalRewardsRequest, QueryDelegationTotalRewardsResponse,
+};
+use celestia_types::state::{Address, ValAddress};
+
+use crate::types::coin::Coin;
+use crate::types::{FromGrpcResponse, IntoGrpcParam};
+use crate::Error;
+
+/// Rewards of the delegation to a validator, which weren't withdrawn yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DelegationReward {
+    /// Validator the tokens are delegated to
+    pub validator: ValAddress,
+    /// Pending rewards, truncated to the whole base units of each denomination
+    pub reward: Vec<Coin>,
+}
+
+impl FromGrpcResponse<Vec<DelegationReward>> for QueryDelegationTotalRewardsResponse {
+    fn try_from_response(self) -> Result<Vec<DelegationReward>, Error> {
+        self.rewards
+            .into_iter()
+            .map(|reward| {
+                Ok(DelegationReward {
+                    validator: reward.validator_address.parse()?,
+                    reward: reward
+                        .reward
+                        .into_iter()
+                        .map(Coin::try_from)
+                        .collect::<Result<_, _>>()?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl IntoGrpcParam<QueryDelegationTotalRewardsRequest> for &Address {
+    fn into_parameter(self) -> QueryDelegationTotalRewardsRequest {
+        QueryDelegationTotalRewardsRequest {
+            delegator_address: self.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use celestia_proto::cosmos::base::v1beta1::DecCoin as RawDecCoin;
+    use celestia_proto::cosmos::distribution::v1beta1::DelegationDelegatorReward as RawDelegationReward;
+
+    use super::*;
+    use crate::test_utils::assert_response_conversion;
+    use crate::types::coin::UTIA_DENOM;
+
+    const VALIDATOR: &str = "celestiavaloper1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5thlh2h";
+
+    fn raw_utia(amount: &str) -> RawDecCoin {
+        RawDecCoin {
+            denom: UTIA_DENOM.to_owned(),
+            amount: amount.to_owned(),
+        }
+    }
+
+    #[test]
+    fn rewards_conversion() {
+        let raw = QueryDelegationTotalRewardsResponse {
+            rewards: vec![
+                RawDelegationReward {
+                    validator_address: VALIDATOR.to_owned(),
+                    reward: vec![raw_utia("12345678000000000000000")],
+                },
+                RawDelegationReward {
+                    validator_address: VALIDATOR.to_owned(),
+                    reward: Vec::new(),
+                },
+            ],
+            total: vec![raw_utia("12345678000000000000000")],
+        };
+
+        assert_response_conversion(
+            raw,
+            vec![
+                DelegationReward {
+                    validator: VALIDATOR.parse().unwrap(),
+                    reward: vec![Coin::utia(12345)],
+                },
+                DelegationReward {
+                    validator: VALIDATOR.parse().unwrap(),
+                    reward: Vec::new(),
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn rewards_malformed() {
+        let raw = QueryDelegationTotalRewardsResponse {
+            rewards: vec![RawDelegationReward {
+                validator_address: VALIDATOR.to_owned(),
+                reward: vec![raw_utia("-1")],
+            }],
+            total: Vec::new(),
+        };
+
+        let err = FromGrpcResponse::<Vec<DelegationReward>>::try_from_response(raw).unwrap_err();
+        assert!(matches!(err, Error::InvalidAmount(_)), "{err}");
+    }
+}
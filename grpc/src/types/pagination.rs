@@ -0,0 +1,101 @@
+use celestia_proto::cosmos::base::query::v1beta1::{PageRequest, PageResponse};
+
+/// Selection of the page of results of a paginated query.
+///
+/// The page starts either at the `key` returned as [`Paginated::next_key`] of the previous
+/// page, or at the `offset` from the first result. Only one of them can be set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pagination {
+    /// Key of the first result of the page, as returned in [`Paginated::next_key`]
+    pub key: Option<Vec<u8>>,
+    /// Number of results to skip, if the `key` isn't set
+    pub offset: u64,
+    /// Maximum number of results on the page. If `0`, the default of the node is used.
+    pub limit: u64,
+    /// Whether to count the total number of results, reported in [`Paginated::total`]
+    pub count_total: bool,
+    /// Whether the results should be returned in the descending order
+    pub reverse: bool,
+}
+
+impl Pagination {
+    /// Create a [`Pagination`] of the page following the given one.
+    ///
+    /// Returns `None` if that was the last page.
+    pub fn next_page<T>(&self, page: &Paginated<T>) -> Option<Pagination> {
+        let key = page.next_key.clone()?;
+
+        Some(Pagination {
+            key: Some(key),
+            offset: 0,
+            ..self.clone()
+        })
+    }
+}
+
+impl From<Pagination> for PageRequest {
+    fn from(value: Pagination) -> Self {
+        PageRequest {
+            key: value.key.unwrap_or_default(),
+            offset: value.offset,
+            limit: value.limit,
+            count_total: value.count_total,
+            reverse: value.reverse,
+        }
+    }
+}
+
+/// A single page of results of a paginated query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Paginated<T> {
+    /// Results on this page
+    pub items: Vec<T>,
+    /// Key of the next page, `None` if this is the last one
+    pub next_key: Option<Vec<u8>>,
+    /// Total number of results, if it was requested with [`Pagination::count_total`]
+    pub total: Option<u64>,
+}
+
+impl<T> Paginated<T> {
+    /// Create a page from the results and the pagination of the response.
+    pub(crate) fn new(items: Vec<T>, page: Option<PageResponse>) -> Self {
+        let page = page.unwrap_or_default();
+
+        Paginated {
+            items,
+            next_key: (!page.next_key.is_empty()).then_some(page.next_key),
+            total: (page.total != 0).then_some(page.total),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_page() {
+        let pagination = Pagination {
+            offset: 10,
+            limit: 5,
+            ..Default::default()
+        };
+
+        let page = Paginated::new(
+            vec![1, 2, 3],
+            Some(PageResponse {
+                next_key: vec![7, 7],
+                total: 0,
+            }),
+        );
+        assert_eq!(page.total, None);
+
+        let next = pagination.next_page(&page).unwrap();
+        assert_eq!(next.key, Some(vec![7, 7]));
+        assert_eq!(next.offset, 0);
+        assert_eq!(next.limit, 5);
+
+        let last_page = Paginated::<u32>::new(Vec::new(), None);
+        assert!(next.next_page(&last_page).is_none());
+    }
+}
@@ -0,0 +1,136 @@
+use celestia_proto::cosmos::staking::v1beta1::{
+    QueryDelegatorDelegationsRequest, QueryDelegatorDelegationsResponse,
+};
+use celestia_types::state::{Address, ValAddress};
+
+use crate::types::coin::Coin;
+use crate::types::pagination::{Paginated, Pagination};
+use crate::types::{FromGrpcResponse, IntoGrpcParam};
+use crate::Error;
+
+/// Tokens delegated to a validator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delegation {
+    /// Validator the tokens are delegated to
+    pub validator: ValAddress,
+    /// Amount of the delegated tokens
+    pub amount: Coin,
+}
+
+impl FromGrpcResponse<Paginated<Delegation>> for QueryDelegatorDelegationsResponse {
+    fn try_from_response(self) -> Result<Paginated<Delegation>, Error> {
+        let delegations = self
+            .delegation_responses
+            .into_iter()
+            .map(|response| {
+                let delegation = response.delegation.ok_or(Error::FailedToParseResponse)?;
+                let balance = response.balance.ok_or(Error::FailedToParseResponse)?;
+
+                Ok(Delegation {
+                    validator: delegation.validator_address.parse()?,
+                    amount: balance.try_into()?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Paginated::new(delegations, self.pagination))
+    }
+}
+
+impl IntoGrpcParam<QueryDelegatorDelegationsRequest> for (&Address, Option<Pagination>) {
+    fn into_parameter(self) -> QueryDelegatorDelegationsRequest {
+        let (delegator, pagination) = self;
+
+        QueryDelegatorDelegationsRequest {
+            delegator_addr: delegator.to_string(),
+            pagination: pagination.map(Into::into),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use celestia_proto::cosmos::base::query::v1beta1::PageResponse;
+    use celestia_proto::cosmos::base::v1beta1::Coin as RawCoin;
+    use celestia_proto::cosmos::staking::v1beta1::{
+        Delegation as RawDelegation, DelegationResponse as RawDelegationResponse,
+    };
+
+    use super::*;
+    use crate::test_utils::{assert_response_conversion, assert_response_parse_failure};
+    use crate::types::coin::UTIA_DENOM;
+
+    const DELEGATOR: &str = "celestia1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5wgawu3";
+    const VALIDATOR: &str = "celestiavaloper1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5thlh2h";
+
+    fn raw_delegation_response(amount: &str) -> RawDelegationResponse {
+        RawDelegationResponse {
+            delegation: Some(RawDelegation {
+                delegator_address: DELEGATOR.to_owned(),
+                validator_address: VALIDATOR.to_owned(),
+                shares: "1000000000000000000000000".to_owned(),
+            }),
+            balance: Some(RawCoin {
+                denom: UTIA_DENOM.to_owned(),
+                amount: amount.to_owned(),
+            }),
+        }
+    }
+
+    #[test]
+    fn delegations_conversion() {
+        let raw = QueryDelegatorDelegationsResponse {
+            delegation_responses: vec![raw_delegation_response("1000000")],
+            pagination: Some(PageResponse {
+                next_key: vec![7],
+                total: 3,
+            }),
+        };
+
+        assert_response_conversion(
+            raw,
+            Paginated {
+                items: vec![Delegation {
+                    validator: VALIDATOR.parse().unwrap(),
+                    amount: Coin::utia(1_000_000),
+                }],
+                next_key: Some(vec![7]),
+                total: Some(3),
+            },
+        );
+    }
+
+    #[test]
+    fn delegations_malformed() {
+        let mut response = raw_delegation_response("1");
+        response.balance = None;
+        let raw = QueryDelegatorDelegationsResponse {
+            delegation_responses: vec![response],
+            pagination: None,
+        };
+        assert_response_parse_failure::<_, Paginated<Delegation>>(raw);
+
+        // delegator address instead of the validator one
+        let mut response = raw_delegation_response("1");
+        response.delegation.as_mut().unwrap().validator_address = DELEGATOR.to_owned();
+        let raw = QueryDelegatorDelegationsResponse {
+            delegation_responses: vec![response],
+            pagination: None,
+        };
+        FromGrpcResponse::<Paginated<Delegation>>::try_from_response(raw).unwrap_err();
+    }
+
+    #[test]
+    fn delegations_request() {
+        let delegator: Address = DELEGATOR.parse().unwrap();
+        let pagination = Pagination {
+            limit: 10,
+            ..Default::default()
+        };
+
+        let request: QueryDelegatorDelegationsRequest =
+            (&delegator, Some(pagination)).into_parameter();
+        assert_eq!(request.delegator_addr, DELEGATOR);
+        assert_eq!(request.pagination.unwrap().limit, 10);
+    }
+}
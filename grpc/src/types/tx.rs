@@ -1,34 +1,386 @@
 use k256::ecdsa::{signature::Signer, Signature};
 use prost::{Message, Name};
+use sha2::{Digest, Sha256};
 
+use celestia_proto::cosmos::base::abci::v1beta1::GasInfo as RawGasInfo;
 use celestia_proto::cosmos::crypto::secp256k1;
 use celestia_proto::cosmos::tx::v1beta1::{
-    BroadcastTxRequest, BroadcastTxResponse, GetTxRequest as RawGetTxRequest,
-    GetTxResponse as RawGetTxResponse, SignDoc,
+    BroadcastMode as RawBroadcastMode, BroadcastTxRequest, BroadcastTxResponse,
+    GetTxRequest as RawGetTxRequest, GetTxResponse as RawGetTxResponse, SignDoc, SimulateRequest,
+    SimulateResponse,
 };
+use celestia_proto::proto::blob::v1::IndexWrapper as RawIndexWrapper;
+use celestia_types::blob::{MsgPayForBlobs, RawBlobTx};
+use celestia_types::consts::appconsts::{AppVersion, COMPACT_SHARE_RESERVED_BYTES};
+use celestia_types::nmt::Namespace;
 use celestia_types::state::auth::BaseAccount;
 use celestia_types::state::{
-    AuthInfo, Fee, ModeInfo, RawTx, RawTxBody, SignerInfo, Sum, Tx, TxResponse,
+    AccAddress, Address, AddressKind, AddressTrait, AuthInfo, Fee, ModeInfo, RawTx, RawTxBody,
+    SignerInfo, Sum, Tx, TxResponse,
 };
+use celestia_types::{Blob, Share, ShareProof};
+use tendermint::account::Id;
+use tendermint::block::Header;
 use tendermint::public_key::Secp256k1 as VerifyingKey;
 use tendermint_proto::google::protobuf::Any;
 use tendermint_proto::Protobuf;
 
+use crate::types::coin::Coin;
 use crate::types::{FromGrpcResponse, IntoGrpcParam};
 use crate::Error;
 
-pub use celestia_proto::cosmos::tx::v1beta1::BroadcastMode;
+// From https://github.com/celestiaorg/celestia-core/blob/v1.43.0-tm-v0.34.35/pkg/consts/consts.go#L19
+pub(crate) const BLOB_TX_TYPE_ID: &str = "BLOB";
+pub(crate) const INDEX_WRAPPER_TYPE_ID: &str = "INDX";
+
+/// Mode of broadcasting the transaction.
+///
+/// The `BROADCAST_MODE_BLOCK` mode of the protobuf is deprecated and no longer supported
+/// by the nodes, use [`GrpcClient::broadcast_tx_commit`] to wait for the transaction
+/// to be committed instead.
+///
+/// [`GrpcClient::broadcast_tx_commit`]: crate::GrpcClient::broadcast_tx_commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BroadcastMode {
+    /// Wait for the transaction to pass the `CheckTx` of the node before returning
+    Sync,
+    /// Return as soon as the transaction is sent, without waiting for `CheckTx`
+    Async,
+}
+
+impl From<BroadcastMode> for RawBroadcastMode {
+    fn from(mode: BroadcastMode) -> Self {
+        match mode {
+            BroadcastMode::Sync => RawBroadcastMode::Sync,
+            BroadcastMode::Async => RawBroadcastMode::Async,
+        }
+    }
+}
+
+impl TryFrom<RawBroadcastMode> for BroadcastMode {
+    type Error = Error;
+
+    fn try_from(mode: RawBroadcastMode) -> Result<Self, Self::Error> {
+        match mode {
+            RawBroadcastMode::Sync => Ok(BroadcastMode::Sync),
+            RawBroadcastMode::Async => Ok(BroadcastMode::Async),
+            RawBroadcastMode::Block | RawBroadcastMode::Unspecified => Err(
+                Error::UnsupportedBroadcastMode(mode.as_str_name().to_owned()),
+            ),
+        }
+    }
+}
 
 /// Response to GetTx
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetTxResponse {
     /// Response Transaction
+    #[cfg_attr(feature = "serde", serde(with = "tx_hexstring"))]
     pub tx: Tx,
 
     /// TxResponse to a Query
     pub tx_response: TxResponse,
 }
 
+/// Gas of the transaction simulated by the node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasInfo {
+    /// Gas limit of the transaction
+    pub gas_wanted: u64,
+    /// Gas used by the simulated execution of the transaction
+    pub gas_used: u64,
+}
+
+impl From<RawGasInfo> for GasInfo {
+    fn from(info: RawGasInfo) -> Self {
+        GasInfo {
+            gas_wanted: info.gas_wanted,
+            gas_used: info.gas_used,
+        }
+    }
+}
+
+/// Gas used by the executed transaction, compared to the gas it asked for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GasEfficiency {
+    /// Gas limit of the transaction
+    pub gas_wanted: u64,
+    /// Gas used by the execution of the transaction
+    pub gas_used: u64,
+    /// Fraction of the wanted gas which was used, `0.0` if none was wanted
+    pub ratio: f64,
+}
+
+/// Compare the gas used by the confirmed transaction with the gas it asked for,
+/// e.g. to tune the multiplier applied to the gas estimates.
+pub fn gas_efficiency(tx: &GetTxResponse) -> GasEfficiency {
+    let gas_wanted = u64::try_from(tx.tx_response.gas_wanted).unwrap_or(0);
+    let gas_used = u64::try_from(tx.tx_response.gas_used).unwrap_or(0);
+    let ratio = if gas_wanted > 0 {
+        gas_used as f64 / gas_wanted as f64
+    } else {
+        0.0
+    };
+
+    GasEfficiency {
+        gas_wanted,
+        gas_used,
+        ratio,
+    }
+}
+
+/// Coins paid as the fee of the transaction, as declared in its auth info.
+pub fn fee(tx: &GetTxResponse) -> Vec<Coin> {
+    tx.tx
+        .auth_info
+        .fee
+        .amount
+        .iter()
+        .map(|coin| Coin::new(coin.denom.clone(), coin.amount.into()))
+        .collect()
+}
+
+/// Account whose funds paid the fee of the transaction.
+///
+/// This is the fee granter if the fee was paid from a grant, otherwise the fee payer,
+/// which defaults to the first signer. Returns `None` if the account can't be determined,
+/// e.g. when the first signer's public key isn't included or isn't secp256k1.
+pub fn payer(tx: &GetTxResponse) -> Option<Address> {
+    let fee = &tx.tx.auth_info.fee;
+
+    if !fee.granter.is_empty() {
+        return fee.granter.parse().ok();
+    }
+    if !fee.payer.is_empty() {
+        return fee.payer.parse().ok();
+    }
+
+    let public_key = tx.tx.auth_info.signer_infos.first()?.public_key.as_ref()?;
+    if public_key.type_url != secp256k1::PubKey::type_url() {
+        return None;
+    }
+    let public_key = secp256k1::PubKey::decode(&*public_key.value).ok()?;
+    let verifying_key = VerifyingKey::from_sec1_bytes(&public_key.key).ok()?;
+
+    Some(AccAddress::new(Id::from(verifying_key)).into())
+}
+
+/// Transaction with the proof of its inclusion in the block.
+///
+/// The proof covers the shares of the block's data square holding the transaction.
+/// Together with the header of the block, it lets a light client verify the inclusion
+/// without trusting the node it was fetched from, as long as it trusts the header.
+#[derive(Debug)]
+pub struct TxWithProof {
+    /// Transaction and the result of its execution
+    pub tx: GetTxResponse,
+    /// Header of the block which includes the transaction
+    pub header: Header,
+    /// Transaction as encoded in the block. For blob transactions, it doesn't
+    /// include the blobs.
+    pub tx_bytes: Vec<u8>,
+    /// Proof of inclusion of the shares holding the transaction in the block's data root
+    pub proof: ShareProof,
+}
+
+impl TxWithProof {
+    /// Verify that the transaction is included in the block of the [`header`].
+    ///
+    /// Returns `Ok(false)` if the proof is well-formed, but doesn't prove the inclusion
+    /// of the transaction under the header's data hash. The transaction must be one of
+    /// the units of the proven shares, which need to be in the transaction namespace,
+    /// or in the pay for blob namespace, wrapped with the indexes of its blobs.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidProof`] if the proof or the header is malformed.
+    ///
+    /// [`header`]: TxWithProof::header
+    pub fn verify(&self) -> Result<bool, Error> {
+        let data_root = self
+            .header
+            .data_hash
+            .ok_or_else(|| Error::InvalidProof("Header has no data hash".to_owned()))?;
+
+        match self.proof.verify(data_root) {
+            Ok(()) => {}
+            Err(
+                celestia_types::Error::RootMismatch | celestia_types::Error::RangeProofError(_),
+            ) => return Ok(false),
+            Err(e) => return Err(Error::InvalidProof(e.to_string())),
+        }
+
+        if self.header.height != self.tx.tx_response.height
+            || compute_tx_hash(&self.tx_bytes) != self.tx.tx_response.txhash
+        {
+            return Ok(false);
+        }
+
+        let shares = self.proof.shares();
+        let Some(namespace) = shares_namespace(shares)? else {
+            return Ok(false);
+        };
+        let units = compact_units(shares)?;
+
+        let included = if namespace == Namespace::TRANSACTION {
+            units.contains(&self.tx_bytes)
+        } else if namespace == Namespace::PAY_FOR_BLOB {
+            units
+                .iter()
+                .any(|unit| index_wrapper_of(unit, &self.tx_bytes).is_some())
+        } else {
+            false
+        };
+
+        Ok(included)
+    }
+}
+
+/// Compute the hash of the encoded transaction, as reported by the node.
+///
+/// It's the uppercase hex of the SHA-256 of the bytes, the same as Tendermint uses,
+/// so hash of the transaction can be known before it's broadcasted and then passed
+/// to [`GrpcClient::get_tx`].
+///
+/// [`GrpcClient::get_tx`]: crate::GrpcClient::get_tx
+pub fn compute_tx_hash(tx_bytes: &[u8]) -> String {
+    hex::encode_upper(Sha256::digest(tx_bytes))
+}
+
+/// Concatenate the data of the compact shares, skipping their metadata.
+fn compact_shares_data(shares: &[impl AsRef<[u8]>]) -> Result<Vec<u8>, Error> {
+    let mut data = Vec::new();
+
+    for share in shares {
+        let share =
+            Share::from_raw(share.as_ref()).map_err(|e| Error::InvalidProof(e.to_string()))?;
+        let payload = share
+            .payload()
+            .ok_or_else(|| Error::InvalidProof("Proof of parity shares".to_owned()))?;
+
+        data.extend_from_slice(&payload[COMPACT_SHARE_RESERVED_BYTES..]);
+    }
+
+    Ok(data)
+}
+
+/// Indexes of the first shares of the blobs paid for by the transaction, read from its
+/// index wrapper in the compact shares of the pay for blob namespace.
+///
+/// Returns `None` if the shares don't hold the index wrapper of the transaction.
+pub(crate) fn blob_share_indexes(
+    shares: &[impl AsRef<[u8]>],
+    tx_bytes: &[u8],
+) -> Result<Option<Vec<u32>>, Error> {
+    Ok(compact_units(shares)?
+        .into_iter()
+        .find_map(|unit| index_wrapper_of(&unit, tx_bytes)))
+}
+
+/// Namespace shared by all the shares, or `None` if they differ.
+fn shares_namespace(shares: &[impl AsRef<[u8]>]) -> Result<Option<Namespace>, Error> {
+    let mut namespace = None;
+
+    for share in shares {
+        let share =
+            Share::from_raw(share.as_ref()).map_err(|e| Error::InvalidProof(e.to_string()))?;
+        match namespace {
+            None => namespace = Some(share.namespace()),
+            Some(ns) if ns != share.namespace() => return Ok(None),
+            Some(_) => {}
+        }
+    }
+
+    Ok(namespace)
+}
+
+/// Share indexes from the index wrapper, if the unit is the one of the transaction.
+fn index_wrapper_of(unit: &[u8], tx_bytes: &[u8]) -> Option<Vec<u32>> {
+    match RawIndexWrapper::decode(unit) {
+        Ok(wrapper) if wrapper.type_id == INDEX_WRAPPER_TYPE_ID && wrapper.tx == tx_bytes => {
+            Some(wrapper.share_indexes)
+        }
+        _ => None,
+    }
+}
+
+/// Length-delimited units of the compact shares, starting from the first unit
+/// which starts in the first share.
+fn compact_units(shares: &[impl AsRef<[u8]>]) -> Result<Vec<Vec<u8>>, Error> {
+    let Some((first, rest)) = shares.split_first() else {
+        return Ok(Vec::new());
+    };
+    let first = Share::from_raw(first.as_ref()).map_err(|e| Error::InvalidProof(e.to_string()))?;
+    let payload = first
+        .payload()
+        .ok_or_else(|| Error::InvalidProof("Proof of parity shares".to_owned()))?;
+    // reserved bytes hold the position of the first unit starting in the share, if any
+    let unit_start = u32::from_be_bytes(
+        payload[..COMPACT_SHARE_RESERVED_BYTES]
+            .try_into()
+            .expect("slice of reserved bytes"),
+    ) as usize;
+    if unit_start == 0 || unit_start >= first.data().len() {
+        return Ok(Vec::new());
+    }
+
+    let mut data = first.data()[unit_start..].to_vec();
+    data.extend(compact_shares_data(rest)?);
+
+    let mut units = Vec::new();
+    let mut remaining = &data[..];
+    while let Ok(len) = prost::decode_length_delimiter(&mut remaining) {
+        // rest of the last share is padded with zeros
+        if len == 0 || len > remaining.len() {
+            break;
+        }
+        let (unit, rest) = remaining.split_at(len);
+        units.push(unit.to_vec());
+        remaining = rest;
+    }
+
+    Ok(units)
+}
+
+/// Outcome of broadcasting a transaction in [`BroadcastMode::Sync`].
+///
+/// Sync broadcast only returns the result of the checks done before the transaction
+/// enters the mempool. Whether it was included in a block and executed successfully
+/// is known only after polling for it, e.g. with [`GrpcClient::get_tx`].
+///
+/// [`GrpcClient::get_tx`]: crate::GrpcClient::get_tx
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BroadcastOutcome {
+    /// Transaction passed the checks and was added to the mempool.
+    AcceptedToMempool {
+        /// Hash of the transaction, which can be used to poll for its inclusion.
+        hash: String,
+    },
+    /// Transaction was rejected by the check, it won't be included in a block.
+    CheckTxRejected {
+        /// Error code of the check.
+        code: u32,
+        /// Log of the check, describing the failure.
+        log: String,
+    },
+}
+
+impl From<TxResponse> for BroadcastOutcome {
+    fn from(response: TxResponse) -> Self {
+        if response.code == 0 {
+            BroadcastOutcome::AcceptedToMempool {
+                hash: response.txhash,
+            }
+        } else {
+            BroadcastOutcome::CheckTxRejected {
+                code: response.code,
+                log: response.raw_log,
+            }
+        }
+    }
+}
+
 impl FromGrpcResponse<TxResponse> for BroadcastTxResponse {
     fn try_from_response(self) -> Result<TxResponse, Error> {
         Ok(self
@@ -45,21 +397,56 @@ impl FromGrpcResponse<GetTxResponse> for RawGetTxResponse {
             .ok_or(Error::FailedToParseResponse)?
             .try_into()?;
 
-        let tx = self.tx.ok_or(Error::FailedToParseResponse)?;
+        let tx = tx_from_raw(self.tx.ok_or(Error::FailedToParseResponse)?)?;
 
-        let cosmos_tx = Tx {
-            body: tx.body.ok_or(Error::FailedToParseResponse)?.try_into()?,
-            auth_info: tx
-                .auth_info
-                .ok_or(Error::FailedToParseResponse)?
-                .try_into()?,
-            signatures: tx.signatures,
+        Ok(GetTxResponse { tx, tx_response })
+    }
+}
+
+impl FromGrpcResponse<GasInfo> for SimulateResponse {
+    fn try_from_response(self) -> Result<GasInfo, Error> {
+        Ok(self.gas_info.ok_or(Error::FailedToParseResponse)?.into())
+    }
+}
+
+fn tx_from_raw(tx: RawTx) -> Result<Tx, Error> {
+    Ok(Tx {
+        body: tx.body.ok_or(Error::FailedToParseResponse)?.try_into()?,
+        auth_info: tx
+            .auth_info
+            .ok_or(Error::FailedToParseResponse)?
+            .try_into()?,
+        signatures: tx.signatures,
+    })
+}
+
+/// Serialize the [`Tx`] as a hexstring of its protobuf encoding.
+#[cfg(feature = "serde")]
+mod tx_hexstring {
+    use celestia_proto::serializers::bytes::hexstring;
+    use serde::{de, Deserializer, Serializer};
+
+    use super::*;
+
+    pub(super) fn serialize<S>(tx: &Tx, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let raw = RawTx {
+            body: Some(tx.body.clone().into()),
+            auth_info: Some(tx.auth_info.clone().into()),
+            signatures: tx.signatures.clone(),
         };
+        hexstring::serialize(&raw.encode_to_vec(), serializer)
+    }
 
-        Ok(GetTxResponse {
-            tx: cosmos_tx,
-            tx_response,
-        })
+    pub(super) fn deserialize<'de, D>(deserializer: D) -> Result<Tx, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bytes = hexstring::deserialize(deserializer)?;
+        let raw = RawTx::decode(&*bytes).map_err(de::Error::custom)?;
+        tx_from_raw(raw).map_err(de::Error::custom)
     }
 }
 
@@ -69,7 +456,16 @@ impl IntoGrpcParam<BroadcastTxRequest> for (Vec<u8>, BroadcastMode) {
 
         BroadcastTxRequest {
             tx_bytes,
-            mode: mode.into(),
+            mode: RawBroadcastMode::from(mode).into(),
+        }
+    }
+}
+
+impl IntoGrpcParam<SimulateRequest> for Vec<u8> {
+    fn into_parameter(self) -> SimulateRequest {
+        SimulateRequest {
+            tx_bytes: self,
+            ..Default::default()
         }
     }
 }
@@ -80,6 +476,208 @@ impl IntoGrpcParam<RawGetTxRequest> for String {
     }
 }
 
+/// Transaction with everything needed to sign it, so that it can be signed again
+/// with a different gas limit, e.g. by [`GrpcClient::broadcast_blob_tx_with_gas_bump`].
+///
+/// [`GrpcClient::broadcast_blob_tx_with_gas_bump`]: crate::GrpcClient::broadcast_blob_tx_with_gas_bump
+#[derive(Debug, Clone)]
+pub struct UnsignedTx<S> {
+    /// Body of the transaction
+    pub tx_body: RawTxBody,
+    /// Id of the chain the transaction is meant for
+    pub chain_id: String,
+    /// Account signing the transaction
+    pub base_account: BaseAccount,
+    /// Public key of the account
+    pub verifying_key: VerifyingKey,
+    /// Signer holding the private key of the account
+    pub signer: S,
+    /// Gas limit of the transaction
+    pub gas_limit: u64,
+    /// Price of a unit of gas in `utia`, the fee is computed from it and the gas limit
+    pub gas_price: f64,
+}
+
+impl<S> UnsignedTx<S>
+where
+    S: Signer<Signature> + Clone,
+{
+    /// Sign the transaction with the given gas limit, paying the fee for it.
+    pub fn sign_with_gas_limit(&self, gas_limit: u64) -> RawTx {
+        let fee = (gas_limit as f64 * self.gas_price).ceil() as u64;
+
+        sign_tx(
+            self.tx_body.clone(),
+            self.chain_id.clone(),
+            &self.base_account,
+            self.verifying_key,
+            self.signer.clone(),
+            gas_limit,
+            fee,
+        )
+    }
+
+    /// Sign the transaction with its gas limit.
+    pub fn sign(&self) -> RawTx {
+        self.sign_with_gas_limit(self.gas_limit)
+    }
+}
+
+/// Parameters of a blob transaction built without connecting to the network, which
+/// would otherwise be queried from the node.
+#[derive(Debug, Clone)]
+pub struct OfflineTxParams<S> {
+    /// Id of the chain the transaction is meant for
+    pub chain_id: String,
+    /// Address of the account paying for the blobs
+    pub address: Address,
+    /// Number of the account in the state
+    pub account_number: u64,
+    /// Sequence of the account, i.e. the number of its committed transactions
+    pub sequence: u64,
+    /// Public key of the account
+    pub verifying_key: VerifyingKey,
+    /// Signer holding the private key of the account
+    pub signer: S,
+    /// Gas limit of the transaction
+    pub gas_limit: u64,
+    /// Fee paid for the transaction in `utia`
+    pub fee: u64,
+    /// Version of the application the blob commitments are computed for
+    pub app_version: AppVersion,
+    /// Account granting the fee allowance the transaction is paid from, if any
+    pub fee_granter: Option<Address>,
+}
+
+/// Build and sign the blob transaction paying for `blobs` without any network calls,
+/// returning it encoded for broadcasting, e.g. from another machine with
+/// [`GrpcClient::broadcast_tx`].
+///
+/// # Errors
+///
+/// Returns an error if there are no blobs, any of them has an invalid commitment
+/// or the parameters are missing the chain id or gas limit. The fee granter, if any,
+/// must be an account address other than the one paying for the blobs.
+///
+/// [`GrpcClient::broadcast_tx`]: crate::GrpcClient::broadcast_tx
+pub fn build_blob_tx_offline<S>(
+    params: OfflineTxParams<S>,
+    blobs: Vec<Blob>,
+) -> Result<Vec<u8>, Error>
+where
+    S: Signer<Signature>,
+{
+    if blobs.is_empty() {
+        return Err(Error::TxEmptyBlobList);
+    }
+
+    if params.chain_id.is_empty() {
+        return Err(Error::InvalidTxParams("chain id is empty".to_owned()));
+    }
+
+    if params.gas_limit == 0 {
+        return Err(Error::InvalidTxParams("gas limit is zero".to_owned()));
+    }
+
+    if let Some(granter) = &params.fee_granter {
+        validate_fee_granter(&params.address, granter)?;
+    }
+
+    for blob in &blobs {
+        blob.validate(params.app_version)?;
+    }
+
+    let msg_pay_for_blobs = MsgPayForBlobs::new(&blobs, params.address.clone())?;
+    let base_account = BaseAccount {
+        address: params.address,
+        pub_key: None,
+        account_number: params.account_number,
+        sequence: params.sequence,
+    };
+
+    let tx = sign_tx_with_fee(
+        msg_pay_for_blobs.into(),
+        params.chain_id,
+        &base_account,
+        params.verifying_key,
+        params.signer,
+        granted_fee(params.fee, params.gas_limit, params.fee_granter.as_ref()),
+    );
+
+    Ok(encode_blob_tx(tx, blobs))
+}
+
+/// Encode the transaction together with the blobs it pays for.
+pub(crate) fn encode_blob_tx(tx: RawTx, blobs: Vec<Blob>) -> Vec<u8> {
+    RawBlobTx {
+        tx: tx.encode_to_vec(),
+        blobs: blobs.into_iter().map(Into::into).collect(),
+        type_id: BLOB_TX_TYPE_ID.to_string(),
+    }
+    .encode_to_vec()
+}
+
+/// Decode the blob transaction, e.g. built with [`build_blob_tx_offline`], into the
+/// transaction and the blobs it pays for.
+///
+/// Commitments of the blobs are computed for the given app version.
+///
+/// # Errors
+///
+/// Returns [`Error::InvalidBlobTx`] if the bytes aren't a blob transaction or any of
+/// its parts is malformed.
+pub fn decode_blob_tx(bytes: &[u8], app_version: AppVersion) -> Result<(RawTx, Vec<Blob>), Error> {
+    let blob_tx = RawBlobTx::decode(bytes).map_err(|e| Error::InvalidBlobTx(e.to_string()))?;
+    if blob_tx.type_id != BLOB_TX_TYPE_ID {
+        return Err(Error::InvalidBlobTx(format!(
+            "unexpected type id {:?}",
+            blob_tx.type_id
+        )));
+    }
+
+    let tx = RawTx::decode(&*blob_tx.tx).map_err(|e| Error::InvalidBlobTx(e.to_string()))?;
+    let blobs = blob_tx
+        .blobs
+        .into_iter()
+        .map(|blob| Blob::from_raw(blob, app_version))
+        .collect::<Result<_, _>>()
+        .map_err(|e| Error::InvalidBlobTx(e.to_string()))?;
+
+    Ok((tx, blobs))
+}
+
+/// Fee of `fee` `utia` for `gas_limit`, paid from the allowance of the granter if any.
+pub(crate) fn granted_fee(fee: u64, gas_limit: u64, granter: Option<&Address>) -> Fee {
+    let mut fee = Fee::new(fee, gas_limit);
+    if let Some(granter) = granter {
+        fee.granter = granter.to_string();
+    }
+    fee
+}
+
+/// Check that the fee of the transaction of `payer` can be granted by `granter`.
+pub(crate) fn validate_fee_granter(payer: &Address, granter: &Address) -> Result<(), Error> {
+    if payer.kind() != AddressKind::Account {
+        return Err(Error::InvalidTxParams(format!(
+            "fee payer {payer} is not an account address"
+        )));
+    }
+
+    if granter.kind() != AddressKind::Account {
+        return Err(Error::InvalidTxParams(format!(
+            "fee granter {granter} is not an account address"
+        )));
+    }
+
+    if granter == payer {
+        return Err(Error::InvalidTxParams(format!(
+            "fee granter {granter} is the payer itself"
+        )));
+    }
+
+    Ok(())
+}
+
 /// Sign `tx_body` and the transaction metadata as the `base_account` using `signer`
 pub fn sign_tx(
     tx_body: RawTxBody,
@@ -89,6 +687,25 @@ pub fn sign_tx(
     signer: impl Signer<Signature>,
     gas_limit: u64,
     fee: u64,
+) -> RawTx {
+    sign_tx_with_fee(
+        tx_body,
+        chain_id,
+        base_account,
+        verifying_key,
+        signer,
+        Fee::new(fee, gas_limit),
+    )
+}
+
+/// Sign the transaction paying the given fee, e.g. one with a granter.
+pub(crate) fn sign_tx_with_fee(
+    tx_body: RawTxBody,
+    chain_id: String,
+    base_account: &BaseAccount,
+    verifying_key: VerifyingKey,
+    signer: impl Signer<Signature>,
+    fee: Fee,
 ) -> RawTx {
     // From https://github.com/celestiaorg/cosmos-sdk/blob/v1.25.0-sdk-v0.46.16/proto/cosmos/tx/signing/v1beta1/signing.proto#L24
     const SIGNING_MODE_INFO: ModeInfo = ModeInfo {
@@ -109,7 +726,7 @@ pub fn sign_tx(
             mode_info: SIGNING_MODE_INFO,
             sequence: base_account.sequence,
         }],
-        fee: Fee::new(fee, gas_limit),
+        fee,
     };
 
     let bytes_to_sign = SignDoc {
@@ -128,3 +745,174 @@ pub fn sign_tx(
         signatures: vec![signature.to_bytes().to_vec()],
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use celestia_types::state::{RawTxResponse, TxBody};
+
+    use super::*;
+
+    pub(super) fn get_tx_response() -> GetTxResponse {
+        let tx_response = RawTxResponse {
+            height: 42,
+            txhash: "ABCD".to_string(),
+            data: "0A1E".to_string(),
+            gas_wanted: 100_000,
+            gas_used: 81_234,
+            timestamp: "2024-11-19T12:00:00Z".to_string(),
+            ..Default::default()
+        }
+        .try_into()
+        .unwrap();
+        let tx = Tx {
+            body: TxBody {
+                messages: vec![Any {
+                    type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+                    value: vec![1, 2, 3],
+                }],
+                memo: "lumina".to_string(),
+                timeout_height: 0u32.into(),
+                extension_options: Vec::new(),
+                non_critical_extension_options: Vec::new(),
+            },
+            auth_info: AuthInfo {
+                signer_infos: Vec::new(),
+                fee: Fee::new(2000, 100_000),
+            },
+            signatures: vec![vec![0xab; 64]],
+        };
+
+        GetTxResponse { tx, tx_response }
+    }
+
+    #[test]
+    fn broadcast_mode_mapping() {
+        for (mode, raw) in [
+            (BroadcastMode::Sync, RawBroadcastMode::Sync),
+            (BroadcastMode::Async, RawBroadcastMode::Async),
+        ] {
+            assert_eq!(RawBroadcastMode::from(mode), raw);
+            assert_eq!(BroadcastMode::try_from(raw).unwrap(), mode);
+        }
+
+        // deprecated mode is rejected rather than passed to the node
+        for raw in [RawBroadcastMode::Block, RawBroadcastMode::Unspecified] {
+            assert!(matches!(
+                BroadcastMode::try_from(raw).unwrap_err(),
+                Error::UnsupportedBroadcastMode(name) if name == raw.as_str_name()
+            ));
+        }
+
+        let request = (vec![1, 2], BroadcastMode::Async).into_parameter();
+        assert_eq!(request.mode, RawBroadcastMode::Async as i32);
+    }
+
+    #[test]
+    fn gas_efficiency_of_response() {
+        let response = get_tx_response();
+
+        let efficiency = gas_efficiency(&response);
+        assert_eq!(efficiency.gas_wanted, 100_000);
+        assert_eq!(efficiency.gas_used, 81_234);
+        assert_eq!(efficiency.ratio, 0.81234);
+
+        let mut response = response;
+        response.tx_response.gas_wanted = 0;
+        assert_eq!(gas_efficiency(&response).ratio, 0.0);
+    }
+
+    #[test]
+    fn fee_and_payer_of_response() {
+        let mut response = get_tx_response();
+        assert_eq!(fee(&response), vec![Coin::utia(2000)]);
+        // no signers to pay it
+        assert_eq!(payer(&response), None);
+
+        let signing_key = k256::ecdsa::SigningKey::from_slice(&[7; 32]).unwrap();
+        let public_key = secp256k1::PubKey {
+            key: signing_key
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec(),
+        };
+        response.tx.auth_info.signer_infos.push(SignerInfo {
+            public_key: Some(Any {
+                type_url: secp256k1::PubKey::type_url(),
+                value: public_key.encode_to_vec(),
+            }),
+            mode_info: ModeInfo {
+                sum: Sum::Single { mode: 1 },
+            },
+            sequence: 0,
+        });
+        let signer: Address = "celestia150rtrmj2f8vl9tem8qpfw36ylw5jg9j2zfmer5"
+            .parse()
+            .unwrap();
+        assert_eq!(payer(&response), Some(signer));
+
+        let fee_payer = "celestia1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5wgawu3";
+        response.tx.auth_info.fee.payer = fee_payer.to_owned();
+        assert_eq!(payer(&response), Some(fee_payer.parse().unwrap()));
+
+        let granter = "celestia1zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3shxjgz";
+        response.tx.auth_info.fee.granter = granter.to_owned();
+        assert_eq!(payer(&response), Some(granter.parse().unwrap()));
+    }
+
+    #[test]
+    fn tx_hash_of_fixture() {
+        // `TxRaw` with the body having only the memo set to "lumina"
+        let tx_bytes = hex::decode("0a0812066c756d696e61").unwrap();
+
+        assert_eq!(
+            compute_tx_hash(&tx_bytes),
+            "B8F6343C5EB25D8E4AF9908623C0F18840C193B10CF6AF9F3D78D95DDB509BE5"
+        );
+        // hash of empty input is a well known value
+        assert_eq!(
+            compute_tx_hash(&[]),
+            "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855"
+        );
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::tests::get_tx_response;
+    use super::*;
+
+    #[test]
+    fn tx_response_serde_roundtrip() {
+        let response = get_tx_response().tx_response;
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["height"], "42");
+        assert_eq!(json["txhash"], "ABCD");
+        assert_eq!(json["gas_used"], 81_234);
+
+        let deserialized: TxResponse = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(deserialized.height, response.height);
+        assert_eq!(deserialized.data, response.data);
+        assert_eq!(serde_json::to_value(&deserialized).unwrap(), json);
+    }
+
+    #[test]
+    fn get_tx_response_serde_roundtrip() {
+        let response = get_tx_response();
+
+        let json = serde_json::to_value(&response).unwrap();
+        let tx_hex = json["tx"].as_str().unwrap();
+        assert!(tx_hex.bytes().all(|b| b.is_ascii_hexdigit()));
+
+        let deserialized: GetTxResponse = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(deserialized.tx.body.memo, "lumina");
+        assert_eq!(deserialized.tx.body.messages, response.tx.body.messages);
+        assert_eq!(deserialized.tx.auth_info.fee, response.tx.auth_info.fee);
+        assert_eq!(deserialized.tx.signatures, response.tx.signatures);
+        assert_eq!(serde_json::to_value(&deserialized).unwrap(), json);
+
+        let invalid = serde_json::json!({ "tx": "0xzz", "tx_response": json["tx_response"] });
+        serde_json::from_value::<GetTxResponse>(invalid).unwrap_err();
+    }
+}
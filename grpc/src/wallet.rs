@@ -0,0 +1,957 @@
+//! Wallet submitting transactions of a single account through the [`GrpcClient`].
+
+use std::time::Duration;
+
+use celestia_proto::cosmos::base::v1beta1::Coin as RawCoin;
+use celestia_proto::cosmos::tx::v1beta1::Tx as RawTx;
+use celestia_types::blob::{Commitment, MsgPayForBlobs};
+use celestia_types::consts::appconsts::AppVersion;
+use celestia_types::nmt::Namespace;
+use celestia_types::state::auth::BaseAccount;
+use celestia_types::state::{AccAddress, Address, RawTxBody, TxResponse};
+use celestia_types::{Blob, ShareProof};
+use k256::ecdsa::{signature::Signer, Signature};
+use prost::Message;
+use tendermint::account::Id;
+use tendermint::public_key::Secp256k1 as VerifyingKey;
+use tendermint_proto::google::protobuf::Any;
+use tonic::service::Interceptor;
+
+use crate::types::blob::verify_blob_inclusion;
+use crate::types::coin::{Coin, UTIA_DENOM};
+use crate::types::tx::{
+    blob_share_indexes, encode_blob_tx, granted_fee, sign_tx_with_fee, validate_fee_granter,
+    GasInfo,
+};
+use crate::{Error, GrpcClient};
+
+/// Time the submitted transactions are waited for to be committed, if it's not configured.
+const DEFAULT_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Gas limit of the transactions sending the coins, enough for a single `MsgSend`.
+const SEND_GAS_LIMIT: u64 = 100_000;
+
+// From https://github.com/celestiaorg/celestia-app/blob/v2.3.1/x/blob/types/payforblob.go
+/// Gas charged for every `MsgPayForBlobs`, regardless of its blobs.
+const PFB_GAS_FIXED_COST: u64 = 75_000;
+/// Size of the information about each blob in the transaction, paid for per byte.
+const BYTES_PER_BLOB_INFO: u64 = 70;
+
+// From https://github.com/celestiaorg/cosmos-sdk/blob/v1.25.0-sdk-v0.46.16/types/errors/errors.go
+const UNAUTHORIZED_CODESPACE: &str = "sdk";
+const UNAUTHORIZED_CODE: u32 = 4;
+
+const MSG_SEND_TYPE_URL: &str = "/cosmos.bank.v1beta1.MsgSend";
+
+/// `MsgSend` of the bank module, transferring the coins between the accounts.
+#[derive(Clone, PartialEq, Message)]
+struct RawMsgSend {
+    #[prost(string, tag = "1")]
+    from_address: String,
+    #[prost(string, tag = "2")]
+    to_address: String,
+    #[prost(message, repeated, tag = "3")]
+    amount: Vec<RawCoin>,
+}
+
+/// Options of submitting the blob with [`Wallet::submit_blob_with_options`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SubmitOptions {
+    /// Only simulate the signed transaction instead of broadcasting it, e.g. to check
+    /// the submission without spending funds.
+    pub simulate_only: bool,
+}
+
+/// Outcome of submitting the blob with [`Wallet::submit_blob_with_options`].
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum SubmitOutcome {
+    /// Transaction was broadcasted and waited for to be committed.
+    Committed(TxResponse),
+    /// Transaction was only simulated, as asked by [`SubmitOptions::simulate_only`].
+    Simulated {
+        /// Gas of the simulated transaction.
+        gas_info: GasInfo,
+        /// Signed blob transaction, which would have been broadcasted.
+        tx_bytes: Vec<u8>,
+    },
+}
+
+/// Blob submitted with [`Wallet::submit_blob_with_proof`], with the proof of its inclusion.
+#[derive(Debug)]
+pub struct SubmitResult {
+    /// Height of the block including the blob.
+    pub height: u64,
+    /// Commitment of the blob.
+    pub commitment: Commitment,
+    /// Proof of inclusion of the shares of the blob in the data root of the block.
+    pub proof: ShareProof,
+}
+
+/// Account signing and submitting its transactions through the [`GrpcClient`].
+///
+/// Each transaction is signed with the current account number and sequence queried
+/// from the node, pays the fee for its estimated gas at the minimum gas price of the
+/// node, and is waited for until it's committed.
+///
+/// The chain id and the app version are queried with the first transaction and kept
+/// until a transaction fails the signature verification, in which case they're queried
+/// again. If the chain id changed, [`Error::ChainIdMismatch`] is returned.
+pub struct Wallet<I, S>
+where
+    I: Interceptor,
+{
+    client: GrpcClient<I>,
+    verifying_key: VerifyingKey,
+    signer: S,
+    address: Address,
+    confirmation_timeout: Duration,
+    fee_granter: Option<Address>,
+    chain: Option<(String, AppVersion)>,
+}
+
+impl<I, S> Wallet<I, S>
+where
+    I: Interceptor + Clone,
+    S: Signer<Signature> + Clone,
+{
+    /// Create a wallet of the account with the given public key, signing with `signer`.
+    pub fn new(client: GrpcClient<I>, verifying_key: VerifyingKey, signer: S) -> Self {
+        let address = AccAddress::new(Id::from(verifying_key)).into();
+
+        Wallet {
+            client,
+            verifying_key,
+            signer,
+            address,
+            confirmation_timeout: DEFAULT_CONFIRMATION_TIMEOUT,
+            fee_granter: None,
+            chain: None,
+        }
+    }
+
+    /// Wait at most `timeout` for the submitted transactions to be committed,
+    /// instead of the default of 60 seconds.
+    pub fn with_confirmation_timeout(mut self, timeout: Duration) -> Self {
+        self.confirmation_timeout = timeout;
+        self
+    }
+
+    /// Pay the fees of the submitted transactions from the allowance granted to the
+    /// account by `granter`, instead of from the account itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidTxParams`] if the granter isn't an account address or
+    /// is the account of the wallet.
+    pub fn with_fee_granter(mut self, granter: Address) -> Result<Self, Error> {
+        validate_fee_granter(&self.address, &granter)?;
+        self.fee_granter = Some(granter);
+        Ok(self)
+    }
+
+    /// Address of the account.
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// Get the balance of the account in `utia`.
+    pub async fn balance(&mut self) -> Result<Coin, Error> {
+        self.client.get_balance(&self.address, UTIA_DENOM).await
+    }
+
+    /// Send the coins to the given address and wait for the transaction to be committed.
+    ///
+    /// If the transaction is rejected, the response with its error code is returned.
+    pub async fn send(&mut self, to: &Address, coins: Vec<Coin>) -> Result<TxResponse, Error> {
+        let msg = RawMsgSend {
+            from_address: self.address.to_string(),
+            to_address: to.to_string(),
+            amount: coins.into_iter().map(Into::into).collect(),
+        };
+        let tx_body = RawTxBody {
+            messages: vec![Any {
+                type_url: MSG_SEND_TYPE_URL.to_owned(),
+                value: msg.encode_to_vec(),
+            }],
+            ..Default::default()
+        };
+
+        let (chain_id, _) = self.chain().await?;
+        let tx = self.sign(tx_body, chain_id.clone(), SEND_GAS_LIMIT).await?;
+
+        self.broadcast(tx.encode_to_vec(), chain_id).await
+    }
+
+    /// Submit the data as a blob of the namespace and wait for the transaction paying
+    /// for it to be committed.
+    ///
+    /// If the transaction is rejected, the response with its error code is returned.
+    pub async fn submit_blob(
+        &mut self,
+        namespace: Namespace,
+        data: Vec<u8>,
+    ) -> Result<TxResponse, Error> {
+        let (tx, blob, chain_id) = self.sign_blob_tx(namespace, data).await?;
+
+        self.broadcast(encode_blob_tx(tx, vec![blob]), chain_id)
+            .await
+    }
+
+    /// Submit the data of each namespace as a blob and wait for the single transaction
+    /// paying for all of them to be committed.
+    ///
+    /// If the transaction is rejected, the response with its error code is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TxEmptyBlobList`] if there are no blobs to submit.
+    pub async fn submit_blobs(
+        &mut self,
+        blobs: Vec<(Namespace, Vec<u8>)>,
+    ) -> Result<TxResponse, Error> {
+        let (tx, blobs, chain_id) = self.sign_blobs_tx(blobs).await?;
+
+        self.broadcast(encode_blob_tx(tx, blobs), chain_id).await
+    }
+
+    /// Submit the data as a blob of the namespace like [`Wallet::submit_blob`], with
+    /// the given options.
+    ///
+    /// With [`SubmitOptions::simulate_only`], the signed transaction is simulated by
+    /// the node and returned together with its gas, without being broadcasted.
+    pub async fn submit_blob_with_options(
+        &mut self,
+        namespace: Namespace,
+        data: Vec<u8>,
+        options: SubmitOptions,
+    ) -> Result<SubmitOutcome, Error> {
+        let (tx, blob, chain_id) = self.sign_blob_tx(namespace, data).await?;
+
+        if options.simulate_only {
+            // transaction paying for the blob is simulated without the blob itself
+            let gas_info = self.client.simulate(tx.encode_to_vec()).await?;
+
+            return Ok(SubmitOutcome::Simulated {
+                gas_info,
+                tx_bytes: encode_blob_tx(tx, vec![blob]),
+            });
+        }
+
+        self.broadcast(encode_blob_tx(tx, vec![blob]), chain_id)
+            .await
+            .map(SubmitOutcome::Committed)
+    }
+
+    /// Submit the data as a blob of the namespace like [`Wallet::submit_blob`], then
+    /// fetch the proof of inclusion of the blob in the block once it's committed.
+    ///
+    /// Position of the blob in the data square of the block is read from the index
+    /// wrapper of the transaction paying for it, whose inclusion is verified first.
+    /// The proof of the blob is verified against the data root of the block before
+    /// it's returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::TxFailed`] if the transaction is rejected, and
+    /// [`Error::BlobNotFound`] if it's committed, but the shares of the blob aren't
+    /// found in the block.
+    pub async fn submit_blob_with_proof(
+        &mut self,
+        namespace: Namespace,
+        data: Vec<u8>,
+    ) -> Result<SubmitResult, Error> {
+        let (tx, blob, chain_id) = self.sign_blob_tx(namespace, data).await?;
+        let response = self
+            .broadcast(encode_blob_tx(tx, vec![blob.clone()]), chain_id)
+            .await?;
+
+        if response.code != 0 {
+            return Err(Error::TxFailed(response.code, response.raw_log));
+        }
+
+        let height = response.height.value();
+        let not_found = || Error::BlobNotFound {
+            height,
+            commitment: blob.commitment,
+        };
+
+        let tx = self.client.get_tx_with_proof(response.txhash).await?;
+        if !tx.verify()? {
+            return Err(Error::InvalidProof(
+                "Proof of the transaction doesn't verify".to_owned(),
+            ));
+        }
+
+        let start = blob_share_indexes(tx.proof.shares(), &tx.tx_bytes)?
+            .and_then(|indexes| indexes.first().copied())
+            .ok_or_else(not_found)?;
+        let start = u64::from(start);
+        let end = start + blob.to_shares()?.len() as u64;
+        let proof = self
+            .client
+            .get_share_proof(height as i64, start..end)
+            .await?;
+
+        // data hash is known to be present, as the proof of the tx was verified with it
+        let data_root = tx.header.data_hash.expect("verified header has data hash");
+        if !verify_blob_inclusion(&blob, &proof, &data_root)? {
+            return Err(not_found());
+        }
+
+        Ok(SubmitResult {
+            height,
+            commitment: blob.commitment,
+            proof,
+        })
+    }
+
+    /// Sign the transaction paying for the data as a blob of the namespace, returning it
+    /// together with the blob and the chain id it was signed for.
+    async fn sign_blob_tx(
+        &mut self,
+        namespace: Namespace,
+        data: Vec<u8>,
+    ) -> Result<(RawTx, Blob, String), Error> {
+        let (tx, mut blobs, chain_id) = self.sign_blobs_tx(vec![(namespace, data)]).await?;
+        let blob = blobs.pop().expect("signed with a single blob");
+
+        Ok((tx, blob, chain_id))
+    }
+
+    /// Sign the transaction paying for the data of each namespace as a blob, returning it
+    /// together with the blobs and the chain id it was signed for.
+    async fn sign_blobs_tx(
+        &mut self,
+        blobs: Vec<(Namespace, Vec<u8>)>,
+    ) -> Result<(RawTx, Vec<Blob>, String), Error> {
+        if blobs.is_empty() {
+            return Err(Error::TxEmptyBlobList);
+        }
+
+        let (chain_id, app_version) = self.chain().await?;
+        let blobs = blobs
+            .into_iter()
+            .map(|(namespace, data)| Blob::new(namespace, data, app_version))
+            .collect::<Result<Vec<_>, _>>()?;
+        let gas_limit = self.estimate_blob_gas(&blobs).await?;
+        let tx_body = MsgPayForBlobs::new(&blobs, self.address.clone())?.into();
+
+        let tx = self.sign(tx_body, chain_id.clone(), gas_limit).await?;
+
+        Ok((tx, blobs, chain_id))
+    }
+
+    /// Broadcast the transaction signed for the chain and wait for it to be committed.
+    async fn broadcast(
+        &mut self,
+        tx_bytes: Vec<u8>,
+        chain_id: String,
+    ) -> Result<TxResponse, Error> {
+        let response = self
+            .client
+            .broadcast_tx_commit(tx_bytes, self.confirmation_timeout)
+            .await?;
+
+        // chain id is a part of the signed data, so the signature of the transaction
+        // signed for another chain fails the verification
+        if is_unauthorized(&response) {
+            self.chain = None;
+            let (expected, _) = self.chain().await?;
+
+            if expected != chain_id {
+                return Err(Error::ChainIdMismatch {
+                    expected,
+                    actual: chain_id,
+                });
+            }
+        }
+
+        Ok(response)
+    }
+
+    /// Gas needed by the transaction paying for the blobs, as charged by the blob module.
+    async fn estimate_blob_gas(&mut self, blobs: &[Blob]) -> Result<u64, Error> {
+        let blob_params = self.client.get_blob_module_params().await?;
+        let auth_params = self.client.get_auth_params().await?;
+        let mut shares = 0;
+        for blob in blobs {
+            shares += blob.to_shares()?.len() as u64;
+        }
+
+        Ok(PFB_GAS_FIXED_COST
+            + shares * blob_params.gas_per_share
+            + blobs.len() as u64 * BYTES_PER_BLOB_INFO * auth_params.tx_size_cost_per_byte)
+    }
+
+    /// Sign the transaction for the chain with the current sequence of the account,
+    /// paying for the gas at the minimum gas price of the node, from the allowance of
+    /// the fee granter if any.
+    async fn sign(
+        &mut self,
+        tx_body: RawTxBody,
+        chain_id: String,
+        gas_limit: u64,
+    ) -> Result<RawTx, Error> {
+        let (account_number, sequence) = self.client.get_account_sequence(&self.address).await?;
+        let gas_price = self.client.get_min_gas_price().await?;
+        let fee = (gas_limit as f64 * gas_price).ceil() as u64;
+
+        let base_account = BaseAccount {
+            address: self.address.clone(),
+            pub_key: None,
+            account_number,
+            sequence,
+        };
+
+        Ok(sign_tx_with_fee(
+            tx_body,
+            chain_id,
+            &base_account,
+            self.verifying_key,
+            self.signer.clone(),
+            granted_fee(fee, gas_limit, self.fee_granter.as_ref()),
+        ))
+    }
+
+    /// Chain id and app version of the network, queried from the latest block once.
+    async fn chain(&mut self) -> Result<(String, AppVersion), Error> {
+        if let Some(chain) = &self.chain {
+            return Ok(chain.clone());
+        }
+
+        let header = self.client.get_latest_block().await?.header;
+        let app_version = AppVersion::from_u64(header.version.app).ok_or(
+            celestia_types::Error::UnsupportedAppVersion(header.version.app),
+        )?;
+        let chain = (header.chain_id.to_string(), app_version);
+
+        self.chain = Some(chain.clone());
+        Ok(chain)
+    }
+}
+
+/// Returns `true` if the transaction was rejected, because its signature isn't valid.
+fn is_unauthorized(response: &TxResponse) -> bool {
+    response.codespace == UNAUTHORIZED_CODESPACE && response.code == UNAUTHORIZED_CODE
+}
+
+#[cfg(test)]
+#[allow(clippy::result_large_err)] // mock handlers return tonic::Status
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use celestia_proto::celestia::blob::v1::{
+        Params as RawBlobParams, QueryParamsRequest as QueryBlobParamsRequest,
+        QueryParamsResponse as QueryBlobParamsResponse,
+    };
+    use celestia_proto::celestia::core::v1::proof::ShareProof as RawShareProof;
+    use celestia_proto::cosmos::auth::v1beta1::{
+        Params as RawAuthParams, QueryParamsRequest as QueryAuthParamsRequest,
+        QueryParamsResponse as QueryAuthParamsResponse,
+    };
+    use celestia_proto::cosmos::base::abci::v1beta1::GasInfo as RawGasInfo;
+    use celestia_proto::cosmos::base::tendermint::v1beta1::{
+        AbciQueryRequest, AbciQueryResponse, GetBlockByHeightRequest, GetBlockByHeightResponse,
+        GetLatestBlockRequest,
+    };
+    use celestia_proto::cosmos::tx::v1beta1::{
+        BroadcastTxRequest, BroadcastTxResponse, GetTxRequest, GetTxResponse as RawGetTxResponse,
+        SimulateRequest, SimulateResponse,
+    };
+    use celestia_proto::proto::blob::v1::IndexWrapper as RawIndexWrapper;
+    use celestia_types::blob::RawBlobTx;
+    use celestia_types::block::{Block, Data};
+    use celestia_types::consts::appconsts::SHARE_SIZE;
+    use celestia_types::state::{AuthInfo, RawTxResponse};
+    use celestia_types::test_utils::ExtendedHeaderGenerator;
+    use celestia_types::{DataAvailabilityHeader, ExtendedDataSquare};
+    use k256::ecdsa::SigningKey;
+    use tonic::Status;
+
+    use super::*;
+    use crate::test_utils::{
+        account, config, latest_block, MockGrpcServer, MockGrpcServerHandle, Recorder, ACCOUNT,
+        AUTH_PARAMS, BLOB_PARAMS, BROADCAST_TX, CONFIG, GET_LATEST_BLOCK, GET_TX,
+        MOCK_ACCOUNT_ADDRESS, MOCK_ACCOUNT_KEY,
+    };
+    use crate::types::tx::{compute_tx_hash, decode_blob_tx, INDEX_WRAPPER_TYPE_ID};
+    use crate::NoAuth;
+
+    const SIMULATE: &str = "/cosmos.tx.v1beta1.Service/Simulate";
+    const GET_BLOCK_BY_HEIGHT: &str = "/cosmos.base.tendermint.v1beta1.Service/GetBlockByHeight";
+    const ABCI_QUERY: &str = "/cosmos.base.tendermint.v1beta1.Service/ABCIQuery";
+
+    async fn wallet(server: &MockGrpcServerHandle) -> Wallet<NoAuth, SigningKey> {
+        let signing_key = SigningKey::from_slice(&MOCK_ACCOUNT_KEY).unwrap();
+
+        Wallet::new(
+            server.client().await,
+            *signing_key.verifying_key(),
+            signing_key,
+        )
+        .with_confirmation_timeout(Duration::from_secs(5))
+    }
+
+    #[tokio::test]
+    async fn submit_blob() {
+        let broadcasted = Recorder::new();
+        let server = MockGrpcServer::new()
+            .with_chain("private")
+            .committing_blob_txs(15, broadcasted.clone())
+            .start()
+            .await;
+
+        let mut wallet = wallet(&server).await;
+        assert_eq!(wallet.address().to_string(), MOCK_ACCOUNT_ADDRESS);
+
+        let namespace = Namespace::new_v0(&[1, 2, 3]).unwrap();
+        let response = wallet.submit_blob(namespace, vec![5; 600]).await.unwrap();
+        assert_eq!(response.height.value(), 15);
+
+        let [blob_tx] = broadcasted.take().try_into().unwrap();
+        assert_eq!(blob_tx.blobs.len(), 1);
+        assert_eq!(blob_tx.blobs[0].data, vec![5; 600]);
+
+        let tx = RawTx::decode(&*blob_tx.tx).unwrap();
+        assert_eq!(tx.signatures.len(), 1);
+        let auth_info = AuthInfo::try_from(tx.auth_info.unwrap()).unwrap();
+        assert_eq!(auth_info.signer_infos[0].sequence, 9);
+        // 2 shares of 8 gas per byte and 70 bytes of the blob info at 10 gas each
+        let gas_limit = 75_000 + 2 * 8 * 512 + 70 * 10;
+        assert_eq!(auth_info.fee.gas_limit, gas_limit);
+        let fee = (gas_limit as f64 * 0.002).ceil() as u64;
+        assert_eq!(auth_info.fee.amount[0].amount, fee);
+        assert_eq!(response.txhash, compute_tx_hash(&blob_tx.tx));
+    }
+
+    #[tokio::test]
+    async fn submit_blobs() {
+        let broadcasted = Recorder::new();
+        let server = MockGrpcServer::new()
+            .with_chain("private")
+            .committing_blob_txs(15, broadcasted.clone())
+            .start()
+            .await;
+        let mut wallet = wallet(&server).await;
+
+        let first = Namespace::new_v0(&[1, 2, 3]).unwrap();
+        let second = Namespace::new_v0(&[4, 5, 6]).unwrap();
+        let response = wallet
+            .submit_blobs(vec![(first, vec![5; 600]), (second, vec![6; 100])])
+            .await
+            .unwrap();
+        assert_eq!(response.height.value(), 15);
+
+        // both blobs are paid for by a single transaction
+        let [blob_tx] = broadcasted.take().try_into().unwrap();
+        let (tx, blobs) = decode_blob_tx(&blob_tx.encode_to_vec(), AppVersion::V2).unwrap();
+        assert_eq!(blobs.len(), 2);
+        assert_eq!(blobs[0].namespace, first);
+        assert_eq!(blobs[1].namespace, second);
+        assert_eq!(blobs[1].data, vec![6; 100]);
+
+        let auth_info = AuthInfo::try_from(tx.auth_info.unwrap()).unwrap();
+        // 3 shares of 8 gas per byte and the info of each blob at 10 gas per byte
+        let gas_limit = 75_000 + 3 * 8 * 512 + 2 * 70 * 10;
+        assert_eq!(auth_info.fee.gas_limit, gas_limit);
+
+        assert!(matches!(
+            wallet.submit_blobs(Vec::new()).await,
+            Err(Error::TxEmptyBlobList)
+        ));
+        assert_eq!(broadcasted.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn submit_blob_simulate_only() {
+        let simulated = Recorder::new();
+        let broadcasted = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(
+                GET_LATEST_BLOCK,
+                |_: tonic::Request<GetLatestBlockRequest>| Ok(latest_block("private")),
+            )
+            .unary(ACCOUNT, account)
+            .unary(BLOB_PARAMS, |_: tonic::Request<QueryBlobParamsRequest>| {
+                Ok(QueryBlobParamsResponse {
+                    params: Some(RawBlobParams {
+                        gas_per_blob_byte: 8,
+                        gov_max_square_size: 64,
+                    }),
+                })
+            })
+            .unary(AUTH_PARAMS, |_: tonic::Request<QueryAuthParamsRequest>| {
+                Ok(QueryAuthParamsResponse {
+                    params: Some(RawAuthParams {
+                        tx_size_cost_per_byte: 10,
+                        ..Default::default()
+                    }),
+                })
+            })
+            .unary(CONFIG, config)
+            .unary(SIMULATE, {
+                let simulated = simulated.clone();
+                move |req: tonic::Request<SimulateRequest>| {
+                    simulated.record(req.into_inner().tx_bytes);
+                    Ok(SimulateResponse {
+                        gas_info: Some(RawGasInfo {
+                            gas_wanted: 84_892,
+                            gas_used: 80_000,
+                        }),
+                        result: None,
+                    })
+                }
+            })
+            .unary(BROADCAST_TX, {
+                let broadcasted = broadcasted.clone();
+                move |req: tonic::Request<BroadcastTxRequest>| {
+                    broadcasted.record(req.into_inner());
+                    Ok(BroadcastTxResponse::default())
+                }
+            })
+            .start()
+            .await;
+        let mut wallet = wallet(&server).await;
+
+        let namespace = Namespace::new_v0(&[1, 2, 3]).unwrap();
+        let options = SubmitOptions {
+            simulate_only: true,
+        };
+        let outcome = wallet
+            .submit_blob_with_options(namespace, vec![5; 600], options)
+            .await
+            .unwrap();
+
+        let SubmitOutcome::Simulated { gas_info, tx_bytes } = outcome else {
+            panic!("expected Simulated, got {outcome:?}");
+        };
+        assert_eq!(
+            gas_info,
+            GasInfo {
+                gas_wanted: 84_892,
+                gas_used: 80_000
+            }
+        );
+
+        let blob_tx = RawBlobTx::decode(&*tx_bytes).unwrap();
+        assert_eq!(blob_tx.blobs[0].data, vec![5; 600]);
+        // the signed transaction itself was simulated
+        assert_eq!(simulated.take(), [blob_tx.tx]);
+        assert_eq!(broadcasted.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn fee_granter_validation() {
+        let server = MockGrpcServer::new().start().await;
+
+        let own_address = wallet(&server).await.address().clone();
+        assert!(matches!(
+            wallet(&server).await.with_fee_granter(own_address),
+            Err(Error::InvalidTxParams(_))
+        ));
+
+        let validator = "celestiavaloper1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5thlh2h"
+            .parse()
+            .unwrap();
+        assert!(matches!(
+            wallet(&server).await.with_fee_granter(validator),
+            Err(Error::InvalidTxParams(_))
+        ));
+
+        let granter = "celestia1zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3shxjgz"
+            .parse()
+            .unwrap();
+        assert!(wallet(&server).await.with_fee_granter(granter).is_ok());
+    }
+
+    #[tokio::test]
+    async fn chain_id_mismatch() {
+        let chain_id = Arc::new(Mutex::new("mocha-3".to_owned()));
+
+        let server = MockGrpcServer::new()
+            .unary(GET_LATEST_BLOCK, {
+                let chain_id = chain_id.clone();
+                move |_: tonic::Request<GetLatestBlockRequest>| {
+                    Ok(latest_block(&chain_id.lock().unwrap()))
+                }
+            })
+            .unary(ACCOUNT, account)
+            .unary(CONFIG, config)
+            .unary(
+                BROADCAST_TX,
+                move |_: tonic::Request<BroadcastTxRequest>| {
+                    // chain was upgraded after the transaction was signed
+                    *chain_id.lock().unwrap() = "mocha-4".to_owned();
+                    Ok(BroadcastTxResponse {
+                        tx_response: Some(RawTxResponse {
+                            codespace: UNAUTHORIZED_CODESPACE.to_owned(),
+                            code: UNAUTHORIZED_CODE,
+                            raw_log: "signature verification failed".to_owned(),
+                            ..Default::default()
+                        }),
+                    })
+                },
+            )
+            .start()
+            .await;
+        let mut wallet = wallet(&server).await;
+        let to: Address = "celestia1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5wgawu3"
+            .parse()
+            .unwrap();
+
+        let err = wallet.send(&to, vec![Coin::utia(1000)]).await.unwrap_err();
+        match err {
+            Error::ChainIdMismatch { expected, actual } => {
+                assert_eq!(expected, "mocha-4");
+                assert_eq!(actual, "mocha-3");
+            }
+            err => panic!("expected ChainIdMismatch, got {err:?}"),
+        }
+
+        // the signature is rejected for another reason on the same chain
+        let response = wallet.send(&to, vec![Coin::utia(1000)]).await.unwrap();
+        assert_eq!(response.code, UNAUTHORIZED_CODE);
+    }
+
+    /// Block including the submitted blob transaction, with its data square.
+    struct CommittedBlock {
+        tx: RawTx,
+        txhash: String,
+        block: Block,
+        eds: ExtendedDataSquare,
+        dah: DataAvailabilityHeader,
+    }
+
+    /// Single compact share of the pay for blob namespace, holding the index wrapper
+    /// of the transaction with the blob starting at the given share.
+    fn pfb_share(tx: Vec<u8>, blob_start: u32) -> Vec<u8> {
+        let wrapper = RawIndexWrapper {
+            tx,
+            share_indexes: vec![blob_start],
+            type_id: INDEX_WRAPPER_TYPE_ID.to_owned(),
+        };
+        let unit = wrapper.encode_length_delimited_to_vec();
+
+        let mut share = Namespace::PAY_FOR_BLOB.as_bytes().to_vec();
+        // info byte of share version 0, starting the sequence
+        share.push(1);
+        share.extend_from_slice(&(unit.len() as u32).to_be_bytes());
+        // offset of the index wrapper in the share
+        share.extend_from_slice(&((share.len() + 4) as u32).to_be_bytes());
+        share.extend_from_slice(&unit);
+        assert!(
+            share.len() <= SHARE_SIZE,
+            "index wrapper doesn't fit a share"
+        );
+        share.resize(SHARE_SIZE, 0);
+        share
+    }
+
+    /// Block of the blob transaction, with its blob replaced by `block_blob_data` if given.
+    fn committed_block(blob_tx_bytes: Vec<u8>, block_blob_data: Option<Vec<u8>>) -> CommittedBlock {
+        let (tx, mut blobs) = decode_blob_tx(&blob_tx_bytes, AppVersion::V2).unwrap();
+        let tx_bytes = tx.encode_to_vec();
+        let mut blob = blobs.remove(0);
+        if let Some(data) = block_blob_data {
+            blob = Blob::new(blob.namespace, data, AppVersion::V2).unwrap();
+        }
+
+        let mut ods = vec![pfb_share(tx_bytes.clone(), 1)];
+        ods.extend(blob.to_shares().unwrap().iter().map(|share| share.to_vec()));
+        let width = (ods.len() as f64).sqrt().ceil().max(1.0) as usize;
+        let width = width.next_power_of_two();
+        let mut tail_padding = Namespace::TAIL_PADDING.as_bytes().to_vec();
+        tail_padding.push(1);
+        tail_padding.resize(SHARE_SIZE, 0);
+        ods.resize(width * width, tail_padding);
+
+        let eds = ExtendedDataSquare::from_ods(ods, AppVersion::V2).unwrap();
+        let dah = DataAvailabilityHeader::from_eds(&eds);
+        let header = ExtendedHeaderGenerator::new().next_with_dah(dah.clone());
+        let data = Data {
+            txs: vec![blob_tx_bytes],
+            square_size: width as u64,
+            hash: dah.hash().as_bytes().to_vec(),
+        };
+
+        CommittedBlock {
+            txhash: compute_tx_hash(&tx_bytes),
+            tx,
+            block: Block::new(header.header, data, Default::default(), None),
+            eds,
+            dah,
+        }
+    }
+
+    /// Proof of all the shares of the namespace, which are all in the first row.
+    fn namespace_proof(committed: &CommittedBlock, namespace: Namespace) -> RawShareProof {
+        let rows = committed
+            .eds
+            .get_namespace_data(namespace, &committed.dah, 1)
+            .unwrap();
+
+        RawShareProof {
+            data: rows
+                .iter()
+                .flat_map(|(_, row)| &row.shares)
+                .map(|share| share.data().to_vec())
+                .collect(),
+            namespace_id: namespace.id().to_vec(),
+            namespace_version: namespace.version().into(),
+            share_proofs: rows.into_iter().map(|(_, row)| row.proof.into()).collect(),
+            row_proof: Some(committed.dah.row_proof(0..=0).unwrap().into()),
+        }
+    }
+
+    /// Server committing the submitted blob in a block, which holds a different blob
+    /// than the submitted one if `block_blob_data` is given.
+    async fn blob_proof_server(
+        namespace: Namespace,
+        block_blob_data: Option<Vec<u8>>,
+    ) -> (
+        MockGrpcServerHandle,
+        Recorder<String>,
+        Arc<Mutex<Option<CommittedBlock>>>,
+    ) {
+        let committed = Arc::new(Mutex::new(None::<CommittedBlock>));
+        let queries = Recorder::new();
+
+        let server = MockGrpcServer::new()
+            .unary(
+                GET_LATEST_BLOCK,
+                |_: tonic::Request<GetLatestBlockRequest>| Ok(latest_block("private")),
+            )
+            .unary(ACCOUNT, account)
+            .unary(BLOB_PARAMS, |_: tonic::Request<QueryBlobParamsRequest>| {
+                Ok(QueryBlobParamsResponse {
+                    params: Some(RawBlobParams {
+                        gas_per_blob_byte: 8,
+                        gov_max_square_size: 64,
+                    }),
+                })
+            })
+            .unary(AUTH_PARAMS, |_: tonic::Request<QueryAuthParamsRequest>| {
+                Ok(QueryAuthParamsResponse {
+                    params: Some(RawAuthParams {
+                        tx_size_cost_per_byte: 10,
+                        ..Default::default()
+                    }),
+                })
+            })
+            .unary(CONFIG, config)
+            .unary(BROADCAST_TX, {
+                let committed = committed.clone();
+                move |req: tonic::Request<BroadcastTxRequest>| {
+                    let block = committed_block(req.into_inner().tx_bytes, block_blob_data.clone());
+                    let txhash = block.txhash.clone();
+                    *committed.lock().unwrap() = Some(block);
+                    Ok(BroadcastTxResponse {
+                        tx_response: Some(RawTxResponse {
+                            txhash,
+                            ..Default::default()
+                        }),
+                    })
+                }
+            })
+            .unary(GET_TX, {
+                let committed = committed.clone();
+                move |req: tonic::Request<GetTxRequest>| {
+                    let hash = req.into_inner().hash;
+                    match &*committed.lock().unwrap() {
+                        Some(committed) if committed.txhash == hash => Ok(RawGetTxResponse {
+                            tx: Some(committed.tx.clone()),
+                            tx_response: Some(RawTxResponse {
+                                height: committed.block.header.height.value() as i64,
+                                txhash: hash,
+                                ..Default::default()
+                            }),
+                        }),
+                        _ => Err(Status::not_found(hash)),
+                    }
+                }
+            })
+            .unary(GET_BLOCK_BY_HEIGHT, {
+                let committed = committed.clone();
+                move |_: tonic::Request<GetBlockByHeightRequest>| {
+                    let committed = committed.lock().unwrap();
+                    Ok(GetBlockByHeightResponse {
+                        block_id: None,
+                        block: committed.as_ref().map(|c| c.block.clone().into()),
+                        sdk_block: None,
+                    })
+                }
+            })
+            .unary(ABCI_QUERY, {
+                let queries = queries.clone();
+                let committed = committed.clone();
+                move |req: tonic::Request<AbciQueryRequest>| {
+                    let path = req.into_inner().path;
+                    let committed = committed.lock().unwrap();
+                    let committed = committed.as_ref().unwrap();
+                    let proof = if path.starts_with("custom/txInclusionProof/") {
+                        namespace_proof(committed, Namespace::PAY_FOR_BLOB)
+                    } else {
+                        namespace_proof(committed, namespace)
+                    };
+                    queries.record(path);
+                    Ok(AbciQueryResponse {
+                        value: proof.encode_to_vec(),
+                        ..Default::default()
+                    })
+                }
+            })
+            .start()
+            .await;
+
+        (server, queries, committed)
+    }
+
+    #[tokio::test]
+    async fn submit_blob_with_proof() {
+        let namespace = Namespace::new_v0(&[1, 2, 3]).unwrap();
+        let (server, queries, committed) = blob_proof_server(namespace, None).await;
+        let mut wallet = wallet(&server).await;
+
+        let result = wallet
+            .submit_blob_with_proof(namespace, b"lumina".to_vec())
+            .await
+            .unwrap();
+
+        let blob = Blob::new(namespace, b"lumina".to_vec(), AppVersion::V2).unwrap();
+        assert_eq!(result.height, 1);
+        assert_eq!(result.commitment, blob.commitment);
+        assert_eq!(
+            queries.take(),
+            [
+                "custom/txInclusionProof/0",
+                "custom/shareInclusionProof/1/2"
+            ]
+        );
+
+        let data_root = committed.lock().unwrap().as_ref().unwrap().dah.hash();
+        assert!(verify_blob_inclusion(&blob, &result.proof, &data_root).unwrap());
+    }
+
+    #[tokio::test]
+    async fn submit_blob_with_proof_not_found() {
+        let namespace = Namespace::new_v0(&[1, 2, 3]).unwrap();
+        // block holds another blob of the namespace than the one submitted
+        let (server, _, _) = blob_proof_server(namespace, Some(b"other".to_vec())).await;
+        let mut wallet = wallet(&server).await;
+
+        let err = wallet
+            .submit_blob_with_proof(namespace, b"lumina".to_vec())
+            .await
+            .unwrap_err();
+        let blob = Blob::new(namespace, b"lumina".to_vec(), AppVersion::V2).unwrap();
+        match err {
+            Error::BlobNotFound { height, commitment } => {
+                assert_eq!(height, 1);
+                assert_eq!(commitment, blob.commitment);
+            }
+            err => panic!("expected BlobNotFound, got {err:?}"),
+        }
+    }
+}
@@ -1,8 +1,7 @@
 #![cfg(not(target_arch = "wasm32"))]
 
 use celestia_grpc::types::auth::Account;
-use celestia_grpc::types::tx::sign_tx;
-use celestia_proto::cosmos::tx::v1beta1::BroadcastMode;
+use celestia_grpc::types::tx::{sign_tx, BroadcastMode};
 use celestia_types::blob::MsgPayForBlobs;
 use celestia_types::nmt::Namespace;
 use celestia_types::{AppVersion, Blob};
@@ -61,6 +60,7 @@ async fn get_account() {
     let address = match first_account {
         Account::Base(acct) => acct.address.clone(),
         Account::Module(acct) => acct.base_account.as_ref().unwrap().address.clone(),
+        Account::Multisig(acct) => acct.address.clone(),
     };
 
     let account = client.get_account(&address).await.unwrap();
@@ -106,5 +106,6 @@ async fn submit_blob() {
     let _submitted_tx = client
         .get_tx(response.txhash)
         .await
-        .expect("get to be successful");
+        .expect("get to be successful")
+        .expect("tx to be included");
 }
@@ -0,0 +1,81 @@
+//! Source of the current time, replaced in tests to drive the time-based logic
+//! without real sleeps.
+
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Source of the current time.
+pub(crate) trait Clock: Send + Sync {
+    /// Current monotonic time, for measuring the time between events.
+    fn now(&self) -> Instant;
+
+    /// Current wall-clock time.
+    fn system_time(&self) -> SystemTime;
+
+    /// Current wall-clock time in milliseconds since the unix epoch.
+    fn unix_ms(&self) -> u64 {
+        unix_ms(self.system_time())
+    }
+}
+
+/// Clock of the operating system.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn system_time(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Milliseconds since the unix epoch of the time, `0` for the times before it.
+pub(crate) fn unix_ms(time: SystemTime) -> u64 {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    since_epoch.as_millis() as u64
+}
+
+#[cfg(test)]
+pub(crate) use mock::MockClock;
+
+#[cfg(test)]
+mod mock {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Clock which stands still until it's advanced.
+    #[derive(Debug)]
+    pub(crate) struct MockClock {
+        times: Mutex<(Instant, SystemTime)>,
+    }
+
+    impl MockClock {
+        /// Create a clock showing the given wall-clock time.
+        pub(crate) fn new(system_time: SystemTime) -> Self {
+            MockClock {
+                times: Mutex::new((Instant::now(), system_time)),
+            }
+        }
+
+        /// Move both the monotonic and the wall-clock time forward.
+        pub(crate) fn advance(&self, by: Duration) {
+            let mut times = self.times.lock().expect("poisoned lock");
+            times.0 += by;
+            times.1 += by;
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> Instant {
+            self.times.lock().expect("poisoned lock").0
+        }
+
+        fn system_time(&self) -> SystemTime {
+            self.times.lock().expect("poisoned lock").1
+        }
+    }
+}
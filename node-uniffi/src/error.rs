@@ -1,3 +1,6 @@
+use std::fmt;
+
+use lumina_node::node::P2pError;
 use lumina_node::NodeError;
 use thiserror::Error;
 
@@ -26,8 +29,11 @@ pub enum LuminaError {
     },
 
     /// Error returned when trying to start a node that's already running
-    #[error("Node is already running")]
-    AlreadyRunning,
+    #[error("Node {peer_id} is already running")]
+    AlreadyRunning {
+        /// Peer ID of the running node
+        peer_id: String,
+    },
 
     /// Error returned when a hash string is invalid or malformed
     #[error("Invalid hash format: {msg}")]
@@ -49,6 +55,142 @@ pub enum LuminaError {
         /// Description of why storage initialization failed
         msg: String,
     },
+
+    /// Error returned when the store is already opened by another node, in this or
+    /// a different process
+    #[error("Storage at {path} is already in use")]
+    StorageLocked {
+        /// Path of the store
+        path: String,
+    },
+
+    /// Error returned when the requested height is not available in the local store
+    #[error("Height {height} is not available in the local store")]
+    HeightNotLocal {
+        /// The requested height
+        height: u64,
+    },
+
+    /// Error returned when the node refuses the operations writing new data
+    #[error("Node is read-only: {reason}")]
+    NodeReadOnly {
+        /// Why the node is read-only
+        reason: ReadOnlyReason,
+    },
+
+    /// Error returned when reading the data of a compromised network, before the compromise
+    /// is acknowledged with
+    /// [`LuminaNode::acknowledge_compromise`](crate::LuminaNode::acknowledge_compromise)
+    #[error("Network is compromised")]
+    NetworkCompromised,
+
+    /// Error returned when syncing was cancelled before reaching the target
+    #[error("Syncing was cancelled")]
+    SyncCancelled,
+
+    /// Error returned when a long-running operation was cancelled with
+    /// [`LuminaNode::cancel_operation`](crate::LuminaNode::cancel_operation)
+    #[error("Operation {id} was cancelled")]
+    OperationCancelled {
+        /// Id of the cancelled operation
+        id: u64,
+    },
+
+    /// Error returned when an operation didn't finish within its timeout
+    #[error("Timed out: {msg}")]
+    Timeout {
+        /// Description of what timed out
+        msg: String,
+    },
+
+    /// Error returned when a namespace is invalid or malformed
+    #[error("Invalid namespace: {msg}")]
+    InvalidNamespace {
+        /// Description of why the namespace is invalid
+        msg: String,
+    },
+
+    /// Error returned when a blob is invalid or malformed
+    #[error("Invalid blob: {msg}")]
+    InvalidBlob {
+        /// Description of why the blob is invalid
+        msg: String,
+    },
+
+    /// Error returned when a range of heights is empty or too long
+    #[error("Invalid range: {msg}")]
+    InvalidRange {
+        /// Description of why the range is invalid
+        msg: String,
+    },
+
+    /// Error returned when a multiaddr is invalid or malformed
+    #[error("Invalid multiaddr `{input}`: {reason}")]
+    InvalidMultiaddr {
+        /// The rejected address
+        input: String,
+        /// Description of why the multiaddr is invalid
+        reason: String,
+    },
+
+    /// Error returned when the node configuration is invalid
+    #[error("Invalid config: {msg}")]
+    InvalidConfig {
+        /// Description of why the configuration is invalid
+        msg: String,
+    },
+
+    /// Error returned when no peer connected to the node within the timeout of the
+    /// queries needing the network
+    #[error("No peers available")]
+    NoPeersAvailable,
+
+    /// Error returned when the network isn't supported by the node, e.g. a custom network
+    /// without any bootnodes to connect to
+    #[error("Unknown network: {network}")]
+    UnknownNetwork {
+        /// Id of the network
+        network: String,
+    },
+
+    /// Error returned when data fails to be decoded into the celestia types
+    #[error("Failed to decode: {msg}")]
+    DecodeError {
+        /// Description of the decoding failure
+        msg: String,
+    },
+
+    /// Error returned when the submitted transaction is rejected by the chain
+    #[error("Transaction failed with code {code}: {msg}")]
+    TxFailed {
+        /// Error code of the transaction
+        code: u32,
+        /// Log of the failure
+        msg: String,
+    },
+}
+
+/// Why the node refuses the operations writing new data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum ReadOnlyReason {
+    /// The node received a valid fraud proof, so the network is compromised.
+    /// Syncing and sampling are stopped for the lifetime of the node.
+    Compromised,
+    /// Syncing of the headers is paused with
+    /// [`LuminaNode::pause_sync`](crate::LuminaNode::pause_sync).
+    Paused,
+    /// The node didn't sync the header of the network head yet.
+    NotSynced,
+}
+
+impl fmt::Display for ReadOnlyReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadOnlyReason::Compromised => f.write_str("network is compromised"),
+            ReadOnlyReason::Paused => f.write_str("syncing is paused"),
+            ReadOnlyReason::NotSynced => f.write_str("node is not synced"),
+        }
+    }
 }
 
 impl LuminaError {
@@ -71,16 +213,141 @@ impl LuminaError {
     pub fn storage_init(msg: impl Into<String>) -> Self {
         Self::StorageInit { msg: msg.into() }
     }
+
+    pub fn timeout(msg: impl Into<String>) -> Self {
+        Self::Timeout { msg: msg.into() }
+    }
+
+    pub fn invalid_namespace(msg: impl Into<String>) -> Self {
+        Self::InvalidNamespace { msg: msg.into() }
+    }
+
+    pub fn invalid_blob(msg: impl Into<String>) -> Self {
+        Self::InvalidBlob { msg: msg.into() }
+    }
+
+    pub fn invalid_range(msg: impl Into<String>) -> Self {
+        Self::InvalidRange { msg: msg.into() }
+    }
+
+    pub fn invalid_multiaddr(input: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::InvalidMultiaddr {
+            input: input.into(),
+            reason: reason.into(),
+        }
+    }
+
+    pub fn invalid_config(msg: impl Into<String>) -> Self {
+        Self::InvalidConfig { msg: msg.into() }
+    }
+
+    pub fn decode(msg: impl Into<String>) -> Self {
+        Self::DecodeError { msg: msg.into() }
+    }
 }
 
 impl From<NodeError> for LuminaError {
     fn from(error: NodeError) -> Self {
-        LuminaError::network(error.to_string())
+        match error {
+            NodeError::P2p(P2pError::CelestiaTypes(e)) => e.into(),
+            NodeError::NetworkCompromised => LuminaError::NodeReadOnly {
+                reason: ReadOnlyReason::Compromised,
+            },
+            error => LuminaError::network(error.to_string()),
+        }
+    }
+}
+
+impl From<celestia_types::Error> for LuminaError {
+    fn from(error: celestia_types::Error) -> Self {
+        use celestia_types::Error;
+
+        let msg = error.to_string();
+
+        match error {
+            Error::MissingHeader
+            | Error::MissingCommit
+            | Error::MissingValidatorSet
+            | Error::MissingDataAvailabilityHeader
+            | Error::UnsupportedAppVersion(_)
+            | Error::InvalidSignatureIndex(..)
+            | Error::UnexpectedAbsentSignature
+            | Error::ZeroBlockHeight
+            | Error::Validation(_)
+            | Error::Verification(_) => LuminaError::invalid_header(msg),
+
+            Error::Multihash(_) | Error::CidError(_) | Error::InvalidComittmentLength => {
+                LuminaError::invalid_hash(msg)
+            }
+
+            Error::UnsupportedNamespaceVersion(_)
+            | Error::InvalidNamespaceSize
+            | Error::InvalidNamespaceV0
+            | Error::InvalidNamespaceV255 => LuminaError::invalid_namespace(msg),
+
+            Error::BlobTooLarge
+            | Error::BlobExceedsMaxSquareSize(..)
+            | Error::BlobSharesMetadataMismatch(_)
+            | Error::EmptyBlobList => LuminaError::invalid_blob(msg),
+
+            _ => LuminaError::decode(msg),
+        }
     }
 }
 
-impl From<libp2p::multiaddr::Error> for LuminaError {
-    fn from(e: libp2p::multiaddr::Error) -> Self {
-        LuminaError::network(format!("Invalid multiaddr: {}", e))
+#[cfg(test)]
+mod tests {
+    use celestia_types::{Error, ValidationError};
+
+    use super::*;
+
+    #[test]
+    fn celestia_types_errors_mapping() {
+        assert!(matches!(
+            Error::MissingHeader.into(),
+            LuminaError::InvalidHeader { .. }
+        ));
+        assert!(matches!(
+            Error::Validation(ValidationError::Other("bad".into())).into(),
+            LuminaError::InvalidHeader { .. }
+        ));
+        assert!(matches!(
+            Error::UnsupportedAppVersion(99).into(),
+            LuminaError::InvalidHeader { .. }
+        ));
+        assert!(matches!(
+            Error::InvalidComittmentLength.into(),
+            LuminaError::InvalidHash { .. }
+        ));
+        assert!(matches!(
+            Error::InvalidNamespaceSize.into(),
+            LuminaError::InvalidNamespace { .. }
+        ));
+        assert!(matches!(
+            Error::EmptyBlobList.into(),
+            LuminaError::InvalidBlob { .. }
+        ));
+
+        let err: LuminaError = Error::InvalidShareSize(7).into();
+        match err {
+            LuminaError::DecodeError { msg } => assert_eq!(msg, "Invalid share size: 7"),
+            err => panic!("unexpected error: {err}"),
+        }
+    }
+
+    #[test]
+    fn celestia_types_errors_from_node() {
+        let err = NodeError::P2p(P2pError::CelestiaTypes(Error::MissingShares));
+        assert!(matches!(err.into(), LuminaError::DecodeError { .. }));
+
+        let err = NodeError::P2p(P2pError::NoConnectedPeers);
+        assert!(matches!(err.into(), LuminaError::Network { .. }));
+
+        assert!(matches!(
+            NodeError::NetworkCompromised.into(),
+            LuminaError::NodeReadOnly {
+                reason: ReadOnlyReason::Compromised
+            }
+        ));
     }
 }
@@ -4,21 +4,87 @@
 //! allowing it to be used from iOS and Android applications.
 #![cfg(not(target_arch = "wasm32"))]
 
+mod clock;
 mod error;
+#[cfg(test)]
+mod test_utils;
 mod types;
 
-use celestia_types::ExtendedHeader;
-use error::{LuminaError, Result};
+use blockstore::Blockstore;
+use celestia_types::blob::BlobParams;
+use celestia_types::nmt::Namespace;
+use celestia_types::{Blob, ExtendedHeader, Share};
+use clock::{Clock, SystemClock};
+use error::{LuminaError, ReadOnlyReason, Result};
+use libp2p::identity::ed25519;
 use lumina_node::{
-    blockstore::RedbBlockstore, events::EventSubscriber, node::PeerTrackerInfo, store::RedbStore,
-    Node,
+    block_ranges::BlockRanges,
+    blockstore::RedbBlockstore,
+    events::{EventSubscriber, NodeEvent as LuminaNodeEvent},
+    network::Network,
+    node::{BandwidthStats, PeerTrackerInfo, PruningBacklog, SyncingInfo as LuminaSyncingInfo},
+    store::{LatencyStats, RedbStore, SamplingMetadata, SamplingStatus, Store, StoreError},
+    Node, NodeError,
 };
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as StdMutex, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
 use tendermint::hash::Hash;
+use tokio::runtime::{Builder as RuntimeBuilder, Handle as RuntimeHandle};
 use tokio::sync::{Mutex, RwLock};
-use types::{NetworkInfo, NodeConfig, NodeEvent, PeerId, SyncingInfo};
+use tokio::task::spawn_blocking;
+use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+use types::{
+    average_block_time, block_contains_namespace, block_namespaces, block_shares,
+    commitment_included, connect_grpc, cross_check, deliver_batched, deliver_namespace_data,
+    deliver_shares, drive_sync, estimate_height_since, estimate_sync_completion,
+    estimate_window_disk_bytes, estimate_window_range, has_network_head, is_stale, is_synced,
+    keypair_from_secret_key, missing_headers, produce_blobs, remove_stale_stores, store_name,
+    submit_blobs, BlobInput, BlobSizeLimits, BlobSource, BlobStream, BlockBlobs, BlockRange,
+    BlockSource, BlockTime, Commitment, ConnectionEvent, ConnectionEventLog, ConnectionRates,
+    CrossCheckResult, DataAvailabilityHeader, DiagnosticsReport, EffectiveConfig, EventBatchConfig,
+    EventHistory, ExtendedHeaderRecord, FetchRateLog, HeadRange, NamespaceListener, NamespaceStats,
+    NetworkInfo, NodeConfig, NodeEvent, NodeEventInfo, NodeEventListener, OperationHandle,
+    OperationKind, Operations, PartialHeaders, PeerId, PeerLatency, PeerScore, ReadPolicy,
+    ReorgInfo, RowSource, SampleOutcome, SamplingDurations, ShareListener, StoredHeights,
+    SyncGapLog, SyncProgress, SyncProgressInfo, SyncedRange, SyncingInfo, TimeRange, TxConfig,
+    DEFAULT_BLOCK_DISK_BYTES, DEFAULT_BLOCK_TIME, DIAGNOSTICS_EVENTS,
+};
 use uniffi::Object;
 
+/// Interval between checks of the syncing progress in [`LuminaNode::sync_to_head`].
+const SYNC_PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Interval between the samples of the sync gap checked by [`LuminaNode::is_sync_stuck`].
+const SYNC_GAP_SAMPLING_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Timeout of waiting for the headers in [`LuminaNode::sync_since`].
+const SYNC_SINCE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Window over which the rate of fetching headers is computed for
+/// [`LuminaNode::estimated_sync_completion_ms`].
+const FETCH_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Timeout of fetching the blobs of a block for [`LuminaNode::subscribe_namespace`] and
+/// [`LuminaNode::get_blobs_in_range`].
+const NAMESPACE_BLOBS_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Timeout of fetching each row of the square for [`LuminaNode::stream_block_shares`].
+const ROW_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Minimum number of the stored blocks, for the average size of a block on disk to be
+/// measured from the store by [`LuminaNode::estimate_window_disk_bytes`].
+const MIN_MEASURED_BLOCKS: u64 = 1000;
+
+/// Maximum number of heights sampled at once by [`LuminaNode::sample_range`].
+const MAX_SAMPLE_RANGE_LEN: u64 = 128;
+
 uniffi::setup_scaffolding!();
 
 lumina_node::uniffi_reexport_scaffolding!();
@@ -26,9 +92,62 @@ lumina_node::uniffi_reexport_scaffolding!();
 /// The main Lumina node that manages the connection to the Celestia network.
 #[derive(Object)]
 pub struct LuminaNode {
-    node: RwLock<Option<Node<RedbBlockstore, RedbStore>>>,
+    node: Arc<RwLock<Option<Node<RedbBlockstore, RedbStore>>>>,
     events_subscriber: Mutex<Option<EventSubscriber>>,
+    sync_cancellation: Mutex<Option<CancellationToken>>,
+    operations: Operations,
+    recorded_events: Arc<StdMutex<RecordedEvents>>,
+    clock: Arc<dyn Clock>,
     config: NodeConfig,
+    /// Secret key installed by [`LuminaNode::rotate_identity`], used instead of
+    /// the identity from the config.
+    rotated_key: StdMutex<Option<Vec<u8>>>,
+    /// Parameters of the blob module, queried with the first [`LuminaNode::max_blob_size`].
+    blob_params: StdMutex<Option<BlobParams>>,
+    /// Whether the compromise of the network of the running node was acknowledged with
+    /// [`LuminaNode::acknowledge_compromise`].
+    compromise_acknowledged: AtomicBool,
+}
+
+/// Events of the running node kept for the queries which aren't served by its store.
+#[derive(Debug, Default)]
+struct RecordedEvents {
+    connections: ConnectionEventLog,
+    sampling_durations: SamplingDurations,
+    fetches: FetchRateLog,
+    sync_gaps: SyncGapLog,
+    history: EventHistory,
+    last_fatal_error: Option<String>,
+}
+
+impl RecordedEvents {
+    /// Record the event, as if it was received at the given time.
+    fn record(&mut self, info: NodeEventInfo, at: Instant) {
+        match &info.event {
+            NodeEvent::PeerConnected { .. } => {
+                self.connections.record(ConnectionEvent::Connected, at)
+            }
+            NodeEvent::PeerDisconnected { .. } => {
+                self.connections.record(ConnectionEvent::Disconnected, at)
+            }
+            NodeEvent::SamplingFinished {
+                height, took_ms, ..
+            } => self
+                .sampling_durations
+                .record(*height, Duration::from_millis(*took_ms)),
+            NodeEvent::FetchingHeadersFinished {
+                from_height,
+                to_height,
+                ..
+            } => self.fetches.record(*from_height..=*to_height, at),
+            NodeEvent::FatalDaserError { error }
+            | NodeEvent::FatalSyncerError { error }
+            | NodeEvent::FatalPrunerError { error } => self.last_fatal_error = Some(error.clone()),
+            _ => {}
+        }
+
+        self.history.record(info);
+    }
 }
 
 #[uniffi::export(async_runtime = "tokio")]
@@ -36,31 +155,57 @@ impl LuminaNode {
     /// Sets a new connection to the Lumina node for the specified network.
     #[uniffi::constructor]
     pub fn new(config: NodeConfig) -> Result<Self> {
-        Ok(Self {
-            node: RwLock::new(None),
-            events_subscriber: Mutex::new(None),
-            config,
-        })
+        Ok(Self::with_clock(config, Arc::new(SystemClock)))
     }
 
     /// Starts the node and connects to the network.
     pub async fn start(&self) -> Result<bool> {
         let mut node_lock = self.node.write().await;
-        if node_lock.is_some() {
-            return Err(LuminaError::AlreadyRunning);
+        if let Some(node) = node_lock.as_ref() {
+            return Err(LuminaError::AlreadyRunning {
+                peer_id: node.local_peer_id().to_base58(),
+            });
         }
 
-        let builder = self.config.clone().into_node_builder().await?;
-        let (new_node, subscriber) = builder.start_subscribed().await?;
+        *node_lock = Some(self.start_node().await?);
 
-        *self.events_subscriber.lock().await = Some(subscriber);
+        Ok(true)
+    }
+
+    /// Replaces the identity of the running node with a new one, keeping its store.
+    ///
+    /// The node is stopped and started again with the Ed25519 secret key, or a freshly
+    /// generated one if `None`. The new identity is kept for the following restarts
+    /// of this node. Long-running operations in progress are cancelled first, like when
+    /// stopping the node. Returns the new peer id.
+    pub async fn rotate_identity(&self, new_key_bytes: Option<Vec<u8>>) -> Result<PeerId> {
+        let key_bytes = match new_key_bytes {
+            Some(key_bytes) => {
+                keypair_from_secret_key(key_bytes.clone())?;
+                key_bytes
+            }
+            None => ed25519::SecretKey::generate().as_ref().to_vec(),
+        };
+
+        self.cancel_operations().await;
+        let mut node_lock = self.node.write().await;
+        let node = node_lock.take().ok_or(LuminaError::NodeNotRunning)?;
+        node.stop().await;
+
+        *self.rotated_key.lock().expect("poisoned lock") = Some(key_bytes);
+        let new_node = self.start_node().await?;
+        let peer_id = (*new_node.local_peer_id()).into();
         *node_lock = Some(new_node);
 
-        Ok(true)
+        Ok(peer_id)
     }
 
     /// Stops the running node and closes all network connections.
+    ///
+    /// Long-running operations in progress are cancelled first.
     pub async fn stop(&self) -> Result<()> {
+        self.cancel_operations().await;
+
         let mut node = self.node.write().await;
         if let Some(node) = node.take() {
             node.stop().await;
@@ -70,11 +215,29 @@ impl LuminaNode {
         }
     }
 
+    /// Gets the configuration the node runs with, after the defaults are applied.
+    pub fn effective_config(&self) -> EffectiveConfig {
+        self.config.effective_config()
+    }
+
+    /// Gets the ids of the protocols the node speaks on its network and advertises to
+    /// the peers, like the versions of header-sub, header-ex and shwap.
+    pub fn supported_protocols(&self) -> Vec<String> {
+        self.config.network.supported_protocols()
+    }
+
     /// Checks if the node is currently running.
     pub async fn is_running(&self) -> bool {
         self.node.read().await.is_some()
     }
 
+    /// Checks if the node has a verified head and was connected to a peer supporting the
+    /// protocols needed to serve the queries. Returns `false` if the node isn't running.
+    pub async fn is_ready(&self) -> bool {
+        let node = self.node.read().await;
+        node.as_ref().is_some_and(|node| node.is_ready())
+    }
+
     /// Gets the local peer ID as a string.
     pub async fn local_peer_id(&self) -> Result<String> {
         let node = self.node.read().await;
@@ -89,6 +252,60 @@ impl LuminaNode {
         Ok(node.peer_tracker_info())
     }
 
+    /// Gets the amount of data transferred by the node since it was started.
+    ///
+    /// Rates are computed over the latest second, independently of the calls.
+    pub async fn bandwidth_stats(&self) -> Result<BandwidthStats> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        Ok(node.bandwidth_stats())
+    }
+
+    /// Gets the headers waiting to be pruned.
+    ///
+    /// Shrinks as the pruning progresses, reported with the `PrunedHeaders` events.
+    pub async fn pruning_backlog(&self) -> Result<PruningBacklog> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        Ok(node.pruning_backlog())
+    }
+
+    /// Gets the statistics of the durations of the recent write transactions of the store,
+    /// in milliseconds. Growing latencies mean the storage of the device stalls the syncing.
+    pub async fn storage_write_latency_ms(&self) -> Result<LatencyStats> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        Ok(node.storage_write_latency().await.unwrap_or_default())
+    }
+
+    /// Checks whether the node received a valid fraud proof, meaning the network is compromised.
+    ///
+    /// Once compromised, syncing and sampling are stopped for good, operations writing new
+    /// data fail with [`LuminaError::NodeReadOnly`] and reading the stored data fails with
+    /// [`LuminaError::NetworkCompromised`] until [`LuminaNode::acknowledge_compromise`] is called.
+    pub async fn is_network_compromised(&self) -> Result<bool> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        Ok(node.is_network_compromised())
+    }
+
+    /// Acknowledges that the network is compromised, allowing to read the already stored data.
+    ///
+    /// Dangerous: the stored data may come from the compromised chain and must not be trusted.
+    /// Syncing, sampling and operations writing new data stay disabled. Returns `false` if
+    /// the network isn't compromised.
+    pub async fn acknowledge_compromise(&self) -> Result<bool> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        if !node.is_network_compromised() {
+            return Ok(false);
+        }
+
+        warn!("Compromised network acknowledged, resuming read-only operation");
+        self.compromise_acknowledged.store(true, Ordering::Release);
+        Ok(true)
+    }
+
     /// Waits until the node is connected to at least one peer.
     pub async fn wait_connected(&self) -> Result<()> {
         let node = self.node.read().await;
@@ -108,7 +325,8 @@ impl LuminaNode {
         let node = self.node.read().await;
         let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
         let info = node.network_info().await?;
-        Ok(info.into())
+        let listeners = node.listeners().await?;
+        Ok(NetworkInfo::new(info, listeners, &node.peer_tracker_info()))
     }
 
     /// Gets list of addresses the node is listening to.
@@ -119,6 +337,16 @@ impl LuminaNode {
         Ok(listeners.into_iter().map(|l| l.to_string()).collect())
     }
 
+    /// Resolves the `/dnsaddr/` names of the configured bootnodes again and dials the
+    /// addresses they point to now, e.g. after their DNS records changed while the node
+    /// was running. Returns the newly resolved addresses.
+    pub async fn refresh_bootnodes(&self) -> Result<Vec<String>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        let addrs = node.refresh_bootnodes().await?;
+        Ok(addrs.into_iter().map(|addr| addr.to_string()).collect())
+    }
+
     /// Gets list of currently connected peer IDs.
     pub async fn connected_peers(&self) -> Result<Vec<PeerId>> {
         let node = self.node.read().await;
@@ -127,6 +355,45 @@ impl LuminaNode {
         Ok(peers.into_iter().map(PeerId::from).collect())
     }
 
+    /// Gets the connected peers which advertised support of the `protocol`, e.g. the one
+    /// used for sampling. Empty, when none of the connected peers supports it.
+    pub async fn peers_supporting(&self, protocol: String) -> Result<Vec<PeerId>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        let peers = node.peers_supporting(&protocol).await?;
+        Ok(peers.into_iter().map(PeerId::from).collect())
+    }
+
+    /// Gets the rates of peers connecting and disconnecting over the last `window_secs` seconds.
+    pub async fn connection_rates(&self, window_secs: u32) -> Result<ConnectionRates> {
+        if self.node.read().await.is_none() {
+            return Err(LuminaError::NodeNotRunning);
+        }
+        let events = self.recorded_events.lock().expect("poisoned lock");
+        Ok(events.connections.rates(window_secs, self.clock.now()))
+    }
+
+    /// Gets the gossipsub scores of the peers, to diagnose why peers are pruned.
+    ///
+    /// Returns an empty list if peer scoring isn't enabled.
+    pub async fn peer_scores(&self) -> Result<Vec<PeerScore>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        let scores = node.peer_scores().await?;
+        Ok(scores.into_iter().map(PeerScore::from).collect())
+    }
+
+    /// Gets the round-trip times of the last pings of the connected peers, pinged
+    /// every [`NodeConfig::keepalive_ping_secs`].
+    ///
+    /// Peers which weren't pinged successfully yet are omitted.
+    pub async fn peer_latencies(&self) -> Result<Vec<PeerLatency>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        let latencies = node.peer_latencies().await?;
+        Ok(latencies.into_iter().map(PeerLatency::from).collect())
+    }
+
     /// Sets whether a peer with give ID is trusted.
     pub async fn set_peer_trust(&self, peer_id: PeerId, is_trusted: bool) -> Result<()> {
         let node = self.node.read().await;
@@ -141,6 +408,7 @@ impl LuminaNode {
     pub async fn request_head_header(&self) -> Result<String> {
         let node = self.node.read().await;
         let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        wait_for_peer(node, self.config.peer_wait_timeout()).await?;
         let header = node.request_head_header().await?;
         Ok(header.to_string()) //if extended header is needed, we need a wrapper
     }
@@ -150,6 +418,7 @@ impl LuminaNode {
         let node = self.node.read().await;
         let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
         let hash = Hash::from_str(&hash).map_err(|e| LuminaError::invalid_hash(e.to_string()))?;
+        wait_for_peer(node, self.config.peer_wait_timeout()).await?;
         let header = node.request_header_by_hash(&hash).await?;
         Ok(header.to_string()) //if extended header is needed, we need a wrapper
     }
@@ -158,6 +427,7 @@ impl LuminaNode {
     pub async fn request_header_by_height(&self, height: u64) -> Result<String> {
         let node = self.node.read().await;
         let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        wait_for_peer(node, self.config.peer_wait_timeout()).await?;
         let header = node.request_header_by_height(height).await?;
         Ok(header.to_string())
     }
@@ -175,6 +445,7 @@ impl LuminaNode {
         let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
         let from: ExtendedHeader = serde_json::from_str(&from)
             .map_err(|e| LuminaError::invalid_header(format!("Invalid header JSON: {}", e)))?;
+        wait_for_peer(node, self.config.peer_wait_timeout()).await?;
         let headers = node.request_verified_headers(&from, amount).await?;
         Ok(headers.into_iter().map(|h| h.to_string()).collect())
     }
@@ -187,6 +458,90 @@ impl LuminaNode {
         Ok(info.into())
     }
 
+    /// Gets the set of heights of the stored headers.
+    ///
+    /// Use it to check many heights at once, as [`StoredHeights::contains`] doesn't need to
+    /// scan the ranges reported by [`LuminaNode::syncer_info`].
+    pub async fn stored_heights_bitmap(&self) -> Result<Arc<StoredHeights>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        let info = node.syncer_info().await?;
+        Ok(Arc::new(StoredHeights::from(&info.stored_headers)))
+    }
+
+    /// Gets the wall-clock times of the lowest and highest stored headers, or `None` if
+    /// no header is stored.
+    pub async fn stored_time_range(&self) -> Result<Option<TimeRange>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        self.ensure_readable(node)?;
+
+        loop {
+            let stored_headers = node.syncer_info().await?.stored_headers;
+            let (Some(tail), Some(head)) = (stored_headers.tail(), stored_headers.head()) else {
+                return Ok(None);
+            };
+
+            let start = node.get_header_by_height(tail).await;
+            let end = node.get_header_by_height(head).await;
+
+            match (start, end) {
+                (Ok(start), Ok(end)) => {
+                    return Ok(Some(TimeRange {
+                        start_unix_ms: BlockTime::from(&start).unix_ms,
+                        end_unix_ms: BlockTime::from(&end).unix_ms,
+                    }));
+                }
+                // pruned since the info was read
+                (Err(NodeError::Store(StoreError::NotFound)), _)
+                | (_, Err(NodeError::Store(StoreError::NotFound))) => continue,
+                (Err(e), _) | (_, Err(e)) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Pins the height, so that its header is kept even once it leaves the pruning window.
+    ///
+    /// Pins are persisted in the store, so they survive restarts of the node.
+    pub async fn pin_height(&self, height: u64) -> Result<()> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        Ok(node.pin_height(height).await?)
+    }
+
+    /// Unpins the height, allowing its header to be pruned again.
+    pub async fn unpin_height(&self, height: u64) -> Result<()> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        Ok(node.unpin_height(height).await?)
+    }
+
+    /// Gets the pinned heights, in ascending order.
+    pub async fn pinned_heights(&self) -> Result<Vec<u64>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        Ok(node.get_pinned_heights().await?.collect())
+    }
+
+    /// Compares the subjective head of the node with the latest block of the trusted
+    /// consensus node at the gRPC endpoint.
+    ///
+    /// Blocks are compared at the lower of the two heads, so a trusted node lagging
+    /// behind or running ahead of this one doesn't count as a divergence.
+    pub async fn cross_check_head(
+        self: Arc<Self>,
+        grpc_endpoint: String,
+    ) -> Result<CrossCheckResult> {
+        let node_head_height = {
+            let node = self.node.read().await;
+            let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+            node.syncer_info().await?.subjective_head
+        };
+        let trusted = connect_grpc(grpc_endpoint).await?;
+
+        cross_check(&trusted, &Arc::downgrade(&self), node_head_height).await
+    }
+
     /// Gets the latest header announced in the network.
     pub async fn get_network_head_header(&self) -> Result<String> {
         let node = self.node.read().await;
@@ -198,18 +553,48 @@ impl LuminaNode {
         )
     }
 
+    /// Gets the details of the header at the subjective head, i.e. the latest header
+    /// announced in the network and verified by the node.
+    ///
+    /// Returns `None` if no head is known yet.
+    pub async fn head_header(&self) -> Result<Option<ExtendedHeaderRecord>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        let header = node.get_network_head_header().await?;
+        header.as_ref().map(TryInto::try_into).transpose()
+    }
+
     /// Gets the latest locally synced header.
     pub async fn get_local_head_header(&self) -> Result<String> {
         let node = self.node.read().await;
         let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        self.ensure_readable(node)?;
         let header = node.get_local_head_header().await?;
         Ok(header.to_string())
     }
 
+    /// Checks if the latest locally synced header is older than `max_age_secs`, e.g. to warn
+    /// that the shown data may be outdated. Returns `true` if nothing was synced yet.
+    pub async fn is_head_stale(&self, max_age_secs: u64) -> Result<bool> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+
+        match node.get_local_head_header().await {
+            Ok(head) => Ok(is_stale(
+                BlockTime::from(&head),
+                self.clock.unix_ms(),
+                Duration::from_secs(max_age_secs),
+            )),
+            Err(NodeError::Store(StoreError::NotFound)) => Ok(true),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// Get a synced header for the block with a given hash.
     pub async fn get_header_by_hash(&self, hash: String) -> Result<String> {
         let node = self.node.read().await;
         let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        self.ensure_readable(node)?;
         let hash = Hash::from_str(&hash).map_err(|e| LuminaError::invalid_hash(e.to_string()))?;
         let header = node.get_header_by_hash(&hash).await?;
         Ok(header.to_string())
@@ -219,10 +604,111 @@ impl LuminaNode {
     pub async fn get_header_by_height(&self, height: u64) -> Result<String> {
         let node = self.node.read().await;
         let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        self.ensure_readable(node)?;
         let header = node.get_header_by_height(height).await?;
         Ok(header.to_string())
     }
 
+    /// Get the data availability header of the synced block with a given height.
+    ///
+    /// Returns [`LuminaError::HeightNotLocal`] if the header isn't stored.
+    pub async fn get_dah(&self, height: u64) -> Result<DataAvailabilityHeader> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        self.ensure_readable(node)?;
+
+        match node.get_header_by_height(height).await {
+            Ok(header) => Ok((&header.dah).into()),
+            Err(NodeError::Store(StoreError::NotFound)) => {
+                Err(LuminaError::HeightNotLocal { height })
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Get the width of the extended data square of the synced block with a given height,
+    /// the same as reported by the `SamplingStarted` event.
+    ///
+    /// Returns [`LuminaError::HeightNotLocal`] if the header isn't stored.
+    pub async fn square_width(&self, height: u64) -> Result<u16> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        self.ensure_readable(node)?;
+
+        match node.get_header_by_height(height).await {
+            Ok(header) => Ok(header.dah.square_width()),
+            Err(NodeError::Store(StoreError::NotFound)) => {
+                Err(LuminaError::HeightNotLocal { height })
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Get the raw namespaces which have data in the synced block with a given height,
+    /// sorted and deduplicated. Reserved namespaces, e.g. of the transactions, are omitted.
+    ///
+    /// Namespaces of the rows holding a single namespace are read from their row roots, the
+    /// rows holding more of them are fetched from the network to read the namespaces of their
+    /// shares. Fails with [`LuminaError::HeightNotLocal`] if the header isn't stored, or with
+    /// [`LuminaError::NoPeersAvailable`] if a row is needed and no peer connects in time.
+    pub async fn get_namespaces_at_height(&self, height: u64) -> Result<Vec<Vec<u8>>> {
+        let dah = {
+            let node = self.node.read().await;
+            let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+            self.ensure_readable(node)?;
+
+            match node.get_header_by_height(height).await {
+                Ok(header) => header.dah,
+                Err(NodeError::Store(StoreError::NotFound)) => {
+                    return Err(LuminaError::HeightNotLocal { height })
+                }
+                Err(e) => return Err(e.into()),
+            }
+        };
+
+        let namespaces = block_namespaces(self, height, &dah).await?;
+        Ok(namespaces
+            .into_iter()
+            .map(|namespace| namespace.as_bytes().to_vec())
+            .collect())
+    }
+
+    /// Get a header for the block with a given height, following the read policy
+    /// if it isn't synced yet.
+    pub async fn get_header_by_height_with_policy(
+        &self,
+        height: u64,
+        policy: ReadPolicy,
+    ) -> Result<String> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        self.ensure_readable(node)?;
+
+        let header = header_with_policy(node, height, policy).await?;
+        Ok(header.to_string())
+    }
+
+    /// Gets the blobs of the namespace in the block with a given height, following the read
+    /// policy if its header isn't synced yet.
+    ///
+    /// Blobs are fetched from the network for the header, failing with
+    /// [`LuminaError::NoPeersAvailable`] if no peer connects in time.
+    pub async fn get_blobs_with_policy(
+        &self,
+        height: u64,
+        namespace: Vec<u8>,
+        policy: ReadPolicy,
+    ) -> Result<BlockBlobs> {
+        let namespace = Namespace::from_raw(&namespace)
+            .map_err(|e| LuminaError::invalid_namespace(e.to_string()))?;
+        let blobs = self.blobs_with_policy(height, namespace, policy).await?;
+
+        Ok(BlockBlobs {
+            height,
+            blobs: blobs.into_iter().map(|blob| blob.data).collect(),
+        })
+    }
+
     /// Gets headers from the given heights range.
     ///
     /// If start of the range is undefined (None), the first returned header will be of height 1.
@@ -237,6 +723,7 @@ impl LuminaNode {
     ) -> Result<Vec<String>> {
         let node = self.node.read().await;
         let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        self.ensure_readable(node)?;
 
         let headers = match (start_height, end_height) {
             (None, None) => node.get_headers(..).await,
@@ -248,29 +735,3133 @@ impl LuminaNode {
         Ok(headers.into_iter().map(|h| h.to_string()).collect())
     }
 
+    /// Gets the stored headers of the `from..=to` range, together with the heights of the
+    /// range whose headers aren't stored.
+    ///
+    /// Unlike [`LuminaNode::get_headers`], missing headers don't fail the whole range, so
+    /// the caller can decide whether to fetch the gaps.
+    pub async fn get_headers_available(&self, from: u64, to: u64) -> Result<PartialHeaders> {
+        if from == 0 || from > to {
+            return Err(LuminaError::invalid_range(format!(
+                "{from}..={to} is empty"
+            )));
+        }
+
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        self.ensure_readable(node)?;
+
+        let requested = BlockRanges::from_vec([from..=to].into_iter().collect())
+            .expect("single non-empty range");
+        let stored_headers = node.syncer_info().await?.stored_headers;
+        let missing = requested.clone() - stored_headers;
+        let present = requested - &missing;
+
+        let mut headers = Vec::new();
+        for range in present.into_inner() {
+            for header in node.get_headers(range).await? {
+                headers.push(header.to_string());
+            }
+        }
+
+        Ok(PartialHeaders {
+            headers,
+            missing_heights: missing.collect(),
+        })
+    }
+
+    /// Checks whether the stored headers in the inclusive range of heights form a chain,
+    /// each of them committing to the hash of the previous one.
+    ///
+    /// Returns `false` on the first broken link and [`LuminaError::HeightNotLocal`] if any
+    /// of the headers isn't stored.
+    pub async fn verify_header_range(&self, from: u64, to: u64) -> Result<bool> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        self.ensure_readable(node)?;
+
+        if from > to {
+            return Err(LuminaError::InvalidRange {
+                msg: format!("start {from} is above the end {to}"),
+            });
+        }
+
+        let get_header = |height| async move {
+            match node.get_header_by_height(height).await {
+                Ok(header) => Ok(header),
+                Err(NodeError::Store(StoreError::NotFound)) => {
+                    Err(LuminaError::HeightNotLocal { height })
+                }
+                Err(e) => Err(e.into()),
+            }
+        };
+
+        let mut prev = get_header(from).await?;
+        for height in from + 1..=to {
+            let header = get_header(height).await?;
+            if !is_linked(&prev, &header) {
+                return Ok(false);
+            }
+            prev = header;
+        }
+
+        Ok(true)
+    }
+
+    /// Verifies the header obtained out of band, e.g. from a trusted API, and stores it,
+    /// to jump-start syncing from it.
+    ///
+    /// Takes the JSON serialized ExtendedHeader. Header above the local head is verified
+    /// against it, any other one must be adjacent to the stored headers. Returns the height
+    /// of the stored header.
+    pub async fn inject_trusted_header(&self, header_bytes: Vec<u8>) -> Result<u64> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        ensure_writable(node, false).await?;
+
+        let header: ExtendedHeader = serde_json::from_slice(&header_bytes)
+            .map_err(|e| LuminaError::invalid_header(format!("Invalid header JSON: {}", e)))?;
+        let height = header.height().value();
+
+        match node.insert_header(header).await {
+            Ok(()) => Ok(height),
+            Err(NodeError::Store(StoreError::InsertionFailed(e))) => {
+                Err(LuminaError::invalid_header(e.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// Gets data sampling metadata for a height.
     ///
     /// Returns serialized SamplingMetadata string if metadata exists for the height.
     pub async fn get_sampling_metadata(&self, height: u64) -> Result<Option<String>> {
         let node = self.node.read().await;
         let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        self.ensure_readable(node)?;
 
         let metadata = node.get_sampling_metadata(height).await?;
         Ok(metadata.map(|m| serde_json::to_string(&m).unwrap()))
     }
 
-    /// Returns the next event from the node's event channel.
-    pub async fn next_event(&self) -> Result<NodeEvent> {
-        let mut events_subscriber = self.events_subscriber.lock().await;
-        match events_subscriber.as_mut() {
-            Some(subscriber) => {
-                let event = subscriber
-                    .recv()
-                    .await
-                    .map_err(|_| LuminaError::NodeNotRunning)?;
-                Ok(event.event.into())
-            }
-            None => Err(LuminaError::NodeNotRunning),
+    /// Gets the stored result of the data sampling of the block at the height, without
+    /// starting a new sampling.
+    ///
+    /// Returns `None` if the block wasn't sampled yet.
+    pub async fn sampling_result(&self, height: u64) -> Result<Option<SampleOutcome>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        self.ensure_readable(node)?;
+
+        let Some(metadata) = node.get_sampling_metadata(height).await? else {
+            return Ok(None);
+        };
+
+        Ok(SampleOutcome::new(&metadata, self.sampling_took(height)))
+    }
+
+    /// Samples the blocks of the `from..=to` range and returns their outcomes, in the order
+    /// of heights, once all of them are sampled.
+    ///
+    /// Blocks which already have the result of sampling are skipped, unless `force` is set.
+    /// They are sampled with the same concurrency as the automatic sampling, and only when
+    /// the node has some peers connected. At most 128 heights can be sampled at once.
+    /// Waiting for the sampling can be aborted with [`LuminaNode::cancel_operation`].
+    pub async fn sample_range(
+        &self,
+        from: u64,
+        to: u64,
+        force: bool,
+    ) -> Result<Vec<SampleOutcome>> {
+        if from == 0 || from > to {
+            return Err(LuminaError::invalid_range(format!(
+                "{from}..={to} is empty"
+            )));
+        }
+        if to - from >= MAX_SAMPLE_RANGE_LEN {
+            return Err(LuminaError::invalid_range(format!(
+                "{from}..={to} is longer than {MAX_SAMPLE_RANGE_LEN} heights"
+            )));
+        }
+
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+
+        let stored_headers = node.syncer_info().await?.stored_headers;
+        if let Some(height) = (from..=to).find(|height| !stored_headers.contains(*height)) {
+            return Err(LuminaError::HeightNotLocal { height });
+        }
+
+        let operation = self.operations.start(OperationKind::SampleRange);
+        operation
+            .run(async { Ok(node.sample_range(from..=to, force).await?) })
+            .await?;
+
+        let mut outcomes = Vec::with_capacity((to - from + 1) as usize);
+        for height in from..=to {
+            // block could be pruned in the meantime
+            let outcome = node
+                .get_sampling_metadata(height)
+                .await?
+                .and_then(|metadata| SampleOutcome::new(&metadata, self.sampling_took(height)))
+                .ok_or(LuminaError::HeightNotLocal { height })?;
+            outcomes.push(outcome);
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Gets the limits of the blob sizes for the app version of the local head.
+    ///
+    /// Limits are derived from the maximum square size set by the governance of the
+    /// network, queried from the consensus node of [`NodeConfig::tx_config`] once and
+    /// kept for the lifetime of the node.
+    pub async fn max_blob_size(&self) -> Result<BlobSizeLimits> {
+        let app_version = {
+            let node = self.node.read().await;
+            let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+            node.get_local_head_header().await?.app_version()?
+        };
+        let params = self.blob_params().await?;
+
+        Ok(BlobSizeLimits::new(params.gov_max_square_size, app_version))
+    }
+
+    /// Computes the commitments of the blobs, in order, which the transaction submitting
+    /// them would include, for the app version of the local head. Nothing is broadcasted.
+    ///
+    /// Fails with [`LuminaError::InvalidNamespace`] or [`LuminaError::InvalidBlob`] if any
+    /// of the blobs is invalid.
+    pub async fn preview_commitments(&self, blobs: Vec<BlobInput>) -> Result<Vec<Arc<Commitment>>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+
+        let head = node.get_local_head_header().await?;
+        let app_version = head.app_version()?;
+
+        blobs
+            .into_iter()
+            .map(|input| {
+                let blob = input.into_blob(app_version)?;
+                Ok(Arc::new(blob.commitment.into()))
+            })
+            .collect()
+    }
+
+    /// Submits the blobs in a single transaction paying for all of them, through the
+    /// consensus node and with the account of [`NodeConfig::tx_config`], and waits for
+    /// it to be committed. Returns the height of the block including the blobs.
+    ///
+    /// Doesn't need the node to be running, but a running one must be synced up to the
+    /// network head, otherwise it fails with [`LuminaError::NodeReadOnly`]. Fails with
+    /// [`LuminaError::InvalidBlob`] if there are no blobs, [`LuminaError::InvalidNamespace`]
+    /// or [`LuminaError::InvalidBlob`] if any of them is invalid, and
+    /// [`LuminaError::TxFailed`] if the transaction is rejected.
+    pub async fn submit_blobs(&self, blobs: Vec<BlobInput>) -> Result<u64> {
+        if let Some(node) = self.node.read().await.as_ref() {
+            ensure_writable(node, true).await?;
         }
+
+        submit_blobs(self.tx_config()?, blobs).await
+    }
+
+    /// Gets data sampling statistics of the stored blocks containing the given namespace.
+    ///
+    /// Stored headers are read in batches of [`NodeConfig::batch_size`], so the memory used
+    /// doesn't grow with the store. The rows of the sampled blocks which may contain the
+    /// namespace, but hold more namespaces, are fetched from the network to confirm it,
+    /// failing with [`LuminaError::NoPeersAvailable`] if no peer connects in time.
+    pub async fn namespace_sampling_stats(&self, namespace: Vec<u8>) -> Result<NamespaceStats> {
+        let stored_headers = {
+            let node = self.node.read().await;
+            let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+            self.ensure_readable(node)?;
+            node.syncer_info().await?.stored_headers
+        };
+        let namespace = Namespace::from_raw(&namespace)
+            .map_err(|e| LuminaError::invalid_namespace(e.to_string()))?;
+
+        let batch_size = self.config.batch_size().max(1);
+        let mut stats = NamespaceStats::default();
+
+        for range in stored_headers.into_inner() {
+            let mut start = *range.start();
+            while start <= *range.end() {
+                let end = start.saturating_add(batch_size - 1).min(*range.end());
+                for (header, metadata) in self.sampled_headers(start..=end).await? {
+                    let height = header.height().value();
+                    if block_contains_namespace(self, height, &header.dah, namespace).await? {
+                        stats.record(&metadata);
+                    }
+                }
+                start = end + 1;
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Waits until headers are synced up to the network head, reporting the progress
+    /// to the given callback.
+    ///
+    /// Progress is measured by the highest contiguous range of the stored headers, which
+    /// the syncer extends up to the network head and backfills down to the start of the
+    /// syncing window. Having just the header of the network head doesn't count as synced.
+    ///
+    /// Syncing itself happens in the background for as long as the node is running, this only
+    /// observes it. Waiting can be aborted with [`LuminaNode::cancel_sync`] or
+    /// [`LuminaNode::cancel_operation`], in which case calling it again continues from the
+    /// already stored headers.
+    pub async fn sync_to_head(&self, progress: Box<dyn SyncProgress>) -> Result<()> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        ensure_writable(node, false).await?;
+
+        let operation = self.operations.start(OperationKind::SyncToHead);
+        let cancellation = operation.cancellation().clone();
+        if let Some(previous) = self
+            .sync_cancellation
+            .lock()
+            .await
+            .replace(cancellation.clone())
+        {
+            previous.cancel();
+        }
+
+        let poll = || async {
+            let (info, head_range) = head_range(node).await?;
+            // subjective head is unknown until the first header from the network arrives
+            if info.subjective_head == 0 {
+                return Ok(None);
+            }
+            let missing = missing_headers(&info, head_range, self.syncing_window_start_ms());
+
+            Ok(Some(SyncProgressInfo::new(
+                head_range,
+                info.subjective_head,
+                missing,
+            )))
+        };
+
+        drive_sync(
+            poll,
+            progress.as_ref(),
+            &cancellation,
+            SYNC_PROGRESS_INTERVAL,
+        )
+        .await
+    }
+
+    /// Estimates in how many milliseconds the syncing catches up with the network head,
+    /// from the rate of fetching the headers of the highest contiguous range of the stored
+    /// headers over the last minute, and the [`LuminaNode::sync_gap`] still missing from it.
+    ///
+    /// Returns `None` if no headers were fetched recently, so the rate is unknown, or if
+    /// the node is already synced.
+    pub async fn estimated_sync_completion_ms(&self) -> Result<Option<u64>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+
+        let (info, head_range) = head_range(node).await?;
+        let Some(range) = head_range else {
+            // nothing synced yet, so nothing fetched either
+            return Ok(None);
+        };
+        let remaining = missing_headers(&info, head_range, self.syncing_window_start_ms());
+        let rate = self
+            .recorded_events
+            .lock()
+            .expect("poisoned lock")
+            .fetches
+            .rate(
+                FETCH_RATE_WINDOW,
+                self.clock.now(),
+                &(range.lowest.height..=range.highest.height),
+            );
+
+        Ok(estimate_sync_completion(remaining, rate))
+    }
+
+    /// Gets the number of headers missing from the highest contiguous range of the stored
+    /// headers: the ones above it up to the syncing target, the latest height seen in the
+    /// network, and the ones below it down to the start of the syncing window.
+    ///
+    /// Like in [`LuminaNode::sync_to_head`], having just the header of the network head
+    /// doesn't count as synced.
+    pub async fn sync_gap(&self) -> Result<u64> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+
+        let (info, head_range) = head_range(node).await?;
+        Ok(missing_headers(
+            &info,
+            head_range,
+            self.syncing_window_start_ms(),
+        ))
+    }
+
+    /// Checks if at most `tolerance_heights` headers are missing from the highest contiguous
+    /// range of the stored headers, counted like in [`LuminaNode::sync_gap`].
+    ///
+    /// With the tolerance of zero, the node is synced only if it stored all the headers of
+    /// the syncing window up to the syncing target. Returns `false` if no headers were
+    /// synced yet.
+    pub async fn is_synced(&self, tolerance_heights: u64) -> Result<bool> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+
+        let (info, head_range) = head_range(node).await?;
+        Ok(is_synced(
+            &info,
+            head_range,
+            self.syncing_window_start_ms(),
+            tolerance_heights,
+        ))
+    }
+
+    /// Checks if the syncing is stuck, i.e. the network head keeps advancing while the
+    /// stored headers don't catch up with it.
+    ///
+    /// Syncing is stuck if the [`LuminaNode::sync_gap`] was at least `threshold` headers
+    /// during the whole last `window_secs`, without shrinking over it. The gap is sampled
+    /// in the background while the node is running, so it reports `false` until the node
+    /// has been running for longer than the window.
+    pub async fn is_sync_stuck(&self, threshold: u64, window_secs: u64) -> Result<bool> {
+        let node = self.node.read().await;
+        node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+
+        Ok(self
+            .recorded_events
+            .lock()
+            .expect("poisoned lock")
+            .sync_gaps
+            .is_stuck(
+                threshold,
+                Duration::from_secs(window_secs),
+                self.clock.now(),
+            ))
+    }
+
+    /// Waits until all the headers of blocks produced since the given unix time,
+    /// in milliseconds, are synced.
+    ///
+    /// Starting height is estimated from the average block time of the stored headers.
+    /// Times older than the syncing window are clamped to its beginning, which is reported
+    /// in the returned range. Waiting can be aborted with [`LuminaNode::cancel_operation`],
+    /// and fails with [`LuminaError::Timeout`] if the headers aren't synced within 10 minutes.
+    pub async fn sync_since(&self, unix_ms: u64) -> Result<SyncedRange> {
+        let (reference, head) = {
+            let node = self.node.read().await;
+            let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+            reference_and_head_block_times(node).await?
+        };
+        let window_start_ms = self.syncing_window_start_ms();
+
+        let (start, clamped) = estimate_height_since(reference, head, unix_ms, window_start_ms);
+
+        let operation = self.operations.start(OperationKind::SyncSince);
+        let wait = async {
+            // the node isn't locked between the polls, so that it can be stopped meanwhile
+            while !self.is_synced_since(start, head.height).await? {
+                tokio::time::sleep(SYNC_PROGRESS_INTERVAL).await;
+            }
+            Ok(())
+        };
+        operation
+            .run(async {
+                timeout(SYNC_SINCE_TIMEOUT, wait).await.map_err(|_| {
+                    LuminaError::timeout(format!(
+                        "headers {start}..={} weren't synced within {SYNC_SINCE_TIMEOUT:?}",
+                        head.height
+                    ))
+                })?
+            })
+            .await?;
+
+        Ok(SyncedRange::new(start, head.height, clamped))
+    }
+
+    /// Gets the approximate range of heights within the sampling window, ending at the
+    /// local head.
+    ///
+    /// Heights are estimated from the average block time of the stored headers. Returns an
+    /// empty range if no headers were synced yet, or if the node doesn't sample the blocks.
+    pub async fn sampling_window_heights(&self) -> Result<BlockRange> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        let Some(window) = self.config.sampling_window() else {
+            return Ok(BlockRange::empty());
+        };
+        window_heights(node, window).await
+    }
+
+    /// Gets the approximate range of heights within the syncing window, ending at the
+    /// local head.
+    ///
+    /// Heights are estimated from the average block time of the stored headers. Returns an
+    /// empty range if no headers were synced yet.
+    pub async fn syncing_window_heights(&self) -> Result<BlockRange> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        window_heights(node, self.config.syncing_window()).await
+    }
+
+    /// Estimates how many bytes on disk the blocks of the syncing window of `window_secs`
+    /// would take, e.g. before choosing a long window on a device with little storage.
+    ///
+    /// The estimate assumes that all the blocks within the window are synced and sampled,
+    /// and that they are produced at the average block time of the stored headers, or every
+    /// 6 seconds until any are stored. The average size of a block is measured from the
+    /// store once it holds at least 1000 blocks, until then a mainnet block of 48 KiB with
+    /// its samples is assumed.
+    pub async fn estimate_window_disk_bytes(&self, window_secs: u32) -> Result<u64> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+
+        let block_time = match reference_and_head_block_times(node).await {
+            Ok((reference, head)) => average_block_time(reference, head),
+            // nothing synced yet
+            Err(NodeError::Store(StoreError::NotFound)) => None,
+            Err(e) => return Err(e.into()),
+        };
+        let block_bytes = self.measured_block_disk_bytes(node).await?;
+
+        Ok(estimate_window_disk_bytes(
+            Duration::from_secs(window_secs.into()),
+            block_time.unwrap_or(DEFAULT_BLOCK_TIME),
+            block_bytes.unwrap_or(DEFAULT_BLOCK_DISK_BYTES),
+        ))
+    }
+
+    /// Cancels the pending [`LuminaNode::sync_to_head`] call, if any.
+    pub async fn cancel_sync(&self) {
+        if let Some(cancellation) = self.sync_cancellation.lock().await.take() {
+            cancellation.cancel();
+        }
+    }
+
+    /// Gets the long-running operations in progress, in the order they were started.
+    pub fn operations(&self) -> Vec<OperationHandle> {
+        self.operations.list()
+    }
+
+    /// Cancels the long-running operation in progress, which then returns the
+    /// `OperationCancelled` error, or `SyncCancelled` for [`LuminaNode::sync_to_head`].
+    ///
+    /// Returns `false` if there is no such operation in progress.
+    pub fn cancel_operation(&self, id: u64) -> bool {
+        self.operations.cancel(id)
+    }
+
+    /// Gets the ranges of headers currently being fetched by the syncer.
+    ///
+    /// Use it to show the progress of the fetches which started before subscribing
+    /// to the `FetchingHeadersStarted` and `FetchingHeadersFinished` events.
+    pub async fn in_flight_fetches(&self) -> Result<Vec<BlockRange>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        let ranges = node.in_flight_header_fetches().await?;
+        Ok(ranges.into_iter().map(BlockRange::from).collect())
+    }
+
+    /// Gets the last header received for an already synced height, which conflicted with the
+    /// stored one. Use it together with the `ReorgDetected` event to track the finality.
+    pub async fn last_reorg(&self) -> Result<Option<ReorgInfo>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        Ok(node.last_reorg().await?.map(ReorgInfo::from))
+    }
+
+    /// Pause syncing of the headers, e.g. while on a metered network.
+    ///
+    /// The node stays connected and keeps answering reads from the store. The ongoing
+    /// batch of headers is cancelled and no new headers are requested from the network
+    /// until [`LuminaNode::resume_sync`] is called.
+    pub async fn pause_sync(&self) -> Result<()> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        Ok(node.pause_syncing().await?)
+    }
+
+    /// Resume syncing of the headers paused with [`LuminaNode::pause_sync`].
+    pub async fn resume_sync(&self) -> Result<()> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        Ok(node.resume_syncing().await?)
+    }
+
+    /// Returns `true` if syncing of the headers is paused.
+    pub async fn sync_paused(&self) -> Result<bool> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        Ok(node.is_syncing_paused().await?)
+    }
+
+    /// Make all the pending writes to the store durable, without stopping the node.
+    ///
+    /// Call it e.g. before the app goes to the background, where it may be killed abruptly.
+    pub async fn flush_store(&self) -> Result<()> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        Ok(node.flush_store().await?)
+    }
+
+    /// Removes the stores of the networks other than the kept ones from the base path,
+    /// e.g. left behind after switching networks. Returns the paths of the removed stores.
+    ///
+    /// Fails without removing anything if the store of the running node would be removed.
+    pub async fn cleanup_stale_stores(&self, keep: Vec<Network>) -> Result<Vec<String>> {
+        // holding the lock prevents the node from starting in the meantime
+        let node = self.node.read().await;
+        let keep: Vec<_> = keep
+            .iter()
+            .map(|network| store_name(network.id()))
+            .collect();
+        let base_path = PathBuf::from(&self.config.base_path);
+
+        let own_store = store_name(self.config.network.id());
+        if node.is_some() && !keep.contains(&own_store) {
+            return Err(LuminaError::StorageLocked {
+                path: base_path.join(own_store).to_string_lossy().into_owned(),
+            });
+        }
+
+        spawn_blocking(move || remove_stale_stores(&base_path, &keep))
+            .await
+            .map_err(|e| LuminaError::storage(format!("Failed to remove stores: {}", e)))?
+    }
+
+    /// Deliver only the events emitted from the source files whose path contains `filter`,
+    /// e.g. `"daser"`, from [`LuminaNode::next_event`]. Passing `None` removes the filter.
+    pub async fn set_event_source_filter(&self, filter: Option<String>) -> Result<()> {
+        let mut events_subscriber = self.events_subscriber.lock().await;
+        let subscriber = events_subscriber
+            .as_mut()
+            .ok_or(LuminaError::NodeNotRunning)?;
+        subscriber.set_source_filter(filter);
+        Ok(())
+    }
+
+    /// Returns up to `limit` of the most recent events emitted by the node, from the oldest
+    /// to the newest. The number of the events kept is set by
+    /// [`NodeConfig::event_history_size`].
+    pub async fn recent_events(&self, limit: u32) -> Result<Vec<NodeEventInfo>> {
+        if self.node.read().await.is_none() {
+            return Err(LuminaError::NodeNotRunning);
+        }
+
+        let events = self.recorded_events.lock().expect("poisoned lock");
+        Ok(events.history.recent(limit as usize))
+    }
+
+    /// Gets a snapshot of the state of the node to be attached to bug reports.
+    ///
+    /// The secret key of the node's identity is never included, only its peer id.
+    pub async fn diagnostics(&self) -> Result<DiagnosticsReport> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+
+        let network_info = NetworkInfo::new(
+            node.network_info().await?,
+            node.listeners().await?,
+            &node.peer_tracker_info(),
+        );
+        let syncing_info = node.syncer_info().await?.into();
+        let pinned_heights = node.get_pinned_heights().await?.collect();
+        let (last_fatal_error, recent_events) = {
+            let events = self.recorded_events.lock().expect("poisoned lock");
+            (
+                events.last_fatal_error.clone(),
+                events.history.recent(DIAGNOSTICS_EVENTS),
+            )
+        };
+
+        Ok(DiagnosticsReport {
+            network_id: self.config.network.id().to_owned(),
+            local_peer_id: node.local_peer_id().to_base58(),
+            effective_config: self.effective_config(),
+            store_schema_version: RedbStore::SCHEMA_VERSION,
+            syncing_info,
+            pruning_backlog: node.pruning_backlog(),
+            pinned_heights,
+            storage_write_latency: node.storage_write_latency().await.unwrap_or_default(),
+            network_info,
+            peer_tracker_info: node.peer_tracker_info(),
+            bandwidth_stats: node.bandwidth_stats(),
+            last_fatal_error,
+            recent_events,
+        })
+    }
+
+    /// Returns the next event from the node's event channel.
+    pub async fn next_event(&self) -> Result<NodeEvent> {
+        let mut events_subscriber = self.events_subscriber.lock().await;
+        match events_subscriber.as_mut() {
+            Some(subscriber) => {
+                let event = subscriber
+                    .recv()
+                    .await
+                    .map_err(|_| LuminaError::NodeNotRunning)?;
+                Ok(event.event.into())
+            }
+            None => Err(LuminaError::NodeNotRunning),
+        }
+    }
+
+    /// Delivers the events of the node to the listener in batches, until the node stops.
+    ///
+    /// Crossing the FFI boundary once per batch is much cheaper than once per event under
+    /// high event rates. The listener receives all the events, independently of
+    /// [`LuminaNode::next_event`] and its source filter.
+    pub async fn subscribe_events(
+        &self,
+        listener: Box<dyn NodeEventListener>,
+        batching: EventBatchConfig,
+    ) -> Result<()> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+
+        let events = futures::stream::unfold(node.event_subscriber(), |mut subscriber| async {
+            let info = subscriber.recv().await.ok()?;
+            Some((info.into(), subscriber))
+        });
+
+        tokio::spawn(async move { deliver_batched(events, &*listener, batching).await });
+
+        Ok(())
+    }
+
+    /// Notifies the listener about each block containing blobs of the namespace, once
+    /// the block is sampled and accepted, until the node stops.
+    ///
+    /// Blobs are fetched from the network to count them, blocks whose blobs couldn't be
+    /// fetched are skipped.
+    pub async fn subscribe_namespace(
+        self: Arc<Self>,
+        namespace: Vec<u8>,
+        listener: Box<dyn NamespaceListener>,
+    ) -> Result<()> {
+        let namespace = Namespace::from_raw(&namespace)
+            .map_err(|e| LuminaError::invalid_namespace(e.to_string()))?;
+        let subscriber = {
+            let node = self.node.read().await;
+            let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+            node.event_subscriber()
+        };
+
+        let heights = futures::stream::unfold(subscriber, |mut subscriber| async {
+            loop {
+                let info = subscriber.recv().await.ok()?;
+                if let LuminaNodeEvent::SamplingFinished {
+                    height,
+                    accepted: true,
+                    ..
+                } = info.event
+                {
+                    return Some((height, subscriber));
+                }
+            }
+        });
+        // the subscription mustn't keep the node alive
+        let source = Arc::downgrade(&self);
+
+        tokio::spawn(async move {
+            deliver_namespace_data(heights, namespace, &source, &*listener).await
+        });
+
+        Ok(())
+    }
+
+    /// Streams the blobs of the namespace in the blocks of the `from..=to` range, in the
+    /// order of heights.
+    ///
+    /// Blobs are fetched from the network for the stored headers, at most `capacity` blocks
+    /// ahead of the consumer, so fetching waits while the consumer lags behind. A capacity
+    /// of `0` is treated as `1`. The stream ends after the first block which couldn't be
+    /// fetched, with [`LuminaError::HeightNotLocal`] if its header isn't stored, or with
+    /// [`LuminaError::NoPeersAvailable`] if no peer connects in time.
+    pub async fn get_blobs_in_range(
+        self: Arc<Self>,
+        namespace: Vec<u8>,
+        from: u64,
+        to: u64,
+        capacity: u32,
+    ) -> Result<Arc<BlobStream>> {
+        let namespace = Namespace::from_raw(&namespace)
+            .map_err(|e| LuminaError::invalid_namespace(e.to_string()))?;
+        if from == 0 || from > to {
+            return Err(LuminaError::invalid_range(format!(
+                "{from}..={to} is empty"
+            )));
+        }
+        if self.node.read().await.is_none() {
+            return Err(LuminaError::NodeNotRunning);
+        }
+
+        let (stream, sender) = BlobStream::new(capacity as usize);
+        // the stream mustn't keep the node alive
+        let source = Arc::downgrade(&self);
+
+        tokio::spawn(async move { produce_blobs(&source, namespace, from..=to, sender).await });
+
+        Ok(Arc::new(stream))
+    }
+
+    /// Delivers all the shares of the extended data square of the block at the given height
+    /// to the listener, in row-major order, returning once all of them are delivered.
+    ///
+    /// Rows are fetched from the network one at a time, only after the shares of the previous
+    /// row were delivered, so the whole square is never held in memory. Fails with
+    /// [`LuminaError::HeightNotLocal`] if the header isn't stored, or with
+    /// [`LuminaError::NoPeersAvailable`] if no peer connects in time.
+    pub async fn stream_block_shares(
+        &self,
+        height: u64,
+        listener: Box<dyn ShareListener>,
+    ) -> Result<()> {
+        self.stream_block_shares_with_policy(height, ReadPolicy::LocalOnly, listener)
+            .await
+    }
+
+    /// Delivers all the shares of the block like [`LuminaNode::stream_block_shares`],
+    /// following the read policy if the header of the block isn't synced yet.
+    pub async fn stream_block_shares_with_policy(
+        &self,
+        height: u64,
+        policy: ReadPolicy,
+        listener: Box<dyn ShareListener>,
+    ) -> Result<()> {
+        let square_width = {
+            let node = self.node.read().await;
+            let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+
+            let header = header_with_policy(node, height, policy).await?;
+            wait_for_peer(node, self.config.peer_wait_timeout()).await?;
+            header.dah.square_width()
+        };
+
+        deliver_shares(block_shares(self, height, square_width), &*listener).await
+    }
+
+    /// Checks whether a blob with the commitment is included in the namespace of the block
+    /// at the given height.
+    ///
+    /// Blobs are fetched from the network for the stored header, failing with
+    /// [`LuminaError::HeightNotLocal`] if the header isn't stored, or with
+    /// [`LuminaError::NoPeersAvailable`] if no peer connects in time.
+    pub async fn commitment_included(
+        &self,
+        height: u64,
+        namespace: Vec<u8>,
+        commitment: Vec<u8>,
+    ) -> Result<bool> {
+        let namespace = Namespace::from_raw(&namespace)
+            .map_err(|e| LuminaError::invalid_namespace(e.to_string()))?;
+        let commitment = Commitment::from_bytes(commitment)?.into();
+
+        commitment_included(self, height, namespace, &commitment).await
+    }
+}
+
+#[cfg(feature = "diagnostics")]
+#[uniffi::export(async_runtime = "tokio")]
+impl LuminaNode {
+    /// Replaces the events recorded by the running node with the given ones, e.g. captured
+    /// from [`LuminaNode::recent_events`] on another device, so that the queries derived from
+    /// the events reflect them instead.
+    ///
+    /// Events are replayed in their order, as if the last of them was received just now.
+    /// The node keeps recording its own events on top of them, so replay into a node without
+    /// peers to keep the stats deterministic.
+    pub async fn replay_events(&self, events: Vec<NodeEventInfo>) -> Result<()> {
+        if self.node.read().await.is_none() {
+            return Err(LuminaError::NodeNotRunning);
+        }
+
+        let now = self.clock.now();
+        let last_ms = events.iter().map(|info| info.time_unix_ms).max();
+
+        let mut recorded = self.recorded_events.lock().expect("poisoned lock");
+        *recorded = RecordedEvents {
+            history: EventHistory::new(self.config.event_history_size()),
+            ..Default::default()
+        };
+
+        for info in events {
+            let ago = Duration::from_millis(last_ms.unwrap_or(0) - info.time_unix_ms);
+            let at = now.checked_sub(ago).unwrap_or(now);
+            recorded.record(info, at);
+        }
+
+        Ok(())
+    }
+}
+
+impl LuminaNode {
+    /// Time of the start of the syncing window, in milliseconds since the unix epoch.
+    fn syncing_window_start_ms(&self) -> u64 {
+        syncing_window_start_ms(self.clock.as_ref(), self.config.syncing_window())
+    }
+
+    /// Fetch the blobs of the namespace in the block, getting its header as the read policy
+    /// says if it isn't stored.
+    async fn blobs_with_policy(
+        &self,
+        height: u64,
+        namespace: Namespace,
+        policy: ReadPolicy,
+    ) -> Result<Vec<Blob>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        self.ensure_readable(node)?;
+
+        let header = header_with_policy(node, height, policy).await?;
+        wait_for_peer(node, self.config.peer_wait_timeout()).await?;
+
+        Ok(node
+            .request_all_blobs(&header, namespace, Some(NAMESPACE_BLOBS_TIMEOUT))
+            .await?)
+    }
+
+    /// Stored headers of the range whose blocks finished sampling, with their sampling metadata.
+    async fn sampled_headers(
+        &self,
+        heights: RangeInclusive<u64>,
+    ) -> Result<Vec<(ExtendedHeader, SamplingMetadata)>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+
+        let mut sampled = Vec::new();
+        for header in node.get_headers(heights).await? {
+            let metadata = node.get_sampling_metadata(header.height().value()).await?;
+            if let Some(metadata) = metadata.filter(|m| m.status != SamplingStatus::Unknown) {
+                sampled.push((header, metadata));
+            }
+        }
+
+        Ok(sampled)
+    }
+
+    /// Cancel the long-running operations in progress, which keep the node locked for
+    /// reading until they finish.
+    async fn cancel_operations(&self) {
+        self.cancel_sync().await;
+        self.operations.cancel_all();
+    }
+
+    /// Check if the headers from `start` up to `end` are synced, or the syncer finished
+    /// backfilling them at the start of the syncing window, which `start` was estimated from.
+    async fn is_synced_since(&self, start: u64, end: u64) -> Result<bool> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+
+        let (_, head_range) = head_range(node).await?;
+        let window_start_ms = self.syncing_window_start_ms();
+
+        Ok(head_range.is_some_and(|HeadRange { lowest, highest }| {
+            highest.height >= end
+                && (lowest.height <= start.max(1) || lowest.unix_ms <= window_start_ms)
+        }))
+    }
+
+    /// How long the sampling of the block took, if it was sampled since the node started.
+    fn sampling_took(&self, height: u64) -> Option<Duration> {
+        self.recorded_events
+            .lock()
+            .expect("poisoned lock")
+            .sampling_durations
+            .get(height)
+    }
+
+    /// Average size on disk of the stored blocks, or `None` if too few of them are stored
+    /// to measure it.
+    async fn measured_block_disk_bytes(
+        &self,
+        node: &Node<RedbBlockstore, RedbStore>,
+    ) -> Result<Option<u64>> {
+        let stored_blocks: u64 = node
+            .syncer_info()
+            .await?
+            .stored_headers
+            .into_inner()
+            .iter()
+            .map(|range| range.end() - range.start() + 1)
+            .sum();
+        if stored_blocks < MIN_MEASURED_BLOCKS {
+            return Ok(None);
+        }
+
+        let store_path =
+            PathBuf::from(&self.config.base_path).join(store_name(self.config.network.id()));
+        let store_bytes = spawn_blocking(move || std::fs::metadata(store_path))
+            .await
+            .map_err(|e| LuminaError::storage(format!("Failed to read store size: {}", e)))?
+            .map_err(|e| LuminaError::storage(format!("Failed to read store size: {}", e)))?
+            .len();
+
+        Ok(Some(store_bytes / stored_blocks))
+    }
+
+    /// Config of submitting the transactions, failing if there is none.
+    fn tx_config(&self) -> Result<TxConfig> {
+        self.config
+            .tx_config
+            .clone()
+            .ok_or_else(|| LuminaError::network("Node has no tx config"))
+    }
+
+    /// Parameters of the blob module of the network, queried once.
+    async fn blob_params(&self) -> Result<BlobParams> {
+        if let Some(params) = *self.blob_params.lock().expect("poisoned lock") {
+            return Ok(params);
+        }
+
+        let mut client = connect_grpc(self.tx_config()?.grpc_endpoint).await?;
+        let params = client
+            .get_blob_params()
+            .await
+            .map_err(|e| LuminaError::network(format!("Failed to get blob params: {e}")))?;
+
+        *self.blob_params.lock().expect("poisoned lock") = Some(params);
+        Ok(params)
+    }
+
+    /// Create the node reading the current time from the given clock.
+    pub(crate) fn with_clock(config: NodeConfig, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            node: Arc::default(),
+            events_subscriber: Mutex::new(None),
+            sync_cancellation: Mutex::new(None),
+            operations: Operations::default(),
+            recorded_events: Arc::default(),
+            clock,
+            config,
+            rotated_key: StdMutex::new(None),
+            blob_params: StdMutex::new(None),
+            compromise_acknowledged: AtomicBool::new(false),
+        }
+    }
+
+    /// Fails with [`LuminaError::NetworkCompromised`] if the network is compromised and it
+    /// wasn't acknowledged with [`LuminaNode::acknowledge_compromise`].
+    fn ensure_readable(&self, node: &Node<RedbBlockstore, RedbStore>) -> Result<()> {
+        if node.is_network_compromised() && !self.compromise_acknowledged.load(Ordering::Acquire) {
+            Err(LuminaError::NetworkCompromised)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Start the node from the config, with the identity installed by
+    /// [`LuminaNode::rotate_identity`] if there is one.
+    async fn start_node(&self) -> Result<Node<RedbBlockstore, RedbStore>> {
+        let mut config = self.config.clone();
+        if let Some(key_bytes) = self.rotated_key.lock().expect("poisoned lock").clone() {
+            config.ed25519_secret_key_bytes = Some(key_bytes);
+        }
+
+        let builder = config.into_node_builder().await?;
+        let (new_node, subscriber) = builder.start_subscribed().await?;
+
+        // the compromise is latched only for the lifetime of the node
+        self.compromise_acknowledged.store(false, Ordering::Release);
+
+        *self.recorded_events.lock().expect("poisoned lock") = RecordedEvents {
+            history: EventHistory::new(self.config.event_history_size()),
+            ..Default::default()
+        };
+        spawn_events_recorder(
+            new_node.event_subscriber(),
+            self.node.clone(),
+            self.config.syncing_window(),
+            self.recorded_events.clone(),
+            self.clock.clone(),
+        );
+
+        *self.events_subscriber.lock().await = Some(subscriber);
+
+        Ok(new_node)
+    }
+}
+
+impl BlockSource for Weak<LuminaNode> {
+    async fn header(&self, height: u64) -> Option<ExtendedHeader> {
+        let this = self.upgrade()?;
+        let node = this.node.read().await;
+        let node = node.as_ref()?;
+        this.ensure_readable(node).ok()?;
+        node.get_header_by_height(height).await.ok()
+    }
+
+    async fn blob_count(&self, header: &ExtendedHeader, namespace: Namespace) -> Option<u32> {
+        let this = self.upgrade()?;
+        let node = this.node.read().await;
+        let blobs = node
+            .as_ref()?
+            .request_all_blobs(header, namespace, Some(NAMESPACE_BLOBS_TIMEOUT))
+            .await;
+
+        match blobs {
+            Ok(blobs) => Some(blobs.len() as u32),
+            Err(e) => {
+                warn!("Failed to fetch blobs at {}: {e}", header.height());
+                None
+            }
+        }
+    }
+}
+
+impl BlobSource for LuminaNode {
+    async fn blobs(&self, height: u64, namespace: Namespace) -> Result<Vec<Blob>> {
+        self.blobs_with_policy(height, namespace, ReadPolicy::LocalOnly)
+            .await
+    }
+}
+
+impl RowSource for LuminaNode {
+    async fn row(&self, height: u64, row_index: u16) -> Result<Vec<Share>> {
+        let node = self.node.read().await;
+        let node = node.as_ref().ok_or(LuminaError::NodeNotRunning)?;
+        self.ensure_readable(node)?;
+        wait_for_peer(node, self.config.peer_wait_timeout()).await?;
+
+        let row = node
+            .request_row(row_index, height, Some(ROW_TIMEOUT))
+            .await?;
+
+        Ok(row.shares)
+    }
+}
+
+impl BlobSource for Weak<LuminaNode> {
+    async fn blobs(&self, height: u64, namespace: Namespace) -> Result<Vec<Blob>> {
+        let this = self.upgrade().ok_or(LuminaError::NodeNotRunning)?;
+        this.blobs(height, namespace).await
+    }
+}
+
+impl Drop for LuminaNode {
+    /// Stop the node if the handle is dropped without [`LuminaNode::stop`], so that its
+    /// tasks don't outlive it.
+    fn drop(&mut self) {
+        if let Some(cancellation) = self.sync_cancellation.get_mut().take() {
+            cancellation.cancel();
+        }
+        self.operations.cancel_all();
+
+        // the events recorder may hold the lock for a moment, so it's taken again when stopping
+        if self.node.try_read().is_ok_and(|node| node.is_none()) {
+            return;
+        }
+        let node = self.node.clone();
+
+        // Stopping gracefully also closes the stores, which flushes them.
+        match RuntimeHandle::try_current() {
+            Ok(runtime) => {
+                runtime.spawn(stop_dropped_node(node));
+            }
+            // Dropped by the foreign code outside of the runtime.
+            Err(_) => {
+                thread::spawn(move || {
+                    match RuntimeBuilder::new_current_thread().enable_all().build() {
+                        Ok(runtime) => runtime.block_on(stop_dropped_node(node)),
+                        // Dropping the node still cancels its tasks, without closing the stores.
+                        Err(e) => warn!("Failed to create runtime to stop the dropped node: {e}"),
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Stop the node whose handle was dropped, logging how the stop went, as there's
+/// no caller to return it to. Failures to close the stores are logged by the node.
+async fn stop_dropped_node(node: Arc<RwLock<Option<Node<RedbBlockstore, RedbStore>>>>) {
+    let Some(node) = node.write().await.take() else {
+        return;
+    };
+
+    match tokio::spawn(node.stop()).await {
+        Ok(()) => info!("Stopped the node dropped while running"),
+        Err(e) => warn!("Failed to stop the dropped node: {e}"),
+    }
+}
+
+/// Get the header at the height from the store, or as the read policy says if it isn't
+/// stored: failing with [`LuminaError::HeightNotLocal`], fetching it from the network once
+/// any peer connects, or waiting for the syncer to store it.
+async fn header_with_policy(
+    node: &Node<RedbBlockstore, RedbStore>,
+    height: u64,
+    policy: ReadPolicy,
+) -> Result<ExtendedHeader> {
+    match node.get_header_by_height(height).await {
+        Ok(header) => return Ok(header),
+        Err(NodeError::Store(StoreError::NotFound)) => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    match policy {
+        ReadPolicy::LocalOnly => Err(LuminaError::HeightNotLocal { height }),
+        ReadPolicy::FetchIfMissing { timeout_ms } => {
+            let dur = Duration::from_millis(timeout_ms);
+            let fetch = async {
+                node.wait_connected().await?;
+                node.request_header_by_height(height).await
+            };
+            match timeout(dur, fetch).await {
+                Ok(header) => Ok(header?),
+                Err(_) => Err(LuminaError::timeout(format!(
+                    "fetching header {height} took over {timeout_ms} ms"
+                ))),
+            }
+        }
+        ReadPolicy::LocalOrError { timeout_ms } => {
+            let dur = Duration::from_millis(timeout_ms);
+            match timeout(dur, node.wait_header_by_height(height)).await {
+                Ok(header) => Ok(header?),
+                Err(_) => Err(LuminaError::HeightNotLocal { height }),
+            }
+        }
+    }
+}
+
+/// Waits until the node is connected to any peer, failing with
+/// [`LuminaError::NoPeersAvailable`] if none connects within the timeout.
+async fn wait_for_peer(node: &Node<RedbBlockstore, RedbStore>, wait: Duration) -> Result<()> {
+    match timeout(wait, node.wait_connected()).await {
+        Ok(result) => Ok(result?),
+        Err(_) => Err(LuminaError::NoPeersAvailable),
+    }
+}
+
+/// Fails with [`LuminaError::NodeReadOnly`] if the node refuses the operations writing new data,
+/// or the ones which need the header of the network head synced too if `needs_head` is set.
+async fn ensure_writable(node: &Node<RedbBlockstore, RedbStore>, needs_head: bool) -> Result<()> {
+    let reason = if node.is_network_compromised() {
+        ReadOnlyReason::Compromised
+    } else if node.is_syncing_paused().await? {
+        ReadOnlyReason::Paused
+    } else if needs_head && !has_network_head(&node.syncer_info().await?) {
+        ReadOnlyReason::NotSynced
+    } else {
+        return Ok(());
+    };
+
+    Err(LuminaError::NodeReadOnly { reason })
+}
+
+/// Record the peer connection and sampling events of the node, and sample its sync gap
+/// every [`SYNC_GAP_SAMPLING_INTERVAL`], until it stops.
+fn spawn_events_recorder(
+    mut subscriber: EventSubscriber,
+    node: Arc<RwLock<Option<Node<RedbBlockstore, RedbStore>>>>,
+    syncing_window: Duration,
+    events: Arc<StdMutex<RecordedEvents>>,
+    clock: Arc<dyn Clock>,
+) {
+    tokio::spawn(async move {
+        let mut gap_sampling = tokio::time::interval(SYNC_GAP_SAMPLING_INTERVAL);
+
+        loop {
+            tokio::select! {
+                info = subscriber.recv() => {
+                    let Ok(info) = info else {
+                        break;
+                    };
+                    let mut events = events.lock().expect("poisoned lock");
+                    events.record(info.into(), clock.now());
+                }
+                _ = gap_sampling.tick() => {
+                    // skip the sample while the node is being started, stopped or replaced
+                    let Ok(node) = node.try_read() else {
+                        continue;
+                    };
+                    let Some(node) = node.as_ref() else {
+                        continue;
+                    };
+                    if let Ok((info, head_range)) = head_range(node).await {
+                        let window_start_ms = syncing_window_start_ms(clock.as_ref(), syncing_window);
+                        let gap = missing_headers(&info, head_range, window_start_ms);
+                        let mut events = events.lock().expect("poisoned lock");
+                        events.sync_gaps.record(gap, clock.now());
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Unix time in milliseconds at which the syncing window of the given length starts now.
+fn syncing_window_start_ms(clock: &dyn Clock, syncing_window: Duration) -> u64 {
+    clock
+        .unix_ms()
+        .saturating_sub(syncing_window.as_millis() as u64)
+}
+
+/// Block times of the local head and of the oldest header synced contiguously with it,
+/// which together give the best estimation of the average block time.
+async fn reference_and_head_block_times<B, S>(
+    node: &Node<B, S>,
+) -> lumina_node::Result<(BlockTime, BlockTime)>
+where
+    B: Blockstore + 'static,
+    S: Store + 'static,
+{
+    let head = node.get_local_head_header().await?;
+    let reference_height = node
+        .syncer_info()
+        .await?
+        .stored_headers
+        .into_inner()
+        .last()
+        .map_or(head.height().value(), |range| *range.start());
+    let reference = node.get_header_by_height(reference_height).await?;
+
+    Ok((BlockTime::from(&reference), BlockTime::from(&head)))
+}
+
+/// Get the syncing info, with the boundaries of its highest range of stored headers.
+async fn head_range<B, S>(node: &Node<B, S>) -> Result<(LuminaSyncingInfo, Option<HeadRange>)>
+where
+    B: Blockstore,
+    S: Store,
+{
+    loop {
+        let info = node.syncer_info().await?;
+        let Some(range) = info.stored_headers.as_ref().last().cloned() else {
+            return Ok((info, None));
+        };
+
+        let lowest = node.get_header_by_height(*range.start()).await;
+        let highest = node.get_header_by_height(*range.end()).await;
+
+        match (lowest, highest) {
+            (Ok(lowest), Ok(highest)) => {
+                let head_range = HeadRange {
+                    lowest: BlockTime::from(&lowest),
+                    highest: BlockTime::from(&highest),
+                };
+                return Ok((info, Some(head_range)));
+            }
+            // pruned since the info was read
+            (Err(NodeError::Store(StoreError::NotFound)), _)
+            | (_, Err(NodeError::Store(StoreError::NotFound))) => continue,
+            (Err(e), _) | (_, Err(e)) => return Err(e.into()),
+        }
+    }
+}
+
+/// Estimate the range of heights within the `window` ending at the local head.
+async fn window_heights<B, S>(node: &Node<B, S>, window: Duration) -> Result<BlockRange>
+where
+    B: Blockstore + 'static,
+    S: Store + 'static,
+{
+    match reference_and_head_block_times(node).await {
+        Ok((reference, head)) => Ok(estimate_window_range(reference, head, window)),
+        // nothing synced yet
+        Err(NodeError::Store(StoreError::NotFound)) => Ok(BlockRange::empty()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Checks if the header directly follows the previous one, by its height and the hash
+/// of the previous block it commits to.
+fn is_linked(prev: &ExtendedHeader, header: &ExtendedHeader) -> bool {
+    header.height().value() == prev.height().value() + 1
+        && header
+            .header
+            .last_block_id
+            .is_some_and(|id| id.hash == prev.hash())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::test_utils::{
+        insert_test_headers, next_header_with_namespace, start_test_node,
+        start_test_node_with_headers, start_test_node_with_sampled_headers, test_node_config,
+        RecordingListener,
+    };
+    use crate::types::{GossipsubConfig, InMemoryBackend};
+    use celestia_grpc::test_utils::{MockGrpcServer, Recorder, BLOB_PARAMS, MOCK_ACCOUNT_KEY};
+    use celestia_proto::celestia::blob::v1::{
+        Params as RawBlobParams, QueryParamsRequest as QueryBlobParamsRequest,
+        QueryParamsResponse as QueryBlobParamsResponse,
+    };
+    use celestia_types::blob::MsgPayForBlobs;
+    use celestia_types::consts::appconsts::AppVersion;
+    use celestia_types::test_utils::{corrupt_eds, generate_dummy_eds, ExtendedHeaderGenerator};
+    use celestia_types::{
+        Blob, DataAvailabilityHeader as CelestiaDataAvailabilityHeader, ExtendedDataSquare,
+    };
+    use cid::multihash::Multihash;
+    use cid::Cid;
+    use lumina_node::node::{IpVersion, TransportConfig};
+    use lumina_node::store::{InMemoryStore, SamplingStatus};
+    use lumina_node::test_utils::{spawn_befp_announcer, ExtendedHeaderGeneratorExt};
+    use std::time::UNIX_EPOCH;
+    use tempfile::TempDir;
+    use tendermint::Time;
+
+    #[test]
+    fn supported_protocols() {
+        let base_path = TempDir::new().unwrap();
+        let node = LuminaNode::new(test_node_config(&base_path)).unwrap();
+
+        let protocols = node.supported_protocols();
+
+        assert!(!protocols.is_empty());
+        assert!(protocols.contains(&"/private/header-sub/v0.0.1".to_owned()));
+    }
+
+    #[tokio::test]
+    async fn listen_addresses() {
+        let base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&base_path);
+        config.listen_addresses = Some(vec!["/ip4/127.0.0.1/tcp/0".to_owned()]);
+        let node = LuminaNode::new(config).unwrap();
+        node.start().await.unwrap();
+
+        // listeners are opened asynchronously after the start
+        let listen_addresses = timeout(Duration::from_secs(5), async {
+            loop {
+                let info = node.network_info().await.unwrap();
+                if !info.listen_addresses.is_empty() {
+                    break info.listen_addresses;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        assert!(listen_addresses
+            .iter()
+            .all(|addr| addr.starts_with("/ip4/127.0.0.1/tcp/")));
+
+        let other_base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&other_base_path);
+        config.listen_addresses = Some(vec!["127.0.0.1:2121".to_owned()]);
+        let err = LuminaNode::new(config).unwrap().start().await.unwrap_err();
+        assert!(
+            matches!(&err, LuminaError::InvalidMultiaddr { input, .. } if input == "127.0.0.1:2121"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn identity_from_mnemonic() {
+        let mnemonic =
+            "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let mut peer_ids = Vec::new();
+
+        for _ in 0..2 {
+            let base_path = TempDir::new().unwrap();
+            let mut config = test_node_config(&base_path);
+            config.identity_mnemonic = Some(mnemonic.to_owned());
+            let node = LuminaNode::new(config).unwrap();
+            node.start().await.unwrap();
+            peer_ids.push(node.local_peer_id().await.unwrap());
+            node.stop().await.unwrap();
+        }
+        assert_eq!(peer_ids[0], peer_ids[1]);
+
+        // raw key takes precedence over the mnemonic
+        let base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&base_path);
+        config.identity_mnemonic = Some(mnemonic.to_owned());
+        config.ed25519_secret_key_bytes = Some(vec![7; 32]);
+        let node = LuminaNode::new(config).unwrap();
+        node.start().await.unwrap();
+        assert_ne!(node.local_peer_id().await.unwrap(), peer_ids[0]);
+
+        let base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&base_path);
+        config.identity_mnemonic = Some("not a mnemonic".to_owned());
+        let err = LuminaNode::new(config).unwrap().start().await.unwrap_err();
+        assert!(matches!(err, LuminaError::Network { .. }));
+    }
+
+    #[tokio::test]
+    async fn gossipsub_config() {
+        let tuning = GossipsubConfig {
+            mesh_n: 3,
+            mesh_n_low: 2,
+            mesh_n_high: 4,
+            heartbeat_interval_ms: 500,
+            peer_scoring: true,
+        };
+
+        let base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&base_path);
+        config.gossipsub_config = Some(tuning);
+        let node = LuminaNode::new(config).unwrap();
+        node.start().await.unwrap();
+        node.stop().await.unwrap();
+
+        let base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&base_path);
+        config.gossipsub_config = Some(GossipsubConfig {
+            mesh_n_low: 5,
+            ..tuning
+        });
+        let err = LuminaNode::new(config).unwrap().start().await.unwrap_err();
+        assert!(
+            matches!(&err, LuminaError::Network { msg } if msg.contains("mesh_n_low (5) <= mesh_n (3)")),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn transports_config() {
+        let base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&base_path);
+        config.transports = Some(TransportConfig {
+            quic: false,
+            websocket: false,
+            ip_version: IpVersion::V4,
+            ..TransportConfig::default()
+        });
+        let node = LuminaNode::new(config).unwrap();
+        node.start().await.unwrap();
+        node.stop().await.unwrap();
+
+        let base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&base_path);
+        config.transports = Some(TransportConfig {
+            tcp: false,
+            quic: false,
+            websocket: false,
+            ip_version: IpVersion::Any,
+        });
+        let err = LuminaNode::new(config).unwrap().start().await.unwrap_err();
+        assert!(
+            matches!(&err, LuminaError::Network { msg } if msg.contains("At least one transport")),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn trusted_and_discovered_peer_counts() {
+        let base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&base_path);
+        config.listen_addresses = Some(vec!["/ip4/127.0.0.1/tcp/0".to_owned()]);
+        let node = LuminaNode::new(config).unwrap();
+        node.start().await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let listener = node.listeners().await.unwrap()[0].clone();
+        let _peers: Vec<_> = (0..3)
+            .map(|_| spawn_befp_announcer(listener.parse().unwrap()))
+            .collect();
+        let connected = timeout(Duration::from_secs(5), async {
+            loop {
+                let connected = node.connected_peers().await.unwrap();
+                if connected.len() == 3 {
+                    return connected;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        node.set_peer_trust(connected[0].clone(), true)
+            .await
+            .unwrap();
+        let info = node.network_info().await.unwrap();
+        assert_eq!(info.num_peers, 3);
+        assert_eq!(info.trusted_peer_count, 1);
+        assert_eq!(info.discovered_peer_count, 2);
+        assert_eq!(
+            info.trusted_peer_count + info.discovered_peer_count,
+            info.num_peers
+        );
+    }
+
+    #[tokio::test]
+    async fn read_only_when_network_is_compromised() {
+        let mut gen = ExtendedHeaderGenerator::new();
+        let mut headers = gen.next_many(16);
+        let mut eds = generate_dummy_eds(8, AppVersion::V2);
+        let (header, befp) = corrupt_eds(&mut gen, &mut eds);
+        headers.push(header);
+
+        let base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&base_path);
+        config.listen_addresses = Some(vec!["/ip4/127.0.0.1/tcp/0".to_owned()]);
+        insert_test_headers(&config, headers, Vec::new()).await;
+        let node = LuminaNode::new(config).unwrap();
+        node.start().await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let listener = node.listeners().await.unwrap()[0].parse().unwrap();
+        let befp_announce_tx = spawn_befp_announcer(listener);
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        let peer_id = PeerId::from(libp2p::PeerId::random());
+        node.set_peer_trust(peer_id.clone(), true).await.unwrap();
+
+        befp_announce_tx.send(befp).await.unwrap();
+        timeout(Duration::from_secs(5), async {
+            while !matches!(
+                node.next_event().await.unwrap(),
+                NodeEvent::NetworkCompromised
+            ) {}
+        })
+        .await
+        .unwrap();
+
+        let err = node
+            .inject_trusted_header(serde_json::to_vec(&gen.next()).unwrap())
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(
+                err,
+                LuminaError::NodeReadOnly {
+                    reason: ReadOnlyReason::Compromised
+                }
+            ),
+            "{err}"
+        );
+        assert_eq!(err.to_string(), "Node is read-only: network is compromised");
+        let blob = BlobInput {
+            namespace: Namespace::new_v0(&[1]).unwrap().as_bytes().to_vec(),
+            data: vec![1; 10],
+            share_version: None,
+        };
+        let err = node.submit_blobs(vec![blob]).await.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                LuminaError::NodeReadOnly {
+                    reason: ReadOnlyReason::Compromised
+                }
+            ),
+            "{err}"
+        );
+
+        // changing the trust of the peers doesn't write any data
+        node.set_peer_trust(peer_id, false).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reads_refused_until_compromise_acknowledged() {
+        let mut gen = ExtendedHeaderGenerator::new();
+        let mut headers = gen.next_many(16);
+        let mut eds = generate_dummy_eds(8, AppVersion::V2);
+        let (header, befp) = corrupt_eds(&mut gen, &mut eds);
+        headers.push(header);
+
+        let base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&base_path);
+        config.listen_addresses = Some(vec!["/ip4/127.0.0.1/tcp/0".to_owned()]);
+        insert_test_headers(&config, headers, Vec::new()).await;
+        let node = LuminaNode::new(config).unwrap();
+        node.start().await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let listener = node.listeners().await.unwrap()[0].parse().unwrap();
+        let befp_announce_tx = spawn_befp_announcer(listener);
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        // nothing to acknowledge yet
+        assert!(!node.acknowledge_compromise().await.unwrap());
+        node.get_header_by_height(1).await.unwrap();
+
+        befp_announce_tx.send(befp).await.unwrap();
+        timeout(Duration::from_secs(5), async {
+            while !matches!(
+                node.next_event().await.unwrap(),
+                NodeEvent::NetworkCompromised
+            ) {}
+        })
+        .await
+        .unwrap();
+
+        assert!(node.is_network_compromised().await.unwrap());
+        let err = node.get_header_by_height(1).await.unwrap_err();
+        assert!(matches!(err, LuminaError::NetworkCompromised), "{err}");
+        let err = node.get_headers(None, None).await.unwrap_err();
+        assert!(matches!(err, LuminaError::NetworkCompromised), "{err}");
+
+        assert!(node.acknowledge_compromise().await.unwrap());
+
+        // reads are resumed, but writes stay refused
+        node.get_header_by_height(1).await.unwrap();
+        assert_eq!(node.get_headers(None, None).await.unwrap().len(), 17);
+        let err = node
+            .inject_trusted_header(serde_json::to_vec(&gen.next()).unwrap())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::NodeReadOnly { .. }), "{err}");
+    }
+
+    #[tokio::test]
+    async fn start_twice() {
+        let (node, _dir) = start_test_node().await;
+        let peer_id = node.local_peer_id().await.unwrap();
+
+        let err = node.start().await.unwrap_err();
+        assert!(matches!(&err, LuminaError::AlreadyRunning { peer_id: id } if *id == peer_id));
+        assert!(err.to_string().contains(&peer_id));
+    }
+
+    #[tokio::test]
+    async fn head_header_before_sync() {
+        let (node, _dir) = start_test_node().await;
+
+        assert_eq!(node.head_header().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn get_dah() {
+        let eds = generate_dummy_eds(8, AppVersion::V2);
+        let dah = CelestiaDataAvailabilityHeader::from_eds(&eds);
+        let mut gen = ExtendedHeaderGenerator::new();
+        let mut headers = gen.next_many(2);
+        headers.push(gen.next_with_dah(dah.clone()));
+        let (node, _dir) = start_test_node_with_headers(headers).await;
+
+        let roots = node.get_dah(3).await.unwrap();
+        assert_eq!(roots.row_roots.len(), dah.square_width() as usize);
+        assert_eq!(roots.column_roots.len(), dah.square_width() as usize);
+        assert_eq!(roots, DataAvailabilityHeader::from(&dah));
+
+        let err = node.get_dah(4).await.unwrap_err();
+        assert!(matches!(err, LuminaError::HeightNotLocal { height: 4 }));
+    }
+
+    #[tokio::test]
+    async fn square_width() {
+        let eds = generate_dummy_eds(8, AppVersion::V2);
+        let dah = CelestiaDataAvailabilityHeader::from_eds(&eds);
+        let mut gen = ExtendedHeaderGenerator::new();
+        let mut headers = gen.next_many(2);
+        headers.push(gen.next_with_dah(dah));
+        let (node, _dir) = start_test_node_with_headers(headers).await;
+
+        assert_eq!(node.square_width(3).await.unwrap(), 8);
+
+        let err = node.square_width(4).await.unwrap_err();
+        assert!(matches!(err, LuminaError::HeightNotLocal { height: 4 }));
+    }
+
+    #[tokio::test]
+    async fn commitment_included_requires_stored_header() {
+        let headers = ExtendedHeaderGenerator::new().next_many(2);
+        let (node, _dir) = start_test_node_with_headers(headers).await;
+        let namespace = Namespace::new_v0(&[1]).unwrap().as_bytes().to_vec();
+
+        let err = node
+            .commitment_included(3, namespace.clone(), vec![0; 32])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::HeightNotLocal { height: 3 }));
+
+        let err = node
+            .commitment_included(1, namespace, vec![0; 31])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::InvalidHash { .. }));
+    }
+
+    #[tokio::test]
+    async fn verify_header_range() {
+        let headers = ExtendedHeaderGenerator::new().next_many(5);
+        let (node, _dir) = start_test_node_with_headers(headers.clone()).await;
+
+        assert!(node.verify_header_range(1, 5).await.unwrap());
+        assert!(node.verify_header_range(3, 3).await.unwrap());
+
+        let err = node.verify_header_range(4, 6).await.unwrap_err();
+        assert!(matches!(err, LuminaError::HeightNotLocal { height: 6 }));
+        let err = node.verify_header_range(3, 2).await.unwrap_err();
+        assert!(matches!(err, LuminaError::InvalidRange { .. }));
+
+        // header of the same height from a different chain
+        let forked = ExtendedHeaderGenerator::new().next_many(2).pop().unwrap();
+        assert!(is_linked(&headers[0], &headers[1]));
+        assert!(!is_linked(&headers[0], &forked));
+        assert!(!is_linked(&headers[0], &headers[2]));
+    }
+
+    #[tokio::test]
+    async fn namespace_sampling_stats_in_batches() {
+        let ns_a = Namespace::new_v0(&[1]).unwrap();
+        let ns_b = Namespace::new_v0(&[2]).unwrap();
+        let mut gen = ExtendedHeaderGenerator::new();
+        let headers: Vec<_> = [ns_a, ns_b, ns_a, ns_a, ns_b, ns_a, ns_a]
+            .into_iter()
+            .map(|ns| next_header_with_namespace(&mut gen, ns))
+            .collect();
+        let sampled = [
+            (1, SamplingStatus::Accepted),
+            (2, SamplingStatus::Accepted),
+            (3, SamplingStatus::Rejected),
+            (5, SamplingStatus::Rejected),
+            (6, SamplingStatus::Accepted),
+            (7, SamplingStatus::Accepted),
+        ]
+        .into_iter()
+        .map(|(height, status)| (height, status, Vec::new()))
+        .collect();
+
+        let base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&base_path);
+        // headers are read across the boundaries of the batches
+        config.batch_size = Some(2);
+        insert_test_headers(&config, headers, sampled).await;
+        let node = LuminaNode::new(config).unwrap();
+        node.start().await.unwrap();
+
+        let stats = node
+            .namespace_sampling_stats(ns_a.as_bytes().to_vec())
+            .await
+            .unwrap();
+        assert_eq!(
+            stats,
+            NamespaceStats {
+                sampled: 4,
+                accepted: 3,
+                rejected: 1,
+            }
+        );
+        let stats = node
+            .namespace_sampling_stats(ns_b.as_bytes().to_vec())
+            .await
+            .unwrap();
+        assert_eq!(
+            stats,
+            NamespaceStats {
+                sampled: 2,
+                accepted: 1,
+                rejected: 1,
+            }
+        );
+
+        node.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn get_headers_available() {
+        let headers = ExtendedHeaderGenerator::new().next_many(10);
+        let base_path = TempDir::new().unwrap();
+        let config = test_node_config(&base_path);
+        // gap of 5..=7
+        insert_test_headers(&config, headers[..4].to_vec(), Vec::new()).await;
+        insert_test_headers(&config, headers[7..].to_vec(), Vec::new()).await;
+        let node = LuminaNode::new(config).unwrap();
+        node.start().await.unwrap();
+
+        let partial = node.get_headers_available(3, 12).await.unwrap();
+        let expected: Vec<_> = [2, 3, 7, 8, 9]
+            .into_iter()
+            .map(|i| headers[i].to_string())
+            .collect();
+        assert_eq!(partial.headers, expected);
+        assert_eq!(partial.missing_heights, vec![5, 6, 7, 11, 12]);
+
+        // strict variant fails over the gap
+        node.get_headers(Some(3), Some(9)).await.unwrap_err();
+
+        let partial = node.get_headers_available(5, 6).await.unwrap();
+        assert!(partial.headers.is_empty());
+        assert_eq!(partial.missing_heights, vec![5, 6]);
+
+        let err = node.get_headers_available(0, 3).await.unwrap_err();
+        assert!(matches!(err, LuminaError::InvalidRange { .. }));
+    }
+
+    #[tokio::test]
+    async fn no_peers_available() {
+        let headers = ExtendedHeaderGenerator::new().next_many(3);
+        let base_path = TempDir::new().unwrap();
+        let config = NodeConfig {
+            peer_wait_timeout_ms: Some(100),
+            ..test_node_config(&base_path)
+        };
+        insert_test_headers(&config, headers, Vec::new()).await;
+        let node = Arc::new(LuminaNode::new(config).unwrap());
+        node.start().await.unwrap();
+
+        // Node has no peers, so the retrieval gives up after the timeout.
+        let err = node.request_header_by_height(5).await.unwrap_err();
+        assert!(matches!(err, LuminaError::NoPeersAvailable), "{err}");
+
+        let namespace = Namespace::new_v0(&[1]).unwrap().as_bytes().to_vec();
+        let blobs = node
+            .clone()
+            .get_blobs_in_range(namespace, 2, 3, 1)
+            .await
+            .unwrap();
+        let err = blobs.next().await.unwrap_err();
+        assert!(matches!(err, LuminaError::NoPeersAvailable), "{err}");
+        assert!(blobs.next().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn preview_commitments() {
+        let headers = ExtendedHeaderGenerator::new().next_many(1);
+        let app_version = headers[0].app_version().unwrap();
+        let (node, _dir) = start_test_node_with_headers(headers).await;
+
+        let ns_a = Namespace::new_v0(&[1]).unwrap();
+        let ns_b = Namespace::new_v0(&[2]).unwrap();
+        let blobs = [(ns_a, vec![1; 100]), (ns_b, vec![2; 2000])];
+        let inputs = blobs
+            .iter()
+            .map(|(namespace, data)| BlobInput {
+                namespace: namespace.as_bytes().to_vec(),
+                data: data.clone(),
+                share_version: None,
+            })
+            .collect();
+
+        let previewed = node.preview_commitments(inputs).await.unwrap();
+
+        // commitments of the transaction submitting the same blobs
+        let blobs: Vec<_> = blobs
+            .into_iter()
+            .map(|(namespace, data)| Blob::new(namespace, data, app_version).unwrap())
+            .collect();
+        let signer = "celestia1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5wgawu3"
+            .parse()
+            .unwrap();
+        let msg = MsgPayForBlobs::new(&blobs, signer).unwrap();
+        let expected: Vec<_> = msg
+            .share_commitments
+            .into_iter()
+            .map(Commitment::from)
+            .collect();
+        let previewed: Vec<_> = previewed.iter().map(|c| (**c).clone()).collect();
+        assert_eq!(previewed, expected);
+
+        let invalid = BlobInput {
+            namespace: ns_a.as_bytes().to_vec(),
+            data: vec![1; 10],
+            share_version: Some(1),
+        };
+        let err = node.preview_commitments(vec![invalid]).await.unwrap_err();
+        assert!(matches!(err, LuminaError::InvalidBlob { .. }));
+    }
+
+    #[tokio::test]
+    async fn submit_blobs_in_single_tx() {
+        let broadcasted = Recorder::new();
+        let server = MockGrpcServer::new()
+            .with_chain("private")
+            .committing_blob_txs(15, broadcasted.clone())
+            .start()
+            .await;
+        let base_path = TempDir::new().unwrap();
+        let config = NodeConfig {
+            tx_config: Some(TxConfig {
+                grpc_endpoint: server.url(),
+                secret_key: MOCK_ACCOUNT_KEY.to_vec(),
+            }),
+            ..test_node_config(&base_path)
+        };
+        let node = LuminaNode::new(config).unwrap();
+
+        let ns_a = Namespace::new_v0(&[1]).unwrap();
+        let ns_b = Namespace::new_v0(&[2]).unwrap();
+        let inputs = [(ns_a, vec![1; 100]), (ns_b, vec![2; 2000])]
+            .into_iter()
+            .map(|(namespace, data)| BlobInput {
+                namespace: namespace.as_bytes().to_vec(),
+                data,
+                share_version: None,
+            })
+            .collect();
+
+        assert_eq!(node.submit_blobs(inputs).await.unwrap(), 15);
+
+        // both blobs are paid for by the same transaction
+        let [blob_tx] = broadcasted.take().try_into().unwrap();
+        let namespace_ids: Vec<_> = blob_tx.blobs.iter().map(|b| &b.namespace_id).collect();
+        assert_eq!(namespace_ids, [ns_a.id(), ns_b.id()]);
+        assert_eq!(blob_tx.blobs[1].data, vec![2; 2000]);
+
+        let err = node.submit_blobs(Vec::new()).await.unwrap_err();
+        assert!(matches!(err, LuminaError::InvalidBlob { .. }), "{err}");
+        let invalid = BlobInput {
+            namespace: vec![0; 3],
+            data: vec![1; 10],
+            share_version: None,
+        };
+        let err = node.submit_blobs(vec![invalid]).await.unwrap_err();
+        assert!(matches!(err, LuminaError::InvalidNamespace { .. }), "{err}");
+        assert!(broadcasted.is_empty());
+    }
+
+    #[tokio::test]
+    async fn submit_blobs_without_tx_config() {
+        let base_path = TempDir::new().unwrap();
+        let node = LuminaNode::new(test_node_config(&base_path)).unwrap();
+        let input = BlobInput {
+            namespace: Namespace::new_v0(&[1]).unwrap().as_bytes().to_vec(),
+            data: vec![1; 10],
+            share_version: None,
+        };
+
+        let err = node.submit_blobs(vec![input]).await.unwrap_err();
+        assert!(matches!(err, LuminaError::Network { .. }), "{err}");
+    }
+
+    #[tokio::test]
+    async fn max_blob_size_of_network_params() {
+        let queried = Recorder::new();
+        let server = MockGrpcServer::new()
+            .unary(BLOB_PARAMS, {
+                let queried = queried.clone();
+                move |_: tonic::Request<QueryBlobParamsRequest>| {
+                    queried.record(());
+                    Ok(QueryBlobParamsResponse {
+                        params: Some(RawBlobParams {
+                            gas_per_blob_byte: 8,
+                            gov_max_square_size: 32,
+                        }),
+                    })
+                }
+            })
+            .start()
+            .await;
+        let base_path = TempDir::new().unwrap();
+        let config = NodeConfig {
+            tx_config: Some(TxConfig {
+                grpc_endpoint: server.url(),
+                secret_key: MOCK_ACCOUNT_KEY.to_vec(),
+            }),
+            ..test_node_config(&base_path)
+        };
+        let headers = ExtendedHeaderGenerator::new().next_many(1);
+        let app_version = headers[0].app_version().unwrap();
+        insert_test_headers(&config, headers, Vec::new()).await;
+        let node = LuminaNode::new(config).unwrap();
+        node.start().await.unwrap();
+
+        let limits = node.max_blob_size().await.unwrap();
+        assert_eq!(limits, BlobSizeLimits::new(32, app_version));
+        assert_eq!(limits.max_square_size, 32);
+        // blob of 16 subtrees starts at the index 16, after the transaction paying for it
+        assert_eq!(limits.max_blob_size, 478 + (32 * 32 - 16 - 1) * 482);
+
+        // params are queried only once
+        node.max_blob_size().await.unwrap();
+        assert_eq!(queried.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn get_namespaces_at_height() {
+        let ns_a = Namespace::new_v0(&[1, 2, 3]).unwrap();
+        let ns_b = Namespace::new_v0(&[4, 5, 6]).unwrap();
+        let square_of = |blobs: &[Blob]| {
+            let ods = blobs
+                .iter()
+                .flat_map(|blob| blob.to_shares().unwrap())
+                .map(|share| share.data().to_vec())
+                .collect();
+            let eds = ExtendedDataSquare::from_ods(ods, AppVersion::V2).unwrap();
+            CelestiaDataAvailabilityHeader::from_eds(&eds)
+        };
+        // 2 shares of each namespace fill a row of the 2x2 square each
+        let separate_rows = square_of(&[
+            Blob::new(ns_a, vec![1; 900], AppVersion::V2).unwrap(),
+            Blob::new(ns_b, vec![2; 900], AppVersion::V2).unwrap(),
+        ]);
+        // 3 shares of the first namespace and 1 of the second one share the second row
+        let shared_row = square_of(&[
+            Blob::new(ns_a, vec![1; 961], AppVersion::V2).unwrap(),
+            Blob::new(ns_b, vec![2; 10], AppVersion::V2).unwrap(),
+        ]);
+
+        let mut gen = ExtendedHeaderGenerator::new();
+        let mut headers = gen.next_many(2);
+        headers.push(gen.next_with_dah(separate_rows));
+        headers.push(gen.next_with_dah(shared_row));
+
+        let base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&base_path);
+        config.peer_wait_timeout_ms = Some(100);
+        insert_test_headers(&config, headers, Vec::new()).await;
+        let node = LuminaNode::new(config).unwrap();
+        node.start().await.unwrap();
+
+        let namespaces = node.get_namespaces_at_height(3).await.unwrap();
+        assert_eq!(namespaces, [ns_a.as_bytes(), ns_b.as_bytes()]);
+
+        // Node has no peers, so the shares of the shared row can't be fetched.
+        let err = node.get_namespaces_at_height(4).await.unwrap_err();
+        assert!(matches!(err, LuminaError::NoPeersAvailable), "{err}");
+
+        let err = node.get_namespaces_at_height(5).await.unwrap_err();
+        assert!(matches!(err, LuminaError::HeightNotLocal { height: 5 }));
+    }
+
+    #[tokio::test]
+    async fn connection_rates_without_peers() {
+        let (node, _dir) = start_test_node().await;
+
+        let rates = node.connection_rates(60).await.unwrap();
+        assert_eq!(rates.window_secs, 60);
+        assert_eq!(rates.connects_per_sec, 0.0);
+        assert_eq!(rates.disconnects_per_sec, 0.0);
+
+        node.stop().await.unwrap();
+        let err = node.connection_rates(60).await.unwrap_err();
+        assert!(matches!(err, LuminaError::NodeNotRunning));
+    }
+
+    #[tokio::test]
+    async fn head_staleness() {
+        let headers = ExtendedHeaderGenerator::new().next_many(3);
+        let head_nanos = headers[2].time().unix_timestamp_nanos() as u64;
+        let head_time = UNIX_EPOCH + Duration::from_nanos(head_nanos);
+        let clock = Arc::new(MockClock::new(head_time + Duration::from_secs(10)));
+
+        let base_path = TempDir::new().unwrap();
+        let config = test_node_config(&base_path);
+        insert_test_headers(&config, headers, Vec::new()).await;
+        let node = LuminaNode::with_clock(config, clock.clone());
+        node.start().await.unwrap();
+
+        assert!(!node.is_head_stale(60).await.unwrap());
+        clock.advance(Duration::from_secs(120));
+        assert!(node.is_head_stale(60).await.unwrap());
+        assert!(!node.is_head_stale(600).await.unwrap());
+
+        node.stop().await.unwrap();
+        let err = node.is_head_stale(60).await.unwrap_err();
+        assert!(matches!(err, LuminaError::NodeNotRunning));
+    }
+
+    #[tokio::test]
+    async fn sync_gap_of_synced_node() {
+        let headers = ExtendedHeaderGenerator::new().next_many(3);
+        let clock = Arc::new(MockClock::new(UNIX_EPOCH));
+        let base_path = TempDir::new().unwrap();
+        let config = test_node_config(&base_path);
+        insert_test_headers(&config, headers, Vec::new()).await;
+        let node = LuminaNode::with_clock(config, clock.clone());
+        node.start().await.unwrap();
+
+        assert_eq!(node.sync_gap().await.unwrap(), 0);
+        assert!(node.is_synced(0).await.unwrap());
+        // the gap is sampled right after the start, without polling it
+        timeout(Duration::from_secs(5), async {
+            while !node.is_sync_stuck(0, 0).await.unwrap() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(!node.is_sync_stuck(0, 60).await.unwrap());
+        clock.advance(Duration::from_secs(60));
+        // synced, so only the gap of zero headers is reached
+        assert!(node.is_sync_stuck(0, 60).await.unwrap());
+        assert!(!node.is_sync_stuck(1, 60).await.unwrap());
+
+        node.stop().await.unwrap();
+        let err = node.sync_gap().await.unwrap_err();
+        assert!(matches!(err, LuminaError::NodeNotRunning));
+        let err = node.is_sync_stuck(0, 60).await.unwrap_err();
+        assert!(matches!(err, LuminaError::NodeNotRunning));
+    }
+
+    #[tokio::test]
+    async fn sync_gap_sampled_with_hole_below_head() {
+        let headers = ExtendedHeaderGenerator::new().next_many(100);
+        let head_nanos = headers[99].time().unix_timestamp_nanos() as u64;
+        let head_time = UNIX_EPOCH + Duration::from_nanos(head_nanos);
+        let clock = Arc::new(MockClock::new(head_time + Duration::from_secs(10)));
+
+        let base_path = TempDir::new().unwrap();
+        let config = test_node_config(&base_path);
+        insert_test_headers(&config, headers[..10].to_vec(), Vec::new()).await;
+        insert_test_headers(&config, headers[89..].to_vec(), Vec::new()).await;
+        let node = LuminaNode::with_clock(config, clock.clone());
+        node.start().await.unwrap();
+
+        // heights 11..=89 are missing below the head
+        assert_eq!(node.sync_gap().await.unwrap(), 79);
+        assert!(!node.is_synced(10).await.unwrap());
+        timeout(Duration::from_secs(5), async {
+            while !node.is_sync_stuck(79, 0).await.unwrap() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert!(!node.is_sync_stuck(80, 0).await.unwrap());
+
+        clock.advance(Duration::from_secs(60));
+        assert!(node.is_sync_stuck(79, 60).await.unwrap());
+
+        node.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn sync_since_synced_time() {
+        let mut gen = ExtendedHeaderGenerator::new();
+        gen.set_time(
+            Time::from_unix_timestamp(1_000, 0).unwrap(),
+            Duration::from_secs(10),
+        );
+        let headers = gen.next_many(100);
+        let since_ms = (headers[49].time().unix_timestamp_nanos() / 1_000_000) as u64;
+        let head_nanos = headers[99].time().unix_timestamp_nanos() as u64;
+        let head_time = UNIX_EPOCH + Duration::from_nanos(head_nanos);
+        let clock = Arc::new(MockClock::new(head_time + Duration::from_secs(10)));
+
+        let base_path = TempDir::new().unwrap();
+        let config = test_node_config(&base_path);
+        insert_test_headers(&config, headers, Vec::new()).await;
+        let node = LuminaNode::with_clock(config, clock);
+        node.start().await.unwrap();
+
+        let synced = node.sync_since(since_ms).await.unwrap();
+        assert_eq!(synced, SyncedRange::new(50, 100, false));
+        assert!(node.operations().is_empty());
+
+        node.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn stopping_node_while_syncing_since() {
+        let mut gen = ExtendedHeaderGenerator::new();
+        gen.set_time(
+            Time::from_unix_timestamp(1_000, 0).unwrap(),
+            Duration::from_secs(10),
+        );
+        let headers = gen.next_many(100);
+        let since_ms = (headers[49].time().unix_timestamp_nanos() / 1_000_000) as u64;
+        let head_nanos = headers[99].time().unix_timestamp_nanos() as u64;
+        let head_time = UNIX_EPOCH + Duration::from_nanos(head_nanos);
+        let clock = Arc::new(MockClock::new(head_time + Duration::from_secs(10)));
+
+        let base_path = TempDir::new().unwrap();
+        let config = test_node_config(&base_path);
+        insert_test_headers(&config, headers[89..].to_vec(), Vec::new()).await;
+        let node = LuminaNode::with_clock(config, clock);
+        node.start().await.unwrap();
+
+        // Node has no peers, so the headers below 90 are never backfilled.
+        let (res, _) = tokio::join!(node.sync_since(since_ms), async {
+            while node.operations().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            timeout(Duration::from_secs(5), node.stop())
+                .await
+                .unwrap()
+                .unwrap();
+        });
+        let err = res.unwrap_err();
+        assert!(
+            matches!(err, LuminaError::OperationCancelled { .. }),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn window_disk_bytes() {
+        let (node, _dir) = start_test_node().await;
+
+        let day = node.estimate_window_disk_bytes(24 * 3600).await.unwrap();
+        let week = node
+            .estimate_window_disk_bytes(7 * 24 * 3600)
+            .await
+            .unwrap();
+        assert_eq!(day, 14_400 * DEFAULT_BLOCK_DISK_BYTES);
+        assert_eq!(week, 7 * day);
+
+        node.stop().await.unwrap();
+        let err = node.estimate_window_disk_bytes(3600).await.unwrap_err();
+        assert!(matches!(err, LuminaError::NodeNotRunning));
+    }
+
+    #[tokio::test]
+    async fn sampling_result() {
+        let headers = ExtendedHeaderGenerator::new().next_many(3);
+        let cids = (0..4u8)
+            .map(|i| Cid::new_v1(0x55, Multihash::wrap(0x12, &[i; 32]).unwrap()))
+            .collect();
+        let sampled = vec![
+            (1, SamplingStatus::Accepted, cids),
+            (2, SamplingStatus::Rejected, Vec::new()),
+        ];
+        let (node, _dir) = start_test_node_with_sampled_headers(headers, sampled).await;
+
+        let outcome = node.sampling_result(1).await.unwrap().unwrap();
+        assert_eq!(
+            outcome,
+            SampleOutcome {
+                accepted: true,
+                sampled_shares: 4,
+                // sampled before the node started
+                took_ms: None,
+            }
+        );
+
+        let outcome = node.sampling_result(2).await.unwrap().unwrap();
+        assert!(!outcome.accepted);
+
+        // stored, but never sampled
+        assert_eq!(node.sampling_result(3).await.unwrap(), None);
+        assert_eq!(node.sampling_result(4).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn sample_range_of_sampled_heights() {
+        let headers = ExtendedHeaderGenerator::new().next_many(4);
+        let sampled = vec![
+            (1, SamplingStatus::Accepted, Vec::new()),
+            (2, SamplingStatus::Rejected, Vec::new()),
+            (3, SamplingStatus::Accepted, Vec::new()),
+        ];
+        let (node, _dir) = start_test_node_with_sampled_headers(headers, sampled).await;
+
+        // already sampled, so it doesn't need any peers
+        let outcomes = timeout(Duration::from_secs(5), node.sample_range(1, 3, false))
+            .await
+            .unwrap()
+            .unwrap();
+        let accepted: Vec<_> = outcomes.iter().map(|outcome| outcome.accepted).collect();
+        assert_eq!(accepted, [true, false, true]);
+
+        let err = node.sample_range(3, 5, false).await.unwrap_err();
+        assert!(matches!(err, LuminaError::HeightNotLocal { height: 5 }));
+
+        for (from, to) in [(0, 2), (3, 2), (1, 1000)] {
+            let err = node.sample_range(from, to, false).await.unwrap_err();
+            assert!(matches!(err, LuminaError::InvalidRange { .. }), "{err}");
+        }
+    }
+
+    #[tokio::test]
+    async fn peer_latencies() {
+        let base_path = TempDir::new().unwrap();
+        let config = NodeConfig {
+            keepalive_ping_secs: Some(1),
+            listen_addresses: Some(vec!["/ip4/127.0.0.1/tcp/0".to_owned()]),
+            ..test_node_config(&base_path)
+        };
+        let node = LuminaNode::new(config).unwrap();
+        node.start().await.unwrap();
+        assert_eq!(node.effective_config().keepalive_ping_secs, 1);
+        assert!(node.peer_latencies().await.unwrap().is_empty());
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let listener = node.listeners().await.unwrap()[0].parse().unwrap();
+        let _peer = spawn_befp_announcer(listener);
+
+        // peer is pinged once connected
+        let latencies = timeout(Duration::from_secs(5), async {
+            loop {
+                let latencies = node.peer_latencies().await.unwrap();
+                if !latencies.is_empty() {
+                    break latencies;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(latencies.len(), 1);
+        assert!(latencies[0].rtt_ms < 1000.0, "{latencies:?}");
+    }
+
+    #[tokio::test]
+    async fn peer_scores_without_scoring() {
+        let (node, _dir) = start_test_node().await;
+
+        assert!(node.peer_scores().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn read_policies_on_missing_height() {
+        struct IgnoredShares;
+
+        impl ShareListener for IgnoredShares {
+            fn on_share(&self, _: types::ShareRecord) {}
+        }
+
+        let (node, _dir) = start_test_node().await;
+
+        let err = node
+            .get_header_by_height_with_policy(5, ReadPolicy::LocalOnly)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::HeightNotLocal { height: 5 }));
+
+        let err = node
+            .get_header_by_height_with_policy(5, ReadPolicy::LocalOrError { timeout_ms: 100 })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::HeightNotLocal { height: 5 }));
+
+        // Node has no peers, so fetching from the network must give up.
+        let err = node
+            .get_header_by_height_with_policy(5, ReadPolicy::FetchIfMissing { timeout_ms: 100 })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::Timeout { .. }), "{err}");
+
+        let namespace = Namespace::new_v0(&[1, 2, 3]).unwrap().as_bytes().to_vec();
+        let err = node
+            .get_blobs_with_policy(5, namespace.clone(), ReadPolicy::LocalOnly)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::HeightNotLocal { height: 5 }));
+        let err = node
+            .get_blobs_with_policy(
+                5,
+                namespace.clone(),
+                ReadPolicy::LocalOrError { timeout_ms: 100 },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::HeightNotLocal { height: 5 }));
+        let err = node
+            .get_blobs_with_policy(5, namespace, ReadPolicy::FetchIfMissing { timeout_ms: 100 })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::Timeout { .. }), "{err}");
+
+        let err = node
+            .stream_block_shares_with_policy(5, ReadPolicy::LocalOnly, Box::new(IgnoredShares))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::HeightNotLocal { height: 5 }));
+        let err = node
+            .stream_block_shares_with_policy(
+                5,
+                ReadPolicy::LocalOrError { timeout_ms: 100 },
+                Box::new(IgnoredShares),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::HeightNotLocal { height: 5 }));
+        let err = node
+            .stream_block_shares_with_policy(
+                5,
+                ReadPolicy::FetchIfMissing { timeout_ms: 100 },
+                Box::new(IgnoredShares),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::Timeout { .. }), "{err}");
+
+        node.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn bandwidth_stats_of_stopped_node() {
+        let (node, _dir) = start_test_node().await;
+
+        // Node has no peers, so nothing was transferred yet.
+        let stats = node.bandwidth_stats().await.unwrap();
+        assert_eq!(stats.total_inbound_bytes, 0);
+        assert_eq!(stats.total_outbound_bytes, 0);
+
+        node.stop().await.unwrap();
+
+        let err = node.bandwidth_stats().await.unwrap_err();
+        assert!(matches!(err, LuminaError::NodeNotRunning));
+    }
+
+    #[tokio::test]
+    async fn window_heights_before_sync() {
+        let (node, _dir) = start_test_node().await;
+
+        // Node has no peers, so nothing was synced yet.
+        let range = node.sampling_window_heights().await.unwrap();
+        assert!(range.end < range.start);
+        let range = node.syncing_window_heights().await.unwrap();
+        assert!(range.end < range.start);
+
+        node.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn window_heights_without_sampling() {
+        let mut gen = ExtendedHeaderGenerator::new();
+        gen.set_time(
+            (tendermint::Time::now() - Duration::from_secs(600)).unwrap(),
+            Duration::from_secs(6),
+        );
+
+        let base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&base_path);
+        config.enable_sampling = Some(false);
+        insert_test_headers(&config, gen.next_many(100), Vec::new()).await;
+        let node = LuminaNode::new(config).unwrap();
+        node.start().await.unwrap();
+
+        // headers are synced, but none of the blocks is sampled
+        let range = node.syncing_window_heights().await.unwrap();
+        assert_eq!(range, BlockRange { start: 1, end: 100 });
+        let range = node.sampling_window_heights().await.unwrap();
+        assert!(range.end < range.start);
+
+        node.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn window_heights_of_in_memory_store() {
+        let dir = TempDir::new().unwrap();
+        let mut config = test_node_config(&dir);
+        let base_path = dir.path().join("node");
+        config.base_path = base_path.to_string_lossy().into_owned();
+        // smaller default window of the in-memory stores would prune the headers
+        config.syncing_window_secs = Some(3600);
+
+        let mut gen = ExtendedHeaderGenerator::new();
+        gen.set_time(
+            (tendermint::Time::now() - Duration::from_secs(600)).unwrap(),
+            Duration::from_secs(6),
+        );
+        let store = InMemoryStore::new();
+        store.insert(gen.next_many_verified(100)).await.unwrap();
+
+        let builder = config
+            .into_node_builder_with(InMemoryBackend { store })
+            .await
+            .unwrap();
+        let node = builder.start().await.unwrap();
+
+        let info = node.syncer_info().await.unwrap();
+        assert_eq!(info.stored_headers.into_inner().as_slice(), &[1..=100]);
+        let range = window_heights(&node, Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(
+            range,
+            BlockRange {
+                start: 90,
+                end: 100
+            }
+        );
+        assert!(!base_path.exists());
+
+        node.stop().await;
+    }
+
+    #[tokio::test]
+    async fn inject_trusted_header() {
+        let mut gen = ExtendedHeaderGenerator::new();
+        let (node, _dir) = start_test_node_with_headers(gen.next_many(5)).await;
+
+        gen.skip(4);
+        let header = gen.next();
+        let height = node
+            .inject_trusted_header(serde_json::to_vec(&header).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(height, 10);
+        let stored = node.get_header_by_height(10).await.unwrap();
+        assert_eq!(stored, header.to_string());
+
+        let mut tampered = gen.next();
+        tampered.header.time = (tampered.header.time + Duration::from_secs(1)).unwrap();
+        let err = node
+            .inject_trusted_header(serde_json::to_vec(&tampered).unwrap())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::InvalidHeader { .. }), "{err}");
+        let err = node.get_header_by_height(11).await.unwrap_err();
+        assert!(matches!(err, LuminaError::Network { .. }), "{err}");
+
+        node.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn cleanup_stale_stores() {
+        let base_path = TempDir::new().unwrap();
+        let config = test_node_config(&base_path);
+        for name in ["store-celestia", "store-mocha-4", "store-arabica-11"] {
+            redb::Database::create(base_path.path().join(name)).unwrap();
+        }
+        std::fs::create_dir(base_path.path().join("store-old")).unwrap();
+        std::fs::write(base_path.path().join("keys"), b"other").unwrap();
+
+        let node = LuminaNode::new(config).unwrap();
+        node.start().await.unwrap();
+
+        // store of the running `private` node is not kept
+        let err = node
+            .cleanup_stale_stores(vec![Network::Mocha])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::StorageLocked { .. }), "{err}");
+        assert!(base_path.path().join("store-celestia").exists());
+
+        let private = Network::custom("private").unwrap();
+        let removed = node
+            .cleanup_stale_stores(vec![Network::Mocha, private])
+            .await
+            .unwrap();
+        let expected: Vec<_> = ["store-arabica-11", "store-celestia", "store-old"]
+            .iter()
+            .map(|name| base_path.path().join(name).to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(removed, expected);
+
+        let mut remaining: Vec<_> = std::fs::read_dir(base_path.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        remaining.sort();
+        assert_eq!(remaining, ["keys", "store-mocha-4", "store-private"]);
+
+        node.stop().await.unwrap();
+        let removed = node.cleanup_stale_stores(Vec::new()).await.unwrap();
+        assert_eq!(removed.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn in_flight_fetches_without_peers() {
+        let (node, _dir) = start_test_node().await;
+
+        // Node has no peers, so nothing is fetched.
+        assert!(node.in_flight_fetches().await.unwrap().is_empty());
+
+        node.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn no_reorg_without_peers() {
+        let (node, _dir) = start_test_node().await;
+        assert_eq!(node.last_reorg().await.unwrap(), None);
+        node.stop().await.unwrap();
+
+        let err = node.last_reorg().await.unwrap_err();
+        assert!(matches!(err, LuminaError::NodeNotRunning));
+    }
+
+    #[tokio::test]
+    async fn pausing_sync() {
+        let (node, _dir) = start_test_node().await;
+        assert!(!node.sync_paused().await.unwrap());
+
+        node.pause_sync().await.unwrap();
+        assert!(node.sync_paused().await.unwrap());
+        // Local reads are still answered
+        node.syncer_info().await.unwrap();
+        let blob = BlobInput {
+            namespace: Namespace::new_v0(&[1]).unwrap().as_bytes().to_vec(),
+            data: vec![1; 10],
+            share_version: None,
+        };
+        let err = node.submit_blobs(vec![blob.clone()]).await.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                LuminaError::NodeReadOnly {
+                    reason: ReadOnlyReason::Paused
+                }
+            ),
+            "{err}"
+        );
+
+        node.resume_sync().await.unwrap();
+        assert!(!node.sync_paused().await.unwrap());
+        // Node has no peers, so it never learns the network head.
+        let err = node.submit_blobs(vec![blob]).await.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                LuminaError::NodeReadOnly {
+                    reason: ReadOnlyReason::NotSynced
+                }
+            ),
+            "{err}"
+        );
+
+        node.stop().await.unwrap();
+
+        let err = node.pause_sync().await.unwrap_err();
+        assert!(matches!(err, LuminaError::NodeNotRunning));
+    }
+
+    #[tokio::test]
+    async fn batched_events_until_stopped() {
+        let (node, _dir) = start_test_node().await;
+        let listener = RecordingListener::default();
+        let batching = EventBatchConfig {
+            max_batch_size: 1000,
+            max_delay_ms: 60_000,
+        };
+        node.subscribe_events(Box::new(listener.clone()), batching)
+            .await
+            .unwrap();
+
+        // Incomplete batch is delivered once the node stops.
+        node.stop().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let batches = listener.batches();
+        assert_eq!(batches.len(), 1);
+        assert!(matches!(
+            batches[0].last().map(|info| &info.event),
+            Some(NodeEvent::NodeStopped)
+        ));
+
+        let err = node
+            .subscribe_events(Box::new(listener), batching)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::NodeNotRunning));
+    }
+
+    #[tokio::test]
+    async fn subscribe_namespace_validates_namespace() {
+        struct IgnoredData;
+
+        impl NamespaceListener for IgnoredData {
+            fn on_namespace_data(&self, _: types::NamespaceDataAvailable) {}
+        }
+
+        let (node, _dir) = start_test_node().await;
+        let node = Arc::new(node);
+
+        let err = node
+            .clone()
+            .subscribe_namespace(vec![1; 3], Box::new(IgnoredData))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::InvalidNamespace { .. }));
+
+        let namespace = Namespace::new_v0(&[1, 2, 3]).unwrap();
+        node.clone()
+            .subscribe_namespace(namespace.as_bytes().to_vec(), Box::new(IgnoredData))
+            .await
+            .unwrap();
+
+        node.stop().await.unwrap();
+        let err = node
+            .subscribe_namespace(namespace.as_bytes().to_vec(), Box::new(IgnoredData))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::NodeNotRunning));
+    }
+
+    #[tokio::test]
+    async fn stream_block_shares_validates_height() {
+        struct IgnoredShares;
+
+        impl ShareListener for IgnoredShares {
+            fn on_share(&self, _: types::ShareRecord) {}
+        }
+
+        let headers = ExtendedHeaderGenerator::new().next_many(3);
+        let (node, _dir) = start_test_node_with_headers(headers).await;
+
+        let err = node
+            .stream_block_shares(4, Box::new(IgnoredShares))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::HeightNotLocal { height: 4 }));
+
+        node.stop().await.unwrap();
+        let err = node
+            .stream_block_shares(1, Box::new(IgnoredShares))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::NodeNotRunning));
+    }
+
+    #[tokio::test]
+    async fn zero_idle_connection_timeout() {
+        let base_path = TempDir::new().unwrap();
+        let config = NodeConfig {
+            idle_connection_timeout_secs: Some(0),
+            ..test_node_config(&base_path)
+        };
+
+        let node = LuminaNode::new(config).unwrap();
+        let err = node.start().await.unwrap_err();
+        assert!(err.to_string().contains("Idle connection timeout"), "{err}");
+        assert!(!node.is_running().await);
+    }
+
+    #[tokio::test]
+    async fn stored_time_range() {
+        let (node, _dir) = start_test_node().await;
+        assert_eq!(node.stored_time_range().await.unwrap(), None);
+        node.stop().await.unwrap();
+
+        let start = (tendermint::Time::now() - Duration::from_secs(600)).unwrap();
+        let mut gen = ExtendedHeaderGenerator::new();
+        gen.set_time(start, Duration::from_secs(6));
+        let (node, _dir) = start_test_node_with_headers(gen.next_many(5)).await;
+
+        // first header is produced a block time after the start
+        let start_unix_ms = (start.unix_timestamp_nanos() / 1_000_000) as u64 + 6_000;
+        assert_eq!(
+            node.stored_time_range().await.unwrap(),
+            Some(TimeRange {
+                start_unix_ms,
+                end_unix_ms: start_unix_ms + 4 * 6_000,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn pinned_heights() {
+        let (node, _dir) = start_test_node().await;
+        assert!(node.pinned_heights().await.unwrap().is_empty());
+
+        node.pin_height(5).await.unwrap();
+        node.pin_height(3).await.unwrap();
+        node.pin_height(5).await.unwrap();
+        assert_eq!(node.pinned_heights().await.unwrap(), [3, 5]);
+
+        node.unpin_height(3).await.unwrap();
+        node.unpin_height(7).await.unwrap();
+        assert_eq!(node.pinned_heights().await.unwrap(), [5]);
+    }
+
+    #[tokio::test]
+    async fn sampling_disabled() {
+        let headers = ExtendedHeaderGenerator::new().next_many(3);
+        let base_path = TempDir::new().unwrap();
+        let config = NodeConfig {
+            enable_sampling: Some(false),
+            ..test_node_config(&base_path)
+        };
+        insert_test_headers(&config, headers, Vec::new()).await;
+        let node = LuminaNode::new(config).unwrap();
+        assert!(!node.effective_config().sampling_enabled);
+        node.start().await.unwrap();
+
+        // headers are still synced, but none of them are sampled
+        let stored = node.stored_heights_bitmap().await.unwrap();
+        assert!(stored.contains(1) && stored.contains(3));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let events = node.recent_events(100).await.unwrap();
+        assert!(!events
+            .iter()
+            .any(|info| matches!(info.event, NodeEvent::SamplingStarted { .. })));
+
+        let err = node.sample_range(1, 3, false).await.unwrap_err();
+        assert!(err.to_string().contains("Sampling is disabled"), "{err}");
+
+        node.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn diagnostics_report() {
+        let key = [7; 32];
+        let base_path = TempDir::new().unwrap();
+        let node = LuminaNode::new(NodeConfig {
+            ed25519_secret_key_bytes: Some(key.to_vec()),
+            ..test_node_config(&base_path)
+        })
+        .unwrap();
+        node.start().await.unwrap();
+        node.pin_height(3).await.unwrap();
+
+        let failure = NodeEventInfo {
+            event: NodeEvent::FatalPrunerError {
+                error: "pruner failed".to_owned(),
+            },
+            seq: 0,
+            time_unix_ms: 0,
+            file_path: "fixture".to_owned(),
+            file_line: 1,
+        };
+        node.recorded_events
+            .lock()
+            .unwrap()
+            .record(failure, Instant::now());
+
+        let report = node.diagnostics().await.unwrap();
+        let peer_id = libp2p::identity::Keypair::ed25519_from_bytes(key)
+            .unwrap()
+            .public()
+            .to_peer_id();
+        assert_eq!(report.local_peer_id, peer_id.to_base58());
+        assert_eq!(report.network_id, node.config.network.id());
+        assert_eq!(report.effective_config, node.effective_config());
+        assert_eq!(report.store_schema_version, RedbStore::SCHEMA_VERSION);
+        assert_eq!(report.pinned_heights, [3]);
+        // the store was written to when opened and pinning
+        assert!(report.storage_write_latency.max > 0.0);
+        assert_eq!(report.last_fatal_error.as_deref(), Some("pruner failed"));
+        assert!(!report.recent_events.is_empty());
+
+        let dump = format!("{report:?}");
+        assert!(!dump.contains(&format!("{:?}", key.to_vec())));
+        assert!(!dump.contains(&format!("{key:?}")));
+        assert!(!dump.contains(&hex::encode(key)));
+    }
+
+    #[tokio::test]
+    async fn rotate_identity_keeps_store() {
+        let headers = ExtendedHeaderGenerator::new().next_many(5);
+        let (node, _dir) = start_test_node_with_headers(headers).await;
+        let initial_peer_id = node.local_peer_id().await.unwrap();
+        let stored = node.stored_heights_bitmap().await.unwrap().to_bytes();
+
+        let generated = node.rotate_identity(None).await.unwrap();
+        assert_ne!(generated.peer_id, initial_peer_id);
+        assert_eq!(node.local_peer_id().await.unwrap(), generated.peer_id);
+
+        let key = [7; 32];
+        let expected = libp2p::identity::Keypair::ed25519_from_bytes(key)
+            .unwrap()
+            .public()
+            .to_peer_id();
+        let provided = node.rotate_identity(Some(key.to_vec())).await.unwrap();
+        assert_eq!(provided.peer_id, expected.to_base58());
+        let stored_after = node.stored_heights_bitmap().await.unwrap();
+        assert_eq!(stored_after.to_bytes(), stored);
+        assert!((1..=5).all(|height| stored_after.contains(height)));
+
+        // rotated identity survives the restarts
+        node.stop().await.unwrap();
+        node.start().await.unwrap();
+        assert_eq!(node.local_peer_id().await.unwrap(), expected.to_base58());
+
+        let err = node.rotate_identity(Some(vec![1; 16])).await.unwrap_err();
+        assert!(matches!(err, LuminaError::Network { .. }));
+        assert!(node.is_running().await);
+    }
+
+    #[tokio::test]
+    async fn rotating_identity_cancels_operations() {
+        let headers = ExtendedHeaderGenerator::new().next_many(3);
+        let (node, _dir) = start_test_node_with_headers(headers).await;
+
+        // Node has no peers, so sampling never finishes.
+        let (sample_res, rotated) = tokio::join!(node.sample_range(1, 3, false), async {
+            while node.operations().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            timeout(Duration::from_secs(5), node.rotate_identity(None))
+                .await
+                .unwrap()
+        });
+
+        let err = sample_res.unwrap_err();
+        assert!(
+            matches!(err, LuminaError::OperationCancelled { .. }),
+            "{err}"
+        );
+        assert_eq!(
+            node.local_peer_id().await.unwrap(),
+            rotated.unwrap().peer_id
+        );
+        assert!(node.operations().is_empty());
+    }
+
+    #[tokio::test]
+    async fn dropping_running_node() {
+        let (node, _dir) = start_test_node().await;
+        let listener = RecordingListener::default();
+        let batching = EventBatchConfig {
+            max_batch_size: 1,
+            max_delay_ms: 0,
+        };
+        node.subscribe_events(Box::new(listener.clone()), batching)
+            .await
+            .unwrap();
+
+        drop(node);
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        let delivered = listener.events();
+        assert!(matches!(delivered.last(), Some(NodeEvent::NodeStopped)));
+
+        // nothing is running anymore to publish new events
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(listener.events().len(), delivered.len());
+    }
+
+    #[tokio::test]
+    async fn cancelling_sync_to_head() {
+        struct NoopProgress;
+
+        impl SyncProgress for NoopProgress {
+            fn on_progress(&self, _: types::SyncProgressInfo) {}
+        }
+
+        let (node, _dir) = start_test_node().await;
+
+        // Node has no peers, so it never learns the network head.
+        let (res, _) = tokio::join!(node.sync_to_head(Box::new(NoopProgress)), async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            node.cancel_sync().await;
+        });
+        assert!(matches!(res.unwrap_err(), LuminaError::SyncCancelled));
+
+        node.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn stopping_node_cancels_operations() {
+        let headers = ExtendedHeaderGenerator::new().next_many(3);
+        let (node, _dir) = start_test_node_with_headers(headers).await;
+
+        // Node has no peers, so sampling never finishes.
+        let (sample_res, _) = tokio::join!(node.sample_range(1, 3, false), async {
+            while node.operations().is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            timeout(Duration::from_secs(5), node.stop())
+                .await
+                .unwrap()
+                .unwrap();
+        });
+
+        let err = sample_res.unwrap_err();
+        assert!(
+            matches!(err, LuminaError::OperationCancelled { .. }),
+            "{err}"
+        );
+        assert!(node.operations().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancelling_operation_by_id() {
+        struct NoopProgress;
+
+        impl SyncProgress for NoopProgress {
+            fn on_progress(&self, _: types::SyncProgressInfo) {}
+        }
+
+        let headers = ExtendedHeaderGenerator::new().next_many(3);
+        let (node, _dir) = start_test_node_with_headers(headers).await;
+        assert!(node.operations().is_empty());
+
+        // Node has no peers, so neither syncing nor sampling ever finishes.
+        let (sync_res, sample_res, sample_id) = tokio::join!(
+            node.sync_to_head(Box::new(NoopProgress)),
+            node.sample_range(1, 3, false),
+            async {
+                let operations = loop {
+                    let operations = node.operations();
+                    if operations.len() == 2 {
+                        break operations;
+                    }
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                };
+                // both are started concurrently, so in any order
+                let id_of = |kind| {
+                    operations
+                        .iter()
+                        .find(|op| op.kind == kind)
+                        .map(|op| op.id)
+                        .unwrap()
+                };
+                let sync_id = id_of(OperationKind::SyncToHead);
+                let sample_id = id_of(OperationKind::SampleRange);
+
+                assert!(node.cancel_operation(sample_id));
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                let remaining: Vec<_> = node.operations().iter().map(|op| op.id).collect();
+                assert_eq!(remaining, [sync_id]);
+                assert!(!node.cancel_operation(sample_id));
+
+                assert!(node.cancel_operation(sync_id));
+                sample_id
+            }
+        );
+
+        assert!(matches!(
+            sample_res.unwrap_err(),
+            LuminaError::OperationCancelled { id } if id == sample_id
+        ));
+        assert!(matches!(sync_res.unwrap_err(), LuminaError::SyncCancelled));
+        assert!(node.operations().is_empty());
+
+        node.stop().await.unwrap();
+    }
+
+    #[cfg(feature = "diagnostics")]
+    #[tokio::test]
+    async fn replaying_recorded_events() {
+        let headers = ExtendedHeaderGenerator::new().next_many(3);
+        let sampled = vec![(2, SamplingStatus::Accepted, Vec::new())];
+        let base_path = TempDir::new().unwrap();
+        let config = test_node_config(&base_path);
+        insert_test_headers(&config, headers, sampled).await;
+        let clock = Arc::new(MockClock::new(UNIX_EPOCH));
+        // leave room for replaying the events older than the start of the clock
+        clock.advance(Duration::from_secs(3600));
+        let node = LuminaNode::with_clock(config, clock.clone());
+        node.start().await.unwrap();
+
+        let peer = PeerId::from_libp2p(&libp2p::PeerId::random());
+        let captured = |event, time_unix_ms, file_line: u32| NodeEventInfo {
+            event,
+            seq: file_line.into(),
+            time_unix_ms,
+            file_path: "fixture".to_owned(),
+            file_line,
+        };
+        let connected = NodeEvent::PeerConnected {
+            id: peer.clone(),
+            trusted: false,
+        };
+        let fixture = vec![
+            // outside of the window of the rates
+            captured(connected.clone(), 1_000_000, 1),
+            captured(connected.clone(), 1_100_000, 2),
+            captured(connected.clone(), 1_101_000, 3),
+            captured(
+                NodeEvent::PeerDisconnected {
+                    id: peer.clone(),
+                    trusted: false,
+                    reason: lumina_node::events::DisconnectReason::Unknown,
+                },
+                1_102_000,
+                4,
+            ),
+            captured(
+                NodeEvent::SamplingFinished {
+                    height: 2,
+                    accepted: true,
+                    took_ms: 250,
+                },
+                1_103_000,
+                5,
+            ),
+            captured(connected, 1_110_000, 6),
+        ];
+
+        node.replay_events(fixture).await.unwrap();
+
+        let rates = node.connection_rates(60).await.unwrap();
+        assert_eq!(rates.connects_per_sec, 3.0 / 60.0);
+        assert_eq!(rates.disconnects_per_sec, 1.0 / 60.0);
+
+        let outcome = node.sampling_result(2).await.unwrap().unwrap();
+        assert_eq!(outcome.took_ms, Some(250));
+
+        let lines: Vec<_> = node
+            .recent_events(10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|info| info.file_line)
+            .collect();
+        assert_eq!(lines, [1, 2, 3, 4, 5, 6]);
+
+        // replaying again starts from scratch
+        node.replay_events(Vec::new()).await.unwrap();
+        assert!(node.recent_events(10).await.unwrap().is_empty());
+        assert_eq!(
+            node.sampling_result(2).await.unwrap().unwrap().took_ms,
+            None
+        );
+
+        node.stop().await.unwrap();
     }
 }
@@ -0,0 +1,141 @@
+//! Utilities for writing tests.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use celestia_types::consts::appconsts::{SHARE_INFO_BYTES, SHARE_SIZE};
+use celestia_types::nmt::{Namespace, NS_SIZE};
+use celestia_types::test_utils::ExtendedHeaderGenerator;
+use celestia_types::{AppVersion, DataAvailabilityHeader, ExtendedDataSquare, ExtendedHeader};
+use cid::Cid;
+use lumina_node::network::Network;
+use lumina_node::store::{RedbStore, SamplingStatus, Store};
+use tempfile::TempDir;
+
+use crate::types::{NodeConfig, NodeEvent, NodeEventInfo, NodeEventListener};
+use crate::LuminaNode;
+
+/// [`NodeConfig`] of a node which stores its data in the given directory and has no bootnodes.
+pub(crate) fn test_node_config(base_path: &TempDir) -> NodeConfig {
+    NodeConfig {
+        base_path: base_path.path().to_string_lossy().into_owned(),
+        network: Network::custom("private").unwrap(),
+        bootnodes: Some(Vec::new()),
+        trusted_peers: None,
+        listen_addresses: None,
+        syncing_window_secs: None,
+        pruning_delay_secs: None,
+        retention: None,
+        sampling_concurrency: None,
+        batch_size: None,
+        header_sub_buffer: None,
+        header_sub_overflow: None,
+        ed25519_secret_key_bytes: None,
+        identity_mnemonic: None,
+        dns_config: None,
+        gossipsub_config: None,
+        transports: None,
+        idle_connection_timeout_secs: None,
+        keepalive_ping_secs: None,
+        event_history_size: None,
+        low_memory: false,
+        peer_wait_timeout_ms: None,
+        enable_sampling: None,
+        tx_config: None,
+    }
+}
+
+/// Next header of the generator, for a block with all the shares in the namespace.
+pub(crate) fn next_header_with_namespace(
+    gen: &mut ExtendedHeaderGenerator,
+    namespace: Namespace,
+) -> ExtendedHeader {
+    let share = [
+        namespace.as_bytes(),
+        &[1; SHARE_INFO_BYTES],
+        &[0; SHARE_SIZE - NS_SIZE - SHARE_INFO_BYTES],
+    ]
+    .concat();
+    let eds = ExtendedDataSquare::from_ods(vec![share; 4], AppVersion::V2).unwrap();
+
+    gen.next_with_dah(DataAvailabilityHeader::from_eds(&eds))
+}
+
+/// Start a [`LuminaNode`] which never connects to any peer.
+///
+/// Returned [`TempDir`] holds the node's store and must outlive the node.
+pub(crate) async fn start_test_node() -> (LuminaNode, TempDir) {
+    let base_path = TempDir::new().unwrap();
+    let node = LuminaNode::new(test_node_config(&base_path)).unwrap();
+    node.start().await.unwrap();
+
+    (node, base_path)
+}
+
+/// Start a [`LuminaNode`] like [`start_test_node`], with the headers already in its store.
+pub(crate) async fn start_test_node_with_headers(
+    headers: Vec<ExtendedHeader>,
+) -> (LuminaNode, TempDir) {
+    start_test_node_with_sampled_headers(headers, Vec::new()).await
+}
+
+/// Start a [`LuminaNode`] like [`start_test_node_with_headers`], with the sampling metadata
+/// of the heights already in its store.
+pub(crate) async fn start_test_node_with_sampled_headers(
+    headers: Vec<ExtendedHeader>,
+    sampled: Vec<(u64, SamplingStatus, Vec<Cid>)>,
+) -> (LuminaNode, TempDir) {
+    let base_path = TempDir::new().unwrap();
+    let config = test_node_config(&base_path);
+    insert_test_headers(&config, headers, sampled).await;
+
+    let node = LuminaNode::new(config).unwrap();
+    node.start().await.unwrap();
+
+    (node, base_path)
+}
+
+/// Insert the headers and their sampling metadata into the store of the node with the config.
+pub(crate) async fn insert_test_headers(
+    config: &NodeConfig,
+    headers: Vec<ExtendedHeader>,
+    sampled: Vec<(u64, SamplingStatus, Vec<Cid>)>,
+) {
+    let store_path = Path::new(&config.base_path).join(format!("store-{}", config.network.id()));
+    let db = redb::Database::create(store_path).unwrap();
+    let store = RedbStore::new(Arc::new(db)).await.unwrap();
+    store.insert(headers).await.unwrap();
+    for (height, status, cids) in sampled {
+        store
+            .update_sampling_metadata(height, status, cids)
+            .await
+            .unwrap();
+    }
+    store.close().await.unwrap();
+}
+
+/// [`NodeEventListener`] recording the batches of the events delivered to it.
+#[derive(Clone, Default)]
+pub(crate) struct RecordingListener(Arc<Mutex<Vec<Vec<NodeEventInfo>>>>);
+
+impl NodeEventListener for RecordingListener {
+    fn on_events(&self, events: Vec<NodeEventInfo>) {
+        self.0.lock().unwrap().push(events);
+    }
+}
+
+impl RecordingListener {
+    /// Batches of the events delivered so far.
+    pub(crate) fn batches(&self) -> Vec<Vec<NodeEventInfo>> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Events delivered so far, in the order of delivery.
+    pub(crate) fn events(&self) -> Vec<NodeEvent> {
+        self.batches()
+            .into_iter()
+            .flatten()
+            .map(|info| info.event)
+            .collect()
+    }
+}
@@ -0,0 +1,245 @@
+use celestia_grpc::Wallet;
+use celestia_types::blob::RawBlob;
+use celestia_types::consts::appconsts::{
+    self, AppVersion, CONTINUATION_SPARSE_SHARE_CONTENT_SIZE, FIRST_COMPACT_SHARE_CONTENT_SIZE,
+    FIRST_SPARSE_SHARE_CONTENT_SIZE, SHARE_VERSION_ZERO,
+};
+use celestia_types::nmt::Namespace;
+use celestia_types::Blob;
+use k256::ecdsa::SigningKey;
+use uniffi::Record;
+
+use crate::error::{LuminaError, Result};
+use crate::types::{connect_grpc, TxConfig};
+
+/// Blob to be included in a transaction.
+#[derive(Record, Debug, Clone)]
+pub struct BlobInput {
+    /// Raw namespace of the blob, including the version byte.
+    pub namespace: Vec<u8>,
+    /// Data of the blob.
+    pub data: Vec<u8>,
+    /// Version of the shares the blob is split into. Defaults to `0`.
+    pub share_version: Option<u8>,
+}
+
+impl TryFrom<BlobInput> for RawBlob {
+    type Error = LuminaError;
+
+    fn try_from(input: BlobInput) -> Result<Self> {
+        let (namespace, data) = input.into_parts()?;
+
+        Ok(RawBlob {
+            namespace_id: namespace.id().to_vec(),
+            data,
+            share_version: SHARE_VERSION_ZERO.into(),
+            namespace_version: namespace.version().into(),
+            signer: Vec::new(),
+        })
+    }
+}
+
+impl BlobInput {
+    /// Validate the namespace and the share version, returning the namespace and the data.
+    fn into_parts(self) -> Result<(Namespace, Vec<u8>)> {
+        let namespace = Namespace::from_raw(&self.namespace)
+            .map_err(|e| LuminaError::invalid_namespace(e.to_string()))?;
+        let share_version = self.share_version.unwrap_or(SHARE_VERSION_ZERO);
+
+        if share_version != SHARE_VERSION_ZERO {
+            return Err(LuminaError::invalid_blob(format!(
+                "Unsupported share version: {share_version}"
+            )));
+        }
+
+        Ok((namespace, self.data))
+    }
+
+    /// Convert into the blob, computing its commitment for the app version.
+    pub(crate) fn into_blob(self, app_version: AppVersion) -> Result<Blob> {
+        let raw = RawBlob::try_from(self)?;
+        Blob::from_raw(raw, app_version).map_err(|e| LuminaError::invalid_blob(e.to_string()))
+    }
+}
+
+/// Submit the blobs in a single transaction signed by the account of the config and wait
+/// for it to be committed, returning the height of the block including them.
+pub(crate) async fn submit_blobs(config: TxConfig, blobs: Vec<BlobInput>) -> Result<u64> {
+    if blobs.is_empty() {
+        return Err(LuminaError::invalid_blob("No blobs to submit"));
+    }
+    let blobs = blobs
+        .into_iter()
+        .map(BlobInput::into_parts)
+        .collect::<Result<Vec<_>>>()?;
+
+    let signing_key = SigningKey::from_slice(&config.secret_key)
+        .map_err(|e| LuminaError::network(format!("Invalid secp256k1 key: {e}")))?;
+    let client = connect_grpc(config.grpc_endpoint).await?;
+    let mut wallet = Wallet::new(client, *signing_key.verifying_key(), signing_key);
+
+    let response = wallet
+        .submit_blobs(blobs)
+        .await
+        .map_err(|e| LuminaError::network(format!("Failed to submit blobs: {e}")))?;
+
+    if response.code != 0 {
+        return Err(LuminaError::TxFailed {
+            code: response.code,
+            msg: response.raw_log,
+        });
+    }
+
+    Ok(response.height.value())
+}
+
+/// Shares of the square taken by the transaction paying for a single blob.
+const PFB_TX_SHARES: u64 = 1;
+
+/// Index of the first share of the blob placed after the given number of shares, aligned
+/// to the width of its subtrees as of the non-interactive default rules (ADR013).
+fn blob_start(cursor: u64, blob_shares: u64, subtree_root_threshold: u64) -> u64 {
+    let min_square_size = ((blob_shares as f64).sqrt().ceil() as u64).next_power_of_two();
+    let subtree_width = blob_shares
+        .div_ceil(subtree_root_threshold)
+        .next_power_of_two()
+        .min(min_square_size);
+
+    cursor.div_ceil(subtree_width) * subtree_width
+}
+
+/// Limits of the size of blobs accepted by the network.
+#[derive(Record, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlobSizeLimits {
+    /// Maximum width of the original data square.
+    pub max_square_size: u64,
+    /// Maximum size of a single blob in bytes.
+    pub max_blob_size: u64,
+    /// Maximum size of all the blobs in a single transaction in bytes. Each blob
+    /// starts in a new share, so with multiple blobs the limit is reached sooner.
+    pub max_total_blob_size: u64,
+}
+
+impl BlobSizeLimits {
+    /// Limits of the blobs in the square of the given width.
+    ///
+    /// Transaction paying for the blob is assumed to fit the first compact share, which
+    /// is left out of the square and of the maximum transaction size. The blob starts at
+    /// the index aligned to the width of its subtrees, so the shares up to it are left
+    /// out too.
+    pub(crate) fn new(max_square_size: u64, app_version: AppVersion) -> Self {
+        let square_shares = max_square_size.saturating_mul(max_square_size);
+        let threshold = appconsts::subtree_root_threshold(app_version);
+
+        let mut blob_shares = square_shares.saturating_sub(PFB_TX_SHARES);
+        while blob_shares > 0
+            && blob_start(PFB_TX_SHARES, blob_shares, threshold) + blob_shares > square_shares
+        {
+            blob_shares -= 1;
+        }
+
+        let square_capacity = if blob_shares == 0 {
+            0
+        } else {
+            FIRST_SPARSE_SHARE_CONTENT_SIZE as u64
+                + (blob_shares - 1) * CONTINUATION_SPARSE_SHARE_CONTENT_SIZE as u64
+        };
+
+        let max_total_blob_size = match appconsts::max_tx_size(app_version) {
+            Some(max_tx_size) => {
+                let pfb_tx_size = FIRST_COMPACT_SHARE_CONTENT_SIZE as u64;
+                square_capacity.min(max_tx_size.saturating_sub(pfb_tx_size))
+            }
+            None => square_capacity,
+        };
+
+        BlobSizeLimits {
+            max_square_size,
+            max_blob_size: max_total_blob_size,
+            max_total_blob_size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use celestia_types::blob::BlobParams;
+
+    fn blob_input(namespace: Namespace, share_version: Option<u8>) -> BlobInput {
+        BlobInput {
+            namespace: namespace.as_bytes().to_vec(),
+            data: b"lumina".to_vec(),
+            share_version,
+        }
+    }
+
+    #[test]
+    fn blob_input_conversion() {
+        let ns_a = Namespace::new_v0(&[1]).unwrap();
+        let ns_b = Namespace::new_v0(&[2]).unwrap();
+
+        let raw_a = RawBlob::try_from(blob_input(ns_a, None)).unwrap();
+        let raw_b = RawBlob::try_from(blob_input(ns_b, Some(0))).unwrap();
+
+        assert_eq!(raw_a.namespace_id, ns_a.id());
+        assert_eq!(raw_b.namespace_id, ns_b.id());
+        assert_eq!(raw_a.namespace_version, 0);
+        assert_eq!(raw_a.share_version, 0);
+        assert_eq!(raw_a.data, b"lumina");
+    }
+
+    #[test]
+    fn blob_input_invalid_namespace() {
+        let input = BlobInput {
+            namespace: vec![0; 3],
+            data: b"lumina".to_vec(),
+            share_version: None,
+        };
+
+        assert!(matches!(
+            RawBlob::try_from(input).unwrap_err(),
+            LuminaError::InvalidNamespace { .. }
+        ));
+    }
+
+    #[test]
+    fn blob_input_unsupported_share_version() {
+        let input = blob_input(Namespace::new_v0(&[1]).unwrap(), Some(1));
+
+        assert!(matches!(
+            RawBlob::try_from(input).unwrap_err(),
+            LuminaError::InvalidBlob { .. }
+        ));
+    }
+
+    #[test]
+    fn blob_size_limits() {
+        let params = BlobParams {
+            gas_per_blob_byte: 8,
+            gov_max_square_size: 64,
+        };
+
+        // blob of 64 subtrees starts in the second row, after the transaction paying for it
+        let limits = BlobSizeLimits::new(params.gov_max_square_size, AppVersion::V2);
+        assert_eq!(limits.max_square_size, 64);
+        assert_eq!(limits.max_blob_size, 478 + 4031 * 482);
+        assert_eq!(limits.max_total_blob_size, limits.max_blob_size);
+
+        // square fits ~2MB, which is still less than the transaction limit of v3
+        let limits = BlobSizeLimits::new(params.gov_max_square_size, AppVersion::V3);
+        assert_eq!(limits.max_blob_size, 478 + 4031 * 482);
+
+        // transaction size is limited since v3, including the transaction paying for the blob
+        let limits = BlobSizeLimits::new(128, AppVersion::V3);
+        assert_eq!(limits.max_blob_size, 2_097_152 - 474);
+        assert_eq!(limits.max_total_blob_size, 2_097_152 - 474);
+
+        // blobs up to the subtree root threshold aren't aligned
+        let limits = BlobSizeLimits::new(8, AppVersion::V2);
+        assert_eq!(limits.max_blob_size, 478 + 62 * 482);
+
+        let limits = BlobSizeLimits::new(1, AppVersion::V3);
+        assert_eq!(limits.max_blob_size, 0);
+    }
+}
@@ -0,0 +1,220 @@
+//! Streams of the blobs of a namespace over a range of blocks.
+
+use std::ops::RangeInclusive;
+
+use celestia_types::blob::Commitment as LuminaCommitment;
+use celestia_types::nmt::Namespace;
+use celestia_types::Blob;
+use tokio::sync::{mpsc, Mutex};
+use uniffi::{Object, Record};
+
+use crate::error::Result;
+
+/// Blobs of the namespace in a single block.
+#[derive(Record, Debug, Clone, PartialEq, Eq)]
+pub struct BlockBlobs {
+    /// Height of the block.
+    pub height: u64,
+    /// Data of the blobs of the namespace, in the order they appear in the block.
+    pub blobs: Vec<Vec<u8>>,
+}
+
+/// Stream of the blobs of a namespace, block by block, in the order of heights.
+///
+/// Blocks are fetched ahead of the consumer only until the buffer of the stream is
+/// full, then fetching waits for [`BlobStream::next`] to be called.
+#[derive(Object, Debug)]
+pub struct BlobStream {
+    receiver: Mutex<mpsc::Receiver<Result<BlockBlobs>>>,
+}
+
+impl BlobStream {
+    /// Create the stream buffering at most `capacity` blocks, together with the sender
+    /// of the blocks to it.
+    pub(crate) fn new(capacity: usize) -> (Self, mpsc::Sender<Result<BlockBlobs>>) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let stream = BlobStream {
+            receiver: Mutex::new(receiver),
+        };
+
+        (stream, sender)
+    }
+}
+
+#[uniffi::export(async_runtime = "tokio")]
+impl BlobStream {
+    /// Gets the blobs of the next block, or `None` once all the blocks were returned.
+    ///
+    /// The stream ends after the first error.
+    pub async fn next(&self) -> Result<Option<BlockBlobs>> {
+        self.receiver.lock().await.recv().await.transpose()
+    }
+}
+
+/// Source of the blobs of the blocks.
+pub(crate) trait BlobSource {
+    /// Blobs of the namespace in the block.
+    async fn blobs(&self, height: u64, namespace: Namespace) -> Result<Vec<Blob>>;
+}
+
+/// Check whether any of the blobs of the namespace in the block has the commitment.
+pub(crate) async fn commitment_included<B>(
+    source: &B,
+    height: u64,
+    namespace: Namespace,
+    commitment: &LuminaCommitment,
+) -> Result<bool>
+where
+    B: BlobSource,
+{
+    let blobs = source.blobs(height, namespace).await?;
+
+    Ok(blobs.iter().any(|blob| blob.commitment == *commitment))
+}
+
+/// Send the blobs of the namespace in each block of the range, in order, until all of
+/// them are sent, one of them fails or the stream is dropped.
+///
+/// Waits with fetching the next block until the sender has room for it, so at most the
+/// capacity of the sender blocks are held at once.
+pub(crate) async fn produce_blobs<B>(
+    source: &B,
+    namespace: Namespace,
+    heights: RangeInclusive<u64>,
+    sender: mpsc::Sender<Result<BlockBlobs>>,
+) where
+    B: BlobSource,
+{
+    for height in heights {
+        let Ok(permit) = sender.reserve().await else {
+            return;
+        };
+
+        let result = source.blobs(height, namespace).await;
+        let failed = result.is_err();
+
+        permit.send(result.map(|blobs| BlockBlobs {
+            height,
+            blobs: blobs.into_iter().map(|blob| blob.data).collect(),
+        }));
+
+        if failed {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    use celestia_types::AppVersion;
+
+    use super::*;
+    use crate::error::LuminaError;
+
+    #[derive(Default)]
+    struct CountingSource {
+        fetched: AtomicU64,
+        missing_height: Option<u64>,
+    }
+
+    impl BlobSource for CountingSource {
+        async fn blobs(&self, height: u64, namespace: Namespace) -> Result<Vec<Blob>> {
+            self.fetched.fetch_add(1, Ordering::SeqCst);
+
+            if self.missing_height == Some(height) {
+                return Err(LuminaError::HeightNotLocal { height });
+            }
+
+            let blob = Blob::new(namespace, height.to_le_bytes().to_vec(), AppVersion::V2).unwrap();
+            Ok(vec![blob])
+        }
+    }
+
+    /// Let the producer run until it's blocked on the full stream.
+    async fn settle() {
+        for _ in 0..100 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn slow_consumer_bounds_fetched_blocks() {
+        let namespace = Namespace::new_v0(&[1]).unwrap();
+        let source = Arc::new(CountingSource::default());
+        let (stream, sender) = BlobStream::new(4);
+
+        let producer = tokio::spawn({
+            let source = source.clone();
+            async move { produce_blobs(&*source, namespace, 1..=100, sender).await }
+        });
+
+        // nothing is consumed, so only the buffered blocks are fetched
+        settle().await;
+        assert_eq!(source.fetched.load(Ordering::SeqCst), 4);
+
+        for height in 1..=10 {
+            let blocks = stream.next().await.unwrap().unwrap();
+            assert_eq!(blocks.height, height);
+            assert_eq!(blocks.blobs, vec![height.to_le_bytes().to_vec()]);
+
+            settle().await;
+            assert_eq!(source.fetched.load(Ordering::SeqCst), height + 4);
+        }
+
+        // producer stops once the stream is dropped
+        drop(stream);
+        producer.await.unwrap();
+        assert_eq!(source.fetched.load(Ordering::SeqCst), 14);
+    }
+
+    #[tokio::test]
+    async fn commitment_included_in_block() {
+        let namespace = Namespace::new_v0(&[1]).unwrap();
+        let source = CountingSource {
+            missing_height: Some(3),
+            ..Default::default()
+        };
+
+        let included = Blob::new(namespace, 2u64.to_le_bytes().to_vec(), AppVersion::V2).unwrap();
+        assert!(
+            commitment_included(&source, 2, namespace, &included.commitment)
+                .await
+                .unwrap()
+        );
+        // same blob in another block
+        assert!(
+            !commitment_included(&source, 1, namespace, &included.commitment)
+                .await
+                .unwrap()
+        );
+
+        let err = commitment_included(&source, 3, namespace, &included.commitment)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, LuminaError::HeightNotLocal { height: 3 }));
+    }
+
+    #[tokio::test]
+    async fn stream_ends_after_error() {
+        let namespace = Namespace::new_v0(&[1]).unwrap();
+        let source = CountingSource {
+            missing_height: Some(3),
+            ..Default::default()
+        };
+        let (stream, sender) = BlobStream::new(8);
+
+        produce_blobs(&source, namespace, 1..=5, sender).await;
+
+        assert_eq!(stream.next().await.unwrap().unwrap().height, 1);
+        assert_eq!(stream.next().await.unwrap().unwrap().height, 2);
+        assert!(matches!(
+            stream.next().await.unwrap_err(),
+            LuminaError::HeightNotLocal { height: 3 }
+        ));
+        assert!(stream.next().await.unwrap().is_none());
+        assert_eq!(source.fetched.load(Ordering::SeqCst), 3);
+    }
+}
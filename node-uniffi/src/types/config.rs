@@ -1,11 +1,28 @@
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    net::{IpAddr, SocketAddr},
+    ops::RangeInclusive,
+    path::PathBuf,
+    time::Duration,
+};
 
 use libp2p::identity::Keypair;
-use lumina_node::{blockstore::RedbBlockstore, network, store::RedbStore, NodeBuilder};
-use tokio::task::spawn_blocking;
-use uniffi::Record;
+use libp2p::{multiaddr::Protocol, PeerId};
+use lumina_node::{
+    blockstore::RedbBlockstore,
+    network,
+    node::{
+        GossipsubTuning, HeaderSubOverflow, TransportConfig, DEFAULT_HEADER_SUB_BUFFER,
+        DEFAULT_PING_INTERVAL, DEFAULT_PRUNING_DELAY, DEFAULT_SAMPLING_CONCURRENCY,
+        DEFAULT_SAMPLING_WINDOW, MAX_SAMPLING_CONCURRENCY, MIN_PRUNING_DELAY,
+    },
+    store::RedbStore,
+    NodeBuilder,
+};
+use uniffi::{Enum, Record};
 
 use crate::error::{LuminaError, Result};
+use crate::types::mnemonic::keypair_from_mnemonic;
+use crate::types::{parse_libp2p_multiaddr, RedbBackend, StoreBackend};
 
 /// Configuration options for the Lumina node
 #[derive(Debug, Clone, Record)]
@@ -17,51 +34,325 @@ pub struct NodeConfig {
     /// Custom list of bootstrap peers to connect to.
     /// If None, uses the canonical bootnodes for the network.
     pub bootnodes: Option<Vec<String>>,
+    /// Peers trusted by the node besides the bootnodes, either as peer ids or multiaddrs
+    /// ending with `/p2p/<peer id>`. Unlike the bootnodes, they aren't dialed on start.
+    pub trusted_peers: Option<Vec<String>>,
+    /// Addresses to listen on for incoming connections, as multiaddrs.
+    /// If None, the node only dials other peers.
+    pub listen_addresses: Option<Vec<String>>,
     /// Custom syncing window in seconds. Default is 30 days.
     pub syncing_window_secs: Option<u32>,
     /// Custom pruning delay after syncing window in seconds. Default is 1 hour.
     pub pruning_delay_secs: Option<u32>,
+    /// How long the stored blocks are kept. Overrides `pruning_delay_secs` if set.
+    /// Default is the syncing window with the pruning delay.
+    pub retention: Option<Retention>,
+    /// Maximum number of blocks sampled concurrently, besides the newest head. Default is 1.
+    /// Must be at least 1, values above 16 are capped.
+    pub sampling_concurrency: Option<u32>,
     /// Maximum number of headers in batch while syncing. Default is 128.
     pub batch_size: Option<u64>,
+    /// Maximum number of headers received by header-sub buffered until they are processed.
+    /// Default is 16.
+    pub header_sub_buffer: Option<u32>,
+    /// What to do with headers received by header-sub when the buffer is full.
+    /// Default is to drop the oldest header.
+    pub header_sub_overflow: Option<HeaderSubOverflow>,
     /// Optional Set the keypair to be used as Node's identity. If None, generates a new Ed25519 keypair.
     pub ed25519_secret_key_bytes: Option<Vec<u8>>,
+    /// BIP39 mnemonic to derive the node's Ed25519 keypair from, at the path `m/44'/118'/0'/0'/0'`
+    /// with an empty passphrase. Ignored if `ed25519_secret_key_bytes` is set.
+    pub identity_mnemonic: Option<String>,
+    /// Custom DNS configuration used for resolving bootnode addresses.
+    /// If None, uses the default public DNS servers.
+    pub dns_config: Option<DnsConfig>,
+    /// Tuning of the gossipsub mesh, which new headers are received over.
+    /// If None, uses the defaults of libp2p.
+    pub gossipsub_config: Option<GossipsubConfig>,
+    /// Transports and the IP version used to connect to the peers. At least one transport
+    /// must be enabled. If None, uses TCP, QUIC and websockets over both IPv4 and IPv6.
+    pub transports: Option<TransportConfig>,
+    /// How long a connection without any active streams is kept open, in seconds. Shorter
+    /// timeout saves the battery of mobile devices. Must not be zero. Default is 15 seconds.
+    pub idle_connection_timeout_secs: Option<u32>,
+    /// Interval between the pings of each connected peer in seconds, keeping the NAT mappings
+    /// of mobile devices from expiring. Must be between 1 and 300 seconds. Default is 15 seconds.
+    pub keepalive_ping_secs: Option<u32>,
+    /// Number of the most recent events kept for [`LuminaNode::recent_events`].
+    /// Default is 256, `0` disables keeping them.
+    ///
+    /// [`LuminaNode::recent_events`]: crate::LuminaNode::recent_events
+    pub event_history_size: Option<u32>,
+    /// Keep the memory usage low on constrained devices, by limiting the syncing batches
+    /// to 16 headers, the header-sub buffer to 4 headers and the sampling concurrency to 1.
+    /// Lower values configured explicitly are kept. Default is false.
+    pub low_memory: bool,
+    /// How long the queries fetching data from the network wait for the first peer to
+    /// connect, in milliseconds, e.g. right after the start. Default is 10 seconds.
+    pub peer_wait_timeout_ms: Option<u64>,
+    /// Whether the node samples the blocks. If false, the node only syncs the headers
+    /// and sampling them on demand fails. Default is true.
+    pub enable_sampling: Option<bool>,
+    /// Consensus node and the account submitting the blobs with
+    /// [`LuminaNode::submit_blobs`]. If None, submitting fails.
+    ///
+    /// [`LuminaNode::submit_blobs`]: crate::LuminaNode::submit_blobs
+    pub tx_config: Option<TxConfig>,
 }
 
+/// How long the node keeps the blocks it has stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum Retention {
+    /// Prune the blocks once they are older than `secs`. Must be longer than the syncing
+    /// window by at least a minute.
+    Window {
+        /// Maximum age of the stored blocks in seconds.
+        secs: u32,
+    },
+    /// Never prune the blocks, keeping the full history the node has stored. Only the
+    /// blocks within the syncing window are synced and sampled.
+    KeepAll,
+}
+
+/// Configuration the node runs with, after the defaults are applied.
+#[derive(Debug, Clone, PartialEq, Eq, Record)]
+pub struct EffectiveConfig {
+    /// Syncing window in seconds.
+    pub syncing_window_secs: u64,
+    /// How long the stored blocks are kept.
+    pub retention: Retention,
+    /// Maximum number of headers in batch while syncing.
+    pub batch_size: u64,
+    /// Maximum number of headers received by header-sub buffered until they are processed.
+    pub header_sub_buffer: u32,
+    /// Maximum number of blocks sampled concurrently, besides the newest head.
+    pub sampling_concurrency: u32,
+    /// How long the queries fetching data from the network wait for the first peer to
+    /// connect, in milliseconds.
+    pub peer_wait_timeout_ms: u64,
+    /// Interval between the pings of each connected peer in seconds.
+    pub keepalive_ping_secs: u32,
+    /// Whether the node samples the blocks.
+    pub sampling_enabled: bool,
+}
+
+/// DNS configuration of the node.
+#[derive(Debug, Clone, Record)]
+pub struct DnsConfig {
+    /// Addresses of the DNS resolvers, either `ip` or `ip:port`. Default port is 53.
+    pub resolvers: Vec<String>,
+}
+
+/// Account submitting the transactions and the consensus node they're submitted to.
+#[derive(Debug, Clone, Record)]
+pub struct TxConfig {
+    /// URL of the gRPC endpoint of the consensus node, e.g. `http://localhost:9090`.
+    pub grpc_endpoint: String,
+    /// Secp256k1 secret key of the account, 32 bytes.
+    pub secret_key: Vec<u8>,
+}
+
+/// Tuning of the gossipsub mesh of the node.
+///
+/// Must satisfy `mesh_n_low <= mesh_n <= mesh_n_high`, with non-zero `mesh_n_low`.
+#[derive(Debug, Clone, Copy, Record)]
+pub struct GossipsubConfig {
+    /// Number of peers in the mesh of a topic the node aims for. Default is 6.
+    pub mesh_n: u32,
+    /// Number of peers in the mesh below which the node grafts more of them. Default is 5.
+    pub mesh_n_low: u32,
+    /// Number of peers in the mesh above which the node prunes some of them. Default is 12.
+    pub mesh_n_high: u32,
+    /// Interval between the maintenances of the mesh in milliseconds. Default is 1000.
+    pub heartbeat_interval_ms: u64,
+    /// Score the peers, pruning the misbehaving ones from the mesh. Default is false.
+    pub peer_scoring: bool,
+}
+
+impl From<GossipsubConfig> for GossipsubTuning {
+    fn from(config: GossipsubConfig) -> Self {
+        GossipsubTuning {
+            mesh_n: config.mesh_n as usize,
+            mesh_n_low: config.mesh_n_low as usize,
+            mesh_n_high: config.mesh_n_high as usize,
+            heartbeat_interval: Duration::from_millis(config.heartbeat_interval_ms),
+            peer_scoring: config.peer_scoring,
+        }
+    }
+}
+
+/// Ed25519 keypair of the node from the bytes of its secret key.
+pub(crate) fn keypair_from_secret_key(key_bytes: Vec<u8>) -> Result<Keypair> {
+    if key_bytes.len() != 32 {
+        return Err(LuminaError::network("Ed25519 private key must be 32 bytes"));
+    }
+
+    Keypair::ed25519_from_bytes(key_bytes)
+        .map_err(|e| LuminaError::network(format!("Invalid Ed25519 key: {}", e)))
+}
+
+/// Default port of the DNS servers.
+const DNS_PORT: u16 = 53;
+
+/// Default number of the most recent events kept by the node.
+const DEFAULT_EVENT_HISTORY_SIZE: u32 = 256;
+
+/// Default maximum number of headers in batch while syncing.
+const DEFAULT_BATCH_SIZE: u64 = 128;
+
+/// Default time the queries fetching data from the network wait for the first peer.
+const DEFAULT_PEER_WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Range of the allowed intervals between the pings of the peers, in seconds.
+const KEEPALIVE_PING_SECS: RangeInclusive<u32> = 1..=300;
+
+/// Limits applied in the low-memory mode.
+const LOW_MEMORY_BATCH_SIZE: u64 = 16;
+const LOW_MEMORY_HEADER_SUB_BUFFER: u32 = 4;
+const LOW_MEMORY_SAMPLING_CONCURRENCY: u32 = 1;
+
 impl NodeConfig {
-    /// Convert into NodeBuilder for the implementation
-    pub(crate) async fn into_node_builder(self) -> Result<NodeBuilder<RedbBlockstore, RedbStore>> {
-        let network_id = self.network.id();
-        let base_path = PathBuf::from(self.base_path);
-        let store_path = base_path.join(format!("store-{}", network_id));
+    /// Syncing window of the node.
+    pub(crate) fn syncing_window(&self) -> Duration {
+        self.syncing_window_secs
+            .map(|secs| Duration::from_secs(secs.into()))
+            .unwrap_or(DEFAULT_SAMPLING_WINDOW)
+    }
 
-        spawn_blocking(move || {
-            std::fs::create_dir_all(&base_path).map_err(|e| {
-                LuminaError::storage(format!("Failed to create base directory: {}", e))
-            })
-        })
-        .await
-        .map_err(|e| LuminaError::storage(format!("Failed to create base directory: {}", e)))??;
-
-        let db = spawn_blocking(move || {
-            redb::Database::create(&store_path)
-                .map(Arc::new)
-                .map_err(|e| LuminaError::StorageInit {
-                    msg: format!("Failed to create database: {}", e),
-                })
+    /// Sampling window of the node, the same as its syncing window, or `None` if it doesn't
+    /// sample the blocks.
+    pub(crate) fn sampling_window(&self) -> Option<Duration> {
+        self.sampling_enabled().then(|| self.syncing_window())
+    }
+
+    /// Retention of the stored blocks, with the default pruning delay applied.
+    pub(crate) fn retention(&self) -> Retention {
+        self.retention.unwrap_or_else(|| {
+            let pruning_delay = self
+                .pruning_delay_secs
+                .map(|secs| Duration::from_secs(secs.into()))
+                .unwrap_or(DEFAULT_PRUNING_DELAY);
+            let secs = self
+                .syncing_window()
+                .saturating_add(pruning_delay)
+                .as_secs();
+
+            Retention::Window {
+                secs: secs.try_into().unwrap_or(u32::MAX),
+            }
         })
-        .await
-        .map_err(|e| LuminaError::storage(format!("Failed to create base directory: {}", e)))??;
+    }
 
-        let store = RedbStore::new(db.clone())
-            .await
-            .map_err(|e| LuminaError::storage_init(format!("Failed to initialize store: {}", e)))?;
+    /// Number of the most recent events kept by the node.
+    pub(crate) fn event_history_size(&self) -> usize {
+        self.event_history_size
+            .unwrap_or(DEFAULT_EVENT_HISTORY_SIZE) as usize
+    }
 
-        let blockstore = RedbBlockstore::new(db);
+    /// Maximum number of headers in batch while syncing, limited in the low-memory mode.
+    pub(crate) fn batch_size(&self) -> u64 {
+        let batch_size = self.batch_size.unwrap_or(DEFAULT_BATCH_SIZE);
+        self.limit_memory(batch_size, LOW_MEMORY_BATCH_SIZE)
+    }
+
+    /// Size of the header-sub buffer, limited in the low-memory mode.
+    pub(crate) fn header_sub_buffer(&self) -> u32 {
+        let size = self
+            .header_sub_buffer
+            .unwrap_or(DEFAULT_HEADER_SUB_BUFFER as u32);
+        self.limit_memory(size, LOW_MEMORY_HEADER_SUB_BUFFER)
+    }
+
+    /// Sampling concurrency, capped by the node and limited in the low-memory mode.
+    pub(crate) fn sampling_concurrency(&self) -> u32 {
+        let limit = self
+            .sampling_concurrency
+            .unwrap_or(DEFAULT_SAMPLING_CONCURRENCY as u32)
+            .min(MAX_SAMPLING_CONCURRENCY as u32);
+        self.limit_memory(limit, LOW_MEMORY_SAMPLING_CONCURRENCY)
+    }
+
+    /// How long the queries fetching data from the network wait for the first peer.
+    pub(crate) fn peer_wait_timeout(&self) -> Duration {
+        self.peer_wait_timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_PEER_WAIT_TIMEOUT)
+    }
+
+    /// Interval between the pings of each connected peer.
+    pub(crate) fn keepalive_ping(&self) -> Duration {
+        self.keepalive_ping_secs
+            .map(|secs| Duration::from_secs(secs.into()))
+            .unwrap_or(DEFAULT_PING_INTERVAL)
+    }
+
+    /// Whether the node samples the blocks.
+    pub(crate) fn sampling_enabled(&self) -> bool {
+        self.enable_sampling.unwrap_or(true)
+    }
+
+    fn limit_memory<T: Ord>(&self, value: T, low_memory_limit: T) -> T {
+        if self.low_memory {
+            value.min(low_memory_limit)
+        } else {
+            value
+        }
+    }
+
+    /// Configuration the node runs with.
+    pub(crate) fn effective_config(&self) -> EffectiveConfig {
+        EffectiveConfig {
+            syncing_window_secs: self.syncing_window().as_secs(),
+            retention: self.retention(),
+            batch_size: self.batch_size(),
+            header_sub_buffer: self.header_sub_buffer(),
+            sampling_concurrency: self.sampling_concurrency(),
+            peer_wait_timeout_ms: self.peer_wait_timeout().as_millis() as u64,
+            keepalive_ping_secs: self.keepalive_ping().as_secs() as u32,
+            sampling_enabled: self.sampling_enabled(),
+        }
+    }
+
+    /// Convert into NodeBuilder for the implementation
+    pub(crate) async fn into_node_builder(self) -> Result<NodeBuilder<RedbBlockstore, RedbStore>> {
+        self.into_node_builder_with(RedbBackend).await
+    }
+
+    /// Convert into NodeBuilder with the stores opened by the backend
+    pub(crate) async fn into_node_builder_with<B: StoreBackend>(
+        self,
+        backend: B,
+    ) -> Result<NodeBuilder<B::Blockstore, B::Store>> {
+        // configs are fully validated before creating the store, so that a rejected one
+        // doesn't leave it behind
+        //
+        // without the bootnodes the node can never connect
+        if self.bootnodes.is_none() && self.network.canonical_bootnodes().next().is_none() {
+            return Err(LuminaError::UnknownNetwork {
+                network: self.network.id().to_owned(),
+            });
+        }
+        if let Some(secs) = self.keepalive_ping_secs {
+            if !KEEPALIVE_PING_SECS.contains(&secs) {
+                return Err(LuminaError::network(format!(
+                    "Keepalive ping interval must be between {} and {} seconds, got {secs}",
+                    KEEPALIVE_PING_SECS.start(),
+                    KEEPALIVE_PING_SECS.end()
+                )));
+            }
+        }
+
+        let syncing_window = self.syncing_window();
+        let keepalive_ping = self.keepalive_ping();
+        let batch_size = self.batch_size();
+        let header_sub_buffer = self.header_sub_buffer();
+        let sampling_concurrency = self.sampling_concurrency();
+        let sampling_enabled = self.sampling_enabled();
 
         let bootnodes = if let Some(bootnodes) = self.bootnodes {
             let mut resolved = Vec::with_capacity(bootnodes.len());
-            for addr in bootnodes {
-                resolved.push(addr.parse()?);
+            for addr in &bootnodes {
+                resolved.push(parse_libp2p_multiaddr(addr)?);
             }
             resolved
         } else {
@@ -69,32 +360,367 @@ impl NodeConfig {
         };
 
         let keypair = if let Some(key_bytes) = self.ed25519_secret_key_bytes {
-            if key_bytes.len() != 32 {
-                return Err(LuminaError::network("Ed25519 private key must be 32 bytes"));
-            }
-
-            Keypair::ed25519_from_bytes(key_bytes)
-                .map_err(|e| LuminaError::network(format!("Invalid Ed25519 key: {}", e)))?
+            keypair_from_secret_key(key_bytes)?
+        } else if let Some(mnemonic) = self.identity_mnemonic {
+            keypair_from_mnemonic(&mnemonic)?
         } else {
             libp2p::identity::Keypair::generate_ed25519()
         };
 
+        let trusted_peers = self
+            .trusted_peers
+            .as_deref()
+            .map(|peers| {
+                peers
+                    .iter()
+                    .map(|peer| parse_trusted_peer(peer))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?;
+
+        let listen_addresses = self
+            .listen_addresses
+            .as_deref()
+            .map(|addrs| {
+                addrs
+                    .iter()
+                    .map(|addr| parse_libp2p_multiaddr(addr))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?;
+
+        let dns_servers = self
+            .dns_config
+            .as_ref()
+            .map(|dns_config| {
+                dns_config
+                    .resolvers
+                    .iter()
+                    .map(|addr| parse_dns_resolver(addr))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?;
+
+        let pruning_delay = match self.retention {
+            Some(Retention::KeepAll) => None,
+            Some(Retention::Window { secs }) => {
+                let retention = Duration::from_secs(secs.into());
+                let pruning_delay = retention.saturating_sub(syncing_window);
+
+                if pruning_delay < MIN_PRUNING_DELAY {
+                    return Err(LuminaError::invalid_config(format!(
+                        "Retention window of {retention:?} must exceed the syncing window of \
+                         {syncing_window:?} by at least {MIN_PRUNING_DELAY:?}"
+                    )));
+                }
+
+                Some(pruning_delay)
+            }
+            None => self
+                .pruning_delay_secs
+                .map(|secs| Duration::from_secs(secs.into())),
+        };
+
+        let (blockstore, store) = backend
+            .open(PathBuf::from(self.base_path), self.network.id())
+            .await?;
+
         let mut builder = NodeBuilder::new()
             .store(store)
             .blockstore(blockstore)
             .network(self.network)
             .bootnodes(bootnodes)
             .keypair(keypair)
-            .sync_batch_size(self.batch_size.unwrap_or(128));
+            .sync_batch_size(batch_size)
+            .header_sub_buffer(header_sub_buffer as usize)
+            .sampling_concurrency(sampling_concurrency as usize)
+            .ping_interval(keepalive_ping);
+
+        if let Some(trusted_peers) = trusted_peers {
+            builder = builder.trusted_peers(trusted_peers);
+        }
+
+        if let Some(listen_addresses) = listen_addresses {
+            builder = builder.listen(listen_addresses);
+        }
+
+        if let Some(policy) = self.header_sub_overflow {
+            builder = builder.header_sub_overflow(policy);
+        }
+
+        if let Some(dns_servers) = dns_servers {
+            builder = builder.dns_servers(dns_servers);
+        }
+
+        if let Some(gossipsub_config) = self.gossipsub_config {
+            builder = builder.gossipsub_tuning(gossipsub_config.into());
+        }
+
+        if let Some(transports) = self.transports {
+            builder = builder.transports(transports);
+        }
+
+        if let Some(secs) = self.idle_connection_timeout_secs {
+            builder = builder.idle_connection_timeout(Duration::from_secs(secs.into()));
+        }
+
+        if !sampling_enabled {
+            builder = builder.disable_sampling();
+        }
 
         if let Some(secs) = self.syncing_window_secs {
             builder = builder.sampling_window(Duration::from_secs(secs.into()));
         }
 
-        if let Some(secs) = self.pruning_delay_secs {
-            builder = builder.pruning_delay(Duration::from_secs(secs.into()));
+        if matches!(self.retention, Some(Retention::KeepAll)) {
+            builder = builder.disable_pruning();
+        } else if let Some(pruning_delay) = pruning_delay {
+            builder = builder.pruning_delay(pruning_delay);
         }
 
         Ok(builder)
     }
 }
+
+fn parse_dns_resolver(addr: &str) -> Result<SocketAddr> {
+    addr.parse::<SocketAddr>()
+        .or_else(|_| {
+            addr.parse::<IpAddr>()
+                .map(|ip| SocketAddr::new(ip, DNS_PORT))
+        })
+        .map_err(|_| LuminaError::network(format!("Invalid DNS resolver address: {addr}")))
+}
+
+fn parse_trusted_peer(peer: &str) -> Result<PeerId> {
+    if let Ok(peer_id) = peer.parse::<PeerId>() {
+        return Ok(peer_id);
+    }
+
+    let addr = parse_libp2p_multiaddr(peer)?;
+
+    match addr.iter().last() {
+        Some(Protocol::P2p(peer_id)) => Ok(peer_id),
+        _ => Err(LuminaError::invalid_multiaddr(
+            peer,
+            "address doesn't end with a peer id",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_node_config;
+    use tempfile::TempDir;
+
+    #[test]
+    fn dns_resolver_address() {
+        assert_eq!(
+            parse_dns_resolver("127.0.0.1:5353").unwrap(),
+            "127.0.0.1:5353".parse().unwrap()
+        );
+        assert_eq!(
+            parse_dns_resolver("1.1.1.1").unwrap(),
+            "1.1.1.1:53".parse().unwrap()
+        );
+        assert_eq!(
+            parse_dns_resolver("2606:4700:4700::1111").unwrap(),
+            "[2606:4700:4700::1111]:53".parse().unwrap()
+        );
+        assert!(matches!(
+            parse_dns_resolver("dns.example.com").unwrap_err(),
+            LuminaError::Network { .. }
+        ));
+    }
+
+    #[test]
+    fn trusted_peer() {
+        let peer_id = PeerId::random();
+
+        assert_eq!(parse_trusted_peer(&peer_id.to_string()).unwrap(), peer_id);
+        assert_eq!(
+            parse_trusted_peer(&format!("/ip4/127.0.0.1/tcp/2121/p2p/{peer_id}")).unwrap(),
+            peer_id
+        );
+
+        for peer in ["/ip4/127.0.0.1/tcp/2121", "12D3KooW", ""] {
+            let err = parse_trusted_peer(peer).unwrap_err();
+            assert!(
+                matches!(&err, LuminaError::InvalidMultiaddr { input, .. } if input == peer),
+                "{err}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_entries() {
+        let base_path = TempDir::new().unwrap();
+        let peer_id = PeerId::random();
+        let valid_peer = format!("/ip4/127.0.0.1/tcp/2121/p2p/{peer_id}");
+
+        let configs = [
+            NodeConfig {
+                trusted_peers: Some(vec![valid_peer.clone(), "/ip4/300.0.0.1".to_owned()]),
+                ..test_node_config(&base_path)
+            },
+            NodeConfig {
+                bootnodes: Some(vec![valid_peer.clone(), "/ip4/300.0.0.1".to_owned()]),
+                ..test_node_config(&base_path)
+            },
+            NodeConfig {
+                listen_addresses: Some(vec![valid_peer, "/ip4/300.0.0.1".to_owned()]),
+                ..test_node_config(&base_path)
+            },
+        ];
+
+        for config in configs {
+            let Err(err) = config.into_node_builder().await else {
+                panic!("invalid entry accepted");
+            };
+            // the failing entry is named, even if preceded by valid ones
+            assert!(
+                matches!(&err, LuminaError::InvalidMultiaddr { input, .. } if input == "/ip4/300.0.0.1"),
+                "{err}"
+            );
+        }
+        // rejected before anything is created
+        assert!(!base_path.path().join("store-private").exists());
+    }
+
+    #[tokio::test]
+    async fn retention() {
+        let base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&base_path);
+        config.syncing_window_secs = Some(3600);
+
+        assert_eq!(
+            config.effective_config(),
+            EffectiveConfig {
+                syncing_window_secs: 3600,
+                retention: Retention::Window { secs: 7200 },
+                batch_size: 128,
+                header_sub_buffer: 16,
+                sampling_concurrency: 1,
+                peer_wait_timeout_ms: 10_000,
+                keepalive_ping_secs: 15,
+                sampling_enabled: true,
+            }
+        );
+
+        config.pruning_delay_secs = Some(60);
+        assert_eq!(config.retention(), Retention::Window { secs: 3660 });
+
+        config.retention = Some(Retention::KeepAll);
+        assert_eq!(config.retention(), Retention::KeepAll);
+        config.clone().into_node_builder().await.unwrap();
+
+        let base_path = TempDir::new().unwrap();
+        let config = NodeConfig {
+            base_path: base_path.path().to_string_lossy().into_owned(),
+            retention: Some(Retention::Window { secs: 3600 }),
+            ..config
+        };
+        let Err(err) = config.into_node_builder().await else {
+            panic!("retention shorter than the syncing window accepted");
+        };
+        assert!(matches!(err, LuminaError::InvalidConfig { .. }), "{err}");
+        // rejected before anything is created
+        assert!(!base_path.path().join("store-private").exists());
+    }
+
+    #[tokio::test]
+    async fn low_memory() {
+        let base_path = TempDir::new().unwrap();
+        let mut config = test_node_config(&base_path);
+        config.sampling_concurrency = Some(32);
+        config.header_sub_buffer = Some(2);
+
+        let effective = config.effective_config();
+        assert_eq!(effective.batch_size, 128);
+        assert_eq!(effective.header_sub_buffer, 2);
+        assert_eq!(effective.sampling_concurrency, 16);
+
+        config.low_memory = true;
+        let effective = config.effective_config();
+        assert_eq!(effective.batch_size, 16);
+        // lower values are kept
+        assert_eq!(effective.header_sub_buffer, 2);
+        assert_eq!(effective.sampling_concurrency, 1);
+        assert_eq!(
+            effective.retention,
+            test_node_config(&base_path).retention()
+        );
+
+        config.into_node_builder().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn keepalive_ping_interval() {
+        let base_path = TempDir::new().unwrap();
+        let config = test_node_config(&base_path);
+        assert_eq!(config.effective_config().keepalive_ping_secs, 15);
+
+        for secs in [0, 301] {
+            let config = NodeConfig {
+                keepalive_ping_secs: Some(secs),
+                ..test_node_config(&base_path)
+            };
+            let Err(err) = config.into_node_builder().await else {
+                panic!("keepalive ping interval of {secs}s accepted");
+            };
+            assert!(err.to_string().contains("Keepalive ping"), "{err}");
+        }
+        // rejected before anything is created
+        assert!(!base_path.path().join("store-private").exists());
+
+        let config = NodeConfig {
+            keepalive_ping_secs: Some(300),
+            ..test_node_config(&base_path)
+        };
+        assert_eq!(config.effective_config().keepalive_ping_secs, 300);
+        config.into_node_builder().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn network_without_bootnodes() {
+        let base_path = TempDir::new().unwrap();
+        let config = NodeConfig {
+            bootnodes: None,
+            ..test_node_config(&base_path)
+        };
+
+        let Err(err) = config.clone().into_node_builder().await else {
+            panic!("custom network without bootnodes accepted");
+        };
+        assert!(
+            matches!(&err, LuminaError::UnknownNetwork { network } if network == "private"),
+            "{err}"
+        );
+        // rejected before anything is created
+        assert!(!base_path.path().join("store-private").exists());
+
+        // bootnodes given explicitly, even if none, are the user's choice
+        let config = NodeConfig {
+            bootnodes: Some(Vec::new()),
+            ..config
+        };
+        config.into_node_builder().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn store_opened_twice() {
+        let base_path = TempDir::new().unwrap();
+        let config = test_node_config(&base_path);
+
+        let _builder = config.clone().into_node_builder().await.unwrap();
+        let Err(err) = config.into_node_builder().await else {
+            panic!("store opened twice");
+        };
+
+        let store_path = base_path.path().join("store-private");
+        assert!(
+            matches!(&err, LuminaError::StorageLocked { path } if *path == store_path.to_string_lossy()),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//! Cross-checking of the headers of the node against a trusted consensus node.
+
+use std::time::Duration;
+
+use celestia_grpc::{GrpcClient, NoAuth};
+use celestia_types::hash::Hash;
+use tonic::transport::Endpoint;
+use uniffi::Record;
+
+use crate::error::{LuminaError, Result};
+use crate::types::BlockSource;
+
+/// Timeout of connecting to the trusted endpoint and of each of the calls to it.
+const TRUSTED_ENDPOINT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Result of comparing the headers of the node with the blocks of the trusted endpoint.
+#[derive(Record, Debug, Clone, PartialEq, Eq)]
+pub struct CrossCheckResult {
+    /// Whether the node and the trusted endpoint have the same block at the compared height.
+    pub agrees: bool,
+    /// Subjective head of the node.
+    pub node_head_height: u64,
+    /// Latest height of the trusted endpoint.
+    pub trusted_head_height: u64,
+    /// Height the blocks were compared at, the lower of the two heads.
+    pub compared_height: u64,
+    /// Height the blocks of the node and the trusted endpoint differ at, if they do.
+    pub divergence_height: Option<u64>,
+}
+
+/// Source of the blocks trusted to be canonical.
+pub(crate) trait TrustedBlocks {
+    /// Height and hash of the latest block.
+    async fn latest_block(&self) -> Result<(u64, Hash)>;
+
+    /// Hash of the block at the height.
+    async fn block_hash(&self, height: u64) -> Result<Hash>;
+}
+
+/// Connect to the gRPC endpoint of the consensus node.
+pub(crate) async fn connect_grpc(endpoint: String) -> Result<GrpcClient<NoAuth>> {
+    let channel = Endpoint::from_shared(endpoint)
+        .map_err(|e| LuminaError::network(format!("Invalid gRPC endpoint: {e}")))?
+        .connect_timeout(TRUSTED_ENDPOINT_TIMEOUT)
+        .connect()
+        .await
+        .map_err(|e| LuminaError::network(format!("Failed to connect to gRPC endpoint: {e}")))?;
+
+    Ok(GrpcClient::new_unauthenticated(channel))
+}
+
+impl TrustedBlocks for GrpcClient<NoAuth> {
+    async fn latest_block(&self) -> Result<(u64, Hash)> {
+        let block = self
+            .with_deadline(TRUSTED_ENDPOINT_TIMEOUT)
+            .get_latest_block()
+            .await
+            .map_err(|e| LuminaError::network(format!("Failed to get latest block: {e}")))?;
+
+        Ok((block.header.height.value(), block.header.hash()))
+    }
+
+    async fn block_hash(&self, height: u64) -> Result<Hash> {
+        let block = self
+            .with_deadline(TRUSTED_ENDPOINT_TIMEOUT)
+            .get_block_by_height(height as i64)
+            .await
+            .map_err(|e| LuminaError::network(format!("Failed to get block {height}: {e}")))?;
+
+        Ok(block.header.hash())
+    }
+}
+
+/// Compare the header of the node with the block of the trusted source at the lower
+/// of their heads.
+pub(crate) async fn cross_check<T, L>(
+    trusted: &T,
+    local: &L,
+    node_head_height: u64,
+) -> Result<CrossCheckResult>
+where
+    T: TrustedBlocks,
+    L: BlockSource,
+{
+    let (trusted_head_height, trusted_head_hash) = trusted.latest_block().await?;
+    let compared_height = node_head_height.min(trusted_head_height);
+
+    let trusted_hash = if compared_height == trusted_head_height {
+        trusted_head_hash
+    } else {
+        trusted.block_hash(compared_height).await?
+    };
+    let local_header = local
+        .header(compared_height)
+        .await
+        .ok_or(LuminaError::HeightNotLocal {
+            height: compared_height,
+        })?;
+
+    let agrees = local_header.hash() == trusted_hash;
+
+    Ok(CrossCheckResult {
+        agrees,
+        node_head_height,
+        trusted_head_height,
+        compared_height,
+        divergence_height: (!agrees).then_some(compared_height),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use celestia_types::nmt::Namespace;
+    use celestia_types::test_utils::ExtendedHeaderGenerator;
+    use celestia_types::ExtendedHeader;
+
+    use super::*;
+
+    struct TestHeaders(Vec<ExtendedHeader>);
+
+    impl BlockSource for TestHeaders {
+        async fn header(&self, height: u64) -> Option<ExtendedHeader> {
+            self.0.get(height as usize - 1).cloned()
+        }
+
+        async fn blob_count(&self, _: &ExtendedHeader, _: Namespace) -> Option<u32> {
+            None
+        }
+    }
+
+    /// Trusted blocks with the hashes of the headers, up to the head.
+    struct TestTrusted {
+        hashes: Vec<Hash>,
+        head: u64,
+    }
+
+    impl TrustedBlocks for TestTrusted {
+        async fn latest_block(&self) -> Result<(u64, Hash)> {
+            Ok((self.head, self.hashes[self.head as usize - 1]))
+        }
+
+        async fn block_hash(&self, height: u64) -> Result<Hash> {
+            Ok(self.hashes[height as usize - 1])
+        }
+    }
+
+    #[tokio::test]
+    async fn matching_head() {
+        let headers = ExtendedHeaderGenerator::new().next_many(10);
+        let hashes = headers.iter().map(|header| header.hash()).collect();
+        let local = TestHeaders(headers);
+
+        let trusted = TestTrusted { hashes, head: 10 };
+        assert_eq!(
+            cross_check(&trusted, &local, 10).await.unwrap(),
+            CrossCheckResult {
+                agrees: true,
+                node_head_height: 10,
+                trusted_head_height: 10,
+                compared_height: 10,
+                divergence_height: None,
+            }
+        );
+
+        // trusted endpoint lagging behind the node
+        let trusted = TestTrusted { head: 7, ..trusted };
+        let result = cross_check(&trusted, &local, 10).await.unwrap();
+        assert!(result.agrees);
+        assert_eq!(result.compared_height, 7);
+    }
+
+    #[tokio::test]
+    async fn mismatching_head() {
+        let mut gen = ExtendedHeaderGenerator::new();
+        let headers = gen.next_many(8);
+        let mut fork = gen.fork();
+        let local = TestHeaders([headers, gen.next_many(2)].concat());
+
+        let hashes = local.0[..8]
+            .iter()
+            .chain(&fork.next_many(4))
+            .map(|header| header.hash())
+            .collect();
+
+        let trusted = TestTrusted { hashes, head: 12 };
+        assert_eq!(
+            cross_check(&trusted, &local, 10).await.unwrap(),
+            CrossCheckResult {
+                agrees: false,
+                node_head_height: 10,
+                trusted_head_height: 12,
+                compared_height: 10,
+                divergence_height: Some(10),
+            }
+        );
+
+        let err = cross_check(&trusted, &local, 11).await.unwrap_err();
+        assert!(matches!(err, LuminaError::HeightNotLocal { height: 11 }));
+    }
+}
@@ -0,0 +1,45 @@
+//! Snapshot of the state of the node for bug reports.
+
+use lumina_node::node::{BandwidthStats, PeerTrackerInfo, PruningBacklog};
+use lumina_node::store::LatencyStats;
+use uniffi::Record;
+
+use crate::types::{EffectiveConfig, NetworkInfo, NodeEventInfo, SyncingInfo};
+
+/// Number of the most recent events included in the [`DiagnosticsReport`].
+pub(crate) const DIAGNOSTICS_EVENTS: usize = 100;
+
+/// Snapshot of the state of the node, to be attached to bug reports.
+///
+/// The identity of the node is included only as its peer id, the secret key it runs
+/// with is never part of the report.
+#[derive(Record, Debug)]
+pub struct DiagnosticsReport {
+    /// Id of the network the node follows.
+    pub network_id: String,
+    /// Peer id of the node.
+    pub local_peer_id: String,
+    /// Configuration the node runs with.
+    pub effective_config: EffectiveConfig,
+    /// Version of the schema of the store of the headers.
+    pub store_schema_version: u64,
+    /// Headers held in the store.
+    pub syncing_info: SyncingInfo,
+    /// Headers waiting to be pruned.
+    pub pruning_backlog: PruningBacklog,
+    /// Heights kept regardless of the pruning window.
+    pub pinned_heights: Vec<u64>,
+    /// Durations of the recent write transactions of the store.
+    pub storage_write_latency: LatencyStats,
+    /// Connections of the node.
+    pub network_info: NetworkInfo,
+    /// Statistics of the connected peers.
+    pub peer_tracker_info: PeerTrackerInfo,
+    /// Amount of data transferred by the node since it was started.
+    pub bandwidth_stats: BandwidthStats,
+    /// Error of the latest fatal event, reported when one of the components of the node
+    /// stopped.
+    pub last_fatal_error: Option<String>,
+    /// Most recent events of the node, from the oldest to the newest.
+    pub recent_events: Vec<NodeEventInfo>,
+}
@@ -1,8 +1,20 @@
-use libp2p::PeerId as Libp2pPeerId;
-use lumina_node::events::NodeEvent as LuminaNodeEvent;
+use std::collections::VecDeque;
+use std::pin::pin;
 use std::str::FromStr;
+use std::time::Duration;
+
+use futures::{Stream, StreamExt};
+use libp2p::PeerId as Libp2pPeerId;
+use lumina_node::events::{
+    DisconnectReason, NodeEvent as LuminaNodeEvent, NodeEventInfo as LuminaNodeEventInfo,
+    RejectionReason,
+};
+use tokio::select;
+use tokio::time::sleep;
 use uniffi::Record;
 
+use crate::clock::unix_ms;
+
 #[derive(Record, Clone, Debug)]
 pub struct PeerId {
     /// The peer ID stored as base58 string.
@@ -29,17 +41,23 @@ impl From<Libp2pPeerId> for PeerId {
     }
 }
 
-#[derive(Record)]
+#[derive(Record, Clone, Debug)]
 pub struct ShareCoordinate {
     row: u16,
     column: u16,
 }
 
 /// Events emitted by the node.
-#[derive(uniffi::Enum)]
+#[derive(uniffi::Enum, Clone, Debug)]
 pub enum NodeEvent {
     /// Node is connecting to bootnodes
     ConnectingToBootnodes,
+    /// Names of the bootnodes were resolved, their addresses are going to be dialed.
+    /// Bootnodes which failed to resolve are missing from the list.
+    BootnodesResolved {
+        /// The resolved addresses.
+        addresses: Vec<String>,
+    },
     /// Peer just connected
     PeerConnected {
         /// The ID of the peer.
@@ -52,7 +70,12 @@ pub enum NodeEvent {
         id: PeerId,
         /// Whether peer was in the trusted list or not.
         trusted: bool,
+        /// Why the last connection to the peer was closed.
+        reason: DisconnectReason,
     },
+    /// Node has a verified head and is connected to a peer supporting all the protocols
+    /// needed to serve the queries, i.e. header-ex and shwap. Emitted at most once.
+    ReadyToServe,
     /// Sampling just started.
     SamplingStarted {
         /// The block height that will be sampled.
@@ -74,6 +97,8 @@ pub enum NodeEvent {
         column: u16,
         /// The result of the sampling of the share.
         accepted: bool,
+        /// Why the share was rejected, `None` if it was accepted.
+        rejection_reason: Option<RejectionReason>,
     },
     /// Sampling just finished.
     SamplingFinished {
@@ -130,6 +155,15 @@ pub enum NodeEvent {
         /// How much time fetching took in milliseconds.
         took_ms: u64,
     },
+    /// Header received for an already synchronised height conflicts with the stored one.
+    ReorgDetected {
+        /// The height of the conflicting headers.
+        height: u64,
+        /// Hash of the stored header, as an uppercase hex encoded string.
+        old_hash: String,
+        /// Hash of the newly received header, as an uppercase hex encoded string.
+        new_hash: String,
+    },
     /// Header syncing fatal error.
     FatalSyncerError {
         /// A human readable error.
@@ -160,14 +194,23 @@ impl From<LuminaNodeEvent> for NodeEvent {
     fn from(event: LuminaNodeEvent) -> Self {
         match event {
             LuminaNodeEvent::ConnectingToBootnodes => NodeEvent::ConnectingToBootnodes,
+            LuminaNodeEvent::BootnodesResolved { addresses } => NodeEvent::BootnodesResolved {
+                addresses: addresses.iter().map(|addr| addr.to_string()).collect(),
+            },
             LuminaNodeEvent::PeerConnected { id, trusted } => NodeEvent::PeerConnected {
                 id: PeerId::from_libp2p(&id),
                 trusted,
             },
-            LuminaNodeEvent::PeerDisconnected { id, trusted } => NodeEvent::PeerDisconnected {
+            LuminaNodeEvent::PeerDisconnected {
+                id,
+                trusted,
+                reason,
+            } => NodeEvent::PeerDisconnected {
                 id: PeerId::from_libp2p(&id),
                 trusted,
+                reason,
             },
+            LuminaNodeEvent::ReadyToServe => NodeEvent::ReadyToServe,
             LuminaNodeEvent::SamplingStarted {
                 height,
                 square_width,
@@ -186,12 +229,14 @@ impl From<LuminaNodeEvent> for NodeEvent {
                 row,
                 column,
                 accepted,
+                rejection_reason,
             } => NodeEvent::ShareSamplingResult {
                 height,
                 square_width,
                 row,
                 column,
                 accepted,
+                rejection_reason,
             },
             LuminaNodeEvent::SamplingFinished {
                 height,
@@ -240,6 +285,15 @@ impl From<LuminaNodeEvent> for NodeEvent {
                 error,
                 took_ms: took.as_millis() as u64,
             },
+            LuminaNodeEvent::ReorgDetected {
+                height,
+                old_hash,
+                new_hash,
+            } => NodeEvent::ReorgDetected {
+                height,
+                old_hash: old_hash.to_string(),
+                new_hash: new_hash.to_string(),
+            },
             LuminaNodeEvent::FatalSyncerError { error } => NodeEvent::FatalSyncerError { error },
             LuminaNodeEvent::PrunedHeaders { to_height } => NodeEvent::PrunedHeaders { to_height },
             LuminaNodeEvent::FatalPrunerError { error } => NodeEvent::FatalPrunerError { error },
@@ -249,3 +303,277 @@ impl From<LuminaNodeEvent> for NodeEvent {
         }
     }
 }
+
+/// Event emitted by the node, together with where and when it was emitted.
+#[derive(Record, Clone, Debug)]
+pub struct NodeEventInfo {
+    /// The event.
+    pub event: NodeEvent,
+    /// Sequence number of the event, strictly increasing in the order the node emitted
+    /// the events, so they can be ordered even if the wall clock went backwards.
+    pub seq: u64,
+    /// When the event was emitted, in milliseconds since the unix epoch.
+    pub time_unix_ms: u64,
+    /// Source file which emitted the event.
+    pub file_path: String,
+    /// Line in the source file which emitted the event.
+    pub file_line: u32,
+}
+
+impl From<LuminaNodeEventInfo> for NodeEventInfo {
+    fn from(info: LuminaNodeEventInfo) -> Self {
+        NodeEventInfo {
+            event: info.event.into(),
+            seq: info.seq,
+            time_unix_ms: unix_ms(info.time),
+            file_path: info.file_path.to_owned(),
+            file_line: info.file_line,
+        }
+    }
+}
+
+/// Thresholds of delivering the events in batches to the [`NodeEventListener`].
+///
+/// Batch is delivered as soon as it reaches `max_batch_size` events, or when
+/// `max_delay_ms` passed since its first event, whichever comes first.
+#[derive(Record, Debug, Clone, Copy)]
+pub struct EventBatchConfig {
+    /// Maximum number of events in a single batch. `0` is treated as `1`.
+    pub max_batch_size: u32,
+    /// Maximum time the first event of the batch waits for delivery, in milliseconds.
+    pub max_delay_ms: u64,
+}
+
+/// Receiver of the batches of events emitted by the node.
+#[uniffi::export(callback_interface)]
+pub trait NodeEventListener: Send + Sync {
+    /// Called with each batch of events, in the order they were emitted.
+    fn on_events(&self, events: Vec<NodeEventInfo>);
+}
+
+/// Ring buffer of the most recent events emitted by the node.
+#[derive(Debug, Default)]
+pub(crate) struct EventHistory {
+    capacity: usize,
+    events: VecDeque<NodeEventInfo>,
+}
+
+impl EventHistory {
+    /// Create a history keeping up to `capacity` events.
+    pub(crate) fn new(capacity: usize) -> Self {
+        EventHistory {
+            capacity,
+            events: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Record the event, dropping the oldest one if full.
+    pub(crate) fn record(&mut self, info: NodeEventInfo) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+        }
+        self.events.push_back(info);
+    }
+
+    /// Up to `limit` of the most recent events, from the oldest to the newest.
+    pub(crate) fn recent(&self, limit: usize) -> Vec<NodeEventInfo> {
+        let skip = self.events.len().saturating_sub(limit);
+        self.events.iter().skip(skip).cloned().collect()
+    }
+}
+
+/// Deliver the events to the listener in batches, until the stream of events ends.
+///
+/// Events of an incomplete batch are delivered as soon as the stream ends.
+pub(crate) async fn deliver_batched<S>(
+    events: S,
+    listener: &dyn NodeEventListener,
+    config: EventBatchConfig,
+) where
+    S: Stream<Item = NodeEventInfo>,
+{
+    let mut events = pin!(events);
+    let max_batch_size = config.max_batch_size.max(1) as usize;
+    let max_delay = Duration::from_millis(config.max_delay_ms);
+    let mut ended = false;
+
+    while !ended {
+        let Some(first) = events.next().await else {
+            break;
+        };
+
+        let mut batch = Vec::with_capacity(max_batch_size);
+        batch.push(first);
+
+        let mut deadline = pin!(sleep(max_delay));
+
+        while batch.len() < max_batch_size {
+            select! {
+                event = events.next() => match event {
+                    Some(event) => batch.push(event),
+                    None => {
+                        ended = true;
+                        break;
+                    }
+                },
+                _ = &mut deadline => break,
+            }
+        }
+
+        listener.on_events(batch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use futures::channel::mpsc;
+    use futures::stream;
+
+    use super::*;
+    use crate::test_utils::RecordingListener;
+
+    /// Ordinal numbers of the events in the batches delivered to the listener.
+    fn delivered(listener: &RecordingListener) -> Vec<Vec<u32>> {
+        listener
+            .batches()
+            .into_iter()
+            .map(|batch| batch.into_iter().map(|info| info.file_line).collect())
+            .collect()
+    }
+
+    /// Event with its ordinal number in place of the line.
+    fn lumina_event(n: u32) -> LuminaNodeEventInfo {
+        LuminaNodeEventInfo {
+            event: LuminaNodeEvent::FetchingHeadHeaderStarted,
+            seq: n.into(),
+            time: SystemTime::now(),
+            file_path: "test",
+            file_line: n,
+        }
+    }
+
+    fn event(n: u32) -> NodeEventInfo {
+        lumina_event(n).into()
+    }
+
+    #[test]
+    fn share_rejection_reason() {
+        let sampled =
+            |rejection_reason: Option<RejectionReason>| LuminaNodeEvent::ShareSamplingResult {
+                height: 5,
+                square_width: 4,
+                row: 1,
+                column: 2,
+                accepted: rejection_reason.is_none(),
+                rejection_reason,
+            };
+
+        for reason in [
+            None,
+            Some(RejectionReason::Timeout),
+            Some(RejectionReason::InvalidProof),
+            Some(RejectionReason::NotFound),
+            Some(RejectionReason::Other),
+        ] {
+            match NodeEvent::from(sampled(reason)) {
+                NodeEvent::ShareSamplingResult {
+                    accepted,
+                    rejection_reason,
+                    ..
+                } => {
+                    assert_eq!(accepted, reason.is_none());
+                    assert_eq!(rejection_reason, reason);
+                }
+                event => panic!("Unexpected event: {event:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn history_keeps_most_recent() {
+        let lines = |events: Vec<NodeEventInfo>| -> Vec<u32> {
+            events.into_iter().map(|info| info.file_line).collect()
+        };
+        let mut history = EventHistory::new(5);
+        assert!(history.recent(10).is_empty());
+
+        for n in 0..12 {
+            history.record(event(n));
+        }
+
+        assert_eq!(lines(history.recent(10)), [7, 8, 9, 10, 11]);
+        assert_eq!(lines(history.recent(2)), [10, 11]);
+        assert!(history.recent(0).is_empty());
+
+        let mut disabled = EventHistory::new(0);
+        disabled.record(event(0));
+        assert!(disabled.recent(10).is_empty());
+    }
+
+    #[tokio::test]
+    async fn batches_by_size() {
+        let listener = RecordingListener::default();
+        let config = EventBatchConfig {
+            max_batch_size: 10,
+            max_delay_ms: 60_000,
+        };
+
+        deliver_batched(stream::iter((0..25).map(event)), &listener, config).await;
+
+        let batches = delivered(&listener);
+        let sizes: Vec<_> = batches.iter().map(Vec::len).collect();
+        assert_eq!(sizes, [10, 10, 5]);
+        assert_eq!(batches.concat(), (0..25).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn batches_by_time() {
+        let listener = RecordingListener::default();
+        let config = EventBatchConfig {
+            max_batch_size: 100,
+            max_delay_ms: 50,
+        };
+        let (tx, rx) = mpsc::unbounded();
+
+        let delivery = tokio::spawn({
+            let listener = listener.clone();
+            async move { deliver_batched(rx, &listener, config).await }
+        });
+
+        for n in 0..3 {
+            tx.unbounded_send(event(n)).unwrap();
+        }
+        // nothing is delivered before the delay passed
+        sleep(Duration::from_millis(10)).await;
+        assert!(delivered(&listener).is_empty());
+
+        sleep(Duration::from_millis(200)).await;
+        assert_eq!(delivered(&listener), [vec![0, 1, 2]]);
+
+        for n in 3..5 {
+            tx.unbounded_send(event(n)).unwrap();
+        }
+        drop(tx);
+        delivery.await.unwrap();
+
+        assert_eq!(delivered(&listener), [vec![0, 1, 2], vec![3, 4]]);
+    }
+
+    #[tokio::test]
+    async fn zero_batch_size() {
+        let listener = RecordingListener::default();
+        let config = EventBatchConfig {
+            max_batch_size: 0,
+            max_delay_ms: 60_000,
+        };
+
+        deliver_batched(stream::iter((0..3).map(event)), &listener, config).await;
+
+        assert_eq!(delivered(&listener), [vec![0], vec![1], vec![2]]);
+    }
+}
@@ -0,0 +1,166 @@
+use celestia_types::blob::Commitment as LuminaCommitment;
+use tendermint::hash::Hash as TendermintHash;
+use uniffi::Object;
+
+use crate::error::{LuminaError, Result};
+
+/// Size of the hashes and commitments in bytes.
+const HASH_SIZE: usize = 32;
+
+/// Sha256 hash, e.g. of a block or a transaction.
+#[derive(Object, Debug, Clone, PartialEq, Eq)]
+pub struct Hash([u8; HASH_SIZE]);
+
+#[uniffi::export]
+impl Hash {
+    /// Creates a hash from the hex encoded string.
+    #[uniffi::constructor]
+    pub fn from_hex(hex: String) -> Result<Self> {
+        decode_hex(&hex).map(Hash)
+    }
+
+    /// Creates a hash from its raw bytes.
+    #[uniffi::constructor]
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        to_array(&bytes).map(Hash)
+    }
+
+    /// Returns the hash as an uppercase hex encoded string.
+    pub fn to_hex(&self) -> String {
+        hex::encode_upper(self.0)
+    }
+
+    /// Returns raw bytes of the hash.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+impl From<Hash> for TendermintHash {
+    fn from(hash: Hash) -> Self {
+        TendermintHash::Sha256(hash.0)
+    }
+}
+
+impl TryFrom<TendermintHash> for Hash {
+    type Error = LuminaError;
+
+    fn try_from(hash: TendermintHash) -> Result<Self> {
+        match hash {
+            TendermintHash::Sha256(bytes) => Ok(Hash(bytes)),
+            TendermintHash::None => Err(LuminaError::invalid_hash("Empty hash")),
+        }
+    }
+}
+
+/// Commitment of a blob, computed over the merkle roots of its shares.
+#[derive(Object, Debug, Clone, PartialEq, Eq)]
+pub struct Commitment([u8; HASH_SIZE]);
+
+#[uniffi::export]
+impl Commitment {
+    /// Creates a commitment from the hex encoded string.
+    #[uniffi::constructor]
+    pub fn from_hex(hex: String) -> Result<Self> {
+        decode_hex(&hex).map(Commitment)
+    }
+
+    /// Creates a commitment from its raw bytes.
+    #[uniffi::constructor]
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        to_array(&bytes).map(Commitment)
+    }
+
+    /// Returns the commitment as an uppercase hex encoded string.
+    pub fn to_hex(&self) -> String {
+        hex::encode_upper(self.0)
+    }
+
+    /// Returns raw bytes of the commitment.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+impl From<Commitment> for LuminaCommitment {
+    fn from(commitment: Commitment) -> Self {
+        LuminaCommitment::new(commitment.0)
+    }
+}
+
+impl From<LuminaCommitment> for Commitment {
+    fn from(commitment: LuminaCommitment) -> Self {
+        Commitment(*commitment.hash())
+    }
+}
+
+fn decode_hex(hex: &str) -> Result<[u8; HASH_SIZE]> {
+    let bytes = hex::decode(hex).map_err(|e| LuminaError::invalid_hash(e.to_string()))?;
+    to_array(&bytes)
+}
+
+fn to_array(bytes: &[u8]) -> Result<[u8; HASH_SIZE]> {
+    bytes.try_into().map_err(|_| {
+        LuminaError::invalid_hash(format!("Expected {HASH_SIZE} bytes, got {}", bytes.len()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEX: &str = "E6E0A4EF1DFB8A6A13F4E4DF7C3A617B6E28EF4C1E1CE7A8C4B9A3A4DCB2F5D1";
+
+    #[test]
+    fn hex_round_trip() {
+        let hash = Hash::from_hex(HEX.to_owned()).unwrap();
+        assert_eq!(hash.to_hex(), HEX);
+
+        let lowercase = Hash::from_hex(HEX.to_lowercase()).unwrap();
+        assert_eq!(lowercase, hash);
+
+        let commitment = Commitment::from_hex(HEX.to_owned()).unwrap();
+        assert_eq!(commitment.to_bytes(), hash.to_bytes());
+        assert_eq!(
+            Commitment::from(LuminaCommitment::from(commitment.clone())),
+            commitment
+        );
+    }
+
+    #[test]
+    fn odd_length_hex_rejected() {
+        let odd = &HEX[1..];
+
+        let err = Hash::from_hex(odd.to_owned()).unwrap_err();
+        assert!(matches!(err, LuminaError::InvalidHash { .. }));
+
+        let err = Commitment::from_hex(odd.to_owned()).unwrap_err();
+        assert!(matches!(err, LuminaError::InvalidHash { .. }));
+    }
+
+    #[test]
+    fn wrong_length_rejected() {
+        for len in [0, 20, 31, 33, 64] {
+            let err = Hash::from_bytes(vec![1; len]).unwrap_err();
+            assert!(matches!(err, LuminaError::InvalidHash { .. }));
+
+            let err = Commitment::from_bytes(vec![1; len]).unwrap_err();
+            assert!(matches!(err, LuminaError::InvalidHash { .. }));
+        }
+
+        let short_hex = &HEX[..62];
+        let err = Hash::from_hex(short_hex.to_owned()).unwrap_err();
+        assert!(matches!(err, LuminaError::InvalidHash { .. }));
+    }
+
+    #[test]
+    fn tendermint_hash_conversion() {
+        let hash = Hash::from_bytes(vec![7; HASH_SIZE]).unwrap();
+        let tendermint_hash = TendermintHash::from(hash.clone());
+        assert_eq!(tendermint_hash, TendermintHash::Sha256([7; HASH_SIZE]));
+        assert_eq!(Hash::try_from(tendermint_hash).unwrap(), hash);
+
+        let err = Hash::try_from(TendermintHash::None).unwrap_err();
+        assert!(matches!(err, LuminaError::InvalidHash { .. }));
+    }
+}
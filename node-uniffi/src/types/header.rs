@@ -0,0 +1,250 @@
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use celestia_types::nmt::{Namespace, NamespacedHashExt, NamespacedSha2Hasher};
+use celestia_types::{DataAvailabilityHeader as CelestiaDataAvailabilityHeader, ExtendedHeader};
+use uniffi::Record;
+
+use crate::error::{self, LuminaError};
+use crate::types::{Hash, RowSource};
+
+/// Details of an extended header.
+#[derive(Record, Debug, Clone, PartialEq, Eq)]
+pub struct ExtendedHeaderRecord {
+    /// Height of the block.
+    pub height: u64,
+    /// Hash of the block.
+    pub hash: Arc<Hash>,
+    /// Time when the block was produced, in milliseconds since the unix epoch.
+    pub time_unix_ms: u64,
+    /// Merkle root of the block's data square.
+    pub data_root: Arc<Hash>,
+}
+
+impl TryFrom<&ExtendedHeader> for ExtendedHeaderRecord {
+    type Error = LuminaError;
+
+    fn try_from(header: &ExtendedHeader) -> Result<Self, Self::Error> {
+        Ok(ExtendedHeaderRecord {
+            height: header.height().value(),
+            hash: Arc::new(header.hash().try_into()?),
+            time_unix_ms: (header.time().unix_timestamp_nanos() / 1_000_000) as u64,
+            data_root: Arc::new(header.dah.hash().try_into()?),
+        })
+    }
+}
+
+/// Stored headers of a range of heights, together with the heights of the range whose
+/// headers aren't stored.
+#[derive(Record, Debug, Clone, PartialEq, Eq)]
+pub struct PartialHeaders {
+    /// Serialized stored headers, in the order of heights.
+    pub headers: Vec<String>,
+    /// Heights of the range without a stored header, in ascending order.
+    pub missing_heights: Vec<u64>,
+}
+
+/// Data availability header of a block, holding the roots of its extended data square.
+#[derive(Record, Debug, Clone, PartialEq, Eq)]
+pub struct DataAvailabilityHeader {
+    /// Merkle roots of the rows of the square.
+    pub row_roots: Vec<Vec<u8>>,
+    /// Merkle roots of the columns of the square.
+    pub column_roots: Vec<Vec<u8>>,
+}
+
+impl From<&CelestiaDataAvailabilityHeader> for DataAvailabilityHeader {
+    fn from(dah: &CelestiaDataAvailabilityHeader) -> Self {
+        DataAvailabilityHeader {
+            row_roots: dah.row_roots().iter().map(|root| root.to_vec()).collect(),
+            column_roots: dah
+                .column_roots()
+                .iter()
+                .map(|root| root.to_vec())
+                .collect(),
+        }
+    }
+}
+
+/// Namespaces which have data in the original data square of the block at the height,
+/// sorted and without the reserved ones.
+///
+/// Rows holding a single namespace are resolved from their row roots, only the rows
+/// holding more of them are fetched from the source.
+pub(crate) async fn block_namespaces<R>(
+    source: &R,
+    height: u64,
+    dah: &CelestiaDataAvailabilityHeader,
+) -> error::Result<Vec<Namespace>>
+where
+    R: RowSource,
+{
+    let ods_width = dah.square_width() / 2;
+    let mut namespaces = BTreeSet::new();
+
+    for (row_index, root) in (0..ods_width).zip(dah.row_roots()) {
+        let min = Namespace::from(root.min_namespace());
+        let max = Namespace::from(root.max_namespace());
+
+        if min == max {
+            namespaces.insert(min);
+        } else {
+            let shares = source.row(height, row_index).await?;
+            namespaces.extend(
+                shares
+                    .iter()
+                    .take(ods_width.into())
+                    .map(|share| share.namespace()),
+            );
+        }
+    }
+
+    Ok(namespaces
+        .into_iter()
+        .filter(|namespace| !namespace.is_reserved())
+        .collect())
+}
+
+/// Check if the namespace has data in the original data square of the block at the height.
+///
+/// Row roots only bound the namespaces of their rows, so the rows holding more of them,
+/// whose bounds include the namespace, are fetched from the source to confirm it.
+pub(crate) async fn block_contains_namespace<R>(
+    source: &R,
+    height: u64,
+    dah: &CelestiaDataAvailabilityHeader,
+    namespace: Namespace,
+) -> error::Result<bool>
+where
+    R: RowSource,
+{
+    let ods_width = dah.square_width() / 2;
+
+    for (row_index, root) in (0..ods_width).zip(dah.row_roots()) {
+        if !root.contains::<NamespacedSha2Hasher>(*namespace) {
+            continue;
+        }
+        if root.min_namespace() == root.max_namespace() {
+            return Ok(true);
+        }
+
+        let shares = source.row(height, row_index).await?;
+        if shares
+            .iter()
+            .take(ods_width.into())
+            .any(|share| share.namespace() == namespace)
+        {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use celestia_types::consts::appconsts::SHARE_SIZE;
+    use celestia_types::test_utils::ExtendedHeaderGenerator;
+    use celestia_types::{AppVersion, ExtendedDataSquare, Share};
+
+    struct SquareSource {
+        eds: ExtendedDataSquare,
+        fetched: Mutex<Vec<u16>>,
+    }
+
+    impl RowSource for SquareSource {
+        async fn row(&self, _height: u64, row_index: u16) -> error::Result<Vec<Share>> {
+            self.fetched.lock().unwrap().push(row_index);
+            Ok(self.eds.row(row_index).unwrap())
+        }
+    }
+
+    fn share(namespace: Namespace) -> Vec<u8> {
+        let mut share = namespace.as_bytes().to_vec();
+        share.push(1);
+        share.resize(SHARE_SIZE, 0);
+        share
+    }
+
+    #[tokio::test]
+    async fn namespaces_inside_rows() {
+        let ns_a = Namespace::new_v0(&[1; 3]).unwrap();
+        let ns_b = Namespace::new_v0(&[2; 3]).unwrap();
+        let ns_c = Namespace::new_v0(&[3; 3]).unwrap();
+        // the second row is the only one holding more namespaces, with `ns_b` between
+        // the first and the last share
+        let ods = [[ns_a; 4], [ns_a, ns_b, ns_b, ns_c], [ns_c; 4], [ns_c; 4]]
+            .into_iter()
+            .flatten()
+            .map(share)
+            .collect();
+        let eds = ExtendedDataSquare::from_ods(ods, AppVersion::V2).unwrap();
+        let dah = CelestiaDataAvailabilityHeader::from_eds(&eds);
+        let source = SquareSource {
+            eds,
+            fetched: Mutex::default(),
+        };
+
+        let namespaces = block_namespaces(&source, 1, &dah).await.unwrap();
+
+        assert_eq!(namespaces, [ns_a, ns_b, ns_c]);
+        assert_eq!(*source.fetched.lock().unwrap(), [1]);
+    }
+
+    #[tokio::test]
+    async fn namespace_within_row_bounds() {
+        let ns_a = Namespace::new_v0(&[1; 3]).unwrap();
+        let ns_b = Namespace::new_v0(&[2; 3]).unwrap();
+        let ns_c = Namespace::new_v0(&[3; 3]).unwrap();
+        let ns_d = Namespace::new_v0(&[4; 3]).unwrap();
+        // `ns_b` is within the bounds of the second row, without having any share in it
+        let ods = [[ns_a; 4], [ns_a, ns_a, ns_c, ns_c], [ns_c; 4], [ns_c; 4]]
+            .into_iter()
+            .flatten()
+            .map(share)
+            .collect();
+        let eds = ExtendedDataSquare::from_ods(ods, AppVersion::V2).unwrap();
+        let dah = CelestiaDataAvailabilityHeader::from_eds(&eds);
+        let source = SquareSource {
+            eds,
+            fetched: Mutex::default(),
+        };
+
+        // rows holding a single namespace answer without fetching
+        assert!(block_contains_namespace(&source, 1, &dah, ns_a)
+            .await
+            .unwrap());
+        assert!(source.fetched.lock().unwrap().is_empty());
+
+        assert!(!block_contains_namespace(&source, 1, &dah, ns_b)
+            .await
+            .unwrap());
+        assert_eq!(*source.fetched.lock().unwrap(), [1]);
+        assert!(block_contains_namespace(&source, 1, &dah, ns_c)
+            .await
+            .unwrap());
+        assert_eq!(*source.fetched.lock().unwrap(), [1, 1]);
+        // outside of the bounds of all the rows
+        assert!(!block_contains_namespace(&source, 1, &dah, ns_d)
+            .await
+            .unwrap());
+        assert_eq!(*source.fetched.lock().unwrap(), [1, 1]);
+    }
+
+    #[test]
+    fn header_record_conversion() {
+        let header = ExtendedHeaderGenerator::new().next_many(3).pop().unwrap();
+        let record = ExtendedHeaderRecord::try_from(&header).unwrap();
+
+        assert_eq!(record.height, 3);
+        assert_eq!(record.hash.to_bytes(), header.hash().as_bytes());
+        assert_eq!(
+            record.time_unix_ms as i128,
+            header.time().unix_timestamp_nanos() / 1_000_000
+        );
+        assert_eq!(record.data_root.to_bytes(), header.dah.hash().as_bytes());
+    }
+}
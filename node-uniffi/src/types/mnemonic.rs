@@ -0,0 +1,141 @@
+//! Derivation of the node's identity from a BIP39 mnemonic.
+//!
+//! The mnemonic is turned into a seed as specified by [BIP39], with an empty passphrase,
+//! and the ed25519 key is derived from the seed with [SLIP-0010] at [`DERIVATION_PATH`].
+//!
+//! [BIP39]: https://github.com/bitcoin/bips/blob/master/bip-0039.mediawiki
+//! [SLIP-0010]: https://github.com/satoshilabs/slips/blob/master/slip-0010.md
+
+use bip39::Mnemonic;
+use ed25519_dalek_bip32::{ChildIndex, ExtendedSigningKey};
+use libp2p::identity::Keypair;
+
+use crate::error::{LuminaError, Result};
+
+/// Path of the node's key, `m/44'/118'/0'/0'/0'`. All the levels are hardened,
+/// as SLIP-0010 supports only hardened derivation of ed25519 keys.
+const DERIVATION_PATH: [ChildIndex; 5] = [
+    ChildIndex::Hardened(44),
+    ChildIndex::Hardened(118),
+    ChildIndex::Hardened(0),
+    ChildIndex::Hardened(0),
+    ChildIndex::Hardened(0),
+];
+
+/// Derive the ed25519 keypair of the node from the English BIP39 mnemonic.
+///
+/// The mnemonic is rejected if any of its words isn't in the BIP39 wordlist, or if its
+/// checksum doesn't match.
+pub(crate) fn keypair_from_mnemonic(mnemonic: &str) -> Result<Keypair> {
+    let mnemonic = Mnemonic::parse(mnemonic)
+        .map_err(|e| LuminaError::network(format!("Invalid mnemonic: {e}")))?;
+
+    let secret_key = derive_ed25519_key(&mnemonic.to_seed(""), &DERIVATION_PATH)?;
+
+    Keypair::ed25519_from_bytes(secret_key)
+        .map_err(|e| LuminaError::network(format!("Invalid Ed25519 key: {e}")))
+}
+
+/// SLIP-0010 ed25519 secret key at the path of hardened indexes.
+fn derive_ed25519_key(seed: &[u8], path: &[ChildIndex]) -> Result<[u8; 32]> {
+    let key = ExtendedSigningKey::from_seed(seed)
+        .and_then(|key| key.derive(&path))
+        .map_err(|e| LuminaError::network(format!("Failed to derive the key: {e}")))?;
+
+    Ok(key.signing_key.to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn bip39_seed() {
+        // test vector from the BIP39 reference implementation
+        let seed = Mnemonic::parse(MNEMONIC).unwrap().to_seed("TREZOR");
+        assert_eq!(
+            hex::encode(seed),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+    }
+
+    #[test]
+    fn slip10_derivation() {
+        // test vector 1 for ed25519 from SLIP-0010
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let path = [0, 1, 2, 2, 1_000_000_000].map(ChildIndex::Hardened);
+
+        for (depth, key) in [
+            (
+                0,
+                "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+            ),
+            (
+                1,
+                "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3",
+            ),
+            (
+                5,
+                "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+            ),
+        ] {
+            assert_eq!(
+                hex::encode(derive_ed25519_key(&seed, &path[..depth]).unwrap()),
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn node_key_of_mnemonic() {
+        let keypair = keypair_from_mnemonic(MNEMONIC).unwrap();
+        let secret_key = keypair.try_into_ed25519().unwrap().secret();
+
+        assert_eq!(
+            hex::encode(secret_key.as_ref()),
+            "bb749d9a2787819674d3c3447f5f2d245de2ccdec42e62aa6c89bf8761be14bd"
+        );
+    }
+
+    #[test]
+    fn same_mnemonic_same_peer_id() {
+        let peer_id = keypair_from_mnemonic(MNEMONIC)
+            .unwrap()
+            .public()
+            .to_peer_id();
+
+        for _ in 0..3 {
+            let keypair = keypair_from_mnemonic(MNEMONIC).unwrap();
+            assert_eq!(keypair.public().to_peer_id(), peer_id);
+        }
+
+        // whitespace between the words doesn't matter
+        let spaced = format!("  {}\n", MNEMONIC.replace(' ', "\t "));
+        let keypair = keypair_from_mnemonic(&spaced).unwrap();
+        assert_eq!(keypair.public().to_peer_id(), peer_id);
+
+        let other = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let keypair = keypair_from_mnemonic(other).unwrap();
+        assert_ne!(keypair.public().to_peer_id(), peer_id);
+    }
+
+    #[test]
+    fn invalid_mnemonic() {
+        for mnemonic in [
+            "",
+            "abandon abandon abandon",
+            // checksum doesn't match
+            &MNEMONIC.replace("about", "abandon"),
+            // not in the wordlist
+            &MNEMONIC.replace("about", "abut"),
+            &MNEMONIC.replace("about", "ab0ut"),
+        ] {
+            assert!(matches!(
+                keypair_from_mnemonic(mnemonic).unwrap_err(),
+                LuminaError::Network { .. }
+            ));
+        }
+    }
+}
@@ -1,9 +1,63 @@
+mod blob;
+mod blob_stream;
 mod config;
+mod cross_check;
+mod diagnostics;
 mod event;
+mod hash;
+mod header;
+mod mnemonic;
+mod namespace;
 mod network;
+mod operation;
+mod read_policy;
+mod sampling;
+mod shares;
+mod store_backend;
 mod sync;
 
-pub use config::NodeConfig;
-pub use event::{NodeEvent, PeerId};
-pub use network::NetworkInfo;
-pub use sync::SyncingInfo;
+pub(crate) use blob::submit_blobs;
+pub use blob::{BlobInput, BlobSizeLimits};
+pub(crate) use blob_stream::{commitment_included, produce_blobs, BlobSource};
+pub use blob_stream::{BlobStream, BlockBlobs};
+pub(crate) use config::keypair_from_secret_key;
+#[cfg(test)]
+pub(crate) use config::GossipsubConfig;
+pub use config::{EffectiveConfig, NodeConfig, TxConfig};
+pub use cross_check::CrossCheckResult;
+pub(crate) use cross_check::{connect_grpc, cross_check};
+pub use diagnostics::DiagnosticsReport;
+pub(crate) use diagnostics::DIAGNOSTICS_EVENTS;
+pub(crate) use event::{deliver_batched, EventHistory};
+pub use event::{EventBatchConfig, NodeEvent, NodeEventInfo, NodeEventListener, PeerId};
+pub use hash::{Commitment, Hash};
+pub(crate) use header::{block_contains_namespace, block_namespaces};
+pub use header::{DataAvailabilityHeader, ExtendedHeaderRecord, PartialHeaders};
+#[cfg(test)]
+pub(crate) use namespace::NamespaceDataAvailable;
+pub use namespace::NamespaceListener;
+pub(crate) use namespace::{deliver_namespace_data, BlockSource};
+pub(crate) use network::parse_libp2p_multiaddr;
+pub(crate) use network::{ConnectionEvent, ConnectionEventLog};
+pub use network::{ConnectionRates, NetworkInfo, PeerLatency, PeerScore};
+pub(crate) use operation::Operations;
+pub use operation::{OperationHandle, OperationKind};
+pub use read_policy::ReadPolicy;
+pub(crate) use sampling::SamplingDurations;
+pub use sampling::{NamespaceStats, SampleOutcome};
+pub use shares::ShareListener;
+#[cfg(test)]
+pub(crate) use shares::ShareRecord;
+pub(crate) use shares::{block_shares, deliver_shares, RowSource};
+#[cfg(test)]
+pub(crate) use store_backend::InMemoryBackend;
+pub(crate) use store_backend::{remove_stale_stores, store_name, RedbBackend, StoreBackend};
+pub(crate) use sync::{
+    average_block_time, drive_sync, estimate_height_since, estimate_sync_completion,
+    estimate_window_disk_bytes, estimate_window_range, has_network_head, is_stale, is_synced,
+    missing_headers, BlockTime, FetchRateLog, HeadRange, SyncGapLog, SyncProgressInfo,
+    DEFAULT_BLOCK_DISK_BYTES, DEFAULT_BLOCK_TIME,
+};
+pub use sync::{
+    BlockRange, ReorgInfo, StoredHeights, SyncProgress, SyncedRange, SyncingInfo, TimeRange,
+};
@@ -0,0 +1,157 @@
+//! Notifications about the blocks containing a namespace.
+
+use std::pin::pin;
+
+use celestia_types::nmt::{Namespace, NamespacedSha2Hasher};
+use celestia_types::{DataAvailabilityHeader, ExtendedHeader};
+use futures::{Stream, StreamExt};
+use uniffi::Record;
+
+/// Block containing the data of the namespace the listener subscribed to.
+#[derive(Record, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NamespaceDataAvailable {
+    /// Height of the block.
+    pub height: u64,
+    /// Number of the blobs of the namespace in the block.
+    pub blob_count: u32,
+}
+
+/// Receiver of the notifications about the blocks containing a namespace.
+#[uniffi::export(callback_interface)]
+pub trait NamespaceListener: Send + Sync {
+    /// Called for each processed block containing the namespace, in the order
+    /// they were processed.
+    fn on_namespace_data(&self, data: NamespaceDataAvailable);
+}
+
+/// Source of the headers and blobs of the processed blocks.
+pub(crate) trait BlockSource {
+    /// Header of the block, if it's available.
+    async fn header(&self, height: u64) -> Option<ExtendedHeader>;
+
+    /// Number of the blobs of the namespace in the block, if they could be retrieved.
+    async fn blob_count(&self, header: &ExtendedHeader, namespace: Namespace) -> Option<u32>;
+}
+
+/// Checks if any row of the block may contain the namespace.
+///
+/// Rows are committed only to the range of their namespaces, so this doesn't prove
+/// that the namespace is there.
+pub(crate) fn may_contain_namespace(dah: &DataAvailabilityHeader, namespace: Namespace) -> bool {
+    dah.row_roots()
+        .iter()
+        .any(|row| row.contains::<NamespacedSha2Hasher>(*namespace))
+}
+
+/// Notify the listener about the blocks containing blobs of the namespace, as their
+/// heights come from the stream, until it ends.
+pub(crate) async fn deliver_namespace_data<S, B>(
+    heights: S,
+    namespace: Namespace,
+    source: &B,
+    listener: &dyn NamespaceListener,
+) where
+    S: Stream<Item = u64>,
+    B: BlockSource,
+{
+    let mut heights = pin!(heights);
+
+    while let Some(height) = heights.next().await {
+        let Some(header) = source.header(height).await else {
+            continue;
+        };
+        if !may_contain_namespace(&header.dah, namespace) {
+            continue;
+        }
+
+        match source.blob_count(&header, namespace).await {
+            Some(blob_count) if blob_count > 0 => {
+                listener.on_namespace_data(NamespaceDataAvailable { height, blob_count })
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use celestia_types::test_utils::ExtendedHeaderGenerator;
+    use celestia_types::{AppVersion, Blob, ExtendedDataSquare};
+    use futures::stream;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct RecordedData(Arc<Mutex<Vec<NamespaceDataAvailable>>>);
+
+    impl NamespaceListener for RecordedData {
+        fn on_namespace_data(&self, data: NamespaceDataAvailable) {
+            self.0.lock().unwrap().push(data);
+        }
+    }
+
+    #[derive(Default)]
+    struct TestBlocks(HashMap<u64, (ExtendedHeader, ExtendedDataSquare)>);
+
+    impl BlockSource for TestBlocks {
+        async fn header(&self, height: u64) -> Option<ExtendedHeader> {
+            self.0.get(&height).map(|(header, _)| header.clone())
+        }
+
+        async fn blob_count(&self, header: &ExtendedHeader, namespace: Namespace) -> Option<u32> {
+            let (_, eds) = self.0.get(&header.height().value())?;
+            let rows = eds
+                .get_namespace_data(namespace, &header.dah, header.height().value())
+                .ok()?;
+            let shares = rows.iter().flat_map(|(_, row)| row.shares.iter());
+            let blobs = Blob::reconstruct_all(shares, AppVersion::V2).ok()?;
+
+            Some(blobs.len() as u32)
+        }
+    }
+
+    impl TestBlocks {
+        /// Add a block with 2x2 square of single share blobs of the namespaces, in order.
+        fn push(&mut self, gen: &mut ExtendedHeaderGenerator, namespaces: [Namespace; 4]) {
+            let ods = namespaces
+                .iter()
+                .flat_map(|namespace| {
+                    let blob = Blob::new(*namespace, vec![1; 100], AppVersion::V2).unwrap();
+                    blob.to_shares().unwrap()
+                })
+                .map(|share| share.data().to_vec())
+                .collect();
+            let eds = ExtendedDataSquare::from_ods(ods, AppVersion::V2).unwrap();
+            let header = gen.next_with_dah(DataAvailabilityHeader::from_eds(&eds));
+
+            self.0.insert(header.height().value(), (header, eds));
+        }
+    }
+
+    #[tokio::test]
+    async fn notifies_only_blocks_with_namespace() {
+        let ns = |id: u8| Namespace::new_v0(&[1, id]).unwrap();
+        let mut gen = ExtendedHeaderGenerator::new();
+        let mut blocks = TestBlocks::default();
+
+        blocks.push(&mut gen, [ns(2), ns(2), ns(3), ns(3)]);
+        blocks.push(&mut gen, [ns(3), ns(3), ns(3), ns(3)]);
+        blocks.push(&mut gen, [ns(1), ns(2), ns(3), ns(3)]);
+        // namespace within the range of the first row, but not in the block
+        blocks.push(&mut gen, [ns(1), ns(3), ns(3), ns(3)]);
+        blocks.push(&mut gen, [ns(2), ns(2), ns(2), ns(3)]);
+
+        let listener = RecordedData::default();
+        // 6th block isn't available
+        let heights = stream::iter(1..=6);
+        deliver_namespace_data(heights, ns(2), &blocks, &listener).await;
+
+        let expected = [(1, 2), (3, 1), (5, 3)]
+            .map(|(height, blob_count)| NamespaceDataAvailable { height, blob_count });
+        assert_eq!(*listener.0.lock().unwrap(), expected);
+        assert!(may_contain_namespace(&blocks.0[&4].0.dah, ns(2)));
+    }
+}
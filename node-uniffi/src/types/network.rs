@@ -1,17 +1,68 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
 use libp2p::swarm::ConnectionCounters as Libp2pConnectionCounters;
 use libp2p::swarm::NetworkInfo as Libp2pNetworkInfo;
+use libp2p::{Multiaddr, PeerId as Libp2pPeerId};
+use lumina_node::node::PeerTrackerInfo;
 use uniffi::Record;
 
-#[derive(Record)]
+use crate::error::{LuminaError, Result};
+use crate::types::PeerId;
+
+/// Multiaddr of a peer or a listener, validated when parsed.
+#[derive(Record, Debug, Clone, PartialEq, Eq)]
+pub struct MultiAddr {
+    /// The address in the canonical form, e.g. `/ip4/127.0.0.1/tcp/2121`.
+    pub addr: String,
+}
+
+impl MultiAddr {
+    /// Parse and validate the multiaddr.
+    pub fn parse(input: &str) -> Result<Self> {
+        let addr = parse_libp2p_multiaddr(input)?;
+        Ok(MultiAddr {
+            addr: addr.to_string(),
+        })
+    }
+}
+
+/// Parse and validate the multiaddr, e.g. to check user's input before putting it
+/// into the [`NodeConfig`].
+///
+/// [`NodeConfig`]: crate::types::NodeConfig
+#[uniffi::export]
+pub fn parse_multiaddr(input: String) -> Result<MultiAddr> {
+    MultiAddr::parse(&input)
+}
+
+pub(crate) fn parse_libp2p_multiaddr(input: &str) -> Result<Multiaddr> {
+    // an empty string is a valid, but useless, multiaddr without any protocols
+    if input.trim().is_empty() {
+        return Err(LuminaError::invalid_multiaddr(input, "address is empty"));
+    }
+
+    input
+        .parse()
+        .map_err(|e: libp2p::multiaddr::Error| LuminaError::invalid_multiaddr(input, e.to_string()))
+}
+
+#[derive(Record, Debug)]
 pub struct NetworkInfo {
     /// The total number of connected peers.
     pub num_peers: u32,
+    /// Number of the connected peers which are trusted, e.g. the bootnodes.
+    pub trusted_peer_count: u32,
+    /// Number of the connected peers which aren't trusted, e.g. discovered in the network.
+    pub discovered_peer_count: u32,
     /// Counters of ongoing network connections.
     pub connection_counters: ConnectionCounters,
+    /// Addresses the node is listening on for incoming connections.
+    pub listen_addresses: Vec<String>,
 }
 
 /// Counters of ongoing network connections.
-#[derive(Record)]
+#[derive(Record, Debug)]
 pub struct ConnectionCounters {
     /// The current number of connections.
     pub num_connections: u32,
@@ -29,11 +80,22 @@ pub struct ConnectionCounters {
     pub num_established_outgoing: u32,
 }
 
-impl From<Libp2pNetworkInfo> for NetworkInfo {
-    fn from(info: Libp2pNetworkInfo) -> Self {
+impl NetworkInfo {
+    pub(crate) fn new(
+        info: Libp2pNetworkInfo,
+        listeners: Vec<Multiaddr>,
+        peers: &PeerTrackerInfo,
+    ) -> Self {
+        let num_peers = info.num_peers() as u32;
+        // peer tracker is updated separately from the swarm, so it may briefly disagree
+        let trusted_peer_count = (peers.num_connected_trusted_peers as u32).min(num_peers);
+
         Self {
-            num_peers: info.num_peers() as u32,
+            num_peers,
+            trusted_peer_count,
+            discovered_peer_count: num_peers - trusted_peer_count,
             connection_counters: info.connection_counters().into(),
+            listen_addresses: listeners.iter().map(ToString::to_string).collect(),
         }
     }
 }
@@ -51,3 +113,202 @@ impl From<&Libp2pConnectionCounters> for ConnectionCounters {
         }
     }
 }
+
+/// Gossipsub score of a peer.
+///
+/// Components of the score aren't exposed by libp2p, so only the total is available.
+#[derive(Record, Debug, Clone)]
+pub struct PeerScore {
+    /// Id of the peer.
+    pub peer_id: PeerId,
+    /// Score of the peer. Peers with negative score are pruned from the mesh
+    /// and eventually ignored.
+    pub score: f64,
+}
+
+impl From<(Libp2pPeerId, f64)> for PeerScore {
+    fn from((peer_id, score): (Libp2pPeerId, f64)) -> Self {
+        Self {
+            peer_id: peer_id.into(),
+            score,
+        }
+    }
+}
+
+/// Round-trip time of the last ping of a connected peer.
+#[derive(Record, Debug, Clone)]
+pub struct PeerLatency {
+    /// Id of the peer.
+    pub peer_id: PeerId,
+    /// Round-trip time of the ping in milliseconds.
+    pub rtt_ms: f64,
+}
+
+impl From<(Libp2pPeerId, Duration)> for PeerLatency {
+    fn from((peer_id, rtt): (Libp2pPeerId, Duration)) -> Self {
+        Self {
+            peer_id: peer_id.into(),
+            rtt_ms: rtt.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// Number of the most recent peer connection events kept for computing [`ConnectionRates`].
+const CONNECTION_EVENTS_CAPACITY: usize = 1024;
+
+/// Rates of peers connecting and disconnecting, over a window ending now.
+#[derive(Record, Debug, Clone, Copy, PartialEq)]
+pub struct ConnectionRates {
+    /// Length of the window in seconds.
+    pub window_secs: u32,
+    /// Number of peers connected per second.
+    pub connects_per_sec: f64,
+    /// Number of peers disconnected per second.
+    pub disconnects_per_sec: f64,
+}
+
+/// Change of the connection to a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConnectionEvent {
+    Connected,
+    Disconnected,
+}
+
+/// Ring buffer of the most recent peer connection events.
+///
+/// Only the last [`CONNECTION_EVENTS_CAPACITY`] events are kept, so rates over long
+/// windows of high churn count only the events still in the buffer.
+#[derive(Debug, Default)]
+pub(crate) struct ConnectionEventLog {
+    events: VecDeque<(Instant, ConnectionEvent)>,
+}
+
+impl ConnectionEventLog {
+    /// Record the event which happened at the given time, dropping the oldest one if full.
+    pub(crate) fn record(&mut self, event: ConnectionEvent, at: Instant) {
+        if self.events.len() == CONNECTION_EVENTS_CAPACITY {
+            self.events.pop_front();
+        }
+        self.events.push_back((at, event));
+    }
+
+    /// Compute the rates of the events within `window_secs` before `now`.
+    pub(crate) fn rates(&self, window_secs: u32, now: Instant) -> ConnectionRates {
+        let window = Duration::from_secs(window_secs.into());
+        let mut connects = 0u32;
+        let mut disconnects = 0u32;
+
+        for (at, event) in self.events.iter().rev() {
+            if now.saturating_duration_since(*at) > window {
+                break;
+            }
+            match event {
+                ConnectionEvent::Connected => connects += 1,
+                ConnectionEvent::Disconnected => disconnects += 1,
+            }
+        }
+
+        let per_sec = |count: u32| {
+            if window_secs > 0 {
+                f64::from(count) / f64::from(window_secs)
+            } else {
+                0.0
+            }
+        };
+
+        ConnectionRates {
+            window_secs,
+            connects_per_sec: per_sec(connects),
+            disconnects_per_sec: per_sec(disconnects),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_multiaddr() {
+        let addr = MultiAddr::parse("/ip4/127.0.0.1/tcp/2121").unwrap();
+        assert_eq!(addr.addr, "/ip4/127.0.0.1/tcp/2121");
+
+        let peer_id = Libp2pPeerId::random();
+        let input = format!("/dnsaddr/example.com/p2p/{peer_id}");
+        assert_eq!(MultiAddr::parse(&input).unwrap().addr, input);
+    }
+
+    #[test]
+    fn malformed_multiaddr() {
+        for input in [
+            "",
+            // missing protocol
+            "127.0.0.1:2121",
+            "/127.0.0.1/tcp/2121",
+            "/ip4/127.0.0.1/tcp",
+            // bad port
+            "/ip4/127.0.0.1/tcp/port",
+            "/ip4/127.0.0.1/tcp/65536",
+            "/ip4/127.0.0.1/udp/-1/quic-v1",
+        ] {
+            let err = MultiAddr::parse(input).unwrap_err();
+            assert!(
+                matches!(&err, LuminaError::InvalidMultiaddr { input: rejected, .. } if rejected == input),
+                "{err}"
+            );
+        }
+    }
+
+    #[test]
+    fn connection_rates_of_burst() {
+        let start = Instant::now();
+        let mut log = ConnectionEventLog::default();
+
+        // old events, outside of the window
+        for i in 0..5 {
+            log.record(ConnectionEvent::Connected, start + Duration::from_millis(i));
+        }
+
+        // burst of 20 connects and 10 disconnects
+        let burst = start + Duration::from_secs(60);
+        for i in 0..30 {
+            let event = if i % 3 == 2 {
+                ConnectionEvent::Disconnected
+            } else {
+                ConnectionEvent::Connected
+            };
+            log.record(event, burst + Duration::from_millis(i * 10));
+        }
+
+        let now = burst + Duration::from_secs(1);
+        let rates = log.rates(10, now);
+        assert_eq!(rates.window_secs, 10);
+        assert_eq!(rates.connects_per_sec, 2.0);
+        assert_eq!(rates.disconnects_per_sec, 1.0);
+
+        let rates = log.rates(100, now);
+        assert_eq!(rates.connects_per_sec, 0.25);
+
+        let rates = log.rates(0, now);
+        assert_eq!(rates.connects_per_sec, 0.0);
+        assert_eq!(rates.disconnects_per_sec, 0.0);
+    }
+
+    #[test]
+    fn connection_event_log_capacity() {
+        let now = Instant::now();
+        let mut log = ConnectionEventLog::default();
+
+        for _ in 0..CONNECTION_EVENTS_CAPACITY {
+            log.record(ConnectionEvent::Connected, now);
+        }
+        log.record(ConnectionEvent::Disconnected, now);
+
+        let rates = log.rates(1, now);
+        assert_eq!(
+            rates.connects_per_sec,
+            (CONNECTION_EVENTS_CAPACITY - 1) as f64
+        );
+        assert_eq!(rates.disconnects_per_sec, 1.0);
+    }
+}
@@ -0,0 +1,172 @@
+//! Tracking and cancelling of the long-running operations of the node.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Mutex;
+
+use tokio_util::sync::CancellationToken;
+use uniffi::{Enum, Record};
+
+use crate::error::{LuminaError, Result};
+
+/// Kind of the long-running operation.
+#[derive(Enum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    /// [`LuminaNode::sync_to_head`](crate::LuminaNode::sync_to_head)
+    SyncToHead,
+    /// [`LuminaNode::sync_since`](crate::LuminaNode::sync_since)
+    SyncSince,
+    /// [`LuminaNode::sample_range`](crate::LuminaNode::sample_range)
+    SampleRange,
+}
+
+/// Long-running operation in progress.
+#[derive(Record, Debug, Clone, PartialEq, Eq)]
+pub struct OperationHandle {
+    /// Id of the operation, unique for the lifetime of the node handle.
+    pub id: u64,
+    /// What the operation does.
+    pub kind: OperationKind,
+}
+
+/// Registry of the long-running operations in progress.
+#[derive(Debug, Default)]
+pub(crate) struct Operations {
+    inner: Mutex<OperationsInner>,
+}
+
+#[derive(Debug, Default)]
+struct OperationsInner {
+    next_id: u64,
+    active: BTreeMap<u64, (OperationKind, CancellationToken)>,
+}
+
+impl Operations {
+    /// Register the start of the operation, which stays listed until the returned
+    /// guard is dropped.
+    pub(crate) fn start(&self, kind: OperationKind) -> Operation<'_> {
+        let mut inner = self.inner.lock().expect("poisoned lock");
+        let id = inner.next_id;
+        inner.next_id += 1;
+
+        let cancellation = CancellationToken::new();
+        inner.active.insert(id, (kind, cancellation.clone()));
+
+        Operation {
+            operations: self,
+            id,
+            cancellation,
+        }
+    }
+
+    /// Operations in progress, in the order they were started.
+    pub(crate) fn list(&self) -> Vec<OperationHandle> {
+        let inner = self.inner.lock().expect("poisoned lock");
+
+        inner
+            .active
+            .iter()
+            .map(|(id, (kind, _))| OperationHandle {
+                id: *id,
+                kind: *kind,
+            })
+            .collect()
+    }
+
+    /// Cancel the operation, returning whether it was still in progress.
+    pub(crate) fn cancel(&self, id: u64) -> bool {
+        let inner = self.inner.lock().expect("poisoned lock");
+
+        match inner.active.get(&id) {
+            Some((_, cancellation)) => {
+                cancellation.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancel all operations in progress.
+    pub(crate) fn cancel_all(&self) {
+        let inner = self.inner.lock().expect("poisoned lock");
+
+        for (_, cancellation) in inner.active.values() {
+            cancellation.cancel();
+        }
+    }
+}
+
+/// Guard of the operation in progress, removing it from the registry once dropped.
+#[derive(Debug)]
+pub(crate) struct Operation<'a> {
+    operations: &'a Operations,
+    id: u64,
+    cancellation: CancellationToken,
+}
+
+impl Operation<'_> {
+    /// Token cancelled together with the operation.
+    pub(crate) fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    /// Run the future of the operation until it completes or the operation is cancelled.
+    pub(crate) async fn run<T>(&self, fut: impl Future<Output = Result<T>>) -> Result<T> {
+        tokio::select! {
+            res = fut => res,
+            _ = self.cancellation.cancelled() => Err(LuminaError::OperationCancelled { id: self.id }),
+        }
+    }
+}
+
+impl Drop for Operation<'_> {
+    fn drop(&mut self) {
+        self.operations
+            .inner
+            .lock()
+            .expect("poisoned lock")
+            .active
+            .remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn operation_listed_until_finished() {
+        let operations = Operations::default();
+
+        let sync = operations.start(OperationKind::SyncToHead);
+        let sample = operations.start(OperationKind::SampleRange);
+        assert_eq!(
+            operations.list(),
+            [
+                OperationHandle {
+                    id: 0,
+                    kind: OperationKind::SyncToHead
+                },
+                OperationHandle {
+                    id: 1,
+                    kind: OperationKind::SampleRange
+                },
+            ]
+        );
+
+        assert!(operations.cancel(1));
+        let err = sample.run(std::future::pending::<Result<()>>()).await;
+        assert!(matches!(
+            err,
+            Err(LuminaError::OperationCancelled { id: 1 })
+        ));
+        assert!(!sync.cancellation().is_cancelled());
+
+        drop(sample);
+        assert!(!operations.cancel(1));
+        assert_eq!(operations.list().len(), 1);
+
+        drop(sync);
+        assert!(operations.list().is_empty());
+    }
+}
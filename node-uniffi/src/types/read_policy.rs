@@ -0,0 +1,18 @@
+/// Policy deciding how data missing from the local store is obtained.
+#[derive(uniffi::Enum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadPolicy {
+    /// Read only from the local store, failing immediately with `HeightNotLocal` if data is missing.
+    LocalOnly,
+    /// Read from the local store, requesting missing data from the network and failing
+    /// with `Timeout` if it doesn't arrive in time.
+    FetchIfMissing {
+        /// How long to wait for the network response in milliseconds.
+        timeout_ms: u64,
+    },
+    /// Read from the local store, waiting for the syncer to store missing data and failing
+    /// with `HeightNotLocal` if it doesn't happen in time.
+    LocalOrError {
+        /// How long to wait for the data to be synced in milliseconds.
+        timeout_ms: u64,
+    },
+}
@@ -0,0 +1,156 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use lumina_node::store::{SamplingMetadata, SamplingStatus};
+use uniffi::Record;
+
+/// Data sampling statistics of the stored blocks containing a namespace.
+#[derive(Record, Debug, Default, Clone, PartialEq, Eq)]
+pub struct NamespaceStats {
+    /// Number of heights containing the namespace that finished sampling.
+    pub sampled: u64,
+    /// Number of heights containing the namespace that were accepted.
+    pub accepted: u64,
+    /// Number of heights containing the namespace that were rejected.
+    pub rejected: u64,
+}
+
+impl NamespaceStats {
+    /// Account the sampling result of a block containing the namespace.
+    pub(crate) fn record(&mut self, metadata: &SamplingMetadata) {
+        match metadata.status {
+            SamplingStatus::Accepted => {
+                self.sampled += 1;
+                self.accepted += 1;
+            }
+            SamplingStatus::Rejected => {
+                self.sampled += 1;
+                self.rejected += 1;
+            }
+            SamplingStatus::Unknown => {}
+        }
+    }
+}
+
+/// Number of the most recently sampled heights to keep the duration of the sampling for.
+const SAMPLING_DURATIONS_CAPACITY: usize = 1024;
+
+/// Result of the data sampling of a block.
+#[derive(Record, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SampleOutcome {
+    /// Whether the block was accepted as available.
+    pub accepted: bool,
+    /// Number of the shares sampled.
+    pub sampled_shares: u32,
+    /// How long the sampling took in milliseconds. Durations aren't stored, so
+    /// it's known only for the blocks sampled since the node started.
+    pub took_ms: Option<u64>,
+}
+
+impl SampleOutcome {
+    /// Outcome of the sampling described by the metadata, or `None` if it didn't finish.
+    pub(crate) fn new(metadata: &SamplingMetadata, took: Option<Duration>) -> Option<Self> {
+        let accepted = match metadata.status {
+            SamplingStatus::Accepted => true,
+            SamplingStatus::Rejected => false,
+            SamplingStatus::Unknown => return None,
+        };
+
+        Some(SampleOutcome {
+            accepted,
+            sampled_shares: metadata.cids.len() as u32,
+            took_ms: took.map(|took| took.as_millis() as u64),
+        })
+    }
+}
+
+/// Ring buffer of how long the sampling of the most recent heights took.
+#[derive(Debug, Default)]
+pub(crate) struct SamplingDurations {
+    durations: VecDeque<(u64, Duration)>,
+}
+
+impl SamplingDurations {
+    /// Record the duration of the sampling, dropping the oldest one if full.
+    pub(crate) fn record(&mut self, height: u64, took: Duration) {
+        if self.durations.len() == SAMPLING_DURATIONS_CAPACITY {
+            self.durations.pop_front();
+        }
+        self.durations.push_back((height, took));
+    }
+
+    /// Duration of the latest sampling of the height, if still in the buffer.
+    pub(crate) fn get(&self, height: u64) -> Option<Duration> {
+        self.durations
+            .iter()
+            .rev()
+            .find(|(h, _)| *h == height)
+            .map(|(_, took)| *took)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(status: SamplingStatus) -> SamplingMetadata {
+        SamplingMetadata {
+            status,
+            cids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn stats_count_finished_sampling() {
+        let mut stats = NamespaceStats::default();
+
+        for status in [
+            SamplingStatus::Accepted,
+            SamplingStatus::Rejected,
+            SamplingStatus::Accepted,
+            SamplingStatus::Unknown,
+        ] {
+            stats.record(&metadata(status));
+        }
+
+        assert_eq!(
+            stats,
+            NamespaceStats {
+                sampled: 3,
+                accepted: 2,
+                rejected: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn sample_outcome() {
+        assert_eq!(
+            SampleOutcome::new(&metadata(SamplingStatus::Rejected), None),
+            Some(SampleOutcome {
+                accepted: false,
+                sampled_shares: 0,
+                took_ms: None,
+            })
+        );
+        assert_eq!(
+            SampleOutcome::new(&metadata(SamplingStatus::Unknown), None),
+            None
+        );
+
+        let mut durations = SamplingDurations::default();
+        durations.record(5, Duration::from_millis(120));
+        durations.record(6, Duration::from_millis(80));
+        // resampled
+        durations.record(5, Duration::from_millis(40));
+
+        assert_eq!(durations.get(5), Some(Duration::from_millis(40)));
+        assert_eq!(durations.get(6), Some(Duration::from_millis(80)));
+        assert_eq!(durations.get(7), None);
+
+        for height in 100..100 + SAMPLING_DURATIONS_CAPACITY as u64 {
+            durations.record(height, Duration::from_millis(1));
+        }
+        assert_eq!(durations.get(5), None);
+    }
+}
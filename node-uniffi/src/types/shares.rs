@@ -0,0 +1,196 @@
+//! Streaming of all the shares of the extended data square of a block.
+
+use std::pin::pin;
+
+use celestia_types::Share;
+use futures::stream::{self, Stream, StreamExt};
+use uniffi::Record;
+
+use crate::error::Result;
+
+/// Share of the extended data square, with its coordinates.
+#[derive(Record, Debug, Clone, PartialEq, Eq)]
+pub struct ShareRecord {
+    /// Index of the row of the share.
+    pub row: u16,
+    /// Index of the column of the share.
+    pub column: u16,
+    /// Raw data of the share.
+    pub data: Vec<u8>,
+}
+
+/// Receiver of the shares of the extended data square of a block.
+#[uniffi::export(callback_interface)]
+pub trait ShareListener: Send + Sync {
+    /// Called for each share of the square, row by row, from the first share of the
+    /// first row to the last share of the last row.
+    fn on_share(&self, share: ShareRecord);
+}
+
+/// Source of the rows of the extended data squares.
+pub(crate) trait RowSource {
+    /// Shares of the row of the square of the block.
+    async fn row(&self, height: u64, row_index: u16) -> Result<Vec<Share>>;
+}
+
+/// Stream all the shares of the extended data square of the block in row-major order,
+/// until all of them are streamed or a row fails to be fetched.
+///
+/// Rows are fetched only once the shares of the previous one are consumed, so at most
+/// a single row is held at once.
+pub(crate) fn block_shares<R>(
+    source: &R,
+    height: u64,
+    square_width: u16,
+) -> impl Stream<Item = Result<ShareRecord>> + '_
+where
+    R: RowSource,
+{
+    stream::unfold(Some(0), move |row| async move {
+        let row = row.filter(|row| *row < square_width)?;
+        let shares = source.row(height, row).await;
+        // nothing is fetched after the failed row
+        let next = shares.is_ok().then_some(row + 1);
+
+        Some((shares.map(|shares| (row, shares)), next))
+    })
+    .flat_map(|row| {
+        let records = match row {
+            Ok((row, shares)) => shares
+                .into_iter()
+                .zip(0..)
+                .map(|(share, column)| {
+                    Ok(ShareRecord {
+                        row,
+                        column,
+                        data: share.to_vec(),
+                    })
+                })
+                .collect(),
+            Err(e) => vec![Err(e)],
+        };
+        stream::iter(records)
+    })
+}
+
+/// Deliver the shares from the stream to the listener, until it ends or fails.
+pub(crate) async fn deliver_shares<S>(shares: S, listener: &dyn ShareListener) -> Result<()>
+where
+    S: Stream<Item = Result<ShareRecord>>,
+{
+    let mut shares = pin!(shares);
+
+    while let Some(share) = shares.next().await {
+        listener.on_share(share?);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU16, Ordering};
+    use std::sync::Mutex;
+
+    use celestia_types::consts::appconsts::SHARE_SIZE;
+    use celestia_types::nmt::Namespace;
+    use celestia_types::{AppVersion, ExtendedDataSquare};
+
+    use super::*;
+    use crate::error::LuminaError;
+
+    struct SquareSource {
+        eds: ExtendedDataSquare,
+        fetched: AtomicU16,
+        missing_row: Option<u16>,
+    }
+
+    impl SquareSource {
+        fn new(ods_width: usize) -> Self {
+            let ods = (0..ods_width * ods_width)
+                .map(|i| {
+                    let mut share = Namespace::new_v0(&[1]).unwrap().as_bytes().to_vec();
+                    share.push(1);
+                    share.extend_from_slice(&(i as u32).to_be_bytes());
+                    share.resize(SHARE_SIZE, 0);
+                    share
+                })
+                .collect();
+
+            SquareSource {
+                eds: ExtendedDataSquare::from_ods(ods, AppVersion::V2).unwrap(),
+                fetched: AtomicU16::new(0),
+                missing_row: None,
+            }
+        }
+    }
+
+    impl RowSource for SquareSource {
+        async fn row(&self, height: u64, row_index: u16) -> Result<Vec<Share>> {
+            self.fetched.fetch_add(1, Ordering::SeqCst);
+
+            if self.missing_row == Some(row_index) {
+                return Err(LuminaError::HeightNotLocal { height });
+            }
+
+            Ok(self.eds.row(row_index).unwrap())
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordedShares(Mutex<Vec<ShareRecord>>);
+
+    impl ShareListener for RecordedShares {
+        fn on_share(&self, share: ShareRecord) {
+            self.0.lock().unwrap().push(share);
+        }
+    }
+
+    #[tokio::test]
+    async fn shares_in_row_major_order() {
+        let source = SquareSource::new(4);
+        let width = source.eds.square_width();
+        let listener = RecordedShares::default();
+
+        deliver_shares(block_shares(&source, 7, width), &listener)
+            .await
+            .unwrap();
+
+        let shares = listener.0.into_inner().unwrap();
+        assert_eq!(shares.len(), usize::from(width) * usize::from(width));
+        for (i, share) in shares.iter().enumerate() {
+            assert_eq!(usize::from(share.row), i / usize::from(width));
+            assert_eq!(usize::from(share.column), i % usize::from(width));
+            let expected = source.eds.share(share.row, share.column).unwrap();
+            assert_eq!(share.data, expected.to_vec());
+        }
+    }
+
+    #[tokio::test]
+    async fn rows_fetched_as_consumed() {
+        let source = SquareSource {
+            missing_row: Some(2),
+            ..SquareSource::new(2)
+        };
+        let mut shares = pin!(block_shares(&source, 7, source.eds.square_width()));
+
+        // the first row is fetched only once its first share is polled
+        assert_eq!(source.fetched.load(Ordering::SeqCst), 0);
+        for _ in 0..4 {
+            shares.next().await.unwrap().unwrap();
+        }
+        assert_eq!(source.fetched.load(Ordering::SeqCst), 1);
+        for _ in 0..4 {
+            shares.next().await.unwrap().unwrap();
+        }
+        assert_eq!(source.fetched.load(Ordering::SeqCst), 2);
+
+        // stream ends after the row which failed
+        assert!(matches!(
+            shares.next().await.unwrap(),
+            Err(LuminaError::HeightNotLocal { height: 7 })
+        ));
+        assert!(shares.next().await.is_none());
+        assert_eq!(source.fetched.load(Ordering::SeqCst), 3);
+    }
+}
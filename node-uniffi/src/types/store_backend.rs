@@ -0,0 +1,154 @@
+//! Backends opening the stores of the node's headers and blocks.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use blockstore::Blockstore;
+use lumina_node::blockstore::RedbBlockstore;
+use lumina_node::store::{RedbStore, Store};
+use tokio::task::spawn_blocking;
+
+use crate::error::{LuminaError, Result};
+
+/// Prefix of the names of the stores in the base directory, followed by the network id.
+const STORE_PREFIX: &str = "store-";
+
+/// Backend opening the stores of the node.
+pub(crate) trait StoreBackend {
+    /// Store of the samples of the blocks.
+    type Blockstore: Blockstore + 'static;
+    /// Store of the headers and their sampling metadata.
+    type Store: Store + 'static;
+
+    /// Open the stores of the node connecting to the network, keeping their data
+    /// in the `base_path` directory.
+    async fn open(
+        self,
+        base_path: PathBuf,
+        network_id: &str,
+    ) -> Result<(Self::Blockstore, Self::Store)>;
+}
+
+/// Backend keeping both stores in the `store-<network id>` redb database.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct RedbBackend;
+
+impl StoreBackend for RedbBackend {
+    type Blockstore = RedbBlockstore;
+    type Store = RedbStore;
+
+    async fn open(
+        self,
+        base_path: PathBuf,
+        network_id: &str,
+    ) -> Result<(RedbBlockstore, RedbStore)> {
+        let store_path = base_path.join(store_name(network_id));
+
+        spawn_blocking(move || {
+            std::fs::create_dir_all(&base_path).map_err(|e| {
+                LuminaError::storage(format!("Failed to create base directory: {}", e))
+            })
+        })
+        .await
+        .map_err(|e| LuminaError::storage(format!("Failed to create base directory: {}", e)))??;
+
+        let db = spawn_blocking(move || match redb::Database::create(&store_path) {
+            Ok(db) => Ok(Arc::new(db)),
+            // opened by another node, in this or a different process
+            Err(redb::DatabaseError::DatabaseAlreadyOpen) => Err(LuminaError::StorageLocked {
+                path: store_path.to_string_lossy().into_owned(),
+            }),
+            Err(e) => Err(LuminaError::StorageInit {
+                msg: format!("Failed to create database: {}", e),
+            }),
+        })
+        .await
+        .map_err(|e| LuminaError::storage(format!("Failed to create base directory: {}", e)))??;
+
+        let store = RedbStore::new(db.clone())
+            .await
+            .map_err(|e| LuminaError::storage_init(format!("Failed to initialize store: {}", e)))?;
+
+        let blockstore = RedbBlockstore::new(db);
+
+        Ok((blockstore, store))
+    }
+}
+
+/// Name of the store of the network, in the base directory.
+pub(crate) fn store_name(network_id: &str) -> String {
+    format!("{STORE_PREFIX}{network_id}")
+}
+
+/// Remove the stores of the networks other than the kept ones from the base directory,
+/// returning the paths of the removed stores. Other entries of the directory are left intact.
+pub(crate) fn remove_stale_stores(base_path: &Path, keep: &[String]) -> Result<Vec<String>> {
+    let entries = match std::fs::read_dir(base_path) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(LuminaError::storage(format!(
+                "Failed to read base directory: {}",
+                e
+            )))
+        }
+    };
+
+    let mut removed = Vec::new();
+
+    for entry in entries {
+        let entry = entry
+            .map_err(|e| LuminaError::storage(format!("Failed to read base directory: {}", e)))?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+
+        if !name.starts_with(STORE_PREFIX) || keep.contains(&name) {
+            continue;
+        }
+
+        let path = entry.path();
+        let result = if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+        result.map_err(|e| {
+            LuminaError::storage(format!("Failed to remove {}: {}", path.display(), e))
+        })?;
+
+        removed.push(path.to_string_lossy().into_owned());
+    }
+
+    removed.sort();
+    Ok(removed)
+}
+
+#[cfg(test)]
+pub(crate) use in_memory::InMemoryBackend;
+
+#[cfg(test)]
+mod in_memory {
+    use lumina_node::blockstore::InMemoryBlockstore;
+    use lumina_node::store::InMemoryStore;
+
+    use super::*;
+
+    /// Backend keeping the stores in memory, never touching the base directory.
+    #[derive(Debug)]
+    pub(crate) struct InMemoryBackend {
+        /// Store given to the node, possibly with the headers already in it.
+        pub(crate) store: InMemoryStore,
+    }
+
+    impl StoreBackend for InMemoryBackend {
+        type Blockstore = InMemoryBlockstore;
+        type Store = InMemoryStore;
+
+        async fn open(
+            self,
+            _base_path: PathBuf,
+            _network_id: &str,
+        ) -> Result<(InMemoryBlockstore, InMemoryStore)> {
+            Ok((InMemoryBlockstore::new(), self.store))
+        }
+    }
+}
@@ -1,12 +1,30 @@
-use lumina_node::block_ranges::BlockRange as LuminaBlockRange;
-use lumina_node::node::SyncingInfo as LuminaSyncingInfo;
-use uniffi::Record;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use celestia_types::ExtendedHeader;
+use lumina_node::block_ranges::{BlockRange as LuminaBlockRange, BlockRanges};
+use lumina_node::node::{ReorgInfo as LuminaReorgInfo, SyncingInfo as LuminaSyncingInfo};
+use tokio_util::sync::CancellationToken;
+use uniffi::{Object, Record};
+
+use crate::error::{LuminaError, Result};
 
 /// A range of blocks.
-#[derive(Record)]
-struct BlockRange {
-    start: u64,
-    end: u64,
+#[derive(Record, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockRange {
+    /// First height in the range.
+    pub start: u64,
+    /// Last height in the range, inclusive. Range is empty if it's lower than `start`.
+    pub end: u64,
+}
+
+impl BlockRange {
+    /// Range without any blocks.
+    pub(crate) fn empty() -> Self {
+        BlockRange { start: 1, end: 0 }
+    }
 }
 
 impl From<LuminaBlockRange> for BlockRange {
@@ -18,13 +36,304 @@ impl From<LuminaBlockRange> for BlockRange {
     }
 }
 
+/// Range of headers synced by [`LuminaNode::sync_since`].
+///
+/// [`LuminaNode::sync_since`]: crate::LuminaNode::sync_since
+#[derive(Record, Debug, PartialEq, Eq)]
+pub struct SyncedRange {
+    /// Range of the synced headers
+    range: BlockRange,
+    /// Whether the requested time was older than the syncing window, and the range was
+    /// clamped to start at the beginning of the window
+    clamped: bool,
+}
+
+impl SyncedRange {
+    pub(crate) fn new(start: u64, end: u64, clamped: bool) -> Self {
+        SyncedRange {
+            range: BlockRange { start, end },
+            clamped,
+        }
+    }
+}
+
+/// Wall-clock times of the blocks at the boundaries of a range.
+#[derive(Record, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRange {
+    /// Time of the lowest block of the range, in milliseconds since the unix epoch.
+    pub start_unix_ms: u64,
+    /// Time of the highest block of the range, in milliseconds since the unix epoch.
+    pub end_unix_ms: u64,
+}
+
+/// Height and time of a block, used for estimating heights from the wall-clock time.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BlockTime {
+    pub(crate) height: u64,
+    pub(crate) unix_ms: u64,
+}
+
+impl From<&ExtendedHeader> for BlockTime {
+    fn from(header: &ExtendedHeader) -> Self {
+        BlockTime {
+            height: header.height().value(),
+            unix_ms: (header.time().unix_timestamp_nanos() / 1_000_000) as u64,
+        }
+    }
+}
+
+/// Estimate the height of the first block produced at or after `since_ms`, using the
+/// average block time between `reference` and `head` blocks.
+///
+/// Times older than `window_start_ms` are clamped to it, which is reported in the second
+/// value of the returned pair.
+pub(crate) fn estimate_height_since(
+    reference: BlockTime,
+    head: BlockTime,
+    since_ms: u64,
+    window_start_ms: u64,
+) -> (u64, bool) {
+    let clamped = since_ms < window_start_ms;
+    let since_ms = since_ms.max(window_start_ms);
+
+    if since_ms >= head.unix_ms {
+        return (head.height, clamped);
+    }
+
+    let blocks = head.height.saturating_sub(reference.height);
+    let span_ms = head.unix_ms.saturating_sub(reference.unix_ms);
+
+    if blocks == 0 || span_ms == 0 {
+        // no way to estimate the block time, fall back to the oldest known block
+        return (reference.height, clamped);
+    }
+
+    let blocks_behind = (head.unix_ms - since_ms) as u128 * blocks as u128 / span_ms as u128;
+    let height = head.height.saturating_sub(blocks_behind as u64).max(1);
+
+    (height, clamped)
+}
+
+/// Estimate the range of heights of the blocks produced within `window` before the `head`,
+/// using the average block time between `reference` and `head` blocks.
+pub(crate) fn estimate_window_range(
+    reference: BlockTime,
+    head: BlockTime,
+    window: Duration,
+) -> BlockRange {
+    let since_ms = head.unix_ms.saturating_sub(window.as_millis() as u64);
+    let (start, _) = estimate_height_since(reference, head, since_ms, 0);
+
+    BlockRange {
+        start,
+        end: head.height,
+    }
+}
+
+/// Average size on disk of a synced and sampled block, assumed until it's measured: about
+/// 32 KiB of the header of a mainnet block, with its validator set, commit and the data
+/// availability header, and about 16 KiB of the samples with their proofs.
+pub(crate) const DEFAULT_BLOCK_DISK_BYTES: u64 = 48 * 1024;
+
+/// Block time assumed until it's measured from the stored headers.
+pub(crate) const DEFAULT_BLOCK_TIME: Duration = Duration::from_secs(6);
+
+/// Average block time between `reference` and `head` blocks, or `None` if it's unknown.
+pub(crate) fn average_block_time(reference: BlockTime, head: BlockTime) -> Option<Duration> {
+    let blocks = head.height.checked_sub(reference.height)?;
+    let span_ms = head.unix_ms.checked_sub(reference.unix_ms)?;
+
+    (blocks > 0 && span_ms > 0).then(|| Duration::from_millis(span_ms / blocks))
+}
+
+/// Estimate the bytes on disk taken by the blocks produced within `window`, one every
+/// `block_time`, each taking `block_bytes`.
+pub(crate) fn estimate_window_disk_bytes(
+    window: Duration,
+    block_time: Duration,
+    block_bytes: u64,
+) -> u64 {
+    let blocks = window.as_millis() / block_time.as_millis().max(1);
+    (blocks * u128::from(block_bytes))
+        .try_into()
+        .unwrap_or(u64::MAX)
+}
+
+/// Number of the most recent fetches of headers kept for computing the fetch rate.
+const FETCHES_CAPACITY: usize = 1024;
+
+/// Ring buffer of the most recent fetches of headers, with the heights fetched.
+#[derive(Debug, Default)]
+pub(crate) struct FetchRateLog {
+    fetches: VecDeque<(Instant, RangeInclusive<u64>)>,
+}
+
+impl FetchRateLog {
+    /// Record the fetch finished at the given time, dropping the oldest one if full.
+    pub(crate) fn record(&mut self, heights: RangeInclusive<u64>, at: Instant) {
+        if self.fetches.len() == FETCHES_CAPACITY {
+            self.fetches.pop_front();
+        }
+        self.fetches.push_back((at, heights));
+    }
+
+    /// Number of headers of the `within` heights fetched per second within `window` before
+    /// `now`, or `None` if none of them was fetched within it.
+    pub(crate) fn rate(
+        &self,
+        window: Duration,
+        now: Instant,
+        within: &RangeInclusive<u64>,
+    ) -> Option<f64> {
+        let headers: u64 = self
+            .fetches
+            .iter()
+            .rev()
+            .take_while(|(at, _)| now.saturating_duration_since(*at) <= window)
+            .map(|(_, heights)| {
+                let start = *heights.start().max(within.start());
+                let end = *heights.end().min(within.end());
+                (end + 1).saturating_sub(start)
+            })
+            .sum();
+
+        (headers > 0 && !window.is_zero()).then(|| headers as f64 / window.as_secs_f64())
+    }
+}
+
+/// Number of the most recent changes of the sync gap kept for detecting stuck syncing.
+const SYNC_GAPS_CAPACITY: usize = 1024;
+
+/// Ring buffer of the most recent changes of the gap between the subjective head and
+/// the highest stored header, with the time each of them was first observed.
+#[derive(Debug, Default)]
+pub(crate) struct SyncGapLog {
+    gaps: VecDeque<(Instant, u64)>,
+}
+
+impl SyncGapLog {
+    /// Record the gap observed at the given time, if it changed since the last one.
+    pub(crate) fn record(&mut self, gap: u64, at: Instant) {
+        if self.gaps.back().is_some_and(|(_, last)| *last == gap) {
+            return;
+        }
+        if self.gaps.len() == SYNC_GAPS_CAPACITY {
+            self.gaps.pop_front();
+        }
+        self.gaps.push_back((at, gap));
+    }
+
+    /// Checks if the gap was at least `threshold` during the whole `window` before `now`,
+    /// without shrinking over it. Returns `false` if no gap was observed that long ago.
+    pub(crate) fn is_stuck(&self, threshold: u64, window: Duration, now: Instant) -> bool {
+        let Some(since) = now.checked_sub(window) else {
+            return false;
+        };
+        let Some(first) = self.gaps.iter().rposition(|(at, _)| *at <= since) else {
+            return false;
+        };
+
+        let (_, past) = self.gaps[first];
+        let (_, current) = self.gaps[self.gaps.len() - 1];
+        let above_threshold = self.gaps.range(first..).all(|(_, gap)| *gap >= threshold);
+
+        above_threshold && current >= past
+    }
+}
+
+/// Blocks at the boundaries of the highest range of stored headers, the one the syncer
+/// extends up to the subjective head and backfills down to the start of the syncing window.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HeadRange {
+    pub(crate) lowest: BlockTime,
+    pub(crate) highest: BlockTime,
+}
+
+impl HeadRange {
+    /// Number of headers in the range.
+    fn len(&self) -> u64 {
+        self.highest.height.saturating_sub(self.lowest.height) + 1
+    }
+}
+
+/// Number of headers missing from the highest contiguous range of stored headers: the ones
+/// above it up to the subjective head, and the ones below it down to the start of the syncing
+/// window at `window_start_ms`, estimated from the average block time of the range.
+///
+/// Like the syncer, backfilling is considered done once the lowest header of the range was
+/// produced before the start of the window, it's the genesis, or the range joins the next
+/// lower stored range.
+pub(crate) fn missing_headers(
+    info: &LuminaSyncingInfo,
+    head_range: Option<HeadRange>,
+    window_start_ms: u64,
+) -> u64 {
+    let Some(HeadRange { lowest, highest }) = head_range else {
+        return info.subjective_head;
+    };
+    let above = info.subjective_head.saturating_sub(highest.height);
+
+    if lowest.height <= 1 || lowest.unix_ms <= window_start_ms {
+        return above;
+    }
+
+    let block_time = average_block_time(lowest, highest).unwrap_or(DEFAULT_BLOCK_TIME);
+    let within_window =
+        (lowest.unix_ms - window_start_ms).div_ceil((block_time.as_millis() as u64).max(1));
+    let lower_range_end = info
+        .stored_headers
+        .as_ref()
+        .iter()
+        .rev()
+        .nth(1)
+        .map_or(0, |range| *range.end());
+    let below = within_window.min(lowest.height.saturating_sub(lower_range_end + 1));
+
+    above + below
+}
+
+/// Check if at most `tolerance` headers are [`missing_headers`] from the highest contiguous
+/// range of stored headers. Nothing is synced if no headers are stored yet.
+pub(crate) fn is_synced(
+    info: &LuminaSyncingInfo,
+    head_range: Option<HeadRange>,
+    window_start_ms: u64,
+    tolerance: u64,
+) -> bool {
+    head_range.is_some() && missing_headers(info, head_range, window_start_ms) <= tolerance
+}
+
+/// Check if the header of the subjective head is stored. Nothing is synced until the
+/// subjective head is known.
+pub(crate) fn has_network_head(info: &LuminaSyncingInfo) -> bool {
+    info.subjective_head > 0 && info.stored_headers.contains(info.subjective_head)
+}
+
+/// Estimate in how many milliseconds the `remaining` headers are synced at the `rate` of
+/// headers per second. Returns `None` if the rate is unknown or nothing remains.
+pub(crate) fn estimate_sync_completion(remaining: u64, rate: Option<f64>) -> Option<u64> {
+    if remaining == 0 {
+        return None;
+    }
+    let rate = rate.filter(|rate| *rate > 0.0)?;
+
+    Some((remaining as f64 / rate * 1000.0).ceil() as u64)
+}
+
+/// Checks if the block was produced more than `max_age` before `now_ms`.
+pub(crate) fn is_stale(head: BlockTime, now_ms: u64, max_age: Duration) -> bool {
+    now_ms.saturating_sub(head.unix_ms) > max_age.as_millis() as u64
+}
+
 /// Status of the node syncing.
-#[derive(Record)]
+#[derive(Record, Debug)]
 pub struct SyncingInfo {
     /// Ranges of headers that are already synchronised
     stored_headers: Vec<BlockRange>,
     /// Syncing target. The latest height seen in the network that was successfully verified.
     subjective_head: u64,
+    /// Number of headers from header-sub dropped because syncer didn't keep up with them.
+    dropped_header_sub_headers: u64,
 }
 
 impl From<LuminaSyncingInfo> for SyncingInfo {
@@ -37,6 +346,674 @@ impl From<LuminaSyncingInfo> for SyncingInfo {
                 .map(BlockRange::from)
                 .collect(),
             subjective_head: info.subjective_head,
+            dropped_header_sub_headers: info.dropped_header_sub_headers,
+        }
+    }
+}
+
+/// Header received for an already synchronised height, conflicting with the stored one.
+#[derive(Record, Debug, Clone, PartialEq, Eq)]
+pub struct ReorgInfo {
+    /// Height of the conflicting headers.
+    pub height: u64,
+    /// Hash of the stored header, as an uppercase hex encoded string.
+    pub old_hash: String,
+    /// Hash of the newly received header, as an uppercase hex encoded string.
+    pub new_hash: String,
+}
+
+impl From<LuminaReorgInfo> for ReorgInfo {
+    fn from(info: LuminaReorgInfo) -> Self {
+        ReorgInfo {
+            height: info.height,
+            old_hash: info.old_hash.to_string(),
+            new_hash: info.new_hash.to_string(),
+        }
+    }
+}
+
+/// Set of the heights of stored headers, answering membership queries in constant time.
+///
+/// Unlike the stored header ranges of [`SyncingInfo`], where finding a height requires scanning the
+/// ranges, this keeps a bit for each height between the lowest and highest stored one.
+#[derive(Object, Debug, Clone, PartialEq, Eq)]
+pub struct StoredHeights {
+    /// Height corresponding to the first bit of the bitmap.
+    first_height: u64,
+    /// Bitmap of the stored heights, least significant bit first.
+    bitmap: Vec<u8>,
+}
+
+impl From<&BlockRanges> for StoredHeights {
+    fn from(ranges: &BlockRanges) -> Self {
+        let (Some(first_height), Some(last_height)) = (ranges.tail(), ranges.head()) else {
+            return StoredHeights {
+                first_height: 0,
+                bitmap: Vec::new(),
+            };
+        };
+
+        let len = (last_height - first_height + 1) as usize;
+        let mut bitmap = vec![0u8; len.div_ceil(8)];
+
+        for range in ranges.as_ref() {
+            for height in range.clone() {
+                let bit = (height - first_height) as usize;
+                bitmap[bit / 8] |= 1 << (bit % 8);
+            }
+        }
+
+        StoredHeights {
+            first_height,
+            bitmap,
+        }
+    }
+}
+
+#[uniffi::export]
+impl StoredHeights {
+    /// Returns whether the header of the given height is stored.
+    pub fn contains(&self, height: u64) -> bool {
+        let Some(bit) = height.checked_sub(self.first_height) else {
+            return false;
+        };
+
+        self.bitmap
+            .get((bit / 8) as usize)
+            .is_some_and(|byte| byte & (1 << (bit % 8)) != 0)
+    }
+
+    /// Returns the set serialized as the big endian first height, followed by the bitmap
+    /// of the following heights, least significant bit first.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + self.bitmap.len());
+        bytes.extend_from_slice(&self.first_height.to_be_bytes());
+        bytes.extend_from_slice(&self.bitmap);
+        bytes
+    }
+}
+
+/// Progress of syncing the headers up to the network head.
+#[derive(Record, Debug, Clone, Copy, PartialEq)]
+pub struct SyncProgressInfo {
+    /// Height of the latest synced header.
+    pub current_height: u64,
+    /// Height of the network head being synced to.
+    pub target_height: u64,
+    /// Number of headers still missing from the contiguous range of synced headers, up to
+    /// the network head and down to the start of the syncing window.
+    pub remaining_headers: u64,
+    /// Percentage of the headers of the syncing window that were synced, between 0 and 100.
+    pub percentage: f64,
+}
+
+impl SyncProgressInfo {
+    /// Progress of the syncing with the highest range of the stored headers, missing
+    /// `remaining_headers`.
+    pub(crate) fn new(
+        head_range: Option<HeadRange>,
+        target_height: u64,
+        remaining_headers: u64,
+    ) -> Self {
+        let synced = head_range.map_or(0, |range| range.len());
+        let percentage = if remaining_headers == 0 {
+            100.0
+        } else {
+            synced as f64 / (synced + remaining_headers) as f64 * 100.0
+        };
+
+        SyncProgressInfo {
+            current_height: head_range.map_or(0, |range| range.highest.height),
+            target_height,
+            remaining_headers,
+            percentage,
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.remaining_headers == 0
+    }
+}
+
+/// Receiver of the header syncing progress updates.
+#[uniffi::export(callback_interface)]
+pub trait SyncProgress: Send + Sync {
+    /// Called each time the syncing progressed.
+    fn on_progress(&self, progress: SyncProgressInfo);
+}
+
+/// Report the syncing progress until no headers are missing, or until cancelled.
+///
+/// `poll` returns the current progress, or `None` if the target isn't known yet. Reported
+/// heights and percentage never decrease, even if the polled ones do, e.g. after pruning
+/// or once the network head advances.
+pub(crate) async fn drive_sync<F, Fut>(
+    mut poll: F,
+    progress: &dyn SyncProgress,
+    cancellation: &CancellationToken,
+    interval: Duration,
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Option<SyncProgressInfo>>>,
+{
+    let mut last_reported: Option<SyncProgressInfo> = None;
+
+    loop {
+        if let Some(mut info) = poll().await? {
+            if let Some(last) = last_reported {
+                info.current_height = info.current_height.max(last.current_height);
+                info.target_height = info.target_height.max(last.target_height);
+                info.percentage = info.percentage.max(last.percentage);
+            }
+
+            if last_reported != Some(info) {
+                progress.on_progress(info);
+                last_reported = Some(info);
+            }
+
+            if info.is_done() {
+                return Ok(());
+            }
+        }
+
+        tokio::select! {
+            _ = cancellation.cancelled() => return Err(LuminaError::SyncCancelled),
+            _ = tokio::time::sleep(interval) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use celestia_types::test_utils::ExtendedHeaderGenerator;
+    use tendermint::Time;
+
+    use lumina_node::store::{InMemoryStore, Store};
+    use lumina_node::test_utils::{gen_filled_store, ExtendedHeaderGeneratorExt};
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct RecordedProgress(Arc<Mutex<Vec<SyncProgressInfo>>>);
+
+    impl SyncProgress for RecordedProgress {
+        fn on_progress(&self, progress: SyncProgressInfo) {
+            self.0.lock().unwrap().push(progress);
+        }
+    }
+
+    async fn store_head_range(store: &InMemoryStore) -> Option<HeadRange> {
+        let ranges = store.get_stored_header_ranges().await.unwrap();
+        let range = ranges.as_ref().last()?.clone();
+        let lowest = store.get_by_height(*range.start()).await.unwrap();
+        let highest = store.get_by_height(*range.end()).await.unwrap();
+
+        Some(HeadRange {
+            lowest: BlockTime::from(&lowest),
+            highest: BlockTime::from(&highest),
+        })
+    }
+
+    async fn store_progress(
+        store: &InMemoryStore,
+        target: u64,
+        window_start_ms: u64,
+    ) -> Result<Option<SyncProgressInfo>> {
+        let info = LuminaSyncingInfo {
+            stored_headers: store.get_stored_header_ranges().await.unwrap(),
+            subjective_head: target,
+            dropped_header_sub_headers: 0,
+        };
+        let head_range = store_head_range(store).await;
+        let missing = missing_headers(&info, head_range, window_start_ms);
+
+        Ok(Some(SyncProgressInfo::new(head_range, target, missing)))
+    }
+
+    #[tokio::test]
+    async fn progress_is_monotonic_until_target() {
+        let (store, mut gen) = gen_filled_store(10).await;
+        let store = Arc::new(store);
+        let progress = RecordedProgress::default();
+
+        let inserter = tokio::spawn({
+            let store = store.clone();
+            async move {
+                for _ in 0..4 {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    store.insert(gen.next_many_verified(10)).await.unwrap();
+                }
+            }
+        });
+
+        drive_sync(
+            || store_progress(&store, 50, 0),
+            &progress,
+            &CancellationToken::new(),
+            Duration::from_millis(5),
+        )
+        .await
+        .unwrap();
+        inserter.await.unwrap();
+
+        let reported = progress.0.lock().unwrap().clone();
+        assert_eq!(reported.first().unwrap().current_height, 10);
+        assert_eq!(reported.first().unwrap().remaining_headers, 40);
+        assert_eq!(reported.last().unwrap().current_height, 50);
+        assert_eq!(reported.last().unwrap().remaining_headers, 0);
+        assert_eq!(reported.last().unwrap().percentage, 100.0);
+        assert!(reported.windows(2).all(|w| {
+            w[0].current_height < w[1].current_height && w[0].percentage < w[1].percentage
+        }));
+    }
+
+    #[tokio::test]
+    async fn cancelled_sync_resumes_from_stored_headers() {
+        let (store, mut gen) = gen_filled_store(10).await;
+        let cancellation = CancellationToken::new();
+        let progress = RecordedProgress::default();
+
+        cancellation.cancel();
+        let err = drive_sync(
+            || store_progress(&store, 30, 0),
+            &progress,
+            &cancellation,
+            Duration::from_millis(5),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, LuminaError::SyncCancelled));
+
+        store.insert(gen.next_many_verified(20)).await.unwrap();
+
+        let resumed = RecordedProgress::default();
+        drive_sync(
+            || store_progress(&store, 30, 0),
+            &resumed,
+            &CancellationToken::new(),
+            Duration::from_millis(5),
+        )
+        .await
+        .unwrap();
+
+        let reported = progress.0.lock().unwrap().clone();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(
+            (reported[0].current_height, reported[0].remaining_headers),
+            (10, 20)
+        );
+        let resumed = resumed.0.lock().unwrap().clone();
+        assert_eq!(resumed.len(), 1);
+        assert_eq!(
+            (resumed[0].current_height, resumed[0].remaining_headers),
+            (30, 0)
+        );
+    }
+
+    #[tokio::test]
+    async fn progress_of_backfill_behind_head() {
+        // block at height `h` has time `1_000 + 10 * h` seconds
+        let mut gen = ExtendedHeaderGenerator::new();
+        gen.set_time(
+            Time::from_unix_timestamp(1_000, 0).unwrap(),
+            Duration::from_secs(10),
+        );
+        let mut headers = gen.next_many(50);
+        let store = Arc::new(InMemoryStore::new());
+        // like the syncer, the network head is stored first
+        let head = headers.pop().unwrap();
+        store.insert(vec![head]).await.unwrap();
+        let progress = RecordedProgress::default();
+
+        // window starts after the block 10, so headers are backfilled down to it
+        let window_start_ms = 1_100_000;
+        let backfiller = tokio::spawn({
+            let store = store.clone();
+            async move {
+                while headers.len() > 9 {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    let batch = headers.split_off(headers.len() - 10);
+                    store.insert(batch).await.unwrap();
+                }
+            }
+        });
+
+        drive_sync(
+            || store_progress(&store, 50, window_start_ms),
+            &progress,
+            &CancellationToken::new(),
+            Duration::from_millis(5),
+        )
+        .await
+        .unwrap();
+        backfiller.await.unwrap();
+
+        let reported = progress.0.lock().unwrap().clone();
+        // head is stored, but none of the headers behind it
+        let first = reported.first().unwrap();
+        assert_eq!((first.current_height, first.target_height), (50, 50));
+        // only block time of the default 6 seconds is known, instead of 10
+        assert_eq!(first.remaining_headers, 49);
+        assert!(first.percentage < 10.0);
+        assert!(reported.windows(2).all(|w| {
+            w[0].remaining_headers > w[1].remaining_headers && w[0].percentage < w[1].percentage
+        }));
+        let last = reported.last().unwrap();
+        assert_eq!(
+            store.get_stored_header_ranges().await.unwrap().head(),
+            Some(50)
+        );
+        assert_eq!(
+            store.get_stored_header_ranges().await.unwrap().tail(),
+            Some(10)
+        );
+        assert_eq!((last.remaining_headers, last.percentage), (0, 100.0));
+    }
+
+    #[test]
+    fn missing_headers_of_head_range() {
+        let mut gen = ExtendedHeaderGenerator::new();
+        gen.set_time(
+            Time::from_unix_timestamp(1_000, 0).unwrap(),
+            Duration::from_secs(10),
+        );
+        let headers = gen.next_many(100);
+        let block = |height: u64| BlockTime::from(&headers[height as usize - 1]);
+        let range = |lowest, highest| {
+            Some(HeadRange {
+                lowest: block(lowest),
+                highest: block(highest),
+            })
+        };
+        let info =
+            |stored: Vec<std::ops::RangeInclusive<u64>>, subjective_head| LuminaSyncingInfo {
+                stored_headers: BlockRanges::from_vec(stored.into_iter().collect()).unwrap(),
+                subjective_head,
+                dropped_header_sub_headers: 0,
+            };
+
+        // backfilled down to the genesis
+        assert_eq!(
+            missing_headers(&info(vec![1..=100], 100), range(1, 100), 0),
+            0
+        );
+        // not caught up with the subjective head
+        assert_eq!(
+            missing_headers(&info(vec![1..=90], 100), range(1, 90), 0),
+            10
+        );
+        // lowest header before the start of the window at block 50
+        let window_start_ms = 1_500_000;
+        let stored = info(vec![50..=100], 100);
+        assert_eq!(missing_headers(&stored, range(50, 100), window_start_ms), 0);
+        // 20 blocks of 10 seconds down to the block at the window start
+        let stored = info(vec![70..=100], 100);
+        assert_eq!(
+            missing_headers(&stored, range(70, 100), window_start_ms),
+            20
+        );
+        // backfilling stops at the lower stored range
+        let stored = info(vec![1..=60, 70..=100], 100);
+        assert_eq!(missing_headers(&stored, range(70, 100), 0), 9);
+        // nothing stored yet
+        assert_eq!(missing_headers(&info(Vec::new(), 100), None, 0), 100);
+    }
+
+    #[test]
+    fn sync_stuck_while_head_advances() {
+        let window = Duration::from_secs(60);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut log = SyncGapLog::default();
+        assert!(!log.is_stuck(3, window, start));
+
+        // syncing keeps up with the head
+        for secs in 0..60 {
+            log.record(secs % 2, at(secs));
+        }
+        assert!(!log.is_stuck(1, window, at(60)));
+
+        // head advances by a block every 6 seconds, but nothing gets stored
+        for blocks in 1..=20 {
+            log.record(blocks, at(60 + 6 * blocks));
+        }
+        // gap reached the threshold at 78s, so it's stuck only a window later
+        assert!(!log.is_stuck(3, window, at(137)));
+        assert!(log.is_stuck(3, window, at(138)));
+        assert!(!log.is_stuck(3, Duration::from_secs(600), at(138)));
+
+        // syncing catches up again
+        log.record(0, at(186));
+        assert!(!log.is_stuck(3, window, at(186)));
+    }
+
+    #[test]
+    fn sync_completion_at_steady_rate() {
+        let window = Duration::from_secs(30);
+        let start = Instant::now();
+        let mut log = FetchRateLog::default();
+        assert_eq!(log.rate(window, start, &(1..=10_000)), None);
+
+        // 100 headers backfilled every second below the head at 10_000, all of them missing
+        let mut etas = Vec::new();
+        for secs in 1..=60 {
+            let now = start + Duration::from_secs(secs);
+            let lowest = 10_000 - 100 * secs + 1;
+            log.record(lowest..=lowest + 99, now);
+
+            let rate = log.rate(window, now, &(lowest..=10_000));
+            let remaining = lowest - 1;
+            etas.push(estimate_sync_completion(remaining, rate).unwrap());
+        }
+
+        // the window fills up after 30 seconds, then the rate stays the same
+        let end = start + Duration::from_secs(60);
+        assert_eq!(log.rate(window, end, &(4_001..=10_000)), Some(310.0 / 3.0));
+        assert!(etas.windows(2).skip(30).all(|w| w[0] > w[1]));
+        // 4_000 headers left at 103.3 headers per second
+        assert_eq!(*etas.last().unwrap(), 38_710);
+        // only the fetches of the given heights count
+        assert_eq!(log.rate(window, end, &(4_001..=4_100)), Some(10.0 / 3.0));
+        assert_eq!(log.rate(window, end, &(1..=4_000)), None);
+
+        // nothing fetched recently
+        let later = start + Duration::from_secs(120);
+        assert_eq!(log.rate(window, later, &(4_001..=10_000)), None);
+        assert_eq!(estimate_sync_completion(4_000, None), None);
+        // already synced
+        assert_eq!(estimate_sync_completion(0, Some(100.0)), None);
+    }
+
+    #[test]
+    fn estimated_height_since() {
+        // 100 blocks produced every 10 seconds, block at height `h` has time `1_000 + 10 * h` seconds
+        let mut gen = ExtendedHeaderGenerator::new();
+        gen.set_time(
+            Time::from_unix_timestamp(1_000, 0).unwrap(),
+            Duration::from_secs(10),
+        );
+        let headers = gen.next_many(100);
+        let reference = BlockTime::from(&headers[0]);
+        let head = BlockTime::from(&headers[99]);
+        assert_eq!(reference.unix_ms, 1_010_000);
+        assert_eq!(head.unix_ms, 2_000_000);
+
+        let since = |ms| estimate_height_since(reference, head, ms, 0);
+        assert_eq!(since(1_500_000), (50, false));
+        assert_eq!(since(1_505_000), (51, false));
+        assert_eq!(since(2_000_000), (100, false));
+        assert_eq!(since(5_000_000), (100, false));
+        assert_eq!(since(1_010_000), (1, false));
+        // older than the first block
+        assert_eq!(since(500_000), (1, false));
+
+        // older than the syncing window
+        let (height, clamped) = estimate_height_since(reference, head, 1_000, 1_800_000);
+        assert_eq!((height, clamped), (80, true));
+    }
+
+    #[test]
+    fn stored_heights_match_ranges() {
+        let ranges =
+            BlockRanges::from_vec([1..=3, 8..=8, 10..=25, 40..=41].into_iter().collect()).unwrap();
+        let stored = StoredHeights::from(&ranges);
+
+        assert_eq!(stored.first_height, 1);
+        assert_eq!(stored.bitmap.len(), 41_usize.div_ceil(8));
+
+        for height in 0..=50 {
+            assert_eq!(stored.contains(height), ranges.contains(height), "{height}");
         }
+
+        let bytes = stored.to_bytes();
+        assert_eq!(bytes[..8], 1u64.to_be_bytes());
+        assert_eq!(bytes[8..], stored.bitmap);
+
+        let empty = StoredHeights::from(&BlockRanges::new());
+        assert!(!empty.contains(0));
+        assert!(!empty.contains(1));
+    }
+
+    #[test]
+    fn synced_within_tolerance() {
+        let info =
+            |stored: Vec<std::ops::RangeInclusive<u64>>, subjective_head| LuminaSyncingInfo {
+                stored_headers: BlockRanges::from_vec(stored.into_iter().collect()).unwrap(),
+                subjective_head,
+                dropped_header_sub_headers: 0,
+            };
+
+        let mut gen = ExtendedHeaderGenerator::new();
+        gen.set_time(
+            Time::from_unix_timestamp(1_000, 0).unwrap(),
+            Duration::from_secs(10),
+        );
+        let headers = gen.next_many(100);
+        let block = |height: u64| BlockTime::from(&headers[height as usize - 1]);
+        let range = |lowest, highest| {
+            Some(HeadRange {
+                lowest: block(lowest),
+                highest: block(highest),
+            })
+        };
+
+        // exactly at the head
+        assert!(is_synced(&info(vec![1..=100], 100), range(1, 100), 0, 0));
+        // within the tolerance
+        assert!(is_synced(&info(vec![1..=97], 100), range(1, 97), 0, 3));
+        assert!(!is_synced(&info(vec![1..=97], 100), range(1, 97), 0, 2));
+        // beyond the tolerance
+        assert!(!is_synced(
+            &info(vec![1..=10, 50..=90], 100),
+            range(50, 90),
+            0,
+            5
+        ));
+        // lower range older than the start of the window at block 50 doesn't matter
+        let window_start_ms = 1_500_000;
+        let stored = info(vec![1..=10, 50..=98], 100);
+        assert!(is_synced(&stored, range(50, 98), window_start_ms, 5));
+        // nothing stored yet
+        assert!(!is_synced(&info(Vec::new(), 0), None, 0, 10));
+    }
+
+    #[test]
+    fn not_synced_with_hole_below_head() {
+        let mut gen = ExtendedHeaderGenerator::new();
+        gen.set_time(
+            Time::from_unix_timestamp(1_000, 0).unwrap(),
+            Duration::from_secs(10),
+        );
+        let headers = gen.next_many(100);
+        let info = LuminaSyncingInfo {
+            stored_headers: BlockRanges::from_vec([1..=10, 90..=100].into_iter().collect())
+                .unwrap(),
+            subjective_head: 100,
+            dropped_header_sub_headers: 0,
+        };
+        let head_range = Some(HeadRange {
+            lowest: BlockTime::from(&headers[89]),
+            highest: BlockTime::from(&headers[99]),
+        });
+
+        // the header of the subjective head is stored, but not the heights 11..=89 below it
+        assert_eq!(missing_headers(&info, head_range, 0), 79);
+        assert!(!is_synced(&info, head_range, 0, 10));
+    }
+
+    #[test]
+    fn window_disk_bytes_proportional_to_window() {
+        let day = Duration::from_secs(24 * 3600);
+        let one_day = estimate_window_disk_bytes(day, DEFAULT_BLOCK_TIME, DEFAULT_BLOCK_DISK_BYTES);
+        // 14_400 blocks of 48 KiB
+        assert_eq!(one_day, 14_400 * 48 * 1024);
+
+        let month =
+            estimate_window_disk_bytes(30 * day, DEFAULT_BLOCK_TIME, DEFAULT_BLOCK_DISK_BYTES);
+        assert_eq!(month, 30 * one_day);
+        // twice as fast blocks take twice as much
+        let fast =
+            estimate_window_disk_bytes(day, DEFAULT_BLOCK_TIME / 2, DEFAULT_BLOCK_DISK_BYTES);
+        assert_eq!(fast, 2 * one_day);
+
+        let mut gen = ExtendedHeaderGenerator::new();
+        gen.set_time(
+            Time::from_unix_timestamp(1_000, 0).unwrap(),
+            Duration::from_secs(12),
+        );
+        let headers = gen.next_many(10);
+        let reference = BlockTime::from(&headers[0]);
+        let head = BlockTime::from(&headers[9]);
+        assert_eq!(
+            average_block_time(reference, head),
+            Some(Duration::from_secs(12))
+        );
+        assert_eq!(average_block_time(head, head), None);
+    }
+
+    #[test]
+    fn estimated_window_range() {
+        let block_time = Duration::from_secs(6);
+        let mut gen = ExtendedHeaderGenerator::new();
+        gen.set_time(Time::from_unix_timestamp(1_000, 0).unwrap(), block_time);
+        let headers = gen.next_many(1000);
+        let reference = BlockTime::from(&headers[0]);
+        let head = BlockTime::from(&headers[999]);
+
+        let window = Duration::from_secs(3600);
+        let range = estimate_window_range(reference, head, window);
+        let span = range.end - range.start + 1;
+        let expected = window.as_secs() / block_time.as_secs();
+        assert_eq!(range.end, 1000);
+        assert!(
+            span.abs_diff(expected) <= 1,
+            "span {span}, expected {expected}"
+        );
+
+        // window longer than the known history is limited to the first block
+        let range = estimate_window_range(reference, head, Duration::from_secs(30 * 24 * 3600));
+        assert_eq!(
+            range,
+            BlockRange {
+                start: 1,
+                end: 1000
+            }
+        );
+
+        // single known block
+        let range = estimate_window_range(head, head, window);
+        assert_eq!(
+            range,
+            BlockRange {
+                start: 1000,
+                end: 1000
+            }
+        );
+
+        let empty = BlockRange::empty();
+        assert!(empty.end < empty.start);
     }
 }
@@ -30,6 +30,8 @@ pub struct SyncingInfoSnapshot {
     pub stored_headers: Vec<BlockRange>,
     /// Syncing target. The latest height seen in the network that was successfully verified.
     pub subjective_head: u64,
+    /// Number of headers from header-sub dropped because syncer didn't keep up with them.
+    pub dropped_header_sub_headers: u64,
 }
 
 impl From<PeerTrackerInfo> for PeerTrackerInfoSnapshot {
@@ -55,6 +57,7 @@ impl From<SyncingInfo> for SyncingInfoSnapshot {
         Self {
             stored_headers,
             subjective_head: value.subjective_head,
+            dropped_header_sub_headers: value.dropped_header_sub_headers,
         }
     }
 }
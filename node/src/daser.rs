@@ -28,8 +28,13 @@
 //!     - [`Store`] is updated with the sampling result.
 //! 5. Steps 3 and 4 are repeated concurently, unless we detect that all peers have disconnected.
 //!    At that point Daser cleans the queue and moves back to step 1.
+//!
+//! Besides that, sampling of a range of blocks can be requested explicitly. Its blocks are
+//! queued, even if they were already accepted, and the request is responded once all of them
+//! are sampled.
 
 use std::collections::HashSet;
+use std::mem;
 use std::sync::Arc;
 
 use futures::future::BoxFuture;
@@ -38,15 +43,17 @@ use futures::{FutureExt, StreamExt};
 use rand::Rng;
 use tendermint::Time;
 use tokio::select;
+use tokio::sync::{mpsc, oneshot};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, warn};
 use web_time::{Duration, Instant};
 
-use crate::events::{EventPublisher, NodeEvent};
+use crate::events::{EventPublisher, NodeEvent, RejectionReason};
 use crate::executor::{spawn, JoinHandle};
 use crate::p2p::shwap::sample_cid;
 use crate::p2p::{P2p, P2pError};
-use crate::store::{BlockRanges, SamplingStatus, Store, StoreError};
+use crate::store::{BlockRange, BlockRanges, SamplingStatus, Store, StoreError};
+use crate::utils::OneshotSenderExt;
 
 const MAX_SAMPLES_NEEDED: usize = 16;
 const GET_SAMPLE_TIMEOUT: Duration = Duration::from_secs(10);
@@ -63,10 +70,29 @@ pub enum DaserError {
     /// An error propagated from the [`Store`] component.
     #[error("Store: {0}")]
     Store(#[from] StoreError),
+
+    /// Block requested to be sampled is outside of the sampling window.
+    #[error("Block {0} is outside of the sampling window")]
+    OutsideSamplingWindow(u64),
+
+    /// The worker has died.
+    #[error("Worker died")]
+    WorkerDied,
+
+    /// Channel closed unexpectedly.
+    #[error("Channel closed unexpectedly")]
+    ChannelClosedUnexpectedly,
+}
+
+impl From<oneshot::error::RecvError> for DaserError {
+    fn from(_value: oneshot::error::RecvError) -> Self {
+        DaserError::ChannelClosedUnexpectedly
+    }
 }
 
 /// Component responsible for data availability sampling of blocks from the network.
 pub(crate) struct Daser {
+    cmd_tx: mpsc::Sender<DaserCmd>,
     cancellation_token: CancellationToken,
     join_handle: JoinHandle,
 }
@@ -84,6 +110,17 @@ where
     pub(crate) event_pub: EventPublisher,
     /// Size of the sampling window.
     pub(crate) sampling_window: Duration,
+    /// Maximum number of blocks sampled concurrently, besides the newest head.
+    pub(crate) concurrency_limit: usize,
+}
+
+#[derive(Debug)]
+enum DaserCmd {
+    SampleRange {
+        range: BlockRange,
+        force: bool,
+        respond_to: oneshot::Sender<Result<()>>,
+    },
 }
 
 impl Daser {
@@ -94,7 +131,8 @@ impl Daser {
     {
         let cancellation_token = CancellationToken::new();
         let event_pub = args.event_pub.clone();
-        let mut worker = Worker::new(args, cancellation_token.child_token())?;
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let mut worker = Worker::new(args, cancellation_token.child_token(), cmd_rx)?;
 
         let join_handle = spawn(async move {
             if let Err(e) = worker.run().await {
@@ -107,6 +145,7 @@ impl Daser {
         });
 
         Ok(Daser {
+            cmd_tx,
             cancellation_token,
             join_handle,
         })
@@ -122,6 +161,36 @@ impl Daser {
     pub(crate) async fn join(&self) {
         self.join_handle.join().await;
     }
+
+    async fn send_command(&self, cmd: DaserCmd) -> Result<()> {
+        self.cmd_tx
+            .send(cmd)
+            .await
+            .map_err(|_| DaserError::WorkerDied)
+    }
+
+    /// Sample the blocks of the range and wait until all of them are sampled.
+    ///
+    /// Blocks which already have the result of sampling are skipped, unless `force` is set.
+    /// Sampling waits for the peers to connect and is limited by the same concurrency limit
+    /// as the rest of the blocks.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any block of the range is not in the store
+    /// or is outside of the sampling window, or if the [`Daser`] has been stopped.
+    pub(crate) async fn sample_range(&self, range: BlockRange, force: bool) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+
+        self.send_command(DaserCmd::SampleRange {
+            range,
+            force,
+            respond_to: tx,
+        })
+        .await?;
+
+        rx.await?
+    }
 }
 
 impl Drop for Daser {
@@ -135,6 +204,7 @@ where
     S: Store + 'static,
 {
     cancellation_token: CancellationToken,
+    cmd_rx: mpsc::Receiver<DaserCmd>,
     event_pub: EventPublisher,
     p2p: Arc<P2p>,
     store: Arc<S>,
@@ -143,17 +213,27 @@ where
     queue: BlockRanges,
     done: BlockRanges,
     ongoing: BlockRanges,
+    /// Blocks requested to be sampled explicitly, which weren't sampled yet.
+    forced: BlockRanges,
+    /// Requests for sampling of the blocks, waiting for the blocks left to be sampled.
+    waiters: Vec<(BlockRanges, oneshot::Sender<Result<()>>)>,
     prev_head: Option<u64>,
     sampling_window: Duration,
+    concurrency_limit: usize,
 }
 
 impl<S> Worker<S>
 where
     S: Store,
 {
-    fn new(args: DaserArgs<S>, cancellation_token: CancellationToken) -> Result<Worker<S>> {
+    fn new(
+        args: DaserArgs<S>,
+        cancellation_token: CancellationToken,
+        cmd_rx: mpsc::Receiver<DaserCmd>,
+    ) -> Result<Worker<S>> {
         Ok(Worker {
             cancellation_token,
+            cmd_rx,
             event_pub: args.event_pub,
             p2p: args.p2p,
             store: args.store,
@@ -162,8 +242,11 @@ where
             queue: BlockRanges::default(),
             done: BlockRanges::default(),
             ongoing: BlockRanges::default(),
+            forced: BlockRanges::default(),
+            waiters: Vec::new(),
             prev_head: None,
             sampling_window: args.sampling_window,
+            concurrency_limit: args.concurrency_limit,
         })
     }
 
@@ -173,7 +256,7 @@ where
                 break;
             }
 
-            self.connecting_event_loop().await;
+            self.connecting_event_loop().await?;
 
             if self.cancellation_token.is_cancelled() {
                 break;
@@ -186,14 +269,14 @@ where
         Ok(())
     }
 
-    async fn connecting_event_loop(&mut self) {
+    async fn connecting_event_loop(&mut self) -> Result<()> {
         debug!("Entering connecting_event_loop");
 
         let mut peer_tracker_info_watcher = self.p2p.peer_tracker_info_watcher();
 
         // Check if connection status changed before watcher was created
         if peer_tracker_info_watcher.borrow().num_connected_peers > 0 {
-            return;
+            return Ok(());
         }
 
         loop {
@@ -206,8 +289,13 @@ where
                         break;
                     }
                 }
+                Some(cmd) = self.cmd_rx.recv() => {
+                    self.on_cmd(cmd).await?;
+                }
             }
         }
+
+        Ok(())
     }
 
     async fn connected_event_loop(&mut self) -> Result<()> {
@@ -239,9 +327,15 @@ where
                 }
             }
 
-            // If there is no ongoing data sampling, schedule the next one.
-            if self.sampling_futs.is_empty() {
+            // Schedule the next blocks, until the concurrency limit is reached.
+            while self.sampling_futs.len() < self.concurrency_limit {
+                let ongoing = self.sampling_futs.len();
                 self.schedule_next_sample_block().await?;
+
+                if self.sampling_futs.len() == ongoing {
+                    // Nothing left to schedule
+                    break;
+                }
             }
 
             select! {
@@ -271,7 +365,11 @@ where
 
                     self.ongoing.remove_relaxed(height..=height).expect("invalid height");
                     self.done.insert_relaxed(height..=height).expect("invalid height");
+                    self.finish_forced(height..=height);
                 },
+                Some(cmd) = self.cmd_rx.recv() => {
+                    self.on_cmd(cmd).await?;
+                }
                 _ = &mut wait_new_head => {
                     wait_new_head = store.wait_new_head();
                     self.populate_queue().await?;
@@ -300,6 +398,7 @@ where
                 Err(StoreError::NotFound) => {
                     // Height was pruned and our queue is inconsistent.
                     // Repopulate queue and try again.
+                    self.finish_forced(height..=height);
                     self.populate_queue().await?;
                 }
                 Err(e) => return Err(e.into()),
@@ -319,6 +418,7 @@ where
             self.done
                 .insert_relaxed(1..=height)
                 .expect("invalid height");
+            self.finish_forced(1..=height);
             return Ok(());
         }
 
@@ -371,15 +471,14 @@ where
 
             // Run futures to completion
             while let Some((row, column, res)) = futs.next().await {
-                let share_accepted = match res {
-                    Ok(_) => true,
-                    // Validation is done at Bitswap level, through `ShwapMultihasher`.
-                    // If the sample is not valid, it will never be delivered to us
-                    // as the data of the CID. Because of that, the only signal
-                    // that data sampling verification failed is query timing out.
-                    Err(P2pError::BitswapQueryTimeout) => false,
-                    Err(e) => return Err(e.into()),
+                let rejection_reason = match res {
+                    Ok(_) => None,
+                    Err(e) => match rejection_reason(&e) {
+                        Some(reason) => Some(reason),
+                        None => return Err(e.into()),
+                    },
                 };
+                let share_accepted = rejection_reason.is_none();
 
                 block_accepted &= share_accepted;
 
@@ -389,6 +488,7 @@ where
                     row,
                     column,
                     accepted: share_accepted,
+                    rejection_reason,
                 });
             }
 
@@ -421,10 +521,82 @@ where
         let accepted = self.store.get_accepted_sampling_ranges().await?;
 
         self.queue = stored - accepted - &self.done - &self.ongoing;
+        self.queue = mem::take(&mut self.queue) + (self.forced.clone() - &self.ongoing);
+
+        Ok(())
+    }
+
+    async fn on_cmd(&mut self, cmd: DaserCmd) -> Result<()> {
+        match cmd {
+            DaserCmd::SampleRange {
+                range,
+                force,
+                respond_to,
+            } => match self.force_range(range, force).await {
+                Ok(awaited) if awaited.is_empty() => respond_to.maybe_send(Ok(())),
+                Ok(awaited) => self.waiters.push((awaited, respond_to)),
+                Err(
+                    e @ (DaserError::Store(StoreError::NotFound)
+                    | DaserError::OutsideSamplingWindow(_)),
+                ) => respond_to.maybe_send(Err(e)),
+                Err(e) => return Err(e),
+            },
+        }
 
         Ok(())
     }
 
+    /// Queue the blocks of the range for sampling, returning the blocks to be waited for.
+    async fn force_range(&mut self, range: BlockRange, force: bool) -> Result<BlockRanges> {
+        let mut forced = BlockRanges::new();
+
+        for height in range {
+            let header = self.store.get_by_height(height).await?;
+
+            if !self.in_sampling_window(header.time()) {
+                return Err(DaserError::OutsideSamplingWindow(height));
+            }
+
+            let sampled = self
+                .store
+                .get_sampling_metadata(height)
+                .await?
+                .is_some_and(|metadata| metadata.status != SamplingStatus::Unknown);
+
+            if force || !sampled {
+                forced
+                    .insert_relaxed(height..=height)
+                    .expect("invalid height");
+            }
+        }
+
+        // Ongoing blocks will be finished without queueing them again.
+        self.done = mem::take(&mut self.done) - &forced;
+        self.queue = mem::take(&mut self.queue) + (forced.clone() - &self.ongoing);
+        self.forced = mem::take(&mut self.forced) + &forced;
+
+        Ok(forced)
+    }
+
+    /// Mark the blocks as finished for the sampling requests.
+    fn finish_forced(&mut self, range: BlockRange) {
+        self.forced
+            .remove_relaxed(range.clone())
+            .expect("invalid height");
+
+        for (mut awaited, respond_to) in mem::take(&mut self.waiters) {
+            awaited
+                .remove_relaxed(range.clone())
+                .expect("invalid height");
+
+            if awaited.is_empty() {
+                respond_to.maybe_send(Ok(()));
+            } else {
+                self.waiters.push((awaited, respond_to));
+            }
+        }
+    }
+
     /// Returns true if `time` is within the sampling window.
     fn in_sampling_window(&self, time: Time) -> bool {
         let now = Time::now();
@@ -466,12 +638,28 @@ fn random_indexes(square_width: u16, max_samples_needed: usize) -> HashSet<(u16,
     indexes
 }
 
+/// Returns why the share is rejected because of the error of requesting it, or `None`
+/// if the error is fatal for the sampling.
+fn rejection_reason(error: &P2pError) -> Option<RejectionReason> {
+    match error {
+        // Validation is done at Bitswap level, through `ShwapMultihasher`.
+        // If the sample is not valid, it will never be delivered to us
+        // as the data of the CID. Because of that, the main signal
+        // that data sampling verification failed is query timing out.
+        P2pError::BitswapQueryTimeout => Some(RejectionReason::Timeout),
+        P2pError::Shwap(_) | P2pError::ProtoDecodeFailed(_) => Some(RejectionReason::InvalidProof),
+        P2pError::NoConnectedPeers => Some(RejectionReason::NotFound),
+        P2pError::Cid(_) | P2pError::CelestiaTypes(_) => Some(RejectionReason::Other),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::events::{EventChannel, EventSubscriber};
     use crate::executor::sleep;
-    use crate::node::DEFAULT_SAMPLING_WINDOW;
+    use crate::node::{DEFAULT_SAMPLING_CONCURRENCY, DEFAULT_SAMPLING_WINDOW};
     use crate::p2p::shwap::convert_cid;
     use crate::p2p::P2pCmd;
     use crate::store::InMemoryStore;
@@ -504,6 +692,7 @@ mod tests {
             p2p: Arc::new(mock),
             store: store.clone(),
             sampling_window: DEFAULT_SAMPLING_WINDOW,
+            concurrency_limit: DEFAULT_SAMPLING_CONCURRENCY,
         })
         .unwrap();
 
@@ -531,6 +720,7 @@ mod tests {
             p2p: Arc::new(mock),
             store: store.clone(),
             sampling_window: DEFAULT_SAMPLING_WINDOW,
+            concurrency_limit: DEFAULT_SAMPLING_CONCURRENCY,
         })
         .unwrap();
 
@@ -556,6 +746,7 @@ mod tests {
             p2p: Arc::new(mock),
             store: store.clone(),
             sampling_window: DEFAULT_SAMPLING_WINDOW,
+            concurrency_limit: DEFAULT_SAMPLING_CONCURRENCY,
         })
         .unwrap();
 
@@ -654,6 +845,157 @@ mod tests {
         handle.expect_no_cmd().await;
     }
 
+    #[async_test]
+    async fn concurrent_sampling_limit() {
+        let (mock, mut handle) = P2p::mocked();
+        let store = Arc::new(InMemoryStore::new());
+        let events = EventChannel::new();
+        let mut event_sub = events.subscribe();
+
+        let _daser = Daser::start(DaserArgs {
+            event_pub: events.publisher(),
+            p2p: Arc::new(mock),
+            store: store.clone(),
+            sampling_window: DEFAULT_SAMPLING_WINDOW,
+            concurrency_limit: 3,
+        })
+        .unwrap();
+
+        let mut gen = ExtendedHeaderGenerator::new();
+        let mut edses = Vec::new();
+        let mut headers = Vec::new();
+
+        for _ in 0..6 {
+            let eds = generate_dummy_eds(2, AppVersion::V2);
+            let dah = DataAvailabilityHeader::from_eds(&eds);
+            headers.push(gen.next_with_dah(dah));
+            edses.push(eds);
+        }
+
+        store.insert(headers).await.unwrap();
+
+        handle.expect_no_cmd().await;
+        handle.announce_peer_connected();
+
+        // Blocks 6 to 4 are sampled concurrently
+        handle_concurrent_get_shwap_cid(
+            &mut handle,
+            [
+                (6, &edses[5], false),
+                (5, &edses[4], false),
+                (4, &edses[3], false),
+            ],
+        )
+        .await;
+
+        // Then blocks 3 to 1
+        handle_concurrent_get_shwap_cid(
+            &mut handle,
+            [
+                (3, &edses[2], false),
+                (2, &edses[1], false),
+                (1, &edses[0], false),
+            ],
+        )
+        .await;
+
+        handle.expect_no_cmd().await;
+
+        let mut started = 0;
+        let mut max_concurrent = 0;
+
+        while let Ok(ev) = event_sub.try_recv() {
+            match ev.event {
+                NodeEvent::SamplingStarted { .. } => {
+                    started += 1;
+                    max_concurrent = max_concurrent.max(started);
+                }
+                NodeEvent::SamplingFinished { .. } => started -= 1,
+                _ => {}
+            }
+        }
+
+        assert_eq!(max_concurrent, 3);
+    }
+
+    #[async_test]
+    async fn sample_range() {
+        let (mock, mut handle) = P2p::mocked();
+        let store = Arc::new(InMemoryStore::new());
+        let events = EventChannel::new();
+
+        let daser = Daser::start(DaserArgs {
+            event_pub: events.publisher(),
+            p2p: Arc::new(mock),
+            store: store.clone(),
+            sampling_window: DEFAULT_SAMPLING_WINDOW,
+            concurrency_limit: DEFAULT_SAMPLING_CONCURRENCY,
+        })
+        .unwrap();
+
+        let mut gen = ExtendedHeaderGenerator::new();
+        let edses: Vec<_> = (0..3)
+            .map(|_| generate_dummy_eds(2, AppVersion::V2))
+            .collect();
+        let headers = edses
+            .iter()
+            .map(|eds| gen.next_with_dah(DataAvailabilityHeader::from_eds(eds)))
+            .collect::<Vec<_>>();
+        store.insert(headers).await.unwrap();
+
+        handle.expect_no_cmd().await;
+        handle.announce_peer_connected();
+        handle_concurrent_get_shwap_cid(
+            &mut handle,
+            [
+                (1, &edses[0], false),
+                (2, &edses[1], true),
+                (3, &edses[2], false),
+            ],
+        )
+        .await;
+        handle.expect_no_cmd().await;
+
+        // all the blocks have the result already
+        daser.sample_range(1..=3, false).await.unwrap();
+        handle.expect_no_cmd().await;
+        let status = store
+            .get_sampling_metadata(2)
+            .await
+            .unwrap()
+            .unwrap()
+            .status;
+        assert_eq!(status, SamplingStatus::Rejected);
+
+        let (res, _) = futures::join!(
+            daser.sample_range(1..=3, true),
+            handle_concurrent_get_shwap_cid(
+                &mut handle,
+                [
+                    (1, &edses[0], false),
+                    (2, &edses[1], false),
+                    (3, &edses[2], false)
+                ],
+            )
+        );
+        res.unwrap();
+        handle.expect_no_cmd().await;
+
+        for height in 1..=3 {
+            let status = store
+                .get_sampling_metadata(height)
+                .await
+                .unwrap()
+                .unwrap()
+                .status;
+            assert_eq!(status, SamplingStatus::Accepted);
+        }
+
+        let err = daser.sample_range(3..=4, true).await.unwrap_err();
+        assert!(matches!(err, DaserError::Store(StoreError::NotFound)));
+        handle.expect_no_cmd().await;
+    }
+
     async fn gen_and_sample_block(
         handle: &mut MockP2pHandle,
         gen: &mut ExtendedHeaderGenerator,
@@ -716,6 +1058,7 @@ mod tests {
                     row,
                     column,
                     accepted,
+                    rejection_reason,
                 } => {
                     assert_eq!(ev_height, height);
                     assert_eq!(square_width, eds.square_width());
@@ -723,6 +1066,8 @@ mod tests {
                         accepted,
                         !(simulate_invalid_sampling && i == INVALID_SHARE_REQ_NUM)
                     );
+                    let expected_reason = (!accepted).then_some(RejectionReason::Timeout);
+                    assert_eq!(rejection_reason, expected_reason);
                     // Make sure it is in the list and remove it
                     assert!(remaining_shares.remove(&(row, column)));
                 }
@@ -749,6 +1094,31 @@ mod tests {
         assert!(event_sub.try_recv().is_err());
     }
 
+    #[test]
+    fn rejection_reasons() {
+        let rejections = [
+            (
+                P2pError::BitswapQueryTimeout,
+                Some(RejectionReason::Timeout),
+            ),
+            (
+                P2pError::Shwap("invalid row proof".to_owned()),
+                Some(RejectionReason::InvalidProof),
+            ),
+            (P2pError::NoConnectedPeers, Some(RejectionReason::NotFound)),
+            (
+                P2pError::Cid(celestia_types::Error::EdsIndexOutOfRange(4, 4)),
+                Some(RejectionReason::Other),
+            ),
+            // fatal for the sampling
+            (P2pError::WorkerDied, None),
+        ];
+
+        for (error, reason) in rejections {
+            assert_eq!(rejection_reason(&error), reason, "{error}");
+        }
+    }
+
     /// Responds to get_shwap_cid and returns all CIDs that were requested
     async fn handle_concurrent_get_shwap_cid<const N: usize>(
         handle: &mut MockP2pHandle,
@@ -1,10 +1,14 @@
 //! Events generated by `Node`
 
 use std::fmt;
+use std::io;
 use std::panic::Location;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use libp2p::PeerId;
+use celestia_types::hash::Hash;
+use libp2p::swarm::ConnectionError;
+use libp2p::{Multiaddr, PeerId};
 use serde::Serialize;
 use tokio::sync::broadcast;
 use web_time::SystemTime;
@@ -34,6 +38,7 @@ pub enum TryRecvError {
 #[derive(Debug)]
 pub(crate) struct EventChannel {
     tx: broadcast::Sender<NodeEventInfo>,
+    next_seq: Arc<Mutex<u64>>,
 }
 
 /// `EventPublisher` is used to broadcast events generated by [`Node`] to [`EventSubscriber`]s.
@@ -42,6 +47,7 @@ pub(crate) struct EventChannel {
 #[derive(Debug, Clone)]
 pub(crate) struct EventPublisher {
     tx: broadcast::Sender<NodeEventInfo>,
+    next_seq: Arc<Mutex<u64>>,
 }
 
 /// `EventSubscriber` can be used by users to receive events from [`Node`].
@@ -50,19 +56,24 @@ pub(crate) struct EventPublisher {
 #[derive(Debug)]
 pub struct EventSubscriber {
     rx: broadcast::Receiver<NodeEventInfo>,
+    source_filter: Option<String>,
 }
 
 impl EventChannel {
     /// Create a new `EventChannel`.
     pub(crate) fn new() -> EventChannel {
         let (tx, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
-        EventChannel { tx }
+        EventChannel {
+            tx,
+            next_seq: Arc::default(),
+        }
     }
 
     /// Creates a new [`EventPublisher`].
     pub(crate) fn publisher(&self) -> EventPublisher {
         EventPublisher {
             tx: self.tx.clone(),
+            next_seq: self.next_seq.clone(),
         }
     }
 
@@ -70,6 +81,7 @@ impl EventChannel {
     pub(crate) fn subscribe(&self) -> EventSubscriber {
         EventSubscriber {
             rx: self.tx.subscribe(),
+            source_filter: None,
         }
     }
 }
@@ -81,14 +93,22 @@ impl Default for EventChannel {
 }
 
 impl EventPublisher {
+    #[track_caller]
     pub(crate) fn send(&self, event: NodeEvent) {
         let time = SystemTime::now();
         let location: &'static Location<'static> = Location::caller();
 
+        // Sequence number is taken while sending, so that events are received
+        // in the order of their sequence numbers.
+        let mut next_seq = self.next_seq.lock().expect("poisoned lock");
+        let seq = *next_seq;
+        *next_seq += 1;
+
         // Error is produced if there aren't any subscribers. Since this is
         // a valid case, we ignore the error.
         let _ = self.tx.send(NodeEventInfo {
             event,
+            seq,
             time,
             file_path: location.file(),
             file_line: location.line(),
@@ -97,6 +117,12 @@ impl EventPublisher {
 }
 
 impl EventSubscriber {
+    /// Receive only the events emitted from the source files whose path contains `filter`,
+    /// e.g. `"daser"`. Setting it to `None` removes the filter.
+    pub fn set_source_filter(&mut self, filter: Option<String>) {
+        self.source_filter = filter;
+    }
+
     /// Receive an event from [`Node`].
     ///
     /// # Cancel safety
@@ -107,7 +133,8 @@ impl EventSubscriber {
     pub async fn recv(&mut self) -> Result<NodeEventInfo, RecvError> {
         loop {
             match self.rx.recv().await {
-                Ok(val) => return Ok(val),
+                Ok(val) if self.matches(&val) => return Ok(val),
+                Ok(_) => continue,
                 Err(broadcast::error::RecvError::Lagged(_)) => {
                     // Slow consumer. We will receive a message on the next call.
                     continue;
@@ -125,7 +152,8 @@ impl EventSubscriber {
     pub fn try_recv(&mut self) -> Result<NodeEventInfo, TryRecvError> {
         loop {
             match self.rx.try_recv() {
-                Ok(val) => return Ok(val),
+                Ok(val) if self.matches(&val) => return Ok(val),
+                Ok(_) => continue,
                 Err(broadcast::error::TryRecvError::Lagged(_)) => {
                     // Slow consumer. We will receive a message on the next call.
                     continue;
@@ -135,6 +163,12 @@ impl EventSubscriber {
             }
         }
     }
+
+    fn matches(&self, event: &NodeEventInfo) -> bool {
+        self.source_filter
+            .as_ref()
+            .is_none_or(|filter| event.file_path.contains(filter.as_str()))
+    }
 }
 
 /// This struct include the [`NodeEvent`] and some extra information about the event.
@@ -142,6 +176,9 @@ impl EventSubscriber {
 pub struct NodeEventInfo {
     /// The event
     pub event: NodeEvent,
+    /// Sequence number of the event, strictly increasing in the order the events
+    /// were generated, unlike [`NodeEventInfo::time`] which follows the wall clock.
+    pub seq: u64,
     #[cfg_attr(
         target_arch = "wasm32",
         serde(serialize_with = "serialize_system_time")
@@ -165,6 +202,13 @@ pub enum NodeEvent {
     /// Node is connecting to bootnodes
     ConnectingToBootnodes,
 
+    /// Names of the bootnodes were resolved, their addresses are going to be dialed.
+    /// Bootnodes which failed to resolve are missing from the list.
+    BootnodesResolved {
+        /// The resolved addresses.
+        addresses: Vec<Multiaddr>,
+    },
+
     /// Peer just connected
     PeerConnected {
         #[serde(serialize_with = "serialize_as_string")]
@@ -181,8 +225,14 @@ pub enum NodeEvent {
         id: PeerId,
         /// Whether peer was in the trusted list or not.
         trusted: bool,
+        /// Why the last connection to the peer was closed.
+        reason: DisconnectReason,
     },
 
+    /// Node has a verified head and is connected to a peer supporting all the protocols
+    /// needed to serve the queries, i.e. header-ex and shwap. Emitted at most once.
+    ReadyToServe,
+
     /// Sampling just started.
     SamplingStarted {
         /// The block height that will be sampled.
@@ -205,6 +255,8 @@ pub enum NodeEvent {
         column: u16,
         /// The result of the sampling of the share.
         accepted: bool,
+        /// Why the share was rejected, `None` if it was accepted.
+        rejection_reason: Option<RejectionReason>,
     },
 
     /// Sampling just finished.
@@ -270,6 +322,16 @@ pub enum NodeEvent {
         took: Duration,
     },
 
+    /// Header received for an already synchronised height conflicts with the stored one.
+    ReorgDetected {
+        /// The height of the conflicting headers.
+        height: u64,
+        /// Hash of the stored header.
+        old_hash: Hash,
+        /// Hash of the newly received header.
+        new_hash: Hash,
+    },
+
     /// Header syncing fatal error.
     FatalSyncerError {
         /// A human readable error.
@@ -300,6 +362,55 @@ pub enum NodeEvent {
     NodeStopped,
 }
 
+/// Reason of closing the connection to a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum DisconnectReason {
+    /// Connection was closed without an error, by either side.
+    Graceful,
+    /// Connection timed out, e.g. keep-alive expired.
+    Timeout,
+    /// Peer violated the protocol or sent malformed data.
+    ProtocolError,
+    /// Peer was banned by the node.
+    Banned,
+    /// Cause of the disconnect couldn't be determined.
+    Unknown,
+}
+
+/// Reason of rejecting a sampled share.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum RejectionReason {
+    /// Share wasn't received in time. Shares failing the verification are never
+    /// delivered by Bitswap, so they're rejected this way too.
+    Timeout,
+    /// Share was received, but it couldn't be decoded.
+    InvalidProof,
+    /// No peer was connected to request the share from.
+    NotFound,
+    /// Share couldn't be requested for another reason.
+    Other,
+}
+
+impl From<Option<&ConnectionError>> for DisconnectReason {
+    fn from(cause: Option<&ConnectionError>) -> Self {
+        match cause {
+            None => DisconnectReason::Graceful,
+            Some(ConnectionError::KeepAliveTimeout) => DisconnectReason::Timeout,
+            Some(ConnectionError::IO(e)) => match e.kind() {
+                io::ErrorKind::TimedOut => DisconnectReason::Timeout,
+                io::ErrorKind::InvalidData | io::ErrorKind::InvalidInput => {
+                    DisconnectReason::ProtocolError
+                }
+                _ => DisconnectReason::Unknown,
+            },
+        }
+    }
+}
+
 impl NodeEvent {
     /// Returns `true` if the event indicates an error.
     pub fn is_error(&self) -> bool {
@@ -308,10 +419,13 @@ impl NodeEvent {
             | NodeEvent::FatalSyncerError { .. }
             | NodeEvent::FatalPrunerError { .. }
             | NodeEvent::FetchingHeadersFailed { .. }
+            | NodeEvent::ReorgDetected { .. }
             | NodeEvent::NetworkCompromised => true,
             NodeEvent::ConnectingToBootnodes
+            | NodeEvent::BootnodesResolved { .. }
             | NodeEvent::PeerConnected { .. }
             | NodeEvent::PeerDisconnected { .. }
+            | NodeEvent::ReadyToServe
             | NodeEvent::SamplingStarted { .. }
             | NodeEvent::ShareSamplingResult { .. }
             | NodeEvent::SamplingFinished { .. }
@@ -332,6 +446,9 @@ impl fmt::Display for NodeEvent {
             NodeEvent::ConnectingToBootnodes => {
                 write!(f, "Connecting to bootnodes")
             }
+            NodeEvent::BootnodesResolved { addresses } => {
+                write!(f, "Resolved bootnodes: {addresses:?}")
+            }
             NodeEvent::PeerConnected { id, trusted } => {
                 if *trusted {
                     write!(f, "Trusted peer connected: {id}")
@@ -339,13 +456,20 @@ impl fmt::Display for NodeEvent {
                     write!(f, "Peer connected: {id}")
                 }
             }
-            NodeEvent::PeerDisconnected { id, trusted } => {
+            NodeEvent::PeerDisconnected {
+                id,
+                trusted,
+                reason,
+            } => {
                 if *trusted {
-                    write!(f, "Trusted peer disconnected: {id}")
+                    write!(f, "Trusted peer disconnected: {id}, reason: {reason:?}")
                 } else {
-                    write!(f, "Peer disconnected: {id}")
+                    write!(f, "Peer disconnected: {id}, reason: {reason:?}")
                 }
             }
+            NodeEvent::ReadyToServe => {
+                write!(f, "Node is ready to serve")
+            }
             NodeEvent::SamplingStarted {
                 height,
                 square_width,
@@ -358,13 +482,18 @@ impl fmt::Display for NodeEvent {
                 row,
                 column,
                 accepted,
+                rejection_reason,
                 ..
             } => {
                 let acc = if *accepted { "accepted" } else { "rejected" };
                 write!(
                     f,
                     "Sampling for share [{row}, {column}] of block {height} was {acc}"
-                )
+                )?;
+                if let Some(reason) = rejection_reason {
+                    write!(f, " ({reason:?})")?;
+                }
+                Ok(())
             }
             NodeEvent::SamplingFinished { height, took, .. } => {
                 write!(f, "Sampling of block {height} finished. Took: {took:?}")
@@ -423,6 +552,16 @@ impl fmt::Display for NodeEvent {
                     write!(f, "Fetching headers of blocks {from_height}-{to_height} failed. Took: {took:?}, Error: {error}")
                 }
             }
+            NodeEvent::ReorgDetected {
+                height,
+                old_hash,
+                new_hash,
+            } => {
+                write!(
+                    f,
+                    "Header {new_hash} conflicts with the stored {old_hash} at height {height}"
+                )
+            }
             NodeEvent::FatalSyncerError { error } => {
                 write!(f, "Syncer stopped because of a fatal error: {error}")
             }
@@ -464,3 +603,53 @@ where
         * 1000.0;
     js_time.serialize(serializer)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn source_filter() {
+        let channel = EventChannel::new();
+        let publisher = channel.publisher();
+
+        let mut unfiltered = channel.subscribe();
+        let mut matching = channel.subscribe();
+        matching.set_source_filter(Some("src/events".to_owned()));
+        let mut other = channel.subscribe();
+        other.set_source_filter(Some("daser".to_owned()));
+
+        publisher.send(NodeEvent::NodeStopped);
+
+        let event = unfiltered.try_recv().unwrap();
+        assert!(event.file_path.ends_with("events.rs"));
+        assert!(matches!(
+            matching.try_recv().unwrap().event,
+            NodeEvent::NodeStopped
+        ));
+        assert!(matches!(other.try_recv(), Err(TryRecvError::Empty)));
+
+        other.set_source_filter(None);
+        publisher.send(NodeEvent::NetworkCompromised);
+        assert!(matches!(
+            other.try_recv().unwrap().event,
+            NodeEvent::NetworkCompromised
+        ));
+    }
+
+    #[test]
+    fn sequence_numbers_increase() {
+        let channel = EventChannel::new();
+        let publishers = [channel.publisher(), channel.publisher()];
+        let mut subscriber = channel.subscribe();
+
+        for i in 0..100 {
+            publishers[i % 2].send(NodeEvent::NodeStopped);
+        }
+
+        let seqs: Vec<_> = (0..100)
+            .map(|_| subscriber.try_recv().unwrap().seq)
+            .collect();
+        assert_eq!(seqs, (0..100).collect::<Vec<_>>());
+    }
+}
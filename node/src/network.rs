@@ -128,6 +128,12 @@ impl Network {
             .iter()
             .map(|s| s.parse().expect("Invalid bootstrap address"))
     }
+
+    /// Get the ids of the protocols the node speaks on the network and advertises to its
+    /// peers, including the header-sub topic.
+    pub fn supported_protocols(&self) -> Vec<String> {
+        crate::p2p::supported_protocols(self.id())
+    }
 }
 
 impl FromStr for Network {
@@ -220,6 +226,15 @@ mod tests {
         assert_eq!(private.count(), 0);
     }
 
+    #[test]
+    fn supported_protocols_of_network() {
+        let protocols = Network::Mocha.supported_protocols();
+
+        assert!(protocols.contains(&"/mocha-4/header-sub/v0.0.1".to_owned()));
+        assert!(protocols.contains(&"/mocha-4/header-ex/v0.0.3".to_owned()));
+        assert!(protocols.contains(&"/celestia/mocha-4/shwap/ipfs/bitswap/1.2.0".to_owned()));
+    }
+
     #[test]
     fn check_network_id() {
         Network::custom("foo").unwrap();
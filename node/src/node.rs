@@ -3,6 +3,7 @@
 //! Upon creation, `Node` will try to connect to Celestia's P2P network
 //! and then proceed with synchronization and data sampling of the blocks.
 
+use std::net::SocketAddr;
 use std::ops::RangeBounds;
 use std::sync::Arc;
 use std::time::Duration;
@@ -21,25 +22,33 @@ use tokio::sync::watch;
 use tokio_util::sync::CancellationToken;
 use tracing::warn;
 
+use crate::block_ranges::{BlockRange, BlockRanges};
 use crate::blockstore::InMemoryBlockstore;
 use crate::daser::{Daser, DaserArgs};
 use crate::events::{EventChannel, EventSubscriber, NodeEvent};
 use crate::executor::{spawn_cancellable, JoinHandle};
 use crate::p2p::{P2p, P2pArgs};
 use crate::pruner::{Pruner, PrunerArgs, DEFAULT_PRUNING_INTERVAL};
-use crate::store::{InMemoryStore, SamplingMetadata, Store, StoreError};
+use crate::store::{
+    InMemoryStore, LatencyStats, SamplingMetadata, Store, StoreError, StoreInsertionError,
+};
 use crate::syncer::{Syncer, SyncerArgs};
+use crate::utils::Token;
 
 mod builder;
 
 pub use self::builder::{
-    NodeBuilder, NodeBuilderError, DEFAULT_PRUNING_DELAY, DEFAULT_SAMPLING_WINDOW,
-    MIN_PRUNING_DELAY, MIN_SAMPLING_WINDOW,
+    NodeBuilder, NodeBuilderError, DEFAULT_IDLE_CONNECTION_TIMEOUT, DEFAULT_PING_INTERVAL,
+    DEFAULT_PRUNING_DELAY, DEFAULT_SAMPLING_CONCURRENCY, DEFAULT_SAMPLING_WINDOW,
+    MAX_SAMPLING_CONCURRENCY, MIN_PRUNING_DELAY, MIN_SAMPLING_WINDOW,
 };
 pub use crate::daser::DaserError;
-pub use crate::p2p::{HeaderExError, P2pError};
+pub use crate::p2p::bandwidth::BandwidthStats;
+pub use crate::p2p::header_sub::{HeaderSubOverflow, DEFAULT_HEADER_SUB_BUFFER};
+pub use crate::p2p::{GossipsubTuning, HeaderExError, IpVersion, P2pError, TransportConfig};
 pub use crate::peer_tracker::PeerTrackerInfo;
-pub use crate::syncer::{SyncerError, SyncingInfo};
+pub use crate::pruner::PruningBacklog;
+pub use crate::syncer::{ReorgInfo, SyncProgressDelta, SyncerError, SyncingInfo};
 
 /// Alias of [`Result`] with [`NodeError`] error type
 ///
@@ -68,6 +77,17 @@ pub enum NodeError {
     /// An error propagated from the `Daser` component.
     #[error("Daser: {0}")]
     Daser(#[from] DaserError),
+
+    /// The operation was refused, because the network was compromised.
+    ///
+    /// See [`Node::is_network_compromised`].
+    #[error("Network is compromised")]
+    NetworkCompromised,
+
+    /// The operation needs sampling, which was disabled with
+    /// [`NodeBuilder::disable_sampling`].
+    #[error("Sampling is disabled")]
+    SamplingDisabled,
 }
 
 struct NodeConfig<B, S>
@@ -80,10 +100,20 @@ where
     pub(crate) network_id: String,
     pub(crate) p2p_local_keypair: Keypair,
     pub(crate) p2p_bootnodes: Vec<Multiaddr>,
+    pub(crate) p2p_trusted_peers: Vec<PeerId>,
     pub(crate) p2p_listen_on: Vec<Multiaddr>,
+    pub(crate) p2p_dns_servers: Vec<SocketAddr>,
+    pub(crate) p2p_gossipsub_tuning: GossipsubTuning,
+    pub(crate) p2p_transports: TransportConfig,
+    pub(crate) p2p_idle_connection_timeout: Duration,
+    pub(crate) p2p_ping_interval: Duration,
     pub(crate) sync_batch_size: u64,
+    pub(crate) header_sub_buffer: usize,
+    pub(crate) header_sub_overflow: HeaderSubOverflow,
     pub(crate) sampling_window: Duration,
-    pub(crate) pruning_window: Duration,
+    pub(crate) sampling_concurrency: usize,
+    pub(crate) pruning_window: Option<Duration>,
+    pub(crate) sampling_enabled: bool,
 }
 
 /// Celestia node.
@@ -101,6 +131,7 @@ where
     pruner: Option<Arc<Pruner>>,
     tasks_cancellation_token: CancellationToken,
     network_compromised_task: JoinHandle,
+    network_compromised_token: Token,
 }
 
 impl Node<InMemoryBlockstore, InMemoryStore> {
@@ -145,7 +176,13 @@ where
                 network_id: config.network_id,
                 local_keypair: config.p2p_local_keypair,
                 bootnodes: config.p2p_bootnodes,
+                trusted_peers: config.p2p_trusted_peers,
                 listen_on: config.p2p_listen_on,
+                dns_servers: config.p2p_dns_servers,
+                gossipsub_tuning: config.p2p_gossipsub_tuning,
+                transports: config.p2p_transports,
+                idle_connection_timeout: config.p2p_idle_connection_timeout,
+                ping_interval: config.p2p_ping_interval,
                 blockstore: blockstore.clone(),
                 store: store.clone(),
                 event_pub: event_channel.publisher(),
@@ -161,14 +198,21 @@ where
             // We sync only what we need to sample. So syncing_window is
             // the same as sampling_window.
             syncing_window: config.sampling_window,
+            header_sub_buffer: config.header_sub_buffer,
+            header_sub_overflow: config.header_sub_overflow,
         })?);
 
-        let daser = Arc::new(Daser::start(DaserArgs {
-            p2p: p2p.clone(),
-            store: store.clone(),
-            event_pub: event_channel.publisher(),
-            sampling_window: config.sampling_window,
-        })?);
+        let daser = if config.sampling_enabled {
+            Some(Arc::new(Daser::start(DaserArgs {
+                p2p: p2p.clone(),
+                store: store.clone(),
+                event_pub: event_channel.publisher(),
+                sampling_window: config.sampling_window,
+                concurrency_limit: config.sampling_concurrency,
+            })?))
+        } else {
+            None
+        };
 
         let pruner = Arc::new(Pruner::start(PrunerArgs {
             store: store.clone(),
@@ -179,10 +223,11 @@ where
         }));
 
         let tasks_cancellation_token = CancellationToken::new();
+        let network_compromised_token = p2p.get_network_compromised_token().await?;
 
         // spawn the task that will stop the services when the fraud is detected
         let network_compromised_task = spawn_cancellable(tasks_cancellation_token.child_token(), {
-            let network_compromised_token = p2p.get_network_compromised_token().await?;
+            let network_compromised_token = network_compromised_token.clone();
             let syncer = syncer.clone();
             let daser = daser.clone();
             let pruner = pruner.clone();
@@ -193,7 +238,9 @@ where
 
                 // Network compromised! Stop workers.
                 syncer.stop();
-                daser.stop();
+                if let Some(daser) = &daser {
+                    daser.stop();
+                }
                 pruner.stop();
 
                 event_pub.send(NodeEvent::NetworkCompromised);
@@ -209,10 +256,11 @@ where
             blockstore: Some(blockstore),
             store: Some(store),
             syncer: Some(syncer),
-            daser: Some(daser),
+            daser,
             pruner: Some(pruner),
             tasks_cancellation_token,
             network_compromised_task,
+            network_compromised_token,
         };
 
         Ok((node, event_sub))
@@ -221,7 +269,8 @@ where
     /// Stop the node.
     pub async fn stop(mut self) {
         {
-            let daser = self.daser.take().expect("Daser not initialized");
+            // Daser isn't started if sampling is disabled
+            let daser = self.daser.take();
             let syncer = self.syncer.take().expect("Syncer not initialized");
             let pruner = self.pruner.take().expect("Pruner not initialized");
             let p2p = self.p2p.take().expect("P2p not initialized");
@@ -231,11 +280,15 @@ where
             self.network_compromised_task.join().await;
 
             // Stop all components that use P2p.
-            daser.stop();
+            if let Some(daser) = &daser {
+                daser.stop();
+            }
             syncer.stop();
             pruner.stop();
 
-            daser.join().await;
+            if let Some(daser) = daser {
+                daser.join().await;
+            }
             syncer.join().await;
             pruner.join().await;
 
@@ -265,14 +318,42 @@ where
         self.syncer.as_ref().expect("Syncer not initialized")
     }
 
+    fn daser(&self) -> Result<&Daser> {
+        self.daser.as_deref().ok_or(NodeError::SamplingDisabled)
+    }
+
     fn p2p(&self) -> &P2p {
         self.p2p.as_ref().expect("P2p not initialized")
     }
 
+    fn pruner(&self) -> &Pruner {
+        self.pruner.as_ref().expect("Pruner not initialized")
+    }
+
     fn store(&self) -> &S {
         self.store.as_ref().expect("Store not initialized")
     }
 
+    /// Fails if the network is compromised.
+    fn ensure_writable(&self) -> Result<()> {
+        if self.is_network_compromised() {
+            Err(NodeError::NetworkCompromised)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns `true` if the node received a valid fraud proof, which means that the
+    /// network it follows is compromised.
+    ///
+    /// This state is latched. Once it is reached, syncing, sampling and pruning are
+    /// stopped for the lifetime of the node and [`NodeEvent::NetworkCompromised`] is
+    /// emitted. Any operations that could write new data to the node's stores are
+    /// refused with [`NodeError::NetworkCompromised`].
+    pub fn is_network_compromised(&self) -> bool {
+        self.network_compromised_token.is_triggered()
+    }
+
     /// Returns a new `EventSubscriber`.
     pub fn event_subscriber(&self) -> EventSubscriber {
         self.event_channel.subscribe()
@@ -288,6 +369,28 @@ where
         self.p2p().peer_tracker_info().clone()
     }
 
+    /// Check whether the node has a verified head and was connected to a peer supporting
+    /// the protocols needed to serve the queries, see [`NodeEvent::ReadyToServe`].
+    pub fn is_ready(&self) -> bool {
+        self.p2p().is_ready()
+    }
+
+    /// Get current [`BandwidthStats`].
+    ///
+    /// Counters start from zero when the node starts. Rates are computed over
+    /// the latest second, independently of the calls.
+    pub fn bandwidth_stats(&self) -> BandwidthStats {
+        self.p2p().bandwidth_stats()
+    }
+
+    /// Get current [`PruningBacklog`].
+    ///
+    /// Updated as the pruner progresses, in step with the
+    /// [`NodeEvent::PrunedHeaders`] events.
+    pub fn pruning_backlog(&self) -> PruningBacklog {
+        self.pruner().backlog()
+    }
+
     /// Get [`PeerTrackerInfo`] watcher.
     pub fn peer_tracker_info_watcher(&self) -> watch::Receiver<PeerTrackerInfo> {
         self.p2p().peer_tracker_info_watcher()
@@ -318,6 +421,32 @@ where
         Ok(self.p2p().connected_peers().await?)
     }
 
+    /// Get all the connected peers which advertised support of the protocol, e.g.
+    /// the one used for sampling.
+    pub async fn peers_supporting(&self, protocol: &str) -> Result<Vec<PeerId>> {
+        Ok(self.p2p().peers_supporting(protocol).await?)
+    }
+
+    /// Get the gossipsub scores of the peers, which decide e.g. if the peer is pruned from the mesh.
+    ///
+    /// Returns an empty list if peer scoring isn't enabled.
+    pub async fn peer_scores(&self) -> Result<Vec<(PeerId, f64)>> {
+        Ok(self.p2p().peer_scores().await?)
+    }
+
+    /// Get the round-trip times of the last pings of the connected peers.
+    ///
+    /// Peers which weren't pinged successfully yet are omitted.
+    pub async fn peer_latencies(&self) -> Result<Vec<(PeerId, Duration)>> {
+        Ok(self.p2p().peer_latencies().await?)
+    }
+
+    /// Resolve the `/dnsaddr/` names of the bootnodes again, e.g. after their DNS records
+    /// changed, and dial the addresses they point to now. Returns the resolved addresses.
+    pub async fn refresh_bootnodes(&self) -> Result<Vec<Multiaddr>> {
+        Ok(self.p2p().refresh_bootnodes().await?)
+    }
+
     /// Trust or untrust the peer with a given ID.
     pub async fn set_peer_trust(&self, peer_id: PeerId, is_trusted: bool) -> Result<()> {
         Ok(self.p2p().set_peer_trust(peer_id, is_trusted).await?)
@@ -418,11 +547,82 @@ where
         Ok(self.syncer().info().await?)
     }
 
+    /// Get the ranges of headers which are currently being fetched from the network.
+    pub async fn in_flight_header_fetches(&self) -> Result<Vec<BlockRange>> {
+        Ok(self.syncer().in_flight_fetches().await?)
+    }
+
+    /// Get the last header received for an already synchronised height, which conflicted
+    /// with the stored one.
+    pub async fn last_reorg(&self) -> Result<Option<ReorgInfo>> {
+        Ok(self.syncer().last_reorg().await?)
+    }
+
+    /// Pause the synchronization of headers, keeping the node connected to the network.
+    ///
+    /// The ongoing batch of headers is cancelled and no new headers are requested
+    /// until [`Node::resume_syncing`] is called.
+    pub async fn pause_syncing(&self) -> Result<()> {
+        Ok(self.syncer().set_paused(true).await?)
+    }
+
+    /// Resume the synchronization of headers paused with [`Node::pause_syncing`].
+    pub async fn resume_syncing(&self) -> Result<()> {
+        Ok(self.syncer().set_paused(false).await?)
+    }
+
+    /// Returns `true` if the synchronization of headers is paused.
+    pub async fn is_syncing_paused(&self) -> Result<bool> {
+        Ok(self.syncer().is_paused().await?)
+    }
+
     /// Get the latest header announced in the network.
     pub async fn get_network_head_header(&self) -> Result<Option<ExtendedHeader>> {
         Ok(self.p2p().get_network_head().await?)
     }
 
+    /// Make all the writes to the [`Store`] done so far durable, without stopping the node.
+    ///
+    /// Useful before the process may be killed abruptly, e.g. when a mobile app goes
+    /// to the background.
+    pub async fn flush_store(&self) -> Result<()> {
+        Ok(self.store().flush().await?)
+    }
+
+    /// Get the [`LatencyStats`] of the recent write transactions of the [`Store`], e.g. to
+    /// detect the storage of a slow device stalling the syncing.
+    ///
+    /// Returns `None` if the store doesn't measure them.
+    pub async fn storage_write_latency(&self) -> Option<LatencyStats> {
+        self.store().write_latency_stats().await
+    }
+
+    /// Verify the header and insert it into the [`Store`], e.g. to adopt a header
+    /// obtained from a trusted source out of band.
+    ///
+    /// Header above the local head becomes the new head, after being verified against
+    /// the current one. Any other header must be adjacent to the stored ones and is
+    /// verified against its neighbours.
+    pub async fn insert_header(&self, header: ExtendedHeader) -> Result<()> {
+        self.ensure_writable()?;
+
+        header
+            .validate()
+            .map_err(|e| StoreInsertionError::HeadersVerificationFailed(e.to_string()))
+            .map_err(StoreError::from)?;
+
+        match self.store().get_head().await {
+            Ok(head) if header.height() > head.height() => head
+                .verify(&header)
+                .map_err(|e| StoreInsertionError::NeighborsVerificationFailed(e.to_string()))
+                .map_err(StoreError::from)?,
+            Ok(_) | Err(StoreError::NotFound) => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        Ok(self.store().insert(header).await?)
+    }
+
     /// Get the latest locally synced header.
     pub async fn get_local_head_header(&self) -> Result<ExtendedHeader> {
         Ok(self.store().get_head().await?)
@@ -438,6 +638,12 @@ where
         Ok(self.store().get_by_height(height).await?)
     }
 
+    /// Wait until a header for the block with a given height is synced and return it.
+    pub async fn wait_header_by_height(&self, height: u64) -> Result<ExtendedHeader> {
+        self.store().wait_height(height).await?;
+        Ok(self.store().get_by_height(height).await?)
+    }
+
     /// Get synced headers from the given heights range.
     ///
     /// If start of the range is unbounded, the first returned header will be of height 1.
@@ -465,6 +671,36 @@ where
             Err(e) => Err(e.into()),
         }
     }
+
+    /// Pin the height, so that the pruner keeps its header even once it leaves the
+    /// pruning window.
+    ///
+    /// Pins are kept in the store, so they persist across restarts of persistent stores.
+    pub async fn pin_height(&self, height: u64) -> Result<()> {
+        Ok(self.store().pin_height(height).await?)
+    }
+
+    /// Unpin the height, allowing its header to be pruned again.
+    pub async fn unpin_height(&self, height: u64) -> Result<()> {
+        Ok(self.store().unpin_height(height).await?)
+    }
+
+    /// Get the ranges of the pinned heights.
+    pub async fn get_pinned_heights(&self) -> Result<BlockRanges> {
+        Ok(self.store().get_pinned_ranges().await?)
+    }
+
+    /// Sample the blocks of the range and wait until all of them are sampled.
+    ///
+    /// Blocks which already have the result of sampling are skipped, unless `force` is set.
+    /// The results can be read with [`Node::get_sampling_metadata`] afterwards.
+    ///
+    /// Sampling needs connected peers, so this waits until some are connected. Fails with
+    /// [`NodeError::SamplingDisabled`] if the node was built without sampling.
+    pub async fn sample_range(&self, range: BlockRange, force: bool) -> Result<()> {
+        self.ensure_writable()?;
+        Ok(self.daser()?.sample_range(range, force).await?)
+    }
 }
 
 impl<B, S> Drop for Node<B, S>
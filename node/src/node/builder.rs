@@ -1,15 +1,19 @@
 use std::any::TypeId;
+use std::net::SocketAddr;
 use std::time::Duration;
 
 use blockstore::Blockstore;
 use libp2p::identity::Keypair;
-use libp2p::Multiaddr;
+use libp2p::{Multiaddr, PeerId};
 use tracing::{info, warn};
 
 use crate::blockstore::InMemoryBlockstore;
 use crate::events::EventSubscriber;
 use crate::network::Network;
-use crate::node::{Node, NodeConfig, Result};
+use crate::node::{
+    GossipsubTuning, HeaderSubOverflow, Node, NodeConfig, Result, TransportConfig,
+    DEFAULT_HEADER_SUB_BUFFER,
+};
 use crate::store::{InMemoryStore, Store};
 
 const HOUR: u64 = 60 * 60;
@@ -25,6 +29,20 @@ pub const DEFAULT_PRUNING_DELAY: Duration = Duration::from_secs(HOUR);
 /// Minimum pruning delay that can be used in [`NodeBuilder`].
 pub const MIN_PRUNING_DELAY: Duration = Duration::from_secs(60);
 
+/// Default time a connection without any active streams is kept open.
+// TODO: Refactor code to avoid being idle. This can be done by preloading a
+// handler. This is how they fixed Kademlia:
+// https://github.com/libp2p/rust-libp2p/pull/4675/files
+pub const DEFAULT_IDLE_CONNECTION_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Default interval between the pings of each connected peer.
+pub const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Default maximum number of blocks [`Node`] samples concurrently, besides the newest head.
+pub const DEFAULT_SAMPLING_CONCURRENCY: usize = 1;
+/// Maximum sampling concurrency that can be used in [`NodeBuilder`].
+pub const MAX_SAMPLING_CONCURRENCY: usize = 16;
+
 /// [`Node`] builder.
 pub struct NodeBuilder<B, S>
 where
@@ -36,10 +54,21 @@ where
     keypair: Option<Keypair>,
     network: Option<Network>,
     bootnodes: Vec<Multiaddr>,
+    trusted_peers: Vec<PeerId>,
     listen: Vec<Multiaddr>,
+    dns_servers: Vec<SocketAddr>,
+    gossipsub_tuning: Option<GossipsubTuning>,
+    transports: Option<TransportConfig>,
+    idle_connection_timeout: Option<Duration>,
+    ping_interval: Option<Duration>,
     sync_batch_size: Option<u64>,
+    header_sub_buffer: Option<usize>,
+    header_sub_overflow: Option<HeaderSubOverflow>,
     sampling_window: Option<Duration>,
+    sampling_concurrency: Option<usize>,
     pruning_delay: Option<Duration>,
+    pruning_disabled: bool,
+    sampling_disabled: bool,
 }
 
 /// Representation of all the errors that can occur when interacting with the [`NodeBuilder`].
@@ -56,6 +85,30 @@ pub enum NodeBuilderError {
     /// Pruning delay is smaller than [`MIN_PRUNING_DELAY`].
     #[error("Pruning delay is {0:?} but cannot be smaller than {MIN_PRUNING_DELAY:?}")]
     PruningDelayTooSmall(Duration),
+
+    /// Header-sub buffer has zero size.
+    #[error("Header-sub buffer cannot be empty")]
+    HeaderSubBufferEmpty,
+
+    /// Sampling concurrency is zero.
+    #[error("Sampling concurrency cannot be zero")]
+    SamplingConcurrencyZero,
+
+    /// Gossipsub tuning is inconsistent.
+    #[error("Invalid gossipsub tuning: {0}")]
+    InvalidGossipsubTuning(String),
+
+    /// All the transports are disabled.
+    #[error("At least one transport must be enabled")]
+    NoTransports,
+
+    /// Idle connection timeout is zero.
+    #[error("Idle connection timeout cannot be zero")]
+    IdleConnectionTimeoutZero,
+
+    /// Ping interval is zero.
+    #[error("Ping interval cannot be zero")]
+    PingIntervalZero,
 }
 
 impl NodeBuilder<InMemoryBlockstore, InMemoryStore> {
@@ -85,10 +138,21 @@ impl NodeBuilder<InMemoryBlockstore, InMemoryStore> {
             keypair: None,
             network: None,
             bootnodes: Vec::new(),
+            trusted_peers: Vec::new(),
             listen: Vec::new(),
+            dns_servers: Vec::new(),
+            gossipsub_tuning: None,
+            transports: None,
+            idle_connection_timeout: None,
+            ping_interval: None,
             sync_batch_size: None,
+            header_sub_buffer: None,
+            header_sub_overflow: None,
             sampling_window: None,
+            sampling_concurrency: None,
             pruning_delay: None,
+            pruning_disabled: false,
+            sampling_disabled: false,
         }
     }
 }
@@ -132,10 +196,21 @@ where
             keypair: self.keypair,
             network: self.network,
             bootnodes: self.bootnodes,
+            trusted_peers: self.trusted_peers,
             listen: self.listen,
+            dns_servers: self.dns_servers,
+            gossipsub_tuning: self.gossipsub_tuning,
+            transports: self.transports,
+            idle_connection_timeout: self.idle_connection_timeout,
+            ping_interval: self.ping_interval,
             sync_batch_size: self.sync_batch_size,
+            header_sub_buffer: self.header_sub_buffer,
+            header_sub_overflow: self.header_sub_overflow,
             sampling_window: self.sampling_window,
+            sampling_concurrency: self.sampling_concurrency,
             pruning_delay: self.pruning_delay,
+            pruning_disabled: self.pruning_disabled,
+            sampling_disabled: self.sampling_disabled,
         }
     }
 
@@ -152,10 +227,21 @@ where
             keypair: self.keypair,
             network: self.network,
             bootnodes: self.bootnodes,
+            trusted_peers: self.trusted_peers,
             listen: self.listen,
+            dns_servers: self.dns_servers,
+            gossipsub_tuning: self.gossipsub_tuning,
+            transports: self.transports,
+            idle_connection_timeout: self.idle_connection_timeout,
+            ping_interval: self.ping_interval,
             sync_batch_size: self.sync_batch_size,
+            header_sub_buffer: self.header_sub_buffer,
+            header_sub_overflow: self.header_sub_overflow,
             sampling_window: self.sampling_window,
+            sampling_concurrency: self.sampling_concurrency,
             pruning_delay: self.pruning_delay,
+            pruning_disabled: self.pruning_disabled,
+            sampling_disabled: self.sampling_disabled,
         }
     }
 
@@ -190,6 +276,20 @@ where
         }
     }
 
+    /// Set the peers which [`Node`] will trust, besides the bootnodes.
+    ///
+    /// Unlike the bootnodes, they aren't dialed on start. They are trusted once
+    /// connected, e.g. after being discovered.
+    pub fn trusted_peers<I>(self, peers: I) -> Self
+    where
+        I: IntoIterator<Item = PeerId>,
+    {
+        NodeBuilder {
+            trusted_peers: peers.into_iter().collect(),
+            ..self
+        }
+    }
+
     /// Set the addresses where [`Node`] will listen for incoming connections.
     pub fn listen<I>(self, addrs: I) -> Self
     where
@@ -201,6 +301,62 @@ where
         }
     }
 
+    /// Set the DNS servers used for resolving `/dns*` and `/dnsaddr` addresses,
+    /// e.g. of the bootnodes. Not used in browsers, which always resolve names themselves.
+    ///
+    /// **Default:** Cloudflare public DNS servers
+    pub fn dns_servers<I>(self, addrs: I) -> Self
+    where
+        I: IntoIterator<Item = SocketAddr>,
+    {
+        NodeBuilder {
+            dns_servers: addrs.into_iter().collect(),
+            ..self
+        }
+    }
+
+    /// Tuning of the gossipsub mesh, which new headers are received over.
+    ///
+    /// **Default:** [`GossipsubTuning::default`]
+    pub fn gossipsub_tuning(self, tuning: GossipsubTuning) -> Self {
+        NodeBuilder {
+            gossipsub_tuning: Some(tuning),
+            ..self
+        }
+    }
+
+    /// Transports used to connect to the peers, e.g. to avoid QUIC on networks blocking it.
+    ///
+    /// **Default:** [`TransportConfig::default`]
+    pub fn transports(self, transports: TransportConfig) -> Self {
+        NodeBuilder {
+            transports: Some(transports),
+            ..self
+        }
+    }
+
+    /// How long a connection without any active streams is kept open, e.g. shorter
+    /// to save the battery of mobile devices.
+    ///
+    /// **Default:** [`DEFAULT_IDLE_CONNECTION_TIMEOUT`]
+    pub fn idle_connection_timeout(self, timeout: Duration) -> Self {
+        NodeBuilder {
+            idle_connection_timeout: Some(timeout),
+            ..self
+        }
+    }
+
+    /// Interval between the pings of each connected peer, e.g. shorter to keep the NAT
+    /// mappings of mobile devices from expiring.
+    ///
+    /// **Default:** [`DEFAULT_PING_INTERVAL`]
+    pub fn ping_interval(self, interval: Duration) -> Self {
+        NodeBuilder {
+            ping_interval: Some(interval),
+            ..self
+        }
+    }
+
     /// Maximum number of headers in batch while syncing.
     ///
     /// **Default:** 512
@@ -211,6 +367,27 @@ where
         }
     }
 
+    /// Maximum number of headers received by header-sub buffered until they are processed.
+    ///
+    /// **Default:** [`DEFAULT_HEADER_SUB_BUFFER`]\
+    /// **Minimum:** 1
+    pub fn header_sub_buffer(self, size: usize) -> Self {
+        NodeBuilder {
+            header_sub_buffer: Some(size),
+            ..self
+        }
+    }
+
+    /// What to do with headers received by header-sub when the buffer is full.
+    ///
+    /// **Default:** [`HeaderSubOverflow::DropOldest`]
+    pub fn header_sub_overflow(self, policy: HeaderSubOverflow) -> Self {
+        NodeBuilder {
+            header_sub_overflow: Some(policy),
+            ..self
+        }
+    }
+
     /// Set sampling window.
     ///
     /// Sampling window defines maximum age of a block considered for syncing and sampling.
@@ -225,6 +402,21 @@ where
         }
     }
 
+    /// Set maximum number of blocks sampled concurrently.
+    ///
+    /// The newest head is always sampled immediately, on top of this limit. Higher values
+    /// catch up with the sampling window faster, at the cost of more bandwidth and CPU.
+    /// Values above [`MAX_SAMPLING_CONCURRENCY`] are capped to it.
+    ///
+    /// **Default:** [`DEFAULT_SAMPLING_CONCURRENCY`]\
+    /// **Minimum:** 1
+    pub fn sampling_concurrency(self, limit: usize) -> Self {
+        NodeBuilder {
+            sampling_concurrency: Some(limit),
+            ..self
+        }
+    }
+
     /// Set pruning delay.
     ///
     /// Pruning delay defines how much time the pruner should wait after sampling window in
@@ -240,6 +432,28 @@ where
         }
     }
 
+    /// Disable pruning, keeping all the headers and samples of the blocks the node has
+    /// stored, even after they leave the sampling window.
+    ///
+    /// The node still syncs and samples only the blocks within the sampling window.
+    pub fn disable_pruning(self) -> Self {
+        NodeBuilder {
+            pruning_disabled: true,
+            ..self
+        }
+    }
+
+    /// Disable data availability sampling, e.g. when only the headers are needed.
+    ///
+    /// The node still syncs the headers within the sampling window, but doesn't sample
+    /// their blocks, so [`Node::sample_range`] fails with [`NodeError::SamplingDisabled`].
+    pub fn disable_sampling(self) -> Self {
+        NodeBuilder {
+            sampling_disabled: true,
+            ..self
+        }
+    }
+
     fn build_config(self) -> Result<NodeConfig<B, S>, NodeBuilderError> {
         let network = self.network.ok_or(NodeBuilderError::NetworkNotSpecified)?;
 
@@ -289,9 +503,77 @@ where
             return Err(NodeBuilderError::PruningDelayTooSmall(pruning_delay));
         }
 
-        let pruning_window = sampling_window.saturating_add(pruning_delay);
+        let header_sub_buffer = self.header_sub_buffer.unwrap_or(DEFAULT_HEADER_SUB_BUFFER);
+
+        if header_sub_buffer == 0 {
+            return Err(NodeBuilderError::HeaderSubBufferEmpty);
+        }
+
+        let sampling_concurrency = self
+            .sampling_concurrency
+            .unwrap_or(DEFAULT_SAMPLING_CONCURRENCY);
+
+        if sampling_concurrency == 0 {
+            return Err(NodeBuilderError::SamplingConcurrencyZero);
+        }
+
+        if sampling_concurrency > MAX_SAMPLING_CONCURRENCY {
+            warn!(
+                "Sampling concurrency {sampling_concurrency} capped to {MAX_SAMPLING_CONCURRENCY}"
+            );
+        }
+
+        let gossipsub_tuning = self.gossipsub_tuning.unwrap_or_default();
+
+        if gossipsub_tuning.mesh_n_low == 0 {
+            return Err(NodeBuilderError::InvalidGossipsubTuning(
+                "mesh_n_low cannot be zero".to_owned(),
+            ));
+        }
+
+        if gossipsub_tuning.mesh_n_low > gossipsub_tuning.mesh_n
+            || gossipsub_tuning.mesh_n > gossipsub_tuning.mesh_n_high
+        {
+            return Err(NodeBuilderError::InvalidGossipsubTuning(format!(
+                "expected mesh_n_low ({}) <= mesh_n ({}) <= mesh_n_high ({})",
+                gossipsub_tuning.mesh_n_low, gossipsub_tuning.mesh_n, gossipsub_tuning.mesh_n_high
+            )));
+        }
+
+        if gossipsub_tuning.heartbeat_interval.is_zero() {
+            return Err(NodeBuilderError::InvalidGossipsubTuning(
+                "heartbeat_interval cannot be zero".to_owned(),
+            ));
+        }
+
+        let transports = self.transports.unwrap_or_default();
+
+        if !transports.tcp && !transports.quic && !transports.websocket {
+            return Err(NodeBuilderError::NoTransports);
+        }
+
+        let idle_connection_timeout = self
+            .idle_connection_timeout
+            .unwrap_or(DEFAULT_IDLE_CONNECTION_TIMEOUT);
+
+        if idle_connection_timeout.is_zero() {
+            return Err(NodeBuilderError::IdleConnectionTimeoutZero);
+        }
+
+        let ping_interval = self.ping_interval.unwrap_or(DEFAULT_PING_INTERVAL);
+
+        if ping_interval.is_zero() {
+            return Err(NodeBuilderError::PingIntervalZero);
+        }
 
-        info!("Sampling window: {sampling_window:?}, Pruning window: {pruning_window:?}",);
+        let pruning_window = if self.pruning_disabled {
+            info!("Sampling window: {sampling_window:?}, Pruning disabled");
+            None
+        } else {
+            let pruning_window = sampling_window.saturating_add(pruning_delay);
+            info!("Sampling window: {sampling_window:?}, Pruning window: {pruning_window:?}",);
+            Some(pruning_window)
+        };
 
         Ok(NodeConfig {
             blockstore: self.blockstore,
@@ -299,10 +581,250 @@ where
             network_id: network.id().to_owned(),
             p2p_local_keypair: self.keypair.unwrap_or_else(Keypair::generate_ed25519),
             p2p_bootnodes: bootnodes,
+            p2p_trusted_peers: self.trusted_peers,
             p2p_listen_on: self.listen,
+            p2p_dns_servers: self.dns_servers,
+            p2p_gossipsub_tuning: gossipsub_tuning,
+            p2p_transports: transports,
+            p2p_idle_connection_timeout: idle_connection_timeout,
+            p2p_ping_interval: ping_interval,
             sync_batch_size: self.sync_batch_size.unwrap_or(512),
+            header_sub_buffer,
+            header_sub_overflow: self.header_sub_overflow.unwrap_or_default(),
             sampling_window,
+            sampling_concurrency: sampling_concurrency.min(MAX_SAMPLING_CONCURRENCY),
             pruning_window,
+            sampling_enabled: !self.sampling_disabled,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_sub_config() {
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .build_config()
+            .unwrap();
+        assert_eq!(config.header_sub_buffer, DEFAULT_HEADER_SUB_BUFFER);
+        assert_eq!(config.header_sub_overflow, HeaderSubOverflow::DropOldest);
+
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .header_sub_buffer(256)
+            .header_sub_overflow(HeaderSubOverflow::Block)
+            .build_config()
+            .unwrap();
+        assert_eq!(config.header_sub_buffer, 256);
+        assert_eq!(config.header_sub_overflow, HeaderSubOverflow::Block);
+
+        let res = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .header_sub_buffer(0)
+            .build_config();
+        assert!(matches!(res, Err(NodeBuilderError::HeaderSubBufferEmpty)));
+    }
+
+    #[test]
+    fn gossipsub_tuning_config() {
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .build_config()
+            .unwrap();
+        assert_eq!(config.p2p_gossipsub_tuning, GossipsubTuning::default());
+
+        let tuning = GossipsubTuning {
+            mesh_n: 4,
+            mesh_n_low: 4,
+            mesh_n_high: 8,
+            heartbeat_interval: Duration::from_millis(700),
+            peer_scoring: true,
+        };
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .gossipsub_tuning(tuning)
+            .build_config()
+            .unwrap();
+        assert_eq!(config.p2p_gossipsub_tuning, tuning);
+
+        for invalid in [
+            GossipsubTuning {
+                mesh_n_low: 7,
+                ..tuning
+            },
+            GossipsubTuning {
+                mesh_n_high: 3,
+                ..tuning
+            },
+            GossipsubTuning {
+                mesh_n: 0,
+                mesh_n_low: 0,
+                ..tuning
+            },
+            GossipsubTuning {
+                heartbeat_interval: Duration::ZERO,
+                ..tuning
+            },
+        ] {
+            let res = NodeBuilder::new()
+                .network(Network::custom("private").unwrap())
+                .gossipsub_tuning(invalid)
+                .build_config();
+            assert!(
+                matches!(res, Err(NodeBuilderError::InvalidGossipsubTuning(_))),
+                "{invalid:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn transports_config() {
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .build_config()
+            .unwrap();
+        assert_eq!(config.p2p_transports, TransportConfig::default());
+
+        let transports = TransportConfig {
+            quic: false,
+            ..TransportConfig::default()
+        };
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .transports(transports)
+            .build_config()
+            .unwrap();
+        assert_eq!(config.p2p_transports, transports);
+
+        let res = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .transports(TransportConfig {
+                tcp: false,
+                quic: false,
+                websocket: false,
+                ..TransportConfig::default()
+            })
+            .build_config();
+        assert!(matches!(res, Err(NodeBuilderError::NoTransports)));
+    }
+
+    #[test]
+    fn idle_connection_timeout_config() {
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .build_config()
+            .unwrap();
+        assert_eq!(
+            config.p2p_idle_connection_timeout,
+            DEFAULT_IDLE_CONNECTION_TIMEOUT
+        );
+
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .idle_connection_timeout(Duration::from_secs(5))
+            .build_config()
+            .unwrap();
+        assert_eq!(config.p2p_idle_connection_timeout, Duration::from_secs(5));
+
+        let res = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .idle_connection_timeout(Duration::ZERO)
+            .build_config();
+        assert!(matches!(
+            res,
+            Err(NodeBuilderError::IdleConnectionTimeoutZero)
+        ));
+    }
+
+    #[test]
+    fn ping_interval_config() {
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .build_config()
+            .unwrap();
+        assert_eq!(config.p2p_ping_interval, DEFAULT_PING_INTERVAL);
+
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .ping_interval(Duration::from_secs(5))
+            .build_config()
+            .unwrap();
+        assert_eq!(config.p2p_ping_interval, Duration::from_secs(5));
+
+        let res = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .ping_interval(Duration::ZERO)
+            .build_config();
+        assert!(matches!(res, Err(NodeBuilderError::PingIntervalZero)));
+    }
+
+    #[test]
+    fn sampling_concurrency_config() {
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .build_config()
+            .unwrap();
+        assert_eq!(config.sampling_concurrency, DEFAULT_SAMPLING_CONCURRENCY);
+
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .sampling_concurrency(4)
+            .build_config()
+            .unwrap();
+        assert_eq!(config.sampling_concurrency, 4);
+
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .sampling_concurrency(1000)
+            .build_config()
+            .unwrap();
+        assert_eq!(config.sampling_concurrency, MAX_SAMPLING_CONCURRENCY);
+
+        let res = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .sampling_concurrency(0)
+            .build_config();
+        assert!(matches!(
+            res,
+            Err(NodeBuilderError::SamplingConcurrencyZero)
+        ));
+    }
+
+    #[test]
+    fn pruning_config() {
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .sampling_window(Duration::from_secs(HOUR))
+            .pruning_delay(Duration::from_secs(HOUR))
+            .build_config()
+            .unwrap();
+        assert_eq!(config.pruning_window, Some(Duration::from_secs(2 * HOUR)));
+
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .sampling_window(Duration::from_secs(HOUR))
+            .disable_pruning()
+            .build_config()
+            .unwrap();
+        assert_eq!(config.pruning_window, None);
+    }
+
+    #[test]
+    fn sampling_config() {
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .build_config()
+            .unwrap();
+        assert!(config.sampling_enabled);
+
+        let config = NodeBuilder::new()
+            .network(Network::custom("private").unwrap())
+            .disable_sampling()
+            .build_config()
+            .unwrap();
+        assert!(!config.sampling_enabled);
+    }
+}
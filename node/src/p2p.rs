@@ -15,6 +15,7 @@
 
 use std::collections::HashMap;
 use std::future::poll_fn;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::task::Poll;
 use std::time::Duration;
@@ -43,7 +44,7 @@ use libp2p::{
     ping,
     swarm::{
         dial_opts::{DialOpts, PeerCondition},
-        ConnectionId, DialError, NetworkBehaviour, NetworkInfo, Swarm, SwarmEvent,
+        ConnectionError, ConnectionId, DialError, NetworkBehaviour, NetworkInfo, Swarm, SwarmEvent,
     },
     Multiaddr, PeerId,
 };
@@ -54,24 +55,28 @@ use tokio::sync::{mpsc, oneshot, watch};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, instrument, trace, warn};
 
+pub(crate) mod bandwidth;
 mod connection_control;
 mod header_ex;
 pub(crate) mod header_session;
+pub(crate) mod header_sub;
 pub(crate) mod shwap;
 mod swarm;
 
 use crate::block_ranges::BlockRange;
 use crate::events::{EventPublisher, NodeEvent};
 use crate::executor::{self, spawn, Interval, JoinHandle};
-use crate::p2p::header_ex::{HeaderExBehaviour, HeaderExConfig};
+use crate::p2p::bandwidth::{BandwidthMeter, BandwidthStats};
+use crate::p2p::header_ex::{HeaderExBehaviour, HeaderExConfig, HEADER_EX_PROTOCOL};
 use crate::p2p::header_session::HeaderSession;
+use crate::p2p::header_sub::HeaderSubSender;
 use crate::p2p::shwap::{convert_cid, get_block_container, ShwapMultihasher};
-use crate::p2p::swarm::new_swarm;
+use crate::p2p::swarm::{new_swarm, resolve_bootnodes};
 use crate::peer_tracker::PeerTracker;
 use crate::peer_tracker::PeerTrackerInfo;
 use crate::store::Store;
 use crate::utils::{
-    celestia_protocol_id, fraudsub_ident_topic, gossipsub_ident_topic, MultiaddrExt,
+    celestia_protocol_id, fraudsub_ident_topic, gossipsub_ident_topic, protocol_id, MultiaddrExt,
     OneshotResultSender, OneshotResultSenderExt, OneshotSenderExt, Token,
 };
 
@@ -89,6 +94,18 @@ pub(crate) const MAX_MH_SIZE: usize = 64;
 // will be ignored
 const FRAUD_PROOF_HEAD_HEIGHT_THRESHOLD: u64 = 20;
 
+// Topic on which the new headers are announced, prefixed with the network id.
+const HEADER_SUB_TOPIC: &str = "/header-sub/v0.0.1";
+
+// Protocol of the kademlia DHT, prefixed with the celestia network id.
+const KADEMLIA_PROTOCOL: &str = "/kad/1.0.0";
+
+// Prefix of the bitswap protocol used by shwap, after the celestia network id.
+const SHWAP_PROTOCOL_PREFIX: &str = "shwap";
+
+// Protocol added by beetswap to the shwap prefix.
+const BITSWAP_PROTOCOL: &str = "/ipfs/bitswap/1.2.0";
+
 pub(crate) type Result<T, E = P2pError> = std::result::Result<T, E>;
 
 /// Representation of all the errors that can occur in `P2p` component.
@@ -202,7 +219,94 @@ pub(crate) struct P2p {
     cmd_tx: mpsc::Sender<P2pCmd>,
     join_handle: JoinHandle,
     peer_tracker_info_watcher: watch::Receiver<PeerTrackerInfo>,
+    ready_watcher: watch::Receiver<bool>,
     local_peer_id: PeerId,
+    bandwidth: Arc<BandwidthMeter>,
+}
+
+/// Tuning of the gossipsub mesh, which header-sub and fraud-sub are gossiped over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GossipsubTuning {
+    /// Number of peers in the mesh of a topic the node aims for.
+    pub mesh_n: usize,
+    /// Number of peers in the mesh below which the node grafts more of them.
+    pub mesh_n_low: usize,
+    /// Number of peers in the mesh above which the node prunes some of them.
+    pub mesh_n_high: usize,
+    /// Interval between the maintenances of the mesh.
+    pub heartbeat_interval: Duration,
+    /// Score the peers, pruning the misbehaving ones from the mesh and ignoring their gossip.
+    ///
+    /// Uses the default parameters of the libp2p's peer scoring, without the scores of the
+    /// topics.
+    pub peer_scoring: bool,
+}
+
+impl Default for GossipsubTuning {
+    /// Defaults of the libp2p's gossipsub.
+    fn default() -> Self {
+        GossipsubTuning {
+            mesh_n: 6,
+            mesh_n_low: 5,
+            mesh_n_high: 12,
+            heartbeat_interval: Duration::from_secs(1),
+            peer_scoring: false,
+        }
+    }
+}
+
+/// Transports used to connect to the peers.
+///
+/// In browsers only the transports of the browser are used, regardless of this config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct TransportConfig {
+    /// Connect over TCP.
+    pub tcp: bool,
+    /// Connect over QUIC.
+    pub quic: bool,
+    /// Connect over secure websockets.
+    pub websocket: bool,
+    /// Version of IP the addresses are dialed and listened on with.
+    pub ip_version: IpVersion,
+}
+
+impl Default for TransportConfig {
+    /// All the transports over both IPv4 and IPv6.
+    fn default() -> Self {
+        TransportConfig {
+            tcp: true,
+            quic: true,
+            websocket: true,
+            ip_version: IpVersion::Any,
+        }
+    }
+}
+
+/// Version of IP used by the transports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum IpVersion {
+    /// Both IPv4 and IPv6.
+    #[default]
+    Any,
+    /// Only IPv4.
+    V4,
+    /// Only IPv6.
+    V6,
+}
+
+impl IpVersion {
+    /// Returns `false` if the address is of the other IP version.
+    ///
+    /// Addresses without IP, e.g. DNS names, are always allowed.
+    pub(crate) fn allows(&self, addr: &Multiaddr) -> bool {
+        match addr.iter().next() {
+            Some(Protocol::Ip4(_)) => *self != IpVersion::V6,
+            Some(Protocol::Ip6(_)) => *self != IpVersion::V4,
+            _ => true,
+        }
+    }
 }
 
 /// Arguments used to configure the [`P2p`].
@@ -217,8 +321,20 @@ where
     pub local_keypair: Keypair,
     /// List of bootstrap nodes to connect to and trust.
     pub bootnodes: Vec<Multiaddr>,
+    /// List of the peers to trust, besides the bootnodes.
+    pub trusted_peers: Vec<PeerId>,
     /// List of the addresses on which to listen for incoming connections.
     pub listen_on: Vec<Multiaddr>,
+    /// DNS servers used for resolving addresses, or the default ones if empty.
+    pub dns_servers: Vec<SocketAddr>,
+    /// Tuning of the gossipsub mesh.
+    pub gossipsub_tuning: GossipsubTuning,
+    /// Transports used to connect to the peers.
+    pub transports: TransportConfig,
+    /// How long a connection without any active streams is kept open.
+    pub idle_connection_timeout: Duration,
+    /// Interval between the pings of each connected peer.
+    pub ping_interval: Duration,
     /// The store for headers.
     pub blockstore: Arc<B>,
     /// The store for headers.
@@ -242,10 +358,14 @@ pub(crate) enum P2pCmd {
     ConnectedPeers {
         respond_to: oneshot::Sender<Vec<PeerId>>,
     },
+    PeersSupporting {
+        protocol: String,
+        respond_to: oneshot::Sender<Vec<PeerId>>,
+    },
     InitHeaderSub {
         head: Box<ExtendedHeader>,
         /// Any valid headers received by header-sub will be send to this channel.
-        channel: mpsc::Sender<ExtendedHeader>,
+        channel: HeaderSubSender,
     },
     SetPeerTrust {
         peer_id: PeerId,
@@ -261,6 +381,15 @@ pub(crate) enum P2pCmd {
     GetNetworkHead {
         respond_to: oneshot::Sender<Option<ExtendedHeader>>,
     },
+    PeerScores {
+        respond_to: oneshot::Sender<Vec<(PeerId, f64)>>,
+    },
+    PeerLatencies {
+        respond_to: oneshot::Sender<Vec<(PeerId, Duration)>>,
+    },
+    RefreshBootnodes {
+        respond_to: oneshot::Sender<Vec<Multiaddr>>,
+    },
 }
 
 impl P2p {
@@ -276,12 +405,21 @@ impl P2p {
 
         let peer_tracker = Arc::new(PeerTracker::new(args.event_pub.clone()));
         let peer_tracker_info_watcher = peer_tracker.info_watcher();
+        let (ready_tx, ready_watcher) = watch::channel(false);
 
         let cancellation_token = CancellationToken::new();
         let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let bandwidth = Arc::new(BandwidthMeter::new(Arc::default()));
 
-        let mut worker =
-            Worker::new(args, cancellation_token.child_token(), cmd_rx, peer_tracker).await?;
+        let mut worker = Worker::new(
+            args,
+            cancellation_token.child_token(),
+            cmd_rx,
+            peer_tracker,
+            ready_tx,
+            bandwidth.clone(),
+        )
+        .await?;
 
         let join_handle = spawn(async move {
             worker.run().await;
@@ -292,7 +430,9 @@ impl P2p {
             cmd_tx,
             join_handle,
             peer_tracker_info_watcher,
+            ready_watcher,
             local_peer_id,
+            bandwidth,
         })
     }
 
@@ -311,7 +451,9 @@ impl P2p {
             cancellation_token,
             join_handle,
             peer_tracker_info_watcher: peer_tracker_rx,
+            ready_watcher: watch::channel(false).1,
             local_peer_id: PeerId::random(),
+            bandwidth: Arc::new(BandwidthMeter::new(Arc::default())),
         };
 
         let handle = crate::test_utils::MockP2pHandle {
@@ -357,11 +499,22 @@ impl P2p {
         self.peer_tracker_info_watcher.borrow()
     }
 
+    /// Whether the node has a verified head and was connected to a peer supporting all
+    /// the protocols needed to serve the queries. Stays `true` once that happened.
+    pub fn is_ready(&self) -> bool {
+        *self.ready_watcher.borrow()
+    }
+
+    /// Current [`BandwidthStats`] of the node.
+    pub fn bandwidth_stats(&self) -> BandwidthStats {
+        self.bandwidth.stats()
+    }
+
     /// Initializes `header-sub` protocol with a given `subjective_head`.
     pub async fn init_header_sub(
         &self,
         head: ExtendedHeader,
-        channel: mpsc::Sender<ExtendedHeader>,
+        channel: HeaderSubSender,
     ) -> Result<()> {
         self.send_command(P2pCmd::InitHeaderSub {
             head: Box::new(head),
@@ -616,6 +769,19 @@ impl P2p {
         Ok(rx.await?)
     }
 
+    /// Get the list of connected peers which advertised support of the protocol.
+    pub async fn peers_supporting(&self, protocol: impl Into<String>) -> Result<Vec<PeerId>> {
+        let (tx, rx) = oneshot::channel();
+
+        self.send_command(P2pCmd::PeersSupporting {
+            protocol: protocol.into(),
+            respond_to: tx,
+        })
+        .await?;
+
+        Ok(rx.await?)
+    }
+
     /// Alter the trust status for a given peer.
     pub async fn set_peer_trust(&self, peer_id: PeerId, is_trusted: bool) -> Result<()> {
         self.send_command(P2pCmd::SetPeerTrust {
@@ -625,6 +791,19 @@ impl P2p {
         .await
     }
 
+    /// Resolve the names of the bootnodes again and dial the addresses they point to now,
+    /// returning them.
+    ///
+    /// Bootnodes which fail to resolve are left out, the same as when resolved on startup.
+    pub async fn refresh_bootnodes(&self) -> Result<Vec<Multiaddr>> {
+        let (tx, rx) = oneshot::channel();
+
+        self.send_command(P2pCmd::RefreshBootnodes { respond_to: tx })
+            .await?;
+
+        Ok(rx.await?)
+    }
+
     /// Get the cancellation token which will be cancelled when the network gets compromised.
     ///
     /// After this token is cancelled, the network should be treated as insincere
@@ -647,6 +826,30 @@ impl P2p {
 
         Ok(rx.await?)
     }
+
+    /// Get the gossipsub scores of the peers known to gossipsub.
+    ///
+    /// Returns an empty list if peer scoring isn't enabled.
+    pub async fn peer_scores(&self) -> Result<Vec<(PeerId, f64)>> {
+        let (tx, rx) = oneshot::channel();
+
+        self.send_command(P2pCmd::PeerScores { respond_to: tx })
+            .await?;
+
+        Ok(rx.await?)
+    }
+
+    /// Get the round-trip times of the last pings of the connected peers.
+    ///
+    /// Peers which weren't pinged successfully yet are omitted.
+    pub async fn peer_latencies(&self) -> Result<Vec<(PeerId, Duration)>> {
+        let (tx, rx) = oneshot::channel();
+
+        self.send_command(P2pCmd::PeerLatencies { respond_to: tx })
+            .await?;
+
+        Ok(rx.await?)
+    }
 }
 
 impl Drop for P2p {
@@ -690,11 +893,24 @@ where
     store: Arc<S>,
     event_pub: EventPublisher,
     bootnodes: HashMap<PeerId, Vec<Multiaddr>>,
+    dns_servers: Vec<SocketAddr>,
+    ip_version: IpVersion,
+    /// Whether the bootnodes are being resolved, before dialing them.
+    resolving_bootnodes: bool,
+    resolved_bootnodes_tx: mpsc::Sender<ResolvedBootnodes>,
+    resolved_bootnodes_rx: mpsc::Receiver<ResolvedBootnodes>,
+    /// Protocols which a peer needs to support for the node to be ready to serve.
+    ready_protocols: [String; 2],
+    ready_tx: watch::Sender<bool>,
+    bandwidth: Arc<BandwidthMeter>,
 }
 
+/// Resolved addresses of the bootnodes, with the sender of the refresh which asked for them.
+type ResolvedBootnodes = (Vec<Multiaddr>, Option<oneshot::Sender<Vec<Multiaddr>>>);
+
 struct HeaderSubState {
     known_head: ExtendedHeader,
-    channel: mpsc::Sender<ExtendedHeader>,
+    channel: HeaderSubSender,
 }
 
 impl<B, S> Worker<B, S>
@@ -707,12 +923,14 @@ where
         cancellation_token: CancellationToken,
         cmd_rx: mpsc::Receiver<P2pCmd>,
         peer_tracker: Arc<PeerTracker>,
+        ready_tx: watch::Sender<bool>,
+        bandwidth: Arc<BandwidthMeter>,
     ) -> Result<Self, P2pError> {
         let local_peer_id = PeerId::from(args.local_keypair.public());
 
         let connection_control = connection_control::Behaviour::new();
         let autonat = autonat::Behaviour::new(local_peer_id, autonat::Config::default());
-        let ping = ping::Behaviour::new(ping::Config::default());
+        let ping = ping::Behaviour::new(ping::Config::default().with_interval(args.ping_interval));
 
         let agent_version = format!("lumina/{}/{}", args.network_id, env!("CARGO_PKG_VERSION"));
         let identify = identify::Behaviour::new(
@@ -720,12 +938,13 @@ where
                 .with_agent_version(agent_version),
         );
 
-        let header_sub_topic = gossipsub_ident_topic(&args.network_id, "/header-sub/v0.0.1");
+        let header_sub_topic = gossipsub_ident_topic(&args.network_id, HEADER_SUB_TOPIC);
         let bad_encoding_fraud_sub_topic =
             fraudsub_ident_topic(BadEncodingFraudProof::TYPE, &args.network_id);
         let gossipsub = init_gossipsub(&args, [&header_sub_topic, &bad_encoding_fraud_sub_topic])?;
 
         let kademlia = init_kademlia(&args)?;
+        let ip_version = args.transports.ip_version;
         let bitswap = init_bitswap(
             args.blockstore.clone(),
             args.store.clone(),
@@ -749,7 +968,15 @@ where
             kademlia,
         };
 
-        let mut swarm = new_swarm(args.local_keypair, behaviour).await?;
+        let mut swarm = new_swarm(
+            args.local_keypair,
+            behaviour,
+            &args.dns_servers,
+            bandwidth.counters(),
+            args.transports,
+            args.idle_connection_timeout,
+        )
+        .await?;
         let mut listeners = SmallVec::new();
 
         for addr in args.listen_on {
@@ -775,6 +1002,17 @@ where
             peer_tracker.set_trusted(*peer_id, true);
         }
 
+        for peer_id in args.trusted_peers {
+            peer_tracker.set_trusted(peer_id, true);
+        }
+
+        let (resolved_bootnodes_tx, resolved_bootnodes_rx) = mpsc::channel(1);
+        let shwap = celestia_protocol_id(&args.network_id, SHWAP_PROTOCOL_PREFIX);
+        let ready_protocols = [
+            protocol_id(&args.network_id, HEADER_EX_PROTOCOL).to_string(),
+            format!("{shwap}{BITSWAP_PROTOCOL}"),
+        ];
+
         Ok(Worker {
             cancellation_token,
             cmd_rx,
@@ -789,12 +1027,21 @@ where
             store: args.store,
             event_pub: args.event_pub,
             bootnodes,
+            dns_servers: args.dns_servers,
+            ip_version,
+            resolving_bootnodes: false,
+            resolved_bootnodes_tx,
+            resolved_bootnodes_rx,
+            ready_protocols,
+            ready_tx,
+            bandwidth,
         })
     }
 
     async fn run(&mut self) {
         let mut report_interval = Interval::new(Duration::from_secs(60)).await;
         let mut kademlia_interval = Interval::new(Duration::from_secs(30)).await;
+        let mut bandwidth_interval = Interval::new(Duration::from_secs(1)).await;
         let mut peer_tracker_info_watcher = self.peer_tracker.info_watcher();
 
         // Initiate discovery
@@ -812,6 +1059,9 @@ where
                 _ = report_interval.tick() => {
                     self.report();
                 }
+                _ = bandwidth_interval.tick() => {
+                    self.bandwidth.sample();
+                }
                 _ = kademlia_interval.tick() => {
                     if self.peer_tracker.info().num_connected_peers < MIN_CONNECTED_PEERS
                     {
@@ -831,6 +1081,9 @@ where
                         warn!("Failure while handling command. (error: {e})");
                     }
                 }
+                Some((addrs, respond_to)) = self.resolved_bootnodes_rx.recv() => {
+                    self.on_bootnodes_resolved(addrs, respond_to);
+                }
             }
         }
 
@@ -840,8 +1093,56 @@ where
     fn bootstrap(&mut self) {
         self.event_pub.send(NodeEvent::ConnectingToBootnodes);
 
-        for (peer_id, addrs) in &self.bootnodes {
-            let dial_opts = DialOpts::peer_id(*peer_id)
+        // Names are resolved here, instead of by the transport, to report
+        // which addresses are dialed.
+        if !self.resolving_bootnodes {
+            self.resolving_bootnodes = true;
+            self.resolve_bootnodes(None);
+        }
+
+        // trigger kademlia bootstrap
+        if self.swarm.behaviour_mut().kademlia.bootstrap().is_err() {
+            warn!("Can't run kademlia bootstrap, no known peers");
+        }
+    }
+
+    /// Resolve the bootnodes in the background, handling the addresses once they're resolved.
+    fn resolve_bootnodes(&self, respond_to: Option<oneshot::Sender<Vec<Multiaddr>>>) {
+        let bootnodes = self.bootnodes.values().flatten().cloned().collect();
+        let dns_servers = self.dns_servers.clone();
+        let ip_version = self.ip_version;
+        let tx = self.resolved_bootnodes_tx.clone();
+
+        spawn(async move {
+            let addrs = resolve_bootnodes(bootnodes, &dns_servers, ip_version).await;
+            let _ = tx.send((addrs, respond_to)).await;
+        });
+    }
+
+    fn on_bootnodes_resolved(
+        &mut self,
+        addrs: Vec<Multiaddr>,
+        respond_to: Option<oneshot::Sender<Vec<Multiaddr>>>,
+    ) {
+        match respond_to {
+            Some(respond_to) => respond_to.maybe_send(addrs.clone()),
+            None => self.resolving_bootnodes = false,
+        }
+
+        self.event_pub.send(NodeEvent::BootnodesResolved {
+            addresses: addrs.clone(),
+        });
+
+        let mut bootnodes = HashMap::<_, Vec<_>>::new();
+
+        for addr in addrs {
+            if let Some(peer_id) = addr.peer_id() {
+                bootnodes.entry(peer_id).or_default().push(addr);
+            }
+        }
+
+        for (peer_id, addrs) in bootnodes {
+            let dial_opts = DialOpts::peer_id(peer_id)
                 .addresses(addrs.clone())
                 // Tell Swarm not to dial if peer is already connected or there
                 // is an ongoing dialing.
@@ -854,11 +1155,6 @@ where
                 }
             }
         }
-
-        // trigger kademlia bootstrap
-        if self.swarm.behaviour_mut().kademlia.bootstrap().is_err() {
-            warn!("Can't run kademlia bootstrap, no known peers");
-        }
     }
 
     fn prune_canceled_bitswap_queries(&mut self) {
@@ -910,10 +1206,11 @@ where
                 SwarmEvent::ConnectionClosed {
                     peer_id,
                     connection_id,
+                    cause,
                     ..
                 } => {
                     // This will generate the PeerDisconnected events.
-                    self.on_peer_disconnected(peer_id, connection_id);
+                    self.on_peer_disconnected(peer_id, connection_id, cause);
                 }
                 _ => {}
             }
@@ -943,9 +1240,10 @@ where
             SwarmEvent::ConnectionClosed {
                 peer_id,
                 connection_id,
+                cause,
                 ..
             } => {
-                self.on_peer_disconnected(peer_id, connection_id);
+                self.on_peer_disconnected(peer_id, connection_id, cause);
             }
             _ => {}
         }
@@ -986,6 +1284,12 @@ where
             P2pCmd::ConnectedPeers { respond_to } => {
                 respond_to.maybe_send(self.peer_tracker.connected_peers());
             }
+            P2pCmd::PeersSupporting {
+                protocol,
+                respond_to,
+            } => {
+                respond_to.maybe_send(self.peer_tracker.peers_supporting(&protocol));
+            }
             P2pCmd::InitHeaderSub { head, channel } => {
                 self.on_init_header_sub(*head, channel);
             }
@@ -1010,6 +1314,20 @@ where
                     .map(|state| state.known_head.clone());
                 respond_to.maybe_send(head);
             }
+            P2pCmd::PeerScores { respond_to } => {
+                let gossipsub = &self.swarm.behaviour().gossipsub;
+                let scores =
+                    collect_peer_scores(gossipsub.all_peers().map(|(peer, _)| peer), |peer| {
+                        gossipsub.peer_score(peer)
+                    });
+                respond_to.maybe_send(scores);
+            }
+            P2pCmd::PeerLatencies { respond_to } => {
+                respond_to.maybe_send(self.peer_tracker.latencies());
+            }
+            P2pCmd::RefreshBootnodes { respond_to } => {
+                self.resolve_bootnodes(Some(respond_to));
+            }
         }
 
         Ok(())
@@ -1029,6 +1347,9 @@ where
     async fn on_identify_event(&mut self, ev: identify::Event) -> Result<()> {
         match ev {
             identify::Event::Received { peer_id, info, .. } => {
+                self.peer_tracker.set_protocols(peer_id, info.protocols);
+                self.check_ready();
+
                 // Inform Kademlia about the listening addresses
                 // TODO: Remove this when rust-libp2p#5103 is implemented
                 for addr in info.listen_addrs {
@@ -1058,7 +1379,7 @@ where
                 };
 
                 let acceptance = if message.topic == self.header_sub_topic_hash {
-                    self.on_header_sub_message(&message.data[..])
+                    self.on_header_sub_message(&message.data[..]).await
                 } else if message.topic == self.bad_encoding_fraud_sub_topic {
                     self.on_bad_encoding_fraud_sub_message(&message.data[..], &peer)
                         .await
@@ -1123,10 +1444,13 @@ where
     #[instrument(level = "debug", skip_all)]
     async fn on_ping_event(&mut self, ev: ping::Event) {
         match ev.result {
-            Ok(dur) => debug!(
-                "Ping success: peer: {}, connection_id: {}, time: {:?}",
-                ev.peer, ev.connection, dur
-            ),
+            Ok(dur) => {
+                debug!(
+                    "Ping success: peer: {}, connection_id: {}, time: {:?}",
+                    ev.peer, ev.connection, dur
+                );
+                self.peer_tracker.set_latency(ev.peer, dur);
+            }
             Err(e) => {
                 debug!(
                     "Ping failure: peer: {}, connection_id: {}, error: {}",
@@ -1174,26 +1498,48 @@ where
     }
 
     #[instrument(skip_all, fields(peer_id = %peer_id))]
-    fn on_peer_disconnected(&mut self, peer_id: PeerId, connection_id: ConnectionId) {
+    fn on_peer_disconnected(
+        &mut self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        cause: Option<ConnectionError>,
+    ) {
         if self
             .peer_tracker
-            .set_maybe_disconnected(peer_id, connection_id)
+            .set_maybe_disconnected(peer_id, connection_id, cause.as_ref())
         {
             debug!("Peer disconnected");
         }
     }
 
     #[instrument(skip_all, fields(header = %head))]
-    fn on_init_header_sub(&mut self, head: ExtendedHeader, channel: mpsc::Sender<ExtendedHeader>) {
+    fn on_init_header_sub(&mut self, head: ExtendedHeader, channel: HeaderSubSender) {
         self.header_sub_state = Some(HeaderSubState {
             known_head: head,
             channel,
         });
         trace!("HeaderSub initialized");
+        self.check_ready();
+    }
+
+    /// Announce that the node is ready to serve, the first time it has a verified head
+    /// and a peer supporting all the needed protocols.
+    fn check_ready(&mut self) {
+        if *self.ready_tx.borrow() || self.header_sub_state.is_none() {
+            return;
+        }
+
+        if self
+            .peer_tracker
+            .any_peer_supporting_all(&self.ready_protocols)
+        {
+            self.ready_tx.send_replace(true);
+            self.event_pub.send(NodeEvent::ReadyToServe);
+        }
     }
 
     #[instrument(skip_all)]
-    fn on_header_sub_message(&mut self, data: &[u8]) -> gossipsub::MessageAcceptance {
+    async fn on_header_sub_message(&mut self, data: &[u8]) -> gossipsub::MessageAcceptance {
         let Ok(header) = ExtendedHeader::decode_and_validate(data) else {
             trace!("Malformed or invalid header from header-sub");
             return gossipsub::MessageAcceptance::Reject;
@@ -1206,6 +1552,16 @@ where
             return gossipsub::MessageAcceptance::Ignore;
         };
 
+        if header.height() <= state.known_head.height() {
+            // Header can't be verified against the known head, but it may be a valid one
+            // conflicting with the synchronised header, which `Syncer` reports as reorg.
+            if conflicts_with_store(&*self.store, &header).await {
+                debug!("Header from header-sub conflicts with the stored one ({header})");
+                state.channel.send(header).await;
+            }
+            return gossipsub::MessageAcceptance::Ignore;
+        }
+
         if state.known_head.verify(&header).is_err() {
             trace!("Failed to verify HeaderSub header. Ignoring {header}");
             return gossipsub::MessageAcceptance::Ignore;
@@ -1214,13 +1570,21 @@ where
         trace!("New header from header-sub ({header})");
 
         state.known_head = header.clone();
-        // We intentionally do not `send().await` to avoid blocking `P2p`
-        // in case `Syncer` enters some weird state.
-        let _ = state.channel.try_send(header);
+        // Blocks `P2p` only if `Syncer` configured the channel to do so.
+        state.channel.send(header).await;
 
         gossipsub::MessageAcceptance::Accept
     }
 
+    /// Ban the misbehaving peer, ignoring its messages and closing its connections.
+    fn ban_peer(&mut self, peer: PeerId) {
+        debug!("Banning peer {peer}");
+        self.swarm.behaviour_mut().gossipsub.blacklist_peer(&peer);
+        self.peer_tracker.set_banned(peer);
+        // fails only if the peer isn't connected
+        let _ = self.swarm.disconnect_peer_id(peer);
+    }
+
     #[instrument(skip_all)]
     async fn on_bad_encoding_fraud_sub_message(
         &mut self,
@@ -1229,7 +1593,7 @@ where
     ) -> gossipsub::MessageAcceptance {
         let Ok(befp) = BadEncodingFraudProof::decode(data) else {
             trace!("Malformed bad encoding fraud proof from {peer}");
-            self.swarm.behaviour_mut().gossipsub.blacklist_peer(peer);
+            self.ban_peer(*peer);
             return gossipsub::MessageAcceptance::Reject;
         };
 
@@ -1259,7 +1623,7 @@ where
 
         if let Err(e) = befp.validate(&header) {
             trace!("Received invalid bad encoding fraud proof from {peer}: {e}");
-            self.swarm.behaviour_mut().gossipsub.blacklist_peer(peer);
+            self.ban_peer(*peer);
             return gossipsub::MessageAcceptance::Reject;
         }
 
@@ -1304,6 +1668,38 @@ fn validate_bootnode_addrs(addrs: &[Multiaddr]) -> Result<(), P2pError> {
     }
 }
 
+/// Whether the header differs from the stored one of the same height, while being verified
+/// by the stored header preceding it.
+async fn conflicts_with_store<S>(store: &S, header: &ExtendedHeader) -> bool
+where
+    S: Store,
+{
+    let height = header.height().value();
+
+    let Ok(stored) = store.get_by_height(height).await else {
+        return false;
+    };
+    if stored.hash() == header.hash() {
+        return false;
+    }
+
+    match store.get_by_height(height - 1).await {
+        Ok(previous) => previous.verify(header).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Scores of the peers, skipping the ones without a score, e.g. if scoring isn't enabled.
+fn collect_peer_scores<'a>(
+    peers: impl IntoIterator<Item = &'a PeerId>,
+    score: impl Fn(&PeerId) -> Option<f64>,
+) -> Vec<(PeerId, f64)> {
+    peers
+        .into_iter()
+        .filter_map(|peer| Some((*peer, score(peer)?)))
+        .collect()
+}
+
 fn init_gossipsub<'a, B, S>(
     args: &'a P2pArgs<B, S>,
     topics: impl IntoIterator<Item = &'a gossipsub::IdentTopic>,
@@ -1312,13 +1708,27 @@ where
     B: Blockstore,
     S: Store,
 {
+    new_gossipsub(&args.local_keypair, &args.gossipsub_tuning, topics)
+}
+
+fn new_gossipsub<'a>(
+    keypair: &Keypair,
+    tuning: &GossipsubTuning,
+    topics: impl IntoIterator<Item = &'a gossipsub::IdentTopic>,
+) -> Result<gossipsub::Behaviour> {
     // Set the message authenticity - How we expect to publish messages
     // Here we expect the publisher to sign the message with their key.
-    let message_authenticity = gossipsub::MessageAuthenticity::Signed(args.local_keypair.clone());
+    let message_authenticity = gossipsub::MessageAuthenticity::Signed(keypair.clone());
 
     let config = gossipsub::ConfigBuilder::default()
         .validation_mode(gossipsub::ValidationMode::Strict)
         .validate_messages()
+        .mesh_n(tuning.mesh_n)
+        .mesh_n_low(tuning.mesh_n_low)
+        .mesh_n_high(tuning.mesh_n_high)
+        // gossipsub requires at most half of the mesh to be outbound peers
+        .mesh_outbound_min(tuning.mesh_n_low.min(tuning.mesh_n / 2).min(2))
+        .heartbeat_interval(tuning.heartbeat_interval)
         .build()
         .map_err(|e| P2pError::GossipsubInit(e.to_string()))?;
 
@@ -1327,6 +1737,15 @@ where
         gossipsub::Behaviour::new(message_authenticity, config)
             .map_err(|e| P2pError::GossipsubInit(e.to_string()))?;
 
+    if tuning.peer_scoring {
+        gossipsub
+            .with_peer_score(
+                gossipsub::PeerScoreParams::default(),
+                gossipsub::PeerScoreThresholds::default(),
+            )
+            .map_err(P2pError::GossipsubInit)?;
+    }
+
     for topic in topics {
         gossipsub
             .subscribe(topic)
@@ -1344,7 +1763,7 @@ where
     let local_peer_id = PeerId::from(args.local_keypair.public());
     let store = kad::store::MemoryStore::new(local_peer_id);
 
-    let protocol_id = celestia_protocol_id(&args.network_id, "/kad/1.0.0");
+    let protocol_id = celestia_protocol_id(&args.network_id, KADEMLIA_PROTOCOL);
     let config = kad::Config::new(protocol_id);
 
     let mut kademlia = kad::Behaviour::with_config(local_peer_id, store, config);
@@ -1371,7 +1790,7 @@ where
     B: Blockstore + 'static,
     S: Store + 'static,
 {
-    let protocol_prefix = celestia_protocol_id(network_id, "shwap");
+    let protocol_prefix = celestia_protocol_id(network_id, SHWAP_PROTOCOL_PREFIX);
 
     Ok(beetswap::Behaviour::builder(blockstore)
         .protocol_prefix(protocol_prefix.as_ref())?
@@ -1379,3 +1798,177 @@ where
         .client_set_send_dont_have(false)
         .build())
 }
+
+/// Ids of the protocols which the node on the network speaks and advertises to its peers,
+/// including the header-sub topic.
+pub(crate) fn supported_protocols(network_id: &str) -> Vec<String> {
+    let shwap = celestia_protocol_id(network_id, SHWAP_PROTOCOL_PREFIX);
+
+    vec![
+        gossipsub_ident_topic(network_id, HEADER_SUB_TOPIC).to_string(),
+        protocol_id(network_id, HEADER_EX_PROTOCOL).to_string(),
+        format!("{shwap}{BITSWAP_PROTOCOL}"),
+        celestia_protocol_id(network_id, KADEMLIA_PROTOCOL).to_string(),
+        identify::PROTOCOL_NAME.to_string(),
+        ping::PROTOCOL_NAME.to_string(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    use crate::{
+        blockstore::InMemoryBlockstore,
+        events::{EventChannel, EventSubscriber},
+        executor::timeout,
+        node::{DEFAULT_IDLE_CONNECTION_TIMEOUT, DEFAULT_PING_INTERVAL},
+        store::InMemoryStore,
+        test_utils::{spawn_mock_dns_server, spawn_sequenced_mock_dns_server},
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn bootnodes_resolved_event() {
+        let peer_id = PeerId::random();
+        let resolved: Multiaddr = format!("/ip4/127.0.0.1/tcp/1/p2p/{peer_id}")
+            .parse()
+            .unwrap();
+        let dns_server = spawn_mock_dns_server(format!("dnsaddr={resolved}"));
+
+        let events = EventChannel::new();
+        let mut event_sub = events.subscribe();
+
+        let p2p = P2p::start(P2pArgs {
+            network_id: "private".to_owned(),
+            local_keypair: Keypair::generate_ed25519(),
+            bootnodes: vec![format!("/dnsaddr/bootnode.lumina/p2p/{peer_id}")
+                .parse()
+                .unwrap()],
+            trusted_peers: Vec::new(),
+            listen_on: Vec::new(),
+            dns_servers: vec![dns_server],
+            gossipsub_tuning: GossipsubTuning::default(),
+            transports: TransportConfig::default(),
+            idle_connection_timeout: DEFAULT_IDLE_CONNECTION_TIMEOUT,
+            ping_interval: DEFAULT_PING_INTERVAL,
+            blockstore: Arc::new(InMemoryBlockstore::new()),
+            store: Arc::new(InMemoryStore::new()),
+            event_pub: events.publisher(),
+        })
+        .await
+        .unwrap();
+
+        let addresses = timeout(Duration::from_secs(5), async {
+            loop {
+                if let NodeEvent::BootnodesResolved { addresses } =
+                    event_sub.recv().await.unwrap().event
+                {
+                    break addresses;
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(addresses, [resolved]);
+
+        p2p.stop();
+        p2p.join().await;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[tokio::test]
+    async fn refresh_bootnodes_after_dns_change() {
+        let peer_id = PeerId::random();
+        let [first, second]: [Multiaddr; 2] = [1, 2].map(|port| {
+            format!("/ip4/127.0.0.1/tcp/{port}/p2p/{peer_id}")
+                .parse()
+                .unwrap()
+        });
+        let dns_server = spawn_sequenced_mock_dns_server(vec![
+            format!("dnsaddr={first}"),
+            format!("dnsaddr={second}"),
+        ]);
+
+        let events = EventChannel::new();
+        let mut event_sub = events.subscribe();
+
+        let p2p = P2p::start(P2pArgs {
+            network_id: "private".to_owned(),
+            local_keypair: Keypair::generate_ed25519(),
+            bootnodes: vec![format!("/dnsaddr/bootnode.lumina/p2p/{peer_id}")
+                .parse()
+                .unwrap()],
+            trusted_peers: Vec::new(),
+            listen_on: Vec::new(),
+            dns_servers: vec![dns_server],
+            gossipsub_tuning: GossipsubTuning::default(),
+            transports: TransportConfig::default(),
+            idle_connection_timeout: DEFAULT_IDLE_CONNECTION_TIMEOUT,
+            ping_interval: DEFAULT_PING_INTERVAL,
+            blockstore: Arc::new(InMemoryBlockstore::new()),
+            store: Arc::new(InMemoryStore::new()),
+            event_pub: events.publisher(),
+        })
+        .await
+        .unwrap();
+
+        async fn next_resolved(event_sub: &mut EventSubscriber) -> Vec<Multiaddr> {
+            timeout(Duration::from_secs(5), async {
+                loop {
+                    if let NodeEvent::BootnodesResolved { addresses } =
+                        event_sub.recv().await.unwrap().event
+                    {
+                        break addresses;
+                    }
+                }
+            })
+            .await
+            .unwrap()
+        }
+
+        // resolved on startup
+        assert_eq!(next_resolved(&mut event_sub).await, [first]);
+
+        let refreshed = p2p.refresh_bootnodes().await.unwrap();
+        assert_eq!(refreshed, std::slice::from_ref(&second));
+        // the new addresses are the ones dialed
+        assert_eq!(next_resolved(&mut event_sub).await, [second]);
+
+        p2p.stop();
+        p2p.join().await;
+    }
+
+    #[test]
+    fn peer_scores() {
+        let peers: Vec<_> = (0..3).map(|_| PeerId::random()).collect();
+        let scores = HashMap::from([(peers[0], 12.5), (peers[2], -3.0)]);
+
+        let collected = collect_peer_scores(&peers, |peer| scores.get(peer).copied());
+        assert_eq!(collected, [(peers[0], 12.5), (peers[2], -3.0)]);
+
+        // scoring not enabled
+        let collected = collect_peer_scores(&peers, |_| None);
+        assert!(collected.is_empty());
+    }
+
+    #[test]
+    fn gossipsub_peer_scoring() {
+        let keypair = Keypair::generate_ed25519();
+        let topic = gossipsub::IdentTopic::new("/private/header-sub/v0.0.1");
+        let peer = PeerId::random();
+
+        let tuning = GossipsubTuning::default();
+        let gossipsub = new_gossipsub(&keypair, &tuning, [&topic]).unwrap();
+        assert_eq!(gossipsub.peer_score(&peer), None);
+
+        let tuning = GossipsubTuning {
+            peer_scoring: true,
+            ..tuning
+        };
+        let gossipsub = new_gossipsub(&keypair, &tuning, [&topic]).unwrap();
+        assert_eq!(gossipsub.peer_score(&peer), Some(0.0));
+        assert_eq!(gossipsub.topics().count(), 1);
+    }
+}
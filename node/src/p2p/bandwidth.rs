@@ -0,0 +1,289 @@
+//! Bandwidth usage of the node, counted by the transport of the swarm.
+
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{ready, Context, Poll};
+
+use futures::{AsyncRead, AsyncWrite};
+use libp2p::core::muxing::{
+    StreamMuxer, StreamMuxerBox, StreamMuxerEvent, StreamMuxerExt, SubstreamBox,
+};
+use libp2p::core::transport::Boxed;
+use libp2p::{PeerId, Transport};
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+/// Statistics of the data transferred by the node.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+pub struct BandwidthStats {
+    /// Total number of bytes received since the node started.
+    pub total_inbound_bytes: u64,
+    /// Total number of bytes sent since the node started.
+    pub total_outbound_bytes: u64,
+    /// Receiving rate in bits per second, over the latest second.
+    pub inbound_rate_bps: f64,
+    /// Sending rate in bits per second, over the latest second.
+    pub outbound_rate_bps: f64,
+}
+
+/// Numbers of bytes transferred over the connections of the swarm, counted by
+/// [`count_bandwidth`].
+#[derive(Debug, Default)]
+pub(crate) struct BandwidthCounters {
+    inbound: AtomicU64,
+    outbound: AtomicU64,
+}
+
+impl BandwidthCounters {
+    fn add_inbound(&self, bytes: usize) {
+        self.inbound.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    fn add_outbound(&self, bytes: usize) {
+        self.outbound.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+/// Wrap the transport, counting the bytes read and written over the substreams of its
+/// connections into the counters.
+pub(crate) fn count_bandwidth(
+    transport: Boxed<(PeerId, StreamMuxerBox)>,
+    counters: Arc<BandwidthCounters>,
+) -> Boxed<(PeerId, StreamMuxerBox)> {
+    transport
+        .map(move |(peer_id, conn), _| {
+            let conn = CountingMuxer {
+                inner: conn,
+                counters: counters.clone(),
+            };
+            (peer_id, StreamMuxerBox::new(conn))
+        })
+        .boxed()
+}
+
+/// Computes the [`BandwidthStats`] from the counters of the transport of the swarm.
+///
+/// Rates are computed between the two latest samples of the counters, taken by
+/// [`BandwidthMeter::sample`], so that reading the stats doesn't affect them.
+#[derive(Debug)]
+pub(crate) struct BandwidthMeter {
+    counters: Arc<BandwidthCounters>,
+    samples: Mutex<Samples>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Samples {
+    previous: Sample,
+    latest: Sample,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    time: Instant,
+    inbound: u64,
+    outbound: u64,
+}
+
+impl BandwidthMeter {
+    /// Create a meter reading the given counters.
+    pub(crate) fn new(counters: Arc<BandwidthCounters>) -> Self {
+        let sample = Sample {
+            time: Instant::now(),
+            inbound: 0,
+            outbound: 0,
+        };
+
+        BandwidthMeter {
+            counters,
+            samples: Mutex::new(Samples {
+                previous: sample,
+                latest: sample,
+            }),
+        }
+    }
+
+    /// Counters the meter reads, to be counted into by the transport.
+    pub(crate) fn counters(&self) -> Arc<BandwidthCounters> {
+        self.counters.clone()
+    }
+
+    /// Take a sample of the counters, replacing the older of the two the rates are
+    /// computed from.
+    pub(crate) fn sample(&self) {
+        self.sample_at(Instant::now());
+    }
+
+    fn sample_at(&self, now: Instant) {
+        let mut samples = self.samples.lock().expect("poisoned lock");
+
+        samples.previous = samples.latest;
+        samples.latest = Sample {
+            time: now,
+            inbound: self.counters.inbound.load(Ordering::Relaxed),
+            outbound: self.counters.outbound.load(Ordering::Relaxed),
+        };
+    }
+
+    /// Current [`BandwidthStats`], with the rates between the two latest samples.
+    pub(crate) fn stats(&self) -> BandwidthStats {
+        let inbound = self.counters.inbound.load(Ordering::Relaxed);
+        let outbound = self.counters.outbound.load(Ordering::Relaxed);
+        let Samples { previous, latest } = *self.samples.lock().expect("poisoned lock");
+
+        let elapsed = latest
+            .time
+            .saturating_duration_since(previous.time)
+            .as_secs_f64();
+        let rate = |current: u64, previous: u64| {
+            if elapsed > 0.0 {
+                current.saturating_sub(previous) as f64 * 8.0 / elapsed
+            } else {
+                0.0
+            }
+        };
+
+        BandwidthStats {
+            total_inbound_bytes: inbound,
+            total_outbound_bytes: outbound,
+            inbound_rate_bps: rate(latest.inbound, previous.inbound),
+            outbound_rate_bps: rate(latest.outbound, previous.outbound),
+        }
+    }
+}
+
+/// Connection counting the bytes transferred over its substreams.
+struct CountingMuxer {
+    inner: StreamMuxerBox,
+    counters: Arc<BandwidthCounters>,
+}
+
+impl CountingMuxer {
+    fn wrap(&self, substream: SubstreamBox) -> SubstreamBox {
+        SubstreamBox::new(CountingSubstream {
+            inner: substream,
+            counters: self.counters.clone(),
+        })
+    }
+}
+
+impl StreamMuxer for CountingMuxer {
+    type Substream = SubstreamBox;
+    type Error = io::Error;
+
+    fn poll_inbound(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        let substream = ready!(self.inner.poll_inbound_unpin(cx))?;
+        Poll::Ready(Ok(self.wrap(substream)))
+    }
+
+    fn poll_outbound(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Self::Substream, Self::Error>> {
+        let substream = ready!(self.inner.poll_outbound_unpin(cx))?;
+        Poll::Ready(Ok(self.wrap(substream)))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_close_unpin(cx)
+    }
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<StreamMuxerEvent, Self::Error>> {
+        self.inner.poll_unpin(cx)
+    }
+}
+
+/// Substream counting the bytes read from and written to it.
+struct CountingSubstream {
+    inner: SubstreamBox,
+    counters: Arc<BandwidthCounters>,
+}
+
+impl AsyncRead for CountingSubstream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let read = ready!(Pin::new(&mut self.inner).poll_read(cx, buf))?;
+        self.counters.add_inbound(read);
+        Poll::Ready(Ok(read))
+    }
+}
+
+impl AsyncWrite for CountingSubstream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let written = ready!(Pin::new(&mut self.inner).poll_write(cx, buf))?;
+        self.counters.add_outbound(written);
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn no_traffic() {
+        let meter = BandwidthMeter::new(Arc::default());
+        let stats = meter.stats();
+
+        assert_eq!(stats.total_inbound_bytes, 0);
+        assert_eq!(stats.total_outbound_bytes, 0);
+        assert_eq!(stats.inbound_rate_bps, 0.0);
+        assert_eq!(stats.outbound_rate_bps, 0.0);
+    }
+
+    #[test]
+    fn counted_traffic() {
+        let counters = Arc::new(BandwidthCounters::default());
+        let meter = BandwidthMeter::new(counters.clone());
+        let start = Instant::now();
+
+        counters.add_inbound(1024);
+        counters.add_inbound(6);
+        counters.add_outbound(512);
+        meter.sample_at(start + Duration::from_secs(1));
+
+        let stats = meter.stats();
+        assert_eq!(stats.total_inbound_bytes, 1030);
+        assert_eq!(stats.total_outbound_bytes, 512);
+
+        // rates are of the traffic between the samples
+        counters.add_inbound(500);
+        meter.sample_at(start + Duration::from_secs(3));
+        let stats = meter.stats();
+        assert_eq!(stats.total_inbound_bytes, 1530);
+        assert_eq!(stats.inbound_rate_bps, 2000.0);
+        assert_eq!(stats.outbound_rate_bps, 0.0);
+
+        // reading the stats doesn't affect them, only the totals are live
+        counters.add_outbound(100);
+        let later = meter.stats();
+        assert_eq!(later.total_outbound_bytes, 612);
+        assert_eq!(later.inbound_rate_bps, stats.inbound_rate_bps);
+        assert_eq!(later.outbound_rate_bps, stats.outbound_rate_bps);
+    }
+}
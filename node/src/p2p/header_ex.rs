@@ -33,6 +33,8 @@ use crate::peer_tracker::PeerTracker;
 use crate::store::Store;
 use crate::utils::{protocol_id, OneshotResultSender};
 
+/// Protocol of header-ex, prefixed with the network id
+pub(crate) const HEADER_EX_PROTOCOL: &str = "/header-ex/v0.0.3";
 /// Size limit of a request in bytes
 const REQUEST_SIZE_LIMIT: usize = 1024;
 /// Time limit on reading/writing a request
@@ -104,7 +106,7 @@ where
         HeaderExBehaviour {
             req_resp: ReqRespBehaviour::new(
                 [(
-                    protocol_id(config.network_id, "/header-ex/v0.0.3"),
+                    protocol_id(config.network_id, HEADER_EX_PROTOCOL),
                     ProtocolSupport::Full,
                 )],
                 request_response::Config::default(),
@@ -0,0 +1,224 @@
+//! Channel delivering headers received by header-sub to the [`Syncer`].
+//!
+//! [`Syncer`]: crate::syncer::Syncer
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use celestia_types::ExtendedHeader;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, Notify};
+
+/// Default number of headers buffered between header-sub and [`Syncer`].
+///
+/// [`Syncer`]: crate::syncer::Syncer
+pub const DEFAULT_HEADER_SUB_BUFFER: usize = 16;
+
+/// What to do with headers received by header-sub when the buffer is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "uniffi", derive(uniffi::Enum))]
+pub enum HeaderSubOverflow {
+    /// Drop the oldest buffered header to make space for the new one.
+    #[default]
+    DropOldest,
+    /// Wait until there is space in the buffer. This holds back processing of
+    /// any other network events until [`Syncer`] catches up.
+    ///
+    /// [`Syncer`]: crate::syncer::Syncer
+    Block,
+}
+
+/// Create a channel buffering `capacity` headers, which handles overflow according to the policy.
+pub(crate) fn channel(
+    capacity: usize,
+    overflow: HeaderSubOverflow,
+) -> (HeaderSubSender, HeaderSubReceiver) {
+    match overflow {
+        HeaderSubOverflow::DropOldest => {
+            let shared = Arc::new(RingBuffer {
+                state: Mutex::new(RingBufferState {
+                    headers: VecDeque::with_capacity(capacity),
+                    dropped: 0,
+                    closed: false,
+                }),
+                capacity,
+                notify: Notify::new(),
+            });
+
+            (
+                HeaderSubSender(SenderInner::DropOldest(shared.clone())),
+                HeaderSubReceiver(ReceiverInner::DropOldest(shared)),
+            )
+        }
+        HeaderSubOverflow::Block => {
+            let (tx, rx) = mpsc::channel(capacity);
+            (
+                HeaderSubSender(SenderInner::Block(tx)),
+                HeaderSubReceiver(ReceiverInner::Block(rx)),
+            )
+        }
+    }
+}
+
+/// Sending half of the header-sub channel.
+#[derive(Debug)]
+pub(crate) struct HeaderSubSender(SenderInner);
+
+#[derive(Debug)]
+enum SenderInner {
+    DropOldest(Arc<RingBuffer>),
+    Block(mpsc::Sender<ExtendedHeader>),
+}
+
+impl HeaderSubSender {
+    /// Send the header, waiting for space in the buffer if required by the overflow policy.
+    pub(crate) async fn send(&self, header: ExtendedHeader) {
+        match &self.0 {
+            SenderInner::DropOldest(buffer) => buffer.push(header),
+            SenderInner::Block(tx) => {
+                let _ = tx.send(header).await;
+            }
+        }
+    }
+
+    /// Send the header without waiting. If the buffer is full and policy is
+    /// [`HeaderSubOverflow::Block`], the header is discarded.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub(crate) fn try_send(&self, header: ExtendedHeader) {
+        match &self.0 {
+            SenderInner::DropOldest(buffer) => buffer.push(header),
+            SenderInner::Block(tx) => {
+                let _ = tx.try_send(header);
+            }
+        }
+    }
+}
+
+impl Drop for HeaderSubSender {
+    fn drop(&mut self) {
+        if let SenderInner::DropOldest(buffer) = &self.0 {
+            buffer.state.lock().expect("poisoned lock").closed = true;
+            buffer.notify.notify_one();
+        }
+    }
+}
+
+/// Receiving half of the header-sub channel.
+#[derive(Debug)]
+pub(crate) struct HeaderSubReceiver(ReceiverInner);
+
+#[derive(Debug)]
+enum ReceiverInner {
+    DropOldest(Arc<RingBuffer>),
+    Block(mpsc::Receiver<ExtendedHeader>),
+}
+
+impl HeaderSubReceiver {
+    /// Receive the next header, or `None` if the sending half was dropped.
+    pub(crate) async fn recv(&mut self) -> Option<ExtendedHeader> {
+        match &mut self.0 {
+            ReceiverInner::DropOldest(buffer) => loop {
+                let notified = buffer.notify.notified();
+
+                {
+                    let mut state = buffer.state.lock().expect("poisoned lock");
+
+                    if let Some(header) = state.headers.pop_front() {
+                        return Some(header);
+                    }
+
+                    if state.closed {
+                        return None;
+                    }
+                }
+
+                notified.await;
+            },
+            ReceiverInner::Block(rx) => rx.recv().await,
+        }
+    }
+
+    /// Number of headers dropped because the buffer was full.
+    pub(crate) fn dropped(&self) -> u64 {
+        match &self.0 {
+            ReceiverInner::DropOldest(buffer) => {
+                buffer.state.lock().expect("poisoned lock").dropped
+            }
+            ReceiverInner::Block(_) => 0,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RingBuffer {
+    state: Mutex<RingBufferState>,
+    capacity: usize,
+    notify: Notify,
+}
+
+#[derive(Debug)]
+struct RingBufferState {
+    headers: VecDeque<ExtendedHeader>,
+    dropped: u64,
+    closed: bool,
+}
+
+impl RingBuffer {
+    fn push(&self, header: ExtendedHeader) {
+        let mut state = self.state.lock().expect("poisoned lock");
+
+        if state.headers.len() == self.capacity {
+            state.headers.pop_front();
+            state.dropped += 1;
+        }
+
+        state.headers.push_back(header);
+        drop(state);
+
+        self.notify.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::async_test;
+    use celestia_types::test_utils::ExtendedHeaderGenerator;
+    use futures::FutureExt;
+
+    #[async_test]
+    async fn drop_oldest_counts_dropped_headers() {
+        let headers = ExtendedHeaderGenerator::new().next_many(5);
+        let (tx, mut rx) = channel(3, HeaderSubOverflow::DropOldest);
+
+        for header in &headers {
+            tx.send(header.clone()).await;
+        }
+        drop(tx);
+
+        let mut received = Vec::new();
+        while let Some(header) = rx.recv().await {
+            received.push(header);
+        }
+
+        assert_eq!(received, &headers[2..]);
+        assert_eq!(rx.dropped(), 2);
+    }
+
+    #[async_test]
+    async fn block_waits_for_space() {
+        let headers = ExtendedHeaderGenerator::new().next_many(3);
+        let (tx, mut rx) = channel(2, HeaderSubOverflow::Block);
+
+        tx.send(headers[0].clone()).await;
+        tx.send(headers[1].clone()).await;
+        // buffer is full
+        assert!(tx.send(headers[2].clone()).now_or_never().is_none());
+
+        assert_eq!(rx.recv().await.unwrap(), headers[0]);
+        tx.send(headers[2].clone()).await;
+        assert_eq!(rx.recv().await.unwrap(), headers[1]);
+        assert_eq!(rx.recv().await.unwrap(), headers[2]);
+        assert_eq!(rx.dropped(), 0);
+    }
+}
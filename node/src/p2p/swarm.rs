@@ -1,27 +1,50 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
 use libp2p::identity::Keypair;
 use libp2p::swarm::{NetworkBehaviour, Swarm};
+use libp2p::Multiaddr;
 use web_time::Duration;
 
-use crate::p2p::{P2pError, Result};
+use crate::p2p::bandwidth::{count_bandwidth, BandwidthCounters};
+use crate::p2p::{IpVersion, P2pError, Result, TransportConfig};
 
-pub(crate) use self::imp::new_swarm;
+pub(crate) use self::imp::{new_swarm, resolve_bootnodes};
 
 #[cfg(not(target_arch = "wasm32"))]
 mod imp {
     use std::env;
-    use std::io::Cursor;
+    use std::io::{self, Cursor};
     use std::path::Path;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
 
     use futures::future::Either;
+    use hickory_resolver::config::{LookupIpStrategy, NameServerConfig, Protocol as DnsProtocol};
+    use hickory_resolver::TokioAsyncResolver;
     use libp2p::core::muxing::StreamMuxerBox;
+    use libp2p::core::transport::{
+        DialOpts, ListenerId, OptionalTransport, TransportError, TransportEvent,
+    };
     use libp2p::core::upgrade::Version;
+    use libp2p::multiaddr::Protocol;
     use libp2p::{dns, noise, quic, swarm, tcp, websocket, yamux, PeerId, Transport};
     use rustls_pki_types::{CertificateDer, PrivateKeyDer};
     use tokio::fs;
+    use tracing::warn;
+
+    use crate::utils::MultiaddrExt;
 
     use super::*;
 
-    pub(crate) async fn new_swarm<B>(keypair: Keypair, behaviour: B) -> Result<Swarm<B>>
+    pub(crate) async fn new_swarm<B>(
+        keypair: Keypair,
+        behaviour: B,
+        dns_servers: &[SocketAddr],
+        bandwidth: Arc<BandwidthCounters>,
+        transports: TransportConfig,
+        idle_connection_timeout: Duration,
+    ) -> Result<Swarm<B>>
     where
         B: NetworkBehaviour,
     {
@@ -42,8 +65,10 @@ mod imp {
         //
         // Similarly, if node is started when there's no Internet connection,
         // it won't use the DNS servers offered when Internet connectivity
-        // is restored. Instead we per-define globally-accessible public DNS servers.
-        let dns_config = dns::ResolverConfig::cloudflare();
+        // is restored. Instead we per-define globally-accessible public DNS servers,
+        // unless user provided their own.
+        let dns_config = resolver_config(dns_servers);
+        let dns_opts = resolver_opts(transports.ip_version);
 
         let noise_config =
             noise::Config::new(&keypair).map_err(|e| P2pError::NoiseInit(e.to_string()))?;
@@ -62,9 +87,12 @@ mod imp {
             };
 
             let mut wss_transport = websocket::WsConfig::new(dns::tokio::Transport::custom(
-                tcp::tokio::Transport::new(tcp::Config::default()),
+                IpFilter::new(
+                    tcp::tokio::Transport::new(tcp::Config::default()),
+                    transports.ip_version,
+                ),
                 dns_config.clone(),
-                dns::ResolverOpts::default(),
+                dns_opts.clone(),
             ));
 
             wss_transport.set_tls_config(config);
@@ -82,24 +110,43 @@ mod imp {
 
         let quic_transport = quic::tokio::Transport::new(quic::Config::new(&keypair));
 
+        // Disabled transports reject all the addresses, leaving them to the other ones.
+        let wss_transport = optional(transports.websocket, wss_transport);
+        let tcp_transport = optional(transports.tcp, tcp_transport);
+        let quic_transport = optional(transports.quic, quic_transport);
+
         // WSS must be before TCP transport and must not be wrapped in DNS transport.
         let transport = wss_transport
             .or_transport(dns::tokio::Transport::custom(
-                tcp_transport
-                    .or_transport(quic_transport)
-                    .map(|either, _| match either {
-                        Either::Left((peer_id, conn)) => (peer_id, StreamMuxerBox::new(conn)),
-                        Either::Right((peer_id, conn)) => (peer_id, StreamMuxerBox::new(conn)),
-                    }),
+                IpFilter::new(
+                    tcp_transport
+                        .or_transport(quic_transport)
+                        .map(|either, _| match either {
+                            Either::Left((peer_id, conn)) => (peer_id, StreamMuxerBox::new(conn)),
+                            Either::Right((peer_id, conn)) => (peer_id, StreamMuxerBox::new(conn)),
+                        }),
+                    transports.ip_version,
+                ),
                 dns_config,
-                dns::ResolverOpts::default(),
+                dns_opts,
             ))
             .map(|either, _| match either {
                 Either::Left((peer_id, conn)) => (peer_id, StreamMuxerBox::new(conn)),
                 Either::Right((peer_id, conn)) => (peer_id, StreamMuxerBox::new(conn)),
             })
+            // DNS transport accepts all the addresses and only fails once it passes them
+            // on after resolving, so its rejection is kept distinguishable from a failed dial
+            .map_err(|e| match e.as_ref().right() {
+                Some(dns::Error::MultiaddrNotSupported(addr)) => io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    format!("Multiaddr is not supported: {addr}"),
+                ),
+                _ => io::Error::other(e),
+            })
             .boxed();
 
+        let transport = count_bandwidth(transport, bandwidth);
+
         let local_peer_id = PeerId::from_public_key(&keypair.public());
 
         Ok(Swarm::new(
@@ -107,13 +154,158 @@ mod imp {
             behaviour,
             local_peer_id,
             swarm::Config::with_tokio_executor()
-                // TODO: Refactor code to avoid being idle. This can be done by preloading a
-                // handler. This is how they fixed Kademlia:
-                // https://github.com/libp2p/rust-libp2p/pull/4675/files
-                .with_idle_connection_timeout(Duration::from_secs(15)),
+                .with_idle_connection_timeout(idle_connection_timeout),
         ))
     }
 
+    /// Maximum number of nested `/dnsaddr/` lookups, same as of the DNS transport.
+    const MAX_DNSADDR_DEPTH: u8 = 32;
+
+    /// Resolve the `/dnsaddr/` bootnodes into the addresses they point to, keeping only the ones
+    /// of the same peer. Other addresses are returned as they are.
+    ///
+    /// Bootnodes which fail to resolve are logged and left out.
+    pub(crate) async fn resolve_bootnodes(
+        bootnodes: Vec<Multiaddr>,
+        dns_servers: &[SocketAddr],
+        ip_version: IpVersion,
+    ) -> Vec<Multiaddr> {
+        let resolver =
+            TokioAsyncResolver::tokio(resolver_config(dns_servers), resolver_opts(ip_version));
+        let mut pending: Vec<_> = bootnodes.into_iter().map(|addr| (addr, 0)).collect();
+        let mut resolved = Vec::new();
+
+        while let Some((addr, depth)) = pending.pop() {
+            let Some(Protocol::Dnsaddr(name)) = addr.iter().next() else {
+                resolved.push(addr);
+                continue;
+            };
+
+            if depth >= MAX_DNSADDR_DEPTH {
+                warn!("Too many nested lookups of {addr}");
+                continue;
+            }
+
+            let lookup = match resolver.txt_lookup(format!("_dnsaddr.{name}")).await {
+                Ok(lookup) => lookup,
+                Err(e) => {
+                    warn!("Failed to resolve {addr}: {e}");
+                    continue;
+                }
+            };
+
+            let peer_id = addr.peer_id();
+            let entries = lookup
+                .iter()
+                .flat_map(|txt| txt.txt_data())
+                .filter_map(|data| std::str::from_utf8(data).ok())
+                .filter_map(|entry| entry.strip_prefix("dnsaddr="))
+                .filter_map(|entry| entry.parse::<Multiaddr>().ok())
+                .filter(|entry| peer_id.is_none() || entry.peer_id() == peer_id);
+
+            pending.extend(entries.map(|entry| (entry, depth + 1)));
+        }
+
+        resolved.reverse();
+        resolved
+    }
+
+    /// Configuration of the resolver using given DNS servers, or Cloudflare's if none given.
+    fn resolver_config(dns_servers: &[SocketAddr]) -> dns::ResolverConfig {
+        if dns_servers.is_empty() {
+            return dns::ResolverConfig::cloudflare();
+        }
+
+        let mut config = dns::ResolverConfig::new();
+
+        for addr in dns_servers {
+            config.add_name_server(NameServerConfig::new(*addr, DnsProtocol::Udp));
+            config.add_name_server(NameServerConfig::new(*addr, DnsProtocol::Tcp));
+        }
+
+        config
+    }
+
+    /// Options of the resolver, looking up only the addresses of the allowed IP version.
+    fn resolver_opts(ip_version: IpVersion) -> dns::ResolverOpts {
+        let mut opts = dns::ResolverOpts::default();
+
+        opts.ip_strategy = match ip_version {
+            IpVersion::Any => opts.ip_strategy,
+            IpVersion::V4 => LookupIpStrategy::Ipv4Only,
+            IpVersion::V6 => LookupIpStrategy::Ipv6Only,
+        };
+
+        opts
+    }
+
+    fn optional<T>(enabled: bool, transport: T) -> OptionalTransport<T> {
+        if enabled {
+            OptionalTransport::some(transport)
+        } else {
+            OptionalTransport::none()
+        }
+    }
+
+    /// Transport rejecting the addresses of the IP version which isn't allowed.
+    #[pin_project::pin_project]
+    struct IpFilter<T> {
+        #[pin]
+        inner: T,
+        ip_version: IpVersion,
+    }
+
+    impl<T> IpFilter<T> {
+        fn new(inner: T, ip_version: IpVersion) -> Self {
+            IpFilter { inner, ip_version }
+        }
+    }
+
+    impl<T> Transport for IpFilter<T>
+    where
+        T: Transport,
+    {
+        type Output = T::Output;
+        type Error = T::Error;
+        type ListenerUpgrade = T::ListenerUpgrade;
+        type Dial = T::Dial;
+
+        fn listen_on(
+            &mut self,
+            id: ListenerId,
+            addr: Multiaddr,
+        ) -> Result<(), TransportError<Self::Error>> {
+            if self.ip_version.allows(&addr) {
+                self.inner.listen_on(id, addr)
+            } else {
+                Err(TransportError::MultiaddrNotSupported(addr))
+            }
+        }
+
+        fn remove_listener(&mut self, id: ListenerId) -> bool {
+            self.inner.remove_listener(id)
+        }
+
+        fn dial(
+            &mut self,
+            addr: Multiaddr,
+            opts: DialOpts,
+        ) -> Result<Self::Dial, TransportError<Self::Error>> {
+            if self.ip_version.allows(&addr) {
+                self.inner.dial(addr, opts)
+            } else {
+                Err(TransportError::MultiaddrNotSupported(addr))
+            }
+        }
+
+        fn poll(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<TransportEvent<Self::ListenerUpgrade, Self::Error>> {
+            self.project().inner.poll(cx)
+        }
+    }
+
     impl From<noise::Error> for P2pError {
         fn from(e: noise::Error) -> Self {
             P2pError::NoiseInit(e.to_string())
@@ -156,42 +348,304 @@ mod imp {
             Ok(certs)
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use std::net::TcpListener;
+
+        use futures::StreamExt;
+        use libp2p::core::transport::dummy::DummyTransport;
+        use libp2p::core::Endpoint;
+        use libp2p::swarm::{dummy, DialError, SwarmEvent};
+
+        use super::*;
+        use crate::events::DisconnectReason;
+        use crate::node::DEFAULT_IDLE_CONNECTION_TIMEOUT;
+        use crate::test_utils::spawn_mock_dns_server;
+
+        #[tokio::test]
+        async fn resolve_dnsaddr_bootnodes() {
+            let peer_id = PeerId::random();
+            let resolved: Multiaddr = format!("/ip4/10.0.0.1/tcp/2121/p2p/{peer_id}")
+                .parse()
+                .unwrap();
+            let dns_server = spawn_mock_dns_server(format!("dnsaddr={resolved}"));
+
+            let concrete: Multiaddr = format!("/ip4/10.0.0.2/tcp/2121/p2p/{peer_id}")
+                .parse()
+                .unwrap();
+            let bootnodes = vec![
+                format!("/dnsaddr/bootnode.lumina/p2p/{peer_id}")
+                    .parse()
+                    .unwrap(),
+                // entries of other peers are left out
+                format!("/dnsaddr/bootnode.lumina/p2p/{}", PeerId::random())
+                    .parse()
+                    .unwrap(),
+                concrete.clone(),
+            ];
+
+            let addrs = resolve_bootnodes(bootnodes, &[dns_server], IpVersion::Any).await;
+            assert_eq!(addrs, [resolved, concrete]);
+        }
+
+        #[tokio::test]
+        async fn dnsaddr_with_custom_resolver() {
+            let peer_id = PeerId::random();
+            let resolved: Multiaddr = format!("/ip4/10.0.0.1/tcp/2121/p2p/{peer_id}")
+                .parse()
+                .unwrap();
+            let dns_server = spawn_mock_dns_server(format!("dnsaddr={resolved}"));
+
+            let mut transport = dns::tokio::Transport::custom(
+                DummyTransport::<(PeerId, StreamMuxerBox)>::new(),
+                resolver_config(&[dns_server]),
+                dns::ResolverOpts::default(),
+            );
+
+            let bootnode = format!("/dnsaddr/bootnode.lumina/p2p/{peer_id}")
+                .parse()
+                .unwrap();
+            let dial_opts = DialOpts {
+                role: Endpoint::Dialer,
+                port_use: Default::default(),
+            };
+
+            // Dummy transport rejects every resolved address it is asked to dial.
+            match transport.dial(bootnode, dial_opts).unwrap().await {
+                Err(dns::Error::MultiaddrNotSupported(addr)) => assert_eq!(addr, resolved),
+                res => panic!("unexpected dial result: {res:?}"),
+            }
+        }
+
+        async fn test_swarm(transports: TransportConfig) -> Swarm<dummy::Behaviour> {
+            swarm_with_idle_timeout(transports, DEFAULT_IDLE_CONNECTION_TIMEOUT).await
+        }
+
+        async fn swarm_with_idle_timeout(
+            transports: TransportConfig,
+            idle_connection_timeout: Duration,
+        ) -> Swarm<dummy::Behaviour> {
+            let keypair = Keypair::generate_ed25519();
+            let behaviour = dummy::Behaviour;
+            new_swarm(
+                keypair,
+                behaviour,
+                &[],
+                Arc::default(),
+                transports,
+                idle_connection_timeout,
+            )
+            .await
+            .unwrap()
+        }
+
+        /// Dial the address and wait for the transport errors of the failed dial.
+        async fn dial_errors(
+            swarm: &mut Swarm<dummy::Behaviour>,
+            addr: &str,
+        ) -> Vec<(Multiaddr, TransportError<io::Error>)> {
+            swarm.dial(addr.parse::<Multiaddr>().unwrap()).unwrap();
+
+            loop {
+                if let SwarmEvent::OutgoingConnectionError {
+                    error: DialError::Transport(errors),
+                    ..
+                } = swarm.select_next_some().await
+                {
+                    return errors;
+                }
+            }
+        }
+
+        /// Whether the address was rejected by the transports. Dial errors reaching
+        /// the swarm are wrapped by the boxed transports, so the inner errors are matched.
+        fn is_not_supported(error: &TransportError<io::Error>) -> bool {
+            let mut error = match error {
+                TransportError::MultiaddrNotSupported(_) => return true,
+                TransportError::Other(e) => e,
+            };
+
+            loop {
+                if error.kind() == io::ErrorKind::Unsupported {
+                    return true;
+                }
+                match error.get_ref().and_then(|e| e.downcast_ref::<io::Error>()) {
+                    Some(inner) => error = inner,
+                    None => return false,
+                }
+            }
+        }
+
+        fn closed_tcp_port() -> u16 {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        }
+
+        #[tokio::test]
+        async fn disabled_quic() {
+            let quic_addr: Multiaddr = "/ip4/127.0.0.1/udp/0/quic-v1".parse().unwrap();
+
+            let mut swarm = test_swarm(TransportConfig::default()).await;
+            swarm.listen_on(quic_addr.clone()).unwrap();
+
+            let mut swarm = test_swarm(TransportConfig {
+                quic: false,
+                ..TransportConfig::default()
+            })
+            .await;
+
+            assert!(matches!(
+                swarm.listen_on(quic_addr).unwrap_err(),
+                TransportError::MultiaddrNotSupported(_)
+            ));
+            swarm
+                .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+                .unwrap();
+
+            // QUIC address is rejected without any connection attempt
+            let errors = dial_errors(&mut swarm, "/ip4/127.0.0.1/udp/4242/quic-v1").await;
+            assert!(matches!(&errors[..], [(_, e)] if is_not_supported(e)));
+
+            // while TCP is still dialed and refused
+            let tcp_addr = format!("/ip4/127.0.0.1/tcp/{}", closed_tcp_port());
+            let errors = dial_errors(&mut swarm, &tcp_addr).await;
+            assert!(matches!(&errors[..], [(_, e)] if !is_not_supported(e)));
+        }
+
+        #[tokio::test]
+        async fn ipv4_only() {
+            let mut swarm = test_swarm(TransportConfig {
+                ip_version: IpVersion::V4,
+                ..TransportConfig::default()
+            })
+            .await;
+
+            swarm
+                .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+                .unwrap();
+            assert!(matches!(
+                swarm
+                    .listen_on("/ip6/::1/tcp/0".parse().unwrap())
+                    .unwrap_err(),
+                TransportError::MultiaddrNotSupported(_)
+            ));
+
+            let errors = dial_errors(&mut swarm, "/ip6/::1/tcp/4242").await;
+            assert!(matches!(&errors[..], [(_, e)] if is_not_supported(e)));
+        }
+
+        #[tokio::test]
+        async fn idle_connection_closed() {
+            let timeout = Duration::from_secs(1);
+            let mut listener = swarm_with_idle_timeout(TransportConfig::default(), timeout).await;
+            let mut dialer = swarm_with_idle_timeout(TransportConfig::default(), timeout).await;
+
+            listener
+                .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+                .unwrap();
+            let addr = loop {
+                if let SwarmEvent::NewListenAddr { address, .. } = listener.select_next_some().await
+                {
+                    break address;
+                }
+            };
+            tokio::spawn(async move {
+                loop {
+                    listener.select_next_some().await;
+                }
+            });
+
+            dialer.dial(addr).unwrap();
+            let established = loop {
+                if let SwarmEvent::ConnectionEstablished { .. } = dialer.select_next_some().await {
+                    break tokio::time::Instant::now();
+                }
+            };
+
+            // nothing keeps the connection alive, so it's closed once the timeout passes
+            let cause = loop {
+                if let SwarmEvent::ConnectionClosed { cause, .. } = dialer.select_next_some().await
+                {
+                    break cause;
+                }
+            };
+            assert!(established.elapsed() >= timeout);
+            assert!(established.elapsed() < timeout * 5);
+            assert_eq!(
+                DisconnectReason::from(cause.as_ref()),
+                DisconnectReason::Timeout
+            );
+        }
+
+        #[test]
+        fn default_resolver_config() {
+            let config = resolver_config(&[]);
+            let cloudflare = dns::ResolverConfig::cloudflare();
+
+            assert_eq!(config.name_servers(), cloudflare.name_servers());
+        }
+    }
 }
 
 #[cfg(target_arch = "wasm32")]
 mod imp {
     use super::*;
+    use libp2p::core::muxing::StreamMuxerBox;
     use libp2p::core::upgrade::Version;
     use libp2p::{noise, websocket_websys, webtransport_websys, yamux, SwarmBuilder, Transport};
 
-    pub(crate) async fn new_swarm<B>(keypair: Keypair, behaviour: B) -> Result<Swarm<B>>
+    /// Browser resolves the names itself, so the bootnodes are returned as they are.
+    pub(crate) async fn resolve_bootnodes(
+        bootnodes: Vec<Multiaddr>,
+        _dns_servers: &[SocketAddr],
+        _ip_version: IpVersion,
+    ) -> Vec<Multiaddr> {
+        bootnodes
+    }
+
+    pub(crate) async fn new_swarm<B>(
+        keypair: Keypair,
+        behaviour: B,
+        // Browser resolves the names itself
+        _dns_servers: &[SocketAddr],
+        bandwidth: Arc<BandwidthCounters>,
+        // Only the transports of the browser are available
+        _transports: TransportConfig,
+        idle_connection_timeout: Duration,
+    ) -> Result<Swarm<B>>
     where
         B: NetworkBehaviour,
     {
         let noise_config =
             noise::Config::new(&keypair).map_err(|e| P2pError::NoiseInit(e.to_string()))?;
 
+        let websocket_bandwidth = bandwidth.clone();
+
         Ok(SwarmBuilder::with_existing_identity(keypair)
             .with_wasm_bindgen()
             .with_other_transport(move |_| {
-                Ok(websocket_websys::Transport::default()
+                let transport = websocket_websys::Transport::default()
                     .upgrade(Version::V1Lazy)
                     .authenticate(noise_config)
-                    .multiplex(yamux::Config::default()))
+                    .multiplex(yamux::Config::default())
+                    .map(|(peer_id, conn), _| (peer_id, StreamMuxerBox::new(conn)))
+                    .boxed();
+                Ok(count_bandwidth(transport, websocket_bandwidth))
             })
             .expect("websocket_websys::Transport is infallible")
-            .with_other_transport(|local_keypair| {
+            .with_other_transport(move |local_keypair| {
                 let config = webtransport_websys::Config::new(local_keypair);
-                webtransport_websys::Transport::new(config)
+                let transport = webtransport_websys::Transport::new(config)
+                    .map(|(peer_id, conn), _| (peer_id, StreamMuxerBox::new(conn)))
+                    .boxed();
+                Ok(count_bandwidth(transport, bandwidth))
             })
             .expect("webtransport_websys::Transport is infallible")
             .with_behaviour(|_| behaviour)
             .expect("Moving behaviour doesn't fail")
             .with_swarm_config(|config| {
-                // TODO: Refactor code to avoid being idle. This can be done by preloading a
-                // handler. This is how they fixed Kademlia:
-                // https://github.com/libp2p/rust-libp2p/pull/4675/files
-                config.with_idle_connection_timeout(Duration::from_secs(15))
+                config.with_idle_connection_timeout(idle_connection_timeout)
             })
             .build())
     }
@@ -1,17 +1,21 @@
 //! Primitives related to tracking the state of peers in the network.
 
 use std::borrow::Borrow;
+use std::time::Duration;
 
 use dashmap::mapref::entry::Entry;
 use dashmap::mapref::one::RefMut;
 use dashmap::DashMap;
-use libp2p::{swarm::ConnectionId, Multiaddr, PeerId};
+use libp2p::{
+    swarm::{ConnectionError, ConnectionId},
+    Multiaddr, PeerId, StreamProtocol,
+};
 use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use tokio::sync::watch;
 
-use crate::events::{EventPublisher, NodeEvent};
+use crate::events::{DisconnectReason, EventPublisher, NodeEvent};
 
 /// Keeps track various information about peers.
 #[derive(Debug)]
@@ -36,7 +40,13 @@ struct PeerInfo {
     state: PeerState,
     addrs: SmallVec<[Multiaddr; 4]>,
     connections: SmallVec<[ConnectionId; 1]>,
+    /// Protocols supported by the peer, as reported by identify.
+    protocols: Vec<StreamProtocol>,
+    /// Round-trip time of the last successful ping of the peer.
+    latency: Option<Duration>,
     trusted: bool,
+    /// Whether the peer was banned for misbehaving.
+    banned: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -82,7 +92,10 @@ impl PeerTracker {
                     state: PeerState::Discovered,
                     addrs: SmallVec::new(),
                     connections: SmallVec::new(),
+                    protocols: Vec::new(),
+                    latency: None,
                     trusted: false,
+                    banned: false,
                 });
                 true
             }
@@ -98,7 +111,10 @@ impl PeerTracker {
             state: PeerState::Discovered,
             addrs: SmallVec::new(),
             connections: SmallVec::new(),
+            protocols: Vec::new(),
+            latency: None,
             trusted: false,
+            banned: false,
         })
     }
 
@@ -124,6 +140,19 @@ impl PeerTracker {
         }
     }
 
+    /// Sets the protocols supported by the peer, replacing the previously known ones.
+    pub fn set_protocols<I>(&self, peer: PeerId, protocols: I)
+    where
+        I: IntoIterator<Item = StreamProtocol>,
+    {
+        self.get(peer).protocols = protocols.into_iter().collect();
+    }
+
+    /// Sets the round-trip time of the last successful ping of the peer.
+    pub fn set_latency(&self, peer: PeerId, rtt: Duration) {
+        self.get(peer).latency = Some(rtt);
+    }
+
     /// Sets peer as trusted.
     pub fn set_trusted(&self, peer: PeerId, is_trusted: bool) {
         let mut peer_info = self.get(peer);
@@ -148,6 +177,11 @@ impl PeerTracker {
         }
     }
 
+    /// Sets peer as banned, so that closing its connections is reported as a ban.
+    pub fn set_banned(&self, peer: PeerId) {
+        self.get(peer).banned = true;
+    }
+
     /// Sets peer as connected.
     pub fn set_connected(
         &self,
@@ -181,7 +215,12 @@ impl PeerTracker {
     /// Sets peer as disconnected if `connection_id` was the last connection.
     ///
     /// Returns `true` if was set to disconnected.
-    pub fn set_maybe_disconnected(&self, peer: PeerId, connection_id: ConnectionId) -> bool {
+    pub fn set_maybe_disconnected(
+        &self,
+        peer: PeerId,
+        connection_id: ConnectionId,
+        cause: Option<&ConnectionError>,
+    ) -> bool {
         let mut peer_info = self.get(peer);
 
         peer_info.connections.retain(|id| *id != connection_id);
@@ -196,9 +235,16 @@ impl PeerTracker {
 
             decrement_connected_peers(&self.info_tx, peer_info.trusted);
 
+            let reason = if peer_info.banned {
+                DisconnectReason::Banned
+            } else {
+                cause.into()
+            };
+
             self.event_pub.send(NodeEvent::PeerDisconnected {
                 id: peer,
                 trusted: peer_info.trusted,
+                reason,
             });
 
             true
@@ -234,6 +280,43 @@ impl PeerTracker {
             .collect()
     }
 
+    /// Returns connected peers which were pinged, with the round-trip time of their last ping.
+    pub fn latencies(&self) -> Vec<(PeerId, Duration)> {
+        self.peers
+            .iter()
+            .filter(|pair| pair.value().is_connected())
+            .filter_map(|pair| Some((pair.key().to_owned(), pair.value().latency?)))
+            .collect()
+    }
+
+    /// Returns connected peers which support the protocol.
+    pub fn peers_supporting(&self, protocol: &str) -> Vec<PeerId> {
+        self.peers
+            .iter()
+            .filter(|pair| {
+                let info = pair.value();
+                info.is_connected() && info.protocols.iter().any(|p| p.as_ref() == protocol)
+            })
+            .map(|pair| pair.key().to_owned())
+            .collect()
+    }
+
+    /// Returns `true` if any connected peer supports all the protocols.
+    pub fn any_peer_supporting_all<P>(&self, protocols: &[P]) -> bool
+    where
+        P: AsRef<str>,
+    {
+        self.peers.iter().any(|pair| {
+            let info = pair.value();
+            info.is_connected()
+                && protocols.iter().all(|protocol| {
+                    info.protocols
+                        .iter()
+                        .any(|p| p.as_ref() == protocol.as_ref())
+                })
+        })
+    }
+
     pub fn connections(&self) -> Vec<(PeerId, SmallVec<[ConnectionId; 1]>)> {
         self.peers
             .iter()
@@ -307,6 +390,8 @@ fn decrement_connected_peers(info_tx: &watch::Sender<PeerTrackerInfo>, trusted:
 
 #[cfg(test)]
 mod tests {
+    use std::io;
+
     use crate::events::EventChannel;
 
     use super::*;
@@ -376,4 +461,128 @@ mod tests {
         assert_eq!(info.num_connected_peers, 1);
         assert_eq!(info.num_connected_trusted_peers, 0);
     }
+
+    #[test]
+    fn disconnect_reason() {
+        let event_channel = EventChannel::new();
+        let mut event_sub = event_channel.subscribe();
+        let tracker = PeerTracker::new(event_channel.publisher());
+        let peer = PeerId::random();
+
+        let causes = [
+            (None, DisconnectReason::Graceful),
+            (
+                Some(ConnectionError::KeepAliveTimeout),
+                DisconnectReason::Timeout,
+            ),
+            (
+                Some(io::Error::from(io::ErrorKind::TimedOut).into()),
+                DisconnectReason::Timeout,
+            ),
+            (
+                Some(io::Error::from(io::ErrorKind::InvalidData).into()),
+                DisconnectReason::ProtocolError,
+            ),
+            (
+                Some(io::Error::from(io::ErrorKind::ConnectionReset).into()),
+                DisconnectReason::Unknown,
+            ),
+        ];
+
+        for (cause, expected) in causes {
+            let connection_id = ConnectionId::new_unchecked(1);
+            tracker.set_connected(peer, connection_id, None);
+            assert!(tracker.set_maybe_disconnected(peer, connection_id, cause.as_ref()));
+
+            let mut reason = None;
+            while let Ok(ev) = event_sub.try_recv() {
+                if let NodeEvent::PeerDisconnected { reason: r, .. } = ev.event {
+                    reason = Some(r);
+                }
+            }
+            assert_eq!(reason, Some(expected));
+        }
+
+        // ban overrides the cause of closing the connection
+        let connection_id = ConnectionId::new_unchecked(2);
+        tracker.set_connected(peer, connection_id, None);
+        tracker.set_banned(peer);
+        assert!(tracker.set_maybe_disconnected(peer, connection_id, None));
+
+        let mut reason = None;
+        while let Ok(ev) = event_sub.try_recv() {
+            if let NodeEvent::PeerDisconnected { reason: r, .. } = ev.event {
+                reason = Some(r);
+            }
+        }
+        assert_eq!(reason, Some(DisconnectReason::Banned));
+    }
+
+    #[test]
+    fn latencies_of_connected_peers() {
+        let event_channel = EventChannel::new();
+        let tracker = PeerTracker::new(event_channel.publisher());
+
+        let pinged = PeerId::random();
+        let unpinged = PeerId::random();
+        let disconnected = PeerId::random();
+
+        for (n, peer) in [pinged, unpinged, disconnected].into_iter().enumerate() {
+            tracker.set_connected(peer, ConnectionId::new_unchecked(n), None);
+        }
+        tracker.set_latency(pinged, Duration::from_millis(20));
+        tracker.set_latency(disconnected, Duration::from_millis(30));
+        tracker.set_maybe_disconnected(disconnected, ConnectionId::new_unchecked(2), None);
+
+        assert_eq!(
+            tracker.latencies(),
+            vec![(pinged, Duration::from_millis(20))]
+        );
+
+        // only the last ping is kept
+        tracker.set_latency(pinged, Duration::from_millis(10));
+        assert_eq!(
+            tracker.latencies(),
+            vec![(pinged, Duration::from_millis(10))]
+        );
+    }
+
+    #[test]
+    fn peers_supporting_protocol() {
+        let event_channel = EventChannel::new();
+        let tracker = PeerTracker::new(event_channel.publisher());
+        let shwap = StreamProtocol::new("/celestia/mocha-4/shwap");
+        let identify = StreamProtocol::new("/ipfs/id/1.0.0");
+
+        let capable = PeerId::random();
+        let incapable = PeerId::random();
+        let unidentified = PeerId::random();
+        let disconnected = PeerId::random();
+
+        for (n, peer) in [capable, incapable, unidentified, disconnected]
+            .into_iter()
+            .enumerate()
+        {
+            tracker.set_connected(peer, ConnectionId::new_unchecked(n), None);
+        }
+        assert!(tracker.peers_supporting(shwap.as_ref()).is_empty());
+
+        // information received from identify
+        tracker.set_protocols(capable, [identify.clone(), shwap.clone()]);
+        tracker.set_protocols(incapable, [identify.clone()]);
+        tracker.set_protocols(disconnected, [identify.clone(), shwap.clone()]);
+        tracker.set_maybe_disconnected(disconnected, ConnectionId::new_unchecked(3), None);
+
+        assert_eq!(tracker.peers_supporting(shwap.as_ref()), vec![capable]);
+        assert_eq!(tracker.peers_supporting("/unknown/1.0.0"), vec![]);
+        assert!(tracker.any_peer_supporting_all(&[identify.as_ref(), shwap.as_ref()]));
+        assert!(!tracker.any_peer_supporting_all(&[shwap.as_ref(), "/unknown/1.0.0"]));
+
+        let mut peers = tracker.peers_supporting(identify.as_ref());
+        peers.sort();
+        let mut expected = vec![capable, incapable];
+        expected.sort();
+        assert_eq!(peers, expected);
+        assert_eq!(tracker.connected_peers().len(), 3);
+    }
 }
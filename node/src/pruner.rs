@@ -3,11 +3,14 @@ use std::time::Duration;
 
 use blockstore::Blockstore;
 use celestia_types::ExtendedHeader;
+use serde::{Deserialize, Serialize};
 use tendermint::Time;
 use tokio::select;
+use tokio::sync::watch;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, warn};
 
+use crate::block_ranges::BlockRanges;
 use crate::events::{EventPublisher, NodeEvent};
 use crate::executor::{sleep, spawn, JoinHandle};
 use crate::p2p::P2pError;
@@ -39,9 +42,20 @@ pub(crate) enum PrunerError {
     WrongHeightRemoved,
 }
 
+/// Headers waiting to be pruned
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PruningBacklog {
+    /// Number of the stored headers older than the pruning window, without the pinned ones.
+    pub pending_height_count: u64,
+    /// Lowest height waiting to be pruned, or `None` if there is nothing to prune.
+    pub oldest_height: Option<u64>,
+}
+
 pub(crate) struct Pruner {
     cancellation_token: CancellationToken,
     join_handle: JoinHandle,
+    backlog: watch::Receiver<PruningBacklog>,
 }
 
 pub(crate) struct PrunerArgs<S, B>
@@ -57,8 +71,8 @@ where
     pub event_pub: EventPublisher,
     /// interval at which pruner will run
     pub pruning_interval: Duration,
-    /// Size of pruning window
-    pub pruning_window: Duration,
+    /// Size of pruning window, or `None` if pruning is disabled
+    pub pruning_window: Option<Duration>,
 }
 
 impl Pruner {
@@ -69,8 +83,9 @@ impl Pruner {
     {
         let cancellation_token = CancellationToken::new();
         let event_pub = args.event_pub.clone();
+        let (backlog_tx, backlog) = watch::channel(PruningBacklog::default());
 
-        let mut worker = Worker::new(args, cancellation_token.child_token());
+        let mut worker = Worker::new(args, cancellation_token.child_token(), backlog_tx);
 
         let join_handle = spawn(async move {
             if let Err(e) = worker.run().await {
@@ -85,9 +100,15 @@ impl Pruner {
         Pruner {
             cancellation_token,
             join_handle,
+            backlog,
         }
     }
 
+    /// Headers waiting to be pruned, as of the latest pruning progress.
+    pub(crate) fn backlog(&self) -> PruningBacklog {
+        *self.backlog.borrow()
+    }
+
     /// Stop the worker.
     pub(crate) fn stop(&self) {
         // Singal the Worker to stop.
@@ -116,7 +137,8 @@ where
     store: Arc<S>,
     blockstore: Arc<B>,
     pruning_interval: Duration,
-    pruning_window: Duration,
+    pruning_window: Option<Duration>,
+    backlog: watch::Sender<PruningBacklog>,
 }
 
 impl<S, B> Worker<S, B>
@@ -124,7 +146,11 @@ where
     S: Store,
     B: Blockstore,
 {
-    fn new(args: PrunerArgs<S, B>, cancellation_token: CancellationToken) -> Self {
+    fn new(
+        args: PrunerArgs<S, B>,
+        cancellation_token: CancellationToken,
+        backlog: watch::Sender<PruningBacklog>,
+    ) -> Self {
         Worker {
             cancellation_token,
             event_pub: args.event_pub,
@@ -132,23 +158,27 @@ where
             blockstore: args.blockstore,
             pruning_interval: args.pruning_interval,
             pruning_window: args.pruning_window,
+            backlog,
         }
     }
 
     async fn run(&mut self) -> Result<()> {
+        let Some(pruning_window) = self.pruning_window else {
+            debug!("Pruning disabled, keeping all the headers");
+            return Ok(());
+        };
+
         let mut last_reported = None;
         let mut last_removed = None;
 
         loop {
-            let pruning_window_end =
-                Time::now()
-                    .checked_sub(self.pruning_window)
-                    .unwrap_or_else(|| {
-                        warn!(
-                        "underflow when computing pruning window start, defaulting to unix epoch"
-                    );
-                        Time::unix_epoch()
-                    });
+            let pruning_window_end = Time::now().checked_sub(pruning_window).unwrap_or_else(|| {
+                warn!("underflow when computing pruning window start, defaulting to unix epoch");
+                Time::unix_epoch()
+            });
+
+            let mut backlog = self.get_backlog(&pruning_window_end).await?;
+            self.backlog.send_replace(backlog);
 
             while let Some(header) = self.get_tail_header_to_prune(&pruning_window_end).await? {
                 if self.cancellation_token.is_cancelled() {
@@ -172,6 +202,14 @@ where
                 }
 
                 last_removed = Some(height);
+
+                backlog.pending_height_count = backlog.pending_height_count.saturating_sub(1);
+                backlog.oldest_height = if backlog.pending_height_count > 0 {
+                    self.get_prunable_ranges().await?.tail()
+                } else {
+                    None
+                };
+                self.backlog.send_replace(backlog);
             }
 
             if last_reported != last_removed {
@@ -191,10 +229,53 @@ where
         Ok(())
     }
 
+    /// Get the ranges of the stored headers which aren't pinned
+    async fn get_prunable_ranges(&self) -> Result<BlockRanges> {
+        let stored_ranges = self.store.get_stored_header_ranges().await?;
+        let pinned_ranges = self.store.get_pinned_ranges().await?;
+
+        Ok(stored_ranges - pinned_ranges)
+    }
+
+    /// Count the stored headers produced at or before the cutoff
+    async fn get_backlog(&self, cutoff: &Time) -> Result<PruningBacklog> {
+        let mut backlog = PruningBacklog::default();
+
+        for range in self.get_prunable_ranges().await?.into_inner() {
+            let (start, end) = (*range.start(), *range.end());
+
+            if &self.store.get_by_height(start).await?.time() > cutoff {
+                break;
+            }
+            backlog.oldest_height.get_or_insert(start);
+
+            if &self.store.get_by_height(end).await?.time() <= cutoff {
+                backlog.pending_height_count += end - start + 1;
+                continue;
+            }
+
+            // Header times increase with heights, so the edge of the pruning window can
+            // be searched for. `low` is always pruned and `high` never is.
+            let (mut low, mut high) = (start, end);
+            while high - low > 1 {
+                let mid = low + (high - low) / 2;
+                if &self.store.get_by_height(mid).await?.time() <= cutoff {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            backlog.pending_height_count += low - start + 1;
+            break;
+        }
+
+        Ok(backlog)
+    }
+
     /// Get oldest header from the store to be pruned or None if there's nothing to prune
     async fn get_tail_header_to_prune(&self, cutoff: &Time) -> Result<Option<ExtendedHeader>> {
-        let Some(current_tail_height) = self.store.get_stored_header_ranges().await?.tail() else {
-            // empty store == nothing to prune
+        let Some(current_tail_height) = self.get_prunable_ranges().await?.tail() else {
+            // empty store or only pinned headers == nothing to prune
             return Ok(None);
         };
 
@@ -241,7 +322,7 @@ mod test {
             blockstore,
             event_pub: events.publisher(),
             pruning_interval: Duration::from_secs(1),
-            pruning_window: TEST_PRUNING_WINDOW,
+            pruning_window: Some(TEST_PRUNING_WINDOW),
         });
 
         sleep(Duration::from_secs(1)).await;
@@ -269,7 +350,7 @@ mod test {
             blockstore,
             event_pub: events.publisher(),
             pruning_interval: Duration::from_secs(1),
-            pruning_window: TEST_PRUNING_WINDOW,
+            pruning_window: Some(TEST_PRUNING_WINDOW),
         });
 
         sleep(Duration::from_secs(1)).await;
@@ -329,7 +410,7 @@ mod test {
             blockstore: blockstore.clone(),
             event_pub: events.publisher(),
             pruning_interval: Duration::from_secs(1),
-            pruning_window: TEST_PRUNING_WINDOW,
+            pruning_window: Some(TEST_PRUNING_WINDOW),
         });
 
         sleep(Duration::from_secs(1)).await;
@@ -357,6 +438,112 @@ mod test {
         ));
     }
 
+    #[async_test]
+    async fn backlog_shrinks_after_pruning() {
+        const BLOCK_TIME: Duration = Duration::from_millis(10);
+
+        let events = EventChannel::new();
+        let store = Arc::new(InMemoryStore::new());
+        let mut gen = ExtendedHeaderGenerator::new();
+        let blockstore = Arc::new(InMemoryBlockstore::new());
+        let mut event_subscriber = events.subscribe();
+
+        // 50 headers before pruning window edge
+        let before_pruning_edge = (Time::now() - (TEST_PRUNING_WINDOW + BLOCK_TIME * 100)).unwrap();
+        gen.set_time(before_pruning_edge, BLOCK_TIME);
+        store.insert(gen.next_many_verified(50)).await.unwrap();
+
+        // 10 headers at current time
+        gen.set_time(Time::now(), BLOCK_TIME);
+        store.insert(gen.next_many_verified(10)).await.unwrap();
+
+        let worker = Worker::new(
+            PrunerArgs {
+                store: store.clone(),
+                blockstore: blockstore.clone(),
+                event_pub: events.publisher(),
+                pruning_interval: Duration::from_secs(1),
+                pruning_window: Some(TEST_PRUNING_WINDOW),
+            },
+            CancellationToken::new(),
+            watch::channel(PruningBacklog::default()).0,
+        );
+        let cutoff = (Time::now() - TEST_PRUNING_WINDOW).unwrap();
+        assert_eq!(
+            worker.get_backlog(&cutoff).await.unwrap(),
+            PruningBacklog {
+                pending_height_count: 50,
+                oldest_height: Some(1),
+            }
+        );
+
+        let pruner = Pruner::start(PrunerArgs {
+            store: store.clone(),
+            blockstore,
+            event_pub: events.publisher(),
+            pruning_interval: Duration::from_secs(1),
+            pruning_window: Some(TEST_PRUNING_WINDOW),
+        });
+
+        let pruner_event = event_subscriber.recv().await.unwrap().event;
+        assert!(matches!(
+            pruner_event,
+            NodeEvent::PrunedHeaders { to_height: 50 }
+        ));
+        assert_eq!(pruner.backlog(), PruningBacklog::default());
+
+        pruner.stop();
+    }
+
+    #[async_test]
+    async fn pinned_height_survives_pruning() {
+        const BLOCK_TIME: Duration = Duration::from_millis(10);
+
+        let events = EventChannel::new();
+        let store = Arc::new(InMemoryStore::new());
+        let mut gen = ExtendedHeaderGenerator::new();
+        let blockstore = Arc::new(InMemoryBlockstore::new());
+        let mut event_subscriber = events.subscribe();
+
+        // 50 headers before pruning window edge
+        let before_pruning_edge = (Time::now() - (TEST_PRUNING_WINDOW + BLOCK_TIME * 100)).unwrap();
+        gen.set_time(before_pruning_edge, BLOCK_TIME);
+        store.insert(gen.next_many_verified(50)).await.unwrap();
+        store.pin_height(25).await.unwrap();
+
+        let pruner = Pruner::start(PrunerArgs {
+            store: store.clone(),
+            blockstore,
+            event_pub: events.publisher(),
+            pruning_interval: Duration::from_secs(1),
+            pruning_window: Some(TEST_PRUNING_WINDOW),
+        });
+
+        let pruner_event = event_subscriber.recv().await.unwrap().event;
+        assert!(matches!(
+            pruner_event,
+            NodeEvent::PrunedHeaders { to_height: 50 }
+        ));
+        assert_eq!(
+            store.get_stored_header_ranges().await.unwrap(),
+            new_block_ranges([25..=25])
+        );
+
+        // head advances, while the pinned height stays
+        gen.set_time(Time::now(), BLOCK_TIME);
+        store.insert(gen.next_many_verified(10)).await.unwrap();
+
+        sleep(Duration::from_secs(2)).await;
+
+        assert_eq!(
+            store.get_stored_header_ranges().await.unwrap(),
+            new_block_ranges([25..=25, 51..=60])
+        );
+        assert_eq!(pruner.backlog(), PruningBacklog::default());
+
+        pruner.stop();
+    }
+
     #[async_test]
     async fn prune_tail() {
         const BLOCK_TIME: Duration = Duration::from_millis(10);
@@ -386,7 +573,7 @@ mod test {
             blockstore,
             event_pub: events.publisher(),
             pruning_interval: Duration::from_secs(1),
-            pruning_window: TEST_PRUNING_WINDOW,
+            pruning_window: Some(TEST_PRUNING_WINDOW),
         });
 
         sleep(Duration::from_secs(1)).await;
@@ -424,6 +611,49 @@ mod test {
         );
     }
 
+    #[async_test]
+    async fn pruning_disabled() {
+        const BLOCK_TIME: Duration = Duration::from_millis(10);
+
+        let events = EventChannel::new();
+        let store = Arc::new(InMemoryStore::new());
+        let mut gen = ExtendedHeaderGenerator::new();
+        let blockstore = Arc::new(InMemoryBlockstore::new());
+        let mut event_subscriber = events.subscribe();
+
+        // 50 headers way past the pruning window edge
+        let before_pruning_edge = (Time::now() - (TEST_PRUNING_WINDOW * 2)).unwrap();
+        gen.set_time(before_pruning_edge, BLOCK_TIME);
+        store.insert(gen.next_many_verified(50)).await.unwrap();
+
+        let pruner = Pruner::start(PrunerArgs {
+            store: store.clone(),
+            blockstore,
+            event_pub: events.publisher(),
+            pruning_interval: Duration::from_millis(100),
+            pruning_window: None,
+        });
+
+        // head advances while the pruner would run a few times
+        for _ in 0..10 {
+            gen.set_time(Time::now(), BLOCK_TIME);
+            store.insert(gen.next_many_verified(1)).await.unwrap();
+            sleep(Duration::from_millis(100)).await;
+        }
+
+        pruner.stop();
+        pruner.join().await;
+
+        assert!(matches!(
+            event_subscriber.try_recv().unwrap_err(),
+            TryRecvError::Empty
+        ));
+        assert_eq!(
+            store.get_stored_header_ranges().await.unwrap(),
+            new_block_ranges([1..=60])
+        );
+    }
+
     #[derive(Debug, PartialEq, Clone, Copy)]
     struct TestBlock(pub [u8; 8]);
 
@@ -4,6 +4,7 @@ use std::convert::Infallible;
 use std::fmt::{Debug, Display};
 use std::io::Cursor;
 use std::ops::{Bound, RangeBounds, RangeInclusive};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use celestia_types::hash::Hash;
@@ -195,13 +196,74 @@ pub trait Store: Send + Sync + Debug {
     /// Returns a list of accepted sampling ranges currently held in store.
     async fn get_accepted_sampling_ranges(&self) -> Result<BlockRanges>;
 
-    /// Remove header with lowest height from the store.
+    /// Remove the header with the lowest height which isn't pinned from the store.
     async fn remove_last(&self) -> Result<u64>;
 
+    /// Pin the height, so that its header is never removed with [`Store::remove_last`].
+    ///
+    /// Heights can be pinned regardless of whether their headers are in the store.
+    async fn pin_height(&self, height: u64) -> Result<()>;
+
+    /// Unpin the height, allowing its header to be removed again.
+    async fn unpin_height(&self, height: u64) -> Result<()>;
+
+    /// Returns a list of pinned height ranges.
+    async fn get_pinned_ranges(&self) -> Result<BlockRanges>;
+
+    /// Make all the writes done so far durable, without closing the store.
+    ///
+    /// The default implementation does nothing, which is correct for stores that
+    /// persist every write once it's committed.
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Returns the statistics of the durations of the recent write transactions, or `None`
+    /// if the store doesn't measure them.
+    async fn write_latency_stats(&self) -> Option<LatencyStats> {
+        None
+    }
+
     /// Close store.
     async fn close(self) -> Result<()>;
 }
 
+/// Statistics of the durations of the recent operations, in milliseconds.
+#[cfg_attr(feature = "uniffi", derive(uniffi::Record))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    /// Median duration.
+    pub p50: f64,
+    /// 95th percentile of the durations.
+    pub p95: f64,
+    /// 99th percentile of the durations.
+    pub p99: f64,
+    /// Longest duration.
+    pub max: f64,
+}
+
+impl LatencyStats {
+    /// Compute the statistics of the durations, all zero if there are none.
+    pub(crate) fn from_durations(mut durations: Vec<Duration>) -> Self {
+        durations.sort_unstable();
+
+        // nearest-rank percentile
+        let percentile = |p: usize| {
+            let rank = (durations.len() * p).div_ceil(100).max(1);
+            durations
+                .get(rank - 1)
+                .map_or(0.0, |duration| duration.as_secs_f64() * 1000.0)
+        };
+
+        LatencyStats {
+            p50: percentile(50),
+            p95: percentile(95),
+            p99: percentile(99),
+            max: percentile(100),
+        }
+    }
+}
+
 /// Representation of all the errors that can occur when interacting with the [`Store`].
 #[derive(Error, Debug)]
 pub enum StoreError {
@@ -392,6 +454,26 @@ mod tests {
     use crate::test_utils::async_test as test;
     use crate::test_utils::new_block_ranges;
 
+    #[test]
+    async fn latency_percentiles() {
+        let durations = (1..=200).rev().map(Duration::from_millis).collect();
+        let stats = LatencyStats::from_durations(durations);
+        assert_eq!(
+            stats,
+            LatencyStats {
+                p50: 100.0,
+                p95: 190.0,
+                p99: 198.0,
+                max: 200.0,
+            }
+        );
+
+        assert_eq!(
+            LatencyStats::from_durations(Vec::new()),
+            LatencyStats::default()
+        );
+    }
+
     #[test]
     async fn converts_bounded_ranges() {
         assert_eq!(1..=15, to_headers_range(1..16, 100).unwrap());
@@ -1150,6 +1232,42 @@ mod tests {
         }
     }
 
+    #[rstest]
+    #[case::in_memory(new_in_memory_store())]
+    #[cfg_attr(not(target_arch = "wasm32"), case::redb(new_redb_store()))]
+    #[cfg_attr(target_arch = "wasm32", case::indexed_db(new_indexed_db_store()))]
+    #[self::test]
+    async fn tail_removal_skips_pinned<S: Store>(
+        #[case]
+        #[future(awt)]
+        s: S,
+    ) {
+        let store = s;
+        let headers = ExtendedHeaderGenerator::new().next_many(10);
+
+        store.insert(&headers[..]).await.unwrap();
+        store.pin_height(1).await.unwrap();
+        store.pin_height(3).await.unwrap();
+        store.pin_height(20).await.unwrap();
+        assert_eq!(
+            store.get_pinned_ranges().await.unwrap(),
+            new_block_ranges([1..=1, 3..=3, 20..=20])
+        );
+
+        assert_eq!(store.remove_last().await.unwrap(), 2);
+        assert_eq!(store.remove_last().await.unwrap(), 4);
+        assert_store(&store, &headers, new_block_ranges([1..=1, 3..=3, 5..=10])).await;
+
+        store.unpin_height(1).await.unwrap();
+        assert_eq!(store.remove_last().await.unwrap(), 1);
+        assert_store(&store, &headers, new_block_ranges([3..=3, 5..=10])).await;
+
+        assert!(matches!(
+            store.pin_height(0).await.unwrap_err(),
+            StoreError::InsertionFailed(StoreInsertionError::ContraintsNotMet(_))
+        ));
+    }
+
     /// Fills an empty store
     async fn fill_store<S: Store>(store: &mut S, amount: u64) -> ExtendedHeaderGenerator {
         assert!(!store.has_at(1).await, "Store is not empty");
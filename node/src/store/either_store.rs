@@ -7,7 +7,8 @@ use celestia_types::ExtendedHeader;
 use cid::Cid;
 
 use crate::store::{
-    BlockRanges, Result, SamplingMetadata, SamplingStatus, Store, VerifiedExtendedHeaders,
+    BlockRanges, LatencyStats, Result, SamplingMetadata, SamplingStatus, Store,
+    VerifiedExtendedHeaders,
 };
 
 /// Struct that can be used to build combinations of different [`Store`] types.
@@ -237,6 +238,26 @@ where
         call!(self, remove_last())
     }
 
+    async fn pin_height(&self, height: u64) -> Result<()> {
+        call!(self, pin_height(height))
+    }
+
+    async fn unpin_height(&self, height: u64) -> Result<()> {
+        call!(self, unpin_height(height))
+    }
+
+    async fn get_pinned_ranges(&self) -> Result<BlockRanges> {
+        call!(self, get_pinned_ranges())
+    }
+
+    async fn flush(&self) -> Result<()> {
+        call!(self, flush())
+    }
+
+    async fn write_latency_stats(&self) -> Option<LatencyStats> {
+        call!(self, write_latency_stats())
+    }
+
     async fn close(self) -> Result<()> {
         call!(self, close())
     }
@@ -37,6 +37,8 @@ struct InMemoryStoreInner {
     sampling_data: HashMap<u64, SamplingMetadata>,
     /// Source of truth about accepted sampling ranges present in the db.
     accepted_sampling_ranges: BlockRanges,
+    /// Heights which are never removed from the db.
+    pinned_ranges: BlockRanges,
 }
 
 impl InMemoryStoreInner {
@@ -47,6 +49,7 @@ impl InMemoryStoreInner {
             header_ranges: BlockRanges::default(),
             sampling_data: HashMap::new(),
             accepted_sampling_ranges: BlockRanges::default(),
+            pinned_ranges: BlockRanges::default(),
         }
     }
 }
@@ -138,6 +141,28 @@ impl InMemoryStore {
         let mut inner = self.inner.write().await;
         inner.remove_last()
     }
+
+    async fn pin_height(&self, height: u64) -> Result<()> {
+        self.inner
+            .write()
+            .await
+            .pinned_ranges
+            .insert_relaxed(height..=height)
+            .map_err(|e| StoreInsertionError::ContraintsNotMet(e).into())
+    }
+
+    async fn unpin_height(&self, height: u64) -> Result<()> {
+        self.inner
+            .write()
+            .await
+            .pinned_ranges
+            .remove_relaxed(height..=height)
+            .map_err(|e| StoreInsertionError::ContraintsNotMet(e).into())
+    }
+
+    async fn get_pinned_ranges(&self) -> BlockRanges {
+        self.inner.read().await.pinned_ranges.clone()
+    }
 }
 
 impl InMemoryStoreInner {
@@ -308,7 +333,8 @@ impl InMemoryStoreInner {
     }
 
     fn remove_last(&mut self) -> Result<u64> {
-        let Some(height) = self.header_ranges.tail() else {
+        let removable_ranges = self.header_ranges.clone() - &self.pinned_ranges;
+        let Some(height) = removable_ranges.tail() else {
             return Err(StoreError::NotFound);
         };
 
@@ -331,7 +357,9 @@ impl InMemoryStoreInner {
         height_to_hash.remove_entry();
         header.remove_entry();
 
-        self.header_ranges.pop_tail();
+        self.header_ranges
+            .remove_relaxed(height..=height)
+            .expect("invalid height");
 
         Ok(height)
     }
@@ -431,6 +459,18 @@ impl Store for InMemoryStore {
         self.remove_last().await
     }
 
+    async fn pin_height(&self, height: u64) -> Result<()> {
+        self.pin_height(height).await
+    }
+
+    async fn unpin_height(&self, height: u64) -> Result<()> {
+        self.unpin_height(height).await
+    }
+
+    async fn get_pinned_ranges(&self) -> Result<BlockRanges> {
+        Ok(self.get_pinned_ranges().await)
+    }
+
     async fn close(self) -> Result<()> {
         Ok(())
     }
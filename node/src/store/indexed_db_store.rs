@@ -38,6 +38,7 @@ const HEIGHT_INDEX_NAME: &str = "height";
 
 const ACCEPTED_SAMPLING_RANGES_KEY: &str = "accepted_sampling_ranges";
 const HEADER_RANGES_KEY: &str = "header_ranges";
+const PINNED_RANGES_KEY: &str = "pinned_ranges";
 const VERSION_KEY: &str = "version";
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -304,6 +305,20 @@ impl IndexedDbStore {
         )
         .await
     }
+
+    async fn set_pinned(&self, height: u64, pinned: bool) -> Result<()> {
+        self.write_tx(&[RANGES_STORE_NAME], set_pinned_tx_op, (height, pinned))
+            .await
+    }
+
+    async fn get_pinned_ranges(&self) -> Result<BlockRanges> {
+        let tx = self
+            .db
+            .transaction(&[RANGES_STORE_NAME], TransactionMode::ReadOnly)?;
+        let store = tx.store(RANGES_STORE_NAME)?;
+
+        get_ranges(&store, PINNED_RANGES_KEY).await
+    }
 }
 
 trait TransactionOperationFn<'a, Arg>:
@@ -427,6 +442,21 @@ impl Store for IndexedDbStore {
         fut.await
     }
 
+    async fn pin_height(&self, height: u64) -> Result<()> {
+        let fut = SendWrapper::new(self.set_pinned(height, true));
+        fut.await
+    }
+
+    async fn unpin_height(&self, height: u64) -> Result<()> {
+        let fut = SendWrapper::new(self.set_pinned(height, false));
+        fut.await
+    }
+
+    async fn get_pinned_ranges(&self) -> Result<BlockRanges> {
+        let fut = SendWrapper::new(self.get_pinned_ranges());
+        fut.await
+    }
+
     async fn close(self) -> Result<()> {
         self.db.take().close();
         Ok(())
@@ -710,10 +740,14 @@ async fn remove_last_tx_op(tx: &Transaction, _: ()) -> Result<u64> {
     let ranges_store = tx.store(RANGES_STORE_NAME)?;
 
     let mut header_ranges = get_ranges(&ranges_store, HEADER_RANGES_KEY).await?;
+    let pinned_ranges = get_ranges(&ranges_store, PINNED_RANGES_KEY).await?;
 
-    let Some(height) = header_ranges.pop_tail() else {
+    let Some(height) = (header_ranges.clone() - &pinned_ranges).tail() else {
         return Err(StoreError::NotFound);
     };
+    header_ranges
+        .remove_relaxed(height..=height)
+        .expect("invalid height");
     set_ranges(&ranges_store, HEADER_RANGES_KEY, &header_ranges).await?;
 
     let jsvalue_height = to_value(&height).expect("to create jsvalue");
@@ -731,6 +765,21 @@ async fn remove_last_tx_op(tx: &Transaction, _: ()) -> Result<u64> {
     Ok(height)
 }
 
+async fn set_pinned_tx_op(tx: &Transaction, (height, pinned): (u64, bool)) -> Result<()> {
+    let ranges_store = tx.store(RANGES_STORE_NAME)?;
+
+    let mut pinned_ranges = get_ranges(&ranges_store, PINNED_RANGES_KEY).await?;
+
+    if pinned {
+        pinned_ranges.insert_relaxed(height..=height)
+    } else {
+        pinned_ranges.remove_relaxed(height..=height)
+    }
+    .map_err(StoreInsertionError::ContraintsNotMet)?;
+
+    set_ranges(&ranges_store, PINNED_RANGES_KEY, &pinned_ranges).await
+}
+
 async fn migrate_older_to_v4(db: &Rexie) -> Result<()> {
     let Some(version) = detect_schema_version(db).await? else {
         // New database.
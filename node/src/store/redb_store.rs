@@ -1,16 +1,18 @@
+use std::collections::VecDeque;
 use std::fmt::Display;
 use std::ops::RangeInclusive;
 use std::path::Path;
 use std::pin::pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use celestia_types::hash::Hash;
 use celestia_types::ExtendedHeader;
 use cid::Cid;
 use redb::{
-    CommitError, Database, ReadTransaction, ReadableTable, StorageError, Table, TableDefinition,
-    TableError, TransactionError, WriteTransaction,
+    CommitError, Database, Durability, ReadTransaction, ReadableTable, StorageError, Table,
+    TableDefinition, TableError, TransactionError, WriteTransaction,
 };
 use tendermint_proto::Protobuf;
 use tokio::sync::Notify;
@@ -21,7 +23,7 @@ use tracing::{debug, trace};
 use crate::block_ranges::BlockRanges;
 use crate::store::utils::VerifiedExtendedHeaders;
 use crate::store::{
-    Result, SamplingMetadata, SamplingStatus, Store, StoreError, StoreInsertionError,
+    LatencyStats, Result, SamplingMetadata, SamplingStatus, Store, StoreError, StoreInsertionError,
 };
 use crate::utils::Counter;
 
@@ -29,6 +31,9 @@ use super::utils::{deserialize_extended_header, deserialize_sampling_metadata};
 
 const SCHEMA_VERSION: u64 = 2;
 
+/// Number of the most recent write transactions kept for the latency statistics.
+const WRITE_LATENCIES_CAPACITY: usize = 1024;
+
 const HEIGHTS_TABLE: TableDefinition<'static, &[u8], u64> = TableDefinition::new("STORE.HEIGHTS");
 const HEADERS_TABLE: TableDefinition<'static, u64, &[u8]> = TableDefinition::new("STORE.HEADERS");
 const SAMPLING_METADATA_TABLE: TableDefinition<'static, u64, &[u8]> =
@@ -40,6 +45,7 @@ const RANGES_TABLE: TableDefinition<'static, &str, Vec<(u64, u64)>> =
 
 const ACCEPTED_SAMPING_RANGES_KEY: &str = "KEY.ACCEPTED_SAMPING_RANGES";
 const HEADER_RANGES_KEY: &str = "KEY.HEADER_RANGES";
+const PINNED_RANGES_KEY: &str = "KEY.PINNED_RANGES";
 
 /// A [`Store`] implementation based on a [`redb`] database.
 #[derive(Debug)]
@@ -54,9 +60,26 @@ struct Inner {
     db: Arc<Database>,
     /// Notify when a new header is added
     header_added_notifier: Notify,
+    /// Durations of the most recent write transactions
+    write_latencies: Mutex<VecDeque<Duration>>,
+}
+
+impl Inner {
+    /// Record the duration of the write transaction, dropping the oldest one if full.
+    fn record_write_latency(&self, latency: Duration) {
+        let mut latencies = self.write_latencies.lock().expect("poisoned lock");
+
+        if latencies.len() == WRITE_LATENCIES_CAPACITY {
+            latencies.pop_front();
+        }
+        latencies.push_back(latency);
+    }
 }
 
 impl RedbStore {
+    /// Version of the schema of the databases written by the store.
+    pub const SCHEMA_VERSION: u64 = SCHEMA_VERSION;
+
     /// Open a persistent [`redb`] store.
     pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
         let path = path.as_ref().to_owned();
@@ -83,6 +106,7 @@ impl RedbStore {
             inner: Arc::new(Inner {
                 db,
                 header_added_notifier: Notify::new(),
+                write_latencies: Mutex::new(VecDeque::with_capacity(WRITE_LATENCIES_CAPACITY)),
             }),
             task_counter: Counter::new(),
         };
@@ -178,6 +202,7 @@ impl RedbStore {
             let _guard = guard;
 
             {
+                let started = Instant::now();
                 let mut tx = inner.db.begin_write()?;
                 let res = f(&mut tx);
 
@@ -187,6 +212,7 @@ impl RedbStore {
                     tx.abort()?;
                 }
 
+                inner.record_write_latency(started.elapsed());
                 res
             }
         })
@@ -427,10 +453,14 @@ impl RedbStore {
             let mut ranges_table = tx.open_table(RANGES_TABLE)?;
 
             let mut header_ranges = get_ranges(&ranges_table, HEADER_RANGES_KEY)?;
+            let pinned_ranges = get_ranges(&ranges_table, PINNED_RANGES_KEY)?;
 
-            let Some(height) = header_ranges.pop_tail() else {
+            let Some(height) = (header_ranges.clone() - &pinned_ranges).tail() else {
                 return Err(StoreError::NotFound);
             };
+            header_ranges
+                .remove_relaxed(height..=height)
+                .expect("invalid height");
             set_ranges(&mut ranges_table, HEADER_RANGES_KEY, &header_ranges)?;
 
             let Some(header) = headers_table.remove(height)? else {
@@ -453,6 +483,40 @@ impl RedbStore {
         })
         .await
     }
+
+    async fn pin_height(&self, height: u64) -> Result<()> {
+        self.write_tx(move |tx| {
+            let mut ranges_table = tx.open_table(RANGES_TABLE)?;
+            let mut pinned_ranges = get_ranges(&ranges_table, PINNED_RANGES_KEY)?;
+
+            pinned_ranges
+                .insert_relaxed(height..=height)
+                .map_err(StoreInsertionError::ContraintsNotMet)?;
+            set_ranges(&mut ranges_table, PINNED_RANGES_KEY, &pinned_ranges)
+        })
+        .await
+    }
+
+    async fn unpin_height(&self, height: u64) -> Result<()> {
+        self.write_tx(move |tx| {
+            let mut ranges_table = tx.open_table(RANGES_TABLE)?;
+            let mut pinned_ranges = get_ranges(&ranges_table, PINNED_RANGES_KEY)?;
+
+            pinned_ranges
+                .remove_relaxed(height..=height)
+                .map_err(StoreInsertionError::ContraintsNotMet)?;
+            set_ranges(&mut ranges_table, PINNED_RANGES_KEY, &pinned_ranges)
+        })
+        .await
+    }
+
+    async fn get_pinned_ranges(&self) -> Result<BlockRanges> {
+        self.read_tx(|tx| {
+            let table = tx.open_table(RANGES_TABLE)?;
+            get_ranges(&table, PINNED_RANGES_KEY)
+        })
+        .await
+    }
 }
 
 #[async_trait]
@@ -549,6 +613,38 @@ impl Store for RedbStore {
         self.remove_last().await
     }
 
+    async fn pin_height(&self, height: u64) -> Result<()> {
+        self.pin_height(height).await
+    }
+
+    async fn unpin_height(&self, height: u64) -> Result<()> {
+        self.unpin_height(height).await
+    }
+
+    async fn get_pinned_ranges(&self) -> Result<BlockRanges> {
+        self.get_pinned_ranges().await
+    }
+
+    async fn flush(&self) -> Result<()> {
+        // Write transactions of the store are committed with the default
+        // `Durability::Immediate` of redb, which fsyncs the database before the commit
+        // returns, so the committed writes are already durable. Committing an empty one
+        // waits for the ongoing write transaction, as they're serialized by redb, and
+        // persists any non-durable commit made before it.
+        self.write_tx(|tx| {
+            tx.set_durability(Durability::Immediate);
+            Ok(())
+        })
+        .await
+    }
+
+    async fn write_latency_stats(&self) -> Option<LatencyStats> {
+        let latencies = self.inner.write_latencies.lock().expect("poisoned lock");
+        Some(LatencyStats::from_durations(
+            latencies.iter().copied().collect(),
+        ))
+    }
+
     async fn close(mut self) -> Result<()> {
         // Wait all ongoing `spawn_blocking` tasks to finish.
         self.task_counter.wait_guards().await;
@@ -747,11 +843,72 @@ fn migrate_v1_to_v2(
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::test_utils::ExtendedHeaderGeneratorExt;
+    use crate::test_utils::{new_block_ranges, ExtendedHeaderGeneratorExt};
     use celestia_types::test_utils::ExtendedHeaderGenerator;
     use std::path::Path;
     use tempfile::TempDir;
 
+    #[tokio::test]
+    async fn write_latency_recorded() {
+        let (store, mut gen) = gen_filled_store(0, None).await;
+        let recorded = || store.inner.write_latencies.lock().unwrap().len();
+        let before = recorded();
+
+        for _ in 0..20 {
+            store.insert(gen.next_many_verified(5)).await.unwrap();
+        }
+        assert_eq!(recorded(), before + 20);
+
+        let stats = store.write_latency_stats().await.unwrap();
+        assert!(stats.max > 0.0);
+        assert!(stats.p50 <= stats.p95 && stats.p95 <= stats.p99 && stats.p99 <= stats.max);
+    }
+
+    #[tokio::test]
+    async fn flushed_headers_persist_without_close() {
+        let db_dir = TempDir::with_prefix("lumina.store.test").unwrap();
+        let db = db_dir.path().join("db");
+
+        let (store, mut gen) = gen_filled_store(0, Some(&db)).await;
+        let headers = gen.next_many(10);
+
+        store.insert(headers.clone()).await.unwrap();
+        store.flush().await.unwrap();
+        // simulate a crash, taking the file as it's on the disk while the store is open,
+        // without it being closed or dropped
+        let crashed_db = db_dir.path().join("crashed-db");
+        std::fs::copy(&db, &crashed_db).unwrap();
+
+        let reopened_store = create_store(Some(&crashed_db)).await;
+
+        assert_eq!(reopened_store.head_height().await.unwrap(), 10);
+        for header in &headers {
+            let stored_header = reopened_store
+                .get_by_height(header.height().value())
+                .await
+                .unwrap();
+            assert_eq!(header, &stored_header);
+        }
+    }
+
+    #[tokio::test]
+    async fn pinned_heights_persist() {
+        let db_dir = TempDir::with_prefix("lumina.store.test").unwrap();
+        let db = db_dir.path().join("db");
+
+        let (store, _) = gen_filled_store(10, Some(&db)).await;
+        store.pin_height(1).await.unwrap();
+        store.pin_height(5).await.unwrap();
+        drop(store);
+
+        let reopened_store = create_store(Some(&db)).await;
+        assert_eq!(
+            reopened_store.get_pinned_ranges().await.unwrap(),
+            new_block_ranges([1..=1, 5..=5])
+        );
+        assert_eq!(reopened_store.remove_last().await.unwrap(), 2);
+    }
+
     #[tokio::test]
     async fn test_store_persistence() {
         let db_dir = TempDir::with_prefix("lumina.store.test").unwrap();
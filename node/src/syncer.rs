@@ -16,6 +16,7 @@ use std::time::Duration;
 
 use backoff::backoff::Backoff;
 use backoff::ExponentialBackoffBuilder;
+use celestia_types::hash::Hash;
 use celestia_types::ExtendedHeader;
 use serde::{Deserialize, Serialize};
 use tendermint::Time;
@@ -28,6 +29,7 @@ use web_time::Instant;
 use crate::block_ranges::{BlockRange, BlockRangeExt, BlockRanges};
 use crate::events::{EventPublisher, NodeEvent};
 use crate::executor::{sleep, spawn, Interval, JoinHandle};
+use crate::p2p::header_sub::{self, HeaderSubOverflow, HeaderSubReceiver};
 use crate::p2p::{P2p, P2pError};
 use crate::store::{Store, StoreError};
 use crate::utils::{FusedReusableFuture, OneshotSenderExt};
@@ -99,6 +101,10 @@ where
     pub(crate) batch_size: u64,
     /// Syncing window
     pub(crate) syncing_window: Duration,
+    /// Number of headers buffered between header-sub and syncer.
+    pub(crate) header_sub_buffer: usize,
+    /// What to do with headers from header-sub when the buffer is full.
+    pub(crate) header_sub_overflow: HeaderSubOverflow,
 }
 
 #[derive(Debug)]
@@ -106,6 +112,19 @@ enum SyncerCmd {
     GetInfo {
         respond_to: oneshot::Sender<SyncingInfo>,
     },
+    SetPaused {
+        paused: bool,
+        respond_to: oneshot::Sender<()>,
+    },
+    IsPaused {
+        respond_to: oneshot::Sender<bool>,
+    },
+    GetInFlight {
+        respond_to: oneshot::Sender<Vec<BlockRange>>,
+    },
+    GetLastReorg {
+        respond_to: oneshot::Sender<Option<ReorgInfo>>,
+    },
 }
 
 /// Status of the synchronization.
@@ -115,6 +134,43 @@ pub struct SyncingInfo {
     pub stored_headers: BlockRanges,
     /// Syncing target. The latest height seen in the network that was successfully verified.
     pub subjective_head: u64,
+    /// Number of headers from header-sub dropped because syncer didn't keep up with them.
+    pub dropped_header_sub_headers: u64,
+}
+
+impl SyncingInfo {
+    /// Get what changed since the `previous` snapshot of the status.
+    pub fn diff(&self, previous: &SyncingInfo) -> SyncProgressDelta {
+        SyncProgressDelta {
+            added: self.stored_headers.clone() - &previous.stored_headers,
+            removed: previous.stored_headers.clone() - &self.stored_headers,
+            head_advance: self
+                .subjective_head
+                .saturating_sub(previous.subjective_head),
+        }
+    }
+}
+
+/// Change of the [`SyncingInfo`] between two snapshots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncProgressDelta {
+    /// Ranges of headers synchronised since the previous snapshot.
+    pub added: BlockRanges,
+    /// Ranges of headers removed since the previous snapshot, e.g. pruned.
+    pub removed: BlockRanges,
+    /// Number of heights the syncing target advanced by.
+    pub head_advance: u64,
+}
+
+/// Header received for an already synchronised height, conflicting with the stored one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReorgInfo {
+    /// Height of the conflicting headers.
+    pub height: u64,
+    /// Hash of the header in the store.
+    pub old_hash: Hash,
+    /// Hash of the newly received header.
+    pub new_hash: Hash,
 }
 
 impl<S> Syncer<S>
@@ -177,6 +233,70 @@ where
 
         Ok(rx.await?)
     }
+
+    /// Pause or resume the synchronization of headers.
+    ///
+    /// While paused, no headers are requested from the network and the ongoing batch
+    /// is cancelled, reported with [`NodeEvent::FetchingHeadersFailed`]. Headers announced
+    /// on header-sub still update the subjective head, so after resuming the syncer
+    /// catches up with the latest one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the [`Syncer`] has been stopped.
+    pub(crate) async fn set_paused(&self, paused: bool) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+
+        self.send_command(SyncerCmd::SetPaused {
+            paused,
+            respond_to: tx,
+        })
+        .await?;
+
+        Ok(rx.await?)
+    }
+
+    /// Returns `true` if the synchronization of headers is paused.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the [`Syncer`] has been stopped.
+    pub(crate) async fn is_paused(&self) -> Result<bool> {
+        let (tx, rx) = oneshot::channel();
+
+        self.send_command(SyncerCmd::IsPaused { respond_to: tx })
+            .await?;
+
+        Ok(rx.await?)
+    }
+
+    /// Get the ranges of headers which are currently being fetched from the network.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the [`Syncer`] has been stopped.
+    pub(crate) async fn in_flight_fetches(&self) -> Result<Vec<BlockRange>> {
+        let (tx, rx) = oneshot::channel();
+
+        self.send_command(SyncerCmd::GetInFlight { respond_to: tx })
+            .await?;
+
+        Ok(rx.await?)
+    }
+
+    /// Get the last header received that conflicted with an already synchronised one.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the [`Syncer`] has been stopped.
+    pub(crate) async fn last_reorg(&self) -> Result<Option<ReorgInfo>> {
+        let (tx, rx) = oneshot::channel();
+
+        self.send_command(SyncerCmd::GetLastReorg { respond_to: tx })
+            .await?;
+
+        Ok(rx.await?)
+    }
 }
 
 impl<S> Drop for Syncer<S>
@@ -197,15 +317,22 @@ where
     event_pub: EventPublisher,
     p2p: Arc<P2p>,
     store: Arc<S>,
-    header_sub_rx: Option<mpsc::Receiver<ExtendedHeader>>,
+    header_sub_rx: Option<HeaderSubReceiver>,
+    header_sub_buffer: usize,
+    header_sub_overflow: HeaderSubOverflow,
+    /// Headers dropped by the previous header-sub channels.
+    header_sub_dropped: u64,
     subjective_head_height: Option<u64>,
     batch_size: u64,
     ongoing_batch: Ongoing,
     syncing_window: Duration,
+    paused: bool,
+    last_reorg: Option<ReorgInfo>,
 }
 
 struct Ongoing {
     range: Option<BlockRange>,
+    started: Instant,
     task: FusedReusableFuture<(Result<Vec<ExtendedHeader>, P2pError>, Duration)>,
 }
 
@@ -225,13 +352,19 @@ where
             p2p: args.p2p,
             store: args.store,
             header_sub_rx: None,
+            header_sub_buffer: args.header_sub_buffer,
+            header_sub_overflow: args.header_sub_overflow,
+            header_sub_dropped: 0,
             subjective_head_height: None,
             batch_size: args.batch_size,
             ongoing_batch: Ongoing {
                 range: None,
+                started: Instant::now(),
                 task: FusedReusableFuture::terminated(),
             },
             syncing_window: args.syncing_window,
+            paused: false,
+            last_reorg: None,
         })
     }
 
@@ -278,7 +411,7 @@ where
                 _ = report_interval.tick() => {
                     self.report().await?;
                 }
-                res = &mut try_init_fut => {
+                res = &mut try_init_fut, if !self.paused => {
                     // `try_init_task` propagates only fatal errors
                     let (network_head, took) = res?;
                     let network_head_height = network_head.height().value();
@@ -286,7 +419,8 @@ where
                     info!("Setting initial subjective head to {network_head_height}");
                     self.set_subjective_head_height(network_head_height);
 
-                    let (header_sub_tx, header_sub_rx) = mpsc::channel(16);
+                    let (header_sub_tx, header_sub_rx) =
+                        header_sub::channel(self.header_sub_buffer, self.header_sub_overflow);
                     self.p2p.init_header_sub(network_head, header_sub_tx).await?;
                     self.header_sub_rx = Some(header_sub_rx);
 
@@ -346,6 +480,7 @@ where
                 }
                 Some(cmd) = self.cmd_rx.recv() => {
                     self.on_cmd(cmd).await?;
+                    self.fetch_next_batch().await?;
                 }
                 (res, took) = &mut self.ongoing_batch.task => {
                     self.on_fetch_next_batch_result(res, took).await?;
@@ -359,15 +494,20 @@ where
             self.ongoing_batch.task.terminate();
         }
 
-        self.header_sub_rx.take();
+        if let Some(header_sub_rx) = self.header_sub_rx.take() {
+            self.header_sub_dropped += header_sub_rx.dropped();
+        }
 
         Ok(())
     }
 
     async fn syncing_info(&self) -> Result<SyncingInfo> {
+        let current_dropped = self.header_sub_rx.as_ref().map_or(0, |rx| rx.dropped());
+
         Ok(SyncingInfo {
             stored_headers: self.store.get_stored_header_ranges().await?,
             subjective_head: self.subjective_head_height.unwrap_or(0),
+            dropped_header_sub_headers: self.header_sub_dropped + current_dropped,
         })
     }
 
@@ -376,6 +516,7 @@ where
         let SyncingInfo {
             stored_headers,
             subjective_head,
+            dropped_header_sub_headers,
         } = self.syncing_info().await?;
 
         let ongoing_batch = self
@@ -385,7 +526,7 @@ where
             .map(|range| format!("{}", range.display()))
             .unwrap_or_else(|| "None".to_string());
 
-        info!("syncing: head: {subjective_head}, stored headers: {stored_headers}, ongoing batches: {ongoing_batch}");
+        info!("syncing: head: {subjective_head}, stored headers: {stored_headers}, ongoing batches: {ongoing_batch}, dropped header-sub headers: {dropped_header_sub_headers}");
         Ok(())
     }
 
@@ -395,18 +536,70 @@ where
                 let info = self.syncing_info().await?;
                 respond_to.maybe_send(info);
             }
+            SyncerCmd::SetPaused { paused, respond_to } => {
+                self.set_paused(paused);
+                respond_to.maybe_send(());
+            }
+            SyncerCmd::IsPaused { respond_to } => {
+                respond_to.maybe_send(self.paused);
+            }
+            SyncerCmd::GetInFlight { respond_to } => {
+                let in_flight = self.ongoing_batch.range.iter().cloned().collect();
+                respond_to.maybe_send(in_flight);
+            }
+            SyncerCmd::GetLastReorg { respond_to } => {
+                respond_to.maybe_send(self.last_reorg);
+            }
         }
 
         Ok(())
     }
 
+    fn set_paused(&mut self, paused: bool) {
+        if self.paused == paused {
+            return;
+        }
+
+        if paused {
+            info!("Pausing syncing");
+
+            if let Some(ongoing) = self.ongoing_batch.range.take() {
+                debug!("Cancelling fetching of {}", ongoing.display());
+                self.ongoing_batch.task.terminate();
+
+                // close the started fetching, it's requested again after resuming
+                self.event_pub.send(NodeEvent::FetchingHeadersFailed {
+                    from_height: *ongoing.start(),
+                    to_height: *ongoing.end(),
+                    error: "Syncing paused".to_owned(),
+                    took: self.ongoing_batch.started.elapsed(),
+                });
+            }
+        } else {
+            info!("Resuming syncing");
+        }
+
+        self.paused = paused;
+    }
+
     #[instrument(skip_all)]
     async fn on_header_sub_message(&mut self, new_head: ExtendedHeader) -> Result<()> {
         let new_head_height = new_head.height().value();
 
         self.set_subjective_head_height(new_head_height);
 
-        if let Ok(store_head_height) = self.store.head_height().await {
+        let store_head_height = self.store.head_height().await.ok();
+
+        if store_head_height.is_some_and(|height| new_head_height <= height) {
+            self.detect_reorg(&new_head).await;
+            return Ok(());
+        }
+
+        if self.paused {
+            return Ok(());
+        }
+
+        if let Some(store_head_height) = store_head_height {
             // If our new header is adjacent to the HEAD of the store
             if store_head_height + 1 == new_head_height {
                 // Header is already verified by HeaderSub and will be validated against previous
@@ -422,6 +615,37 @@ where
         Ok(())
     }
 
+    /// Check the header received for an already synchronised height against the stored one.
+    async fn detect_reorg(&mut self, header: &ExtendedHeader) {
+        let height = header.height().value();
+
+        let Ok(stored) = self.store.get_by_height(height).await else {
+            // Height is not synchronised yet or it was already pruned
+            return;
+        };
+
+        if stored.hash() == header.hash() {
+            return;
+        }
+
+        let reorg = ReorgInfo {
+            height,
+            old_hash: stored.hash(),
+            new_hash: header.hash(),
+        };
+
+        warn!(
+            "Received header {} conflicting with the stored {} at height {height}",
+            reorg.new_hash, reorg.old_hash
+        );
+        self.event_pub.send(NodeEvent::ReorgDetected {
+            height,
+            old_hash: reorg.old_hash,
+            new_hash: reorg.new_hash,
+        });
+        self.last_reorg = Some(reorg);
+    }
+
     fn set_subjective_head_height(&mut self, height: u64) {
         if let Some(old_height) = self.subjective_head_height {
             if height <= old_height {
@@ -448,6 +672,10 @@ where
             return Ok(());
         }
 
+        if self.paused {
+            return Ok(());
+        }
+
         if self.p2p.peer_tracker_info().num_connected_peers == 0 {
             // No connected peers. We can't do the request.
             // We will recover from this in `run`.
@@ -491,6 +719,7 @@ where
         let p2p = self.p2p.clone();
 
         self.ongoing_batch.range = Some(next_batch.clone());
+        self.ongoing_batch.started = Instant::now();
 
         self.ongoing_batch.task.set(async move {
             let now = Instant::now();
@@ -679,9 +908,7 @@ where
     Ok(network_head)
 }
 
-async fn header_sub_recv(
-    rx: Option<&mut mpsc::Receiver<ExtendedHeader>>,
-) -> Result<ExtendedHeader> {
+async fn header_sub_recv(rx: Option<&mut HeaderSubReceiver>) -> Result<ExtendedHeader> {
     rx.expect("header-sub not initialized")
         .recv()
         .await
@@ -696,14 +923,61 @@ mod tests {
     use crate::block_ranges::{BlockRange, BlockRangeExt};
     use crate::events::EventChannel;
     use crate::node::HeaderExError;
-    use crate::node::DEFAULT_SAMPLING_WINDOW;
+    use crate::node::{DEFAULT_HEADER_SUB_BUFFER, DEFAULT_SAMPLING_WINDOW};
     use crate::p2p::header_session;
     use crate::store::InMemoryStore;
-    use crate::test_utils::{async_test, gen_filled_store, MockP2pHandle};
+    use crate::test_utils::{async_test, gen_filled_store, new_block_ranges, MockP2pHandle};
     use crate::utils::OneshotResultSenderExt;
     use celestia_types::test_utils::ExtendedHeaderGenerator;
     use libp2p::request_response::OutboundFailure;
 
+    fn syncing_info(stored_headers: BlockRanges, subjective_head: u64) -> SyncingInfo {
+        SyncingInfo {
+            stored_headers,
+            subjective_head,
+            dropped_header_sub_headers: 0,
+        }
+    }
+
+    #[test]
+    fn syncing_info_diff_of_advance() {
+        let previous = syncing_info(new_block_ranges([1..=10]), 10);
+        let current = syncing_info(new_block_ranges([1..=15]), 20);
+
+        let delta = current.diff(&previous);
+        assert_eq!(delta.added, new_block_ranges([11..=15]));
+        assert!(delta.removed.is_empty());
+        assert_eq!(delta.head_advance, 10);
+
+        // nothing changed
+        let delta = current.diff(&current);
+        assert!(delta.added.is_empty());
+        assert!(delta.removed.is_empty());
+        assert_eq!(delta.head_advance, 0);
+    }
+
+    #[test]
+    fn syncing_info_diff_of_gap_fill() {
+        let previous = syncing_info(new_block_ranges([1..=5, 10..=20]), 20);
+        let current = syncing_info(new_block_ranges([1..=20]), 20);
+
+        let delta = current.diff(&previous);
+        assert_eq!(delta.added, new_block_ranges([6..=9]));
+        assert!(delta.removed.is_empty());
+        assert_eq!(delta.head_advance, 0);
+    }
+
+    #[test]
+    fn syncing_info_diff_of_prune() {
+        let previous = syncing_info(new_block_ranges([1..=5, 10..=20]), 20);
+        let current = syncing_info(new_block_ranges([12..=25]), 25);
+
+        let delta = current.diff(&previous);
+        assert_eq!(delta.added, new_block_ranges([21..=25]));
+        assert_eq!(delta.removed, new_block_ranges([1..=5, 10..=11]));
+        assert_eq!(delta.head_advance, 5);
+    }
+
     #[test]
     fn calculate_range_to_fetch_test_header_limit() {
         let head_height = 1024;
@@ -788,6 +1062,8 @@ mod tests {
             event_pub: events.publisher(),
             batch_size: 512,
             syncing_window: DEFAULT_SAMPLING_WINDOW,
+            header_sub_buffer: DEFAULT_HEADER_SUB_BUFFER,
+            header_sub_overflow: HeaderSubOverflow::DropOldest,
         })
         .unwrap();
 
@@ -893,6 +1169,154 @@ mod tests {
         p2p_mock.expect_no_cmd().await;
     }
 
+    #[async_test]
+    async fn reorg_detection() {
+        let mut gen = ExtendedHeaderGenerator::new();
+        let headers = gen.next_many(20);
+
+        let events = EventChannel::new();
+        let mut event_sub = events.subscribe();
+        let (syncer, store, mut p2p_mock) =
+            initialized_syncer_with_events(headers[19].clone(), &events).await;
+        handle_session_batch(&mut p2p_mock, &headers, 1..=19, true).await;
+        assert_syncing(&syncer, &store, &[1..=20], 20).await;
+        assert_eq!(syncer.last_reorg().await.unwrap(), None);
+
+        // Header already in the store is not a reorg
+        p2p_mock.announce_new_head(headers[9].clone());
+        assert_syncing(&syncer, &store, &[1..=20], 20).await;
+        assert_eq!(syncer.last_reorg().await.unwrap(), None);
+
+        let conflicting = gen.another_of(&headers[9]);
+        p2p_mock.announce_new_head(conflicting.clone());
+        assert_syncing(&syncer, &store, &[1..=20], 20).await;
+
+        let expected = ReorgInfo {
+            height: 10,
+            old_hash: headers[9].hash(),
+            new_hash: conflicting.hash(),
+        };
+        assert_eq!(syncer.last_reorg().await.unwrap(), Some(expected));
+        // stored header is kept
+        assert_eq!(store.get_by_height(10).await.unwrap(), headers[9]);
+
+        let mut reorgs = Vec::new();
+        while let Ok(info) = event_sub.try_recv() {
+            if let NodeEvent::ReorgDetected {
+                height,
+                old_hash,
+                new_hash,
+            } = info.event
+            {
+                reorgs.push((height, old_hash, new_hash));
+            }
+        }
+        assert_eq!(reorgs, [(10, headers[9].hash(), conflicting.hash())]);
+        p2p_mock.expect_no_cmd().await;
+    }
+
+    #[async_test]
+    async fn pause_and_resume() {
+        let mut gen = ExtendedHeaderGenerator::new();
+        let headers = gen.next_many(1500);
+
+        let events = EventChannel::new();
+        let mut event_sub = events.subscribe();
+        let (syncer, store, mut p2p_mock) =
+            initialized_syncer_with_events(headers[1499].clone(), &events).await;
+        assert!(!syncer.is_paused().await.unwrap());
+
+        handle_session_batch(&mut p2p_mock, &headers, 988..=1499, true).await;
+        assert_syncing(&syncer, &store, &[988..=1500], 1500).await;
+
+        // Second batch is requested, but pausing cancels it before it is finished
+        handle_session_batch(&mut p2p_mock, &headers, 476..=987, false).await;
+        syncer.set_paused(true).await.unwrap();
+        assert!(syncer.is_paused().await.unwrap());
+        assert_syncing(&syncer, &store, &[988..=1500], 1500).await;
+
+        // Pausing again doesn't change anything
+        syncer.set_paused(true).await.unwrap();
+        assert!(syncer.is_paused().await.unwrap());
+
+        // cancelled batch is reported as failed
+        let mut failed = Vec::new();
+        while let Ok(info) = event_sub.try_recv() {
+            match info.event {
+                NodeEvent::FetchingHeadersFinished { from_height, .. } => {
+                    assert_eq!(from_height, 988);
+                }
+                NodeEvent::FetchingHeadersFailed {
+                    from_height,
+                    to_height,
+                    error,
+                    ..
+                } => failed.push((from_height..=to_height, error)),
+                _ => {}
+            }
+        }
+        assert_eq!(failed, [(476..=987, "Syncing paused".to_owned())]);
+
+        // New head updates the subjective head, but nothing is fetched or stored
+        let header1501 = gen.next();
+        p2p_mock.announce_new_head(header1501.clone());
+        assert_syncing(&syncer, &store, &[988..=1500], 1501).await;
+        p2p_mock.expect_no_cmd().await;
+
+        while let Ok(info) = event_sub.try_recv() {
+            assert!(
+                !matches!(
+                    info.event,
+                    NodeEvent::FetchingHeadersStarted { .. }
+                        | NodeEvent::FetchingHeadersFinished { .. }
+                        | NodeEvent::FetchingHeadersFailed { .. }
+                ),
+                "unexpected event while paused: {:?}",
+                info.event
+            );
+        }
+
+        // After resuming, the syncer catches up with the new head and then
+        // requests the cancelled batch again
+        syncer.set_paused(false).await.unwrap();
+        assert!(!syncer.is_paused().await.unwrap());
+        handle_session_batch(&mut p2p_mock, &[header1501], 1501..=1501, true).await;
+        assert_syncing(&syncer, &store, &[988..=1501], 1501).await;
+        handle_session_batch(&mut p2p_mock, &headers, 476..=987, true).await;
+        assert_syncing(&syncer, &store, &[476..=1501], 1501).await;
+
+        let mut fetched = Vec::new();
+        while let Ok(info) = event_sub.try_recv() {
+            if let NodeEvent::FetchingHeadersFinished {
+                from_height,
+                to_height,
+                ..
+            } = info.event
+            {
+                fetched.push(from_height..=to_height);
+            }
+        }
+        assert_eq!(fetched, vec![1501..=1501, 476..=987]);
+    }
+
+    #[async_test]
+    async fn in_flight_fetches() {
+        let mut gen = ExtendedHeaderGenerator::new();
+        let headers = gen.next_many(513);
+
+        let (syncer, store, mut p2p_mock) = initialized_syncer(headers[512].clone()).await;
+
+        // Syncer started fetching the only missing batch
+        assert_eq!(syncer.in_flight_fetches().await.unwrap(), vec![1..=512]);
+
+        handle_session_batch(&mut p2p_mock, &headers, 1..=512, true).await;
+        assert_syncing(&syncer, &store, &[1..=513], 513).await;
+
+        // Batch is finished and there's nothing more to fetch
+        assert!(syncer.in_flight_fetches().await.unwrap().is_empty());
+        p2p_mock.expect_no_cmd().await;
+    }
+
     #[async_test]
     async fn window_edge() {
         let month_and_day_ago = Duration::from_secs(31 * 24 * 60 * 60);
@@ -936,6 +1360,8 @@ mod tests {
             event_pub: events.publisher(),
             batch_size: 512,
             syncing_window: DEFAULT_SAMPLING_WINDOW,
+            header_sub_buffer: DEFAULT_HEADER_SUB_BUFFER,
+            header_sub_overflow: HeaderSubOverflow::DropOldest,
         })
         .unwrap();
 
@@ -1166,7 +1592,13 @@ mod tests {
     async fn initialized_syncer(
         head: ExtendedHeader,
     ) -> (Syncer<InMemoryStore>, Arc<InMemoryStore>, MockP2pHandle) {
-        let events = EventChannel::new();
+        initialized_syncer_with_events(head, &EventChannel::new()).await
+    }
+
+    async fn initialized_syncer_with_events(
+        head: ExtendedHeader,
+        events: &EventChannel,
+    ) -> (Syncer<InMemoryStore>, Arc<InMemoryStore>, MockP2pHandle) {
         let (mock, mut handle) = P2p::mocked();
         let store = Arc::new(InMemoryStore::new());
 
@@ -1176,6 +1608,8 @@ mod tests {
             event_pub: events.publisher(),
             batch_size: 512,
             syncing_window: DEFAULT_SAMPLING_WINDOW,
+            header_sub_buffer: DEFAULT_HEADER_SUB_BUFFER,
+            header_sub_overflow: HeaderSubOverflow::DropOldest,
         })
         .unwrap();
 
@@ -1,12 +1,28 @@
 //! Utilities for writing tests.
 
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+use std::net::{SocketAddr, UdpSocket};
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+use std::thread;
 use std::time::Duration;
 
 use celestia_proto::p2p::pb::{header_request::Data, HeaderRequest};
+#[cfg(not(target_arch = "wasm32"))]
+use celestia_types::fraud_proof::BadEncodingFraudProof;
 use celestia_types::hash::Hash;
 use celestia_types::test_utils::ExtendedHeaderGenerator;
 use celestia_types::ExtendedHeader;
 use cid::Cid;
+#[cfg(not(target_arch = "wasm32"))]
+use futures::StreamExt;
+#[cfg(not(target_arch = "wasm32"))]
+use libp2p::{
+    gossipsub, noise, ping, swarm::NetworkBehaviour, tcp, yamux, Multiaddr, SwarmBuilder,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use tendermint_proto::Protobuf;
 use tokio::sync::{mpsc, watch};
 
 use crate::{
@@ -14,7 +30,7 @@ use crate::{
     blockstore::InMemoryBlockstore,
     executor::timeout,
     network::Network,
-    p2p::{P2pCmd, P2pError},
+    p2p::{header_sub::HeaderSubSender, P2pCmd, P2pError},
     peer_tracker::PeerTrackerInfo,
     store::{InMemoryStore, VerifiedExtendedHeaders},
     utils::OneshotResultSender,
@@ -28,6 +44,55 @@ pub(crate) use tokio::test as async_test;
 #[cfg(target_arch = "wasm32")]
 pub(crate) use wasm_bindgen_test::wasm_bindgen_test as async_test;
 
+/// Answers every DNS query with a single TXT record.
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn spawn_mock_dns_server(txt: String) -> SocketAddr {
+    spawn_sequenced_mock_dns_server(vec![txt])
+}
+
+/// Answers the DNS queries with a single TXT record, the next one of `txts` for each
+/// query, repeating the last one once they run out.
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn spawn_sequenced_mock_dns_server(txts: Vec<String>) -> SocketAddr {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = socket.local_addr().unwrap();
+
+    thread::spawn(move || {
+        let mut buf = [0u8; 512];
+        let mut answered = 0;
+
+        while let Ok((len, peer)) = socket.recv_from(&mut buf) {
+            let query = &buf[..len];
+            let txt = &txts[answered.min(txts.len() - 1)];
+            answered += 1;
+
+            // end of the question: name labels, null byte, type and class
+            let mut question_end = 12;
+            while query[question_end] != 0 {
+                question_end += query[question_end] as usize + 1;
+            }
+            question_end += 5;
+
+            let mut response = Vec::new();
+            // id, flags (response, recursion desired and available), counts
+            response.extend_from_slice(&query[..2]);
+            response.extend_from_slice(&[0x81, 0x80, 0, 1, 0, 1, 0, 0, 0, 0]);
+            response.extend_from_slice(&query[12..question_end]);
+            // pointer to the name in question, TXT, IN, TTL
+            response.extend_from_slice(&[0xc0, 0x0c, 0, 16, 0, 1, 0, 0, 0, 60]);
+            response.extend_from_slice(&(txt.len() as u16 + 1).to_be_bytes());
+            response.push(txt.len() as u8);
+            response.extend_from_slice(txt.as_bytes());
+
+            let _ = socket.send_to(&response, peer);
+        }
+    });
+
+    addr
+}
+
 /// Generate a store pre-filled with headers.
 pub async fn gen_filled_store(amount: u64) -> (InMemoryStore, ExtendedHeaderGenerator) {
     let s = InMemoryStore::new();
@@ -55,6 +120,102 @@ pub fn listening_test_node_builder() -> NodeBuilder<InMemoryBlockstore, InMemory
     test_node_builder().listen(["/ip4/0.0.0.0/tcp/0".parse().unwrap()])
 }
 
+/// Spawn a libp2p node connected to the address, announcing the fraud proofs sent
+/// over the returned channel on the fraud-sub of the `private` network.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn spawn_befp_announcer(connect_to: Multiaddr) -> mpsc::Sender<BadEncodingFraudProof> {
+    spawn_gossipsub_announcer(
+        connect_to,
+        "/badencoding/fraud-sub/private/v0.0.1",
+        |proof: BadEncodingFraudProof| proof.encode_vec(),
+    )
+}
+
+/// Spawn a libp2p node connected to the address, announcing the raw messages sent over
+/// the returned channel on the fraud-sub of the `private` network, e.g. malformed proofs.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn spawn_raw_befp_announcer(connect_to: Multiaddr) -> mpsc::Sender<Vec<u8>> {
+    spawn_gossipsub_announcer(
+        connect_to,
+        "/badencoding/fraud-sub/private/v0.0.1",
+        |data: Vec<u8>| data,
+    )
+}
+
+/// Spawn a libp2p node connected to the address, announcing the headers sent over
+/// the returned channel on the header-sub of the `private` network.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn spawn_header_announcer(connect_to: Multiaddr) -> mpsc::Sender<ExtendedHeader> {
+    spawn_gossipsub_announcer(
+        connect_to,
+        "/private/header-sub/v0.0.1",
+        |header: ExtendedHeader| header.encode_vec(),
+    )
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_gossipsub_announcer<T>(
+    connect_to: Multiaddr,
+    topic: &str,
+    encode: fn(T) -> Vec<u8>,
+) -> mpsc::Sender<T>
+where
+    T: Send + 'static,
+{
+    #[derive(NetworkBehaviour)]
+    struct Behaviour {
+        ping: ping::Behaviour,
+        gossipsub: gossipsub::Behaviour,
+    }
+
+    // create a new libp2p node with gossipsub
+    let mut announcer = SwarmBuilder::with_new_identity()
+        .with_tokio()
+        .with_tcp(
+            tcp::Config::default(),
+            noise::Config::new,
+            yamux::Config::default,
+        )
+        .unwrap()
+        .with_behaviour(|key| {
+            let ping = ping::Behaviour::new(ping::Config::default());
+
+            let config = gossipsub::ConfigBuilder::default().build().unwrap();
+            let message_authenticity = gossipsub::MessageAuthenticity::Signed(key.clone());
+            let gossipsub = gossipsub::Behaviour::new(message_authenticity, config).unwrap();
+
+            Ok(Behaviour { ping, gossipsub })
+        })
+        .unwrap()
+        .build();
+
+    announcer.dial(connect_to).unwrap();
+
+    let topic = gossipsub::IdentTopic::new(topic);
+    announcer
+        .behaviour_mut()
+        .gossipsub
+        .subscribe(&topic)
+        .unwrap();
+
+    // a channel for the announcements
+    let (tx, mut rx) = mpsc::channel::<T>(8);
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = announcer.select_next_some() => (),
+                Some(message) = rx.recv() => {
+                    let message = encode(message);
+                    announcer.behaviour_mut().gossipsub.publish(topic.hash(), message).unwrap();
+                }
+            }
+        }
+    });
+
+    tx
+}
+
 /// Extends test header generator for easier insertion into the store
 pub trait ExtendedHeaderGeneratorExt {
     /// Generate next amount verified headers
@@ -74,7 +235,7 @@ pub struct MockP2pHandle {
     #[allow(dead_code)]
     pub(crate) cmd_tx: mpsc::Sender<P2pCmd>,
     pub(crate) cmd_rx: mpsc::Receiver<P2pCmd>,
-    pub(crate) header_sub_tx: Option<mpsc::Sender<ExtendedHeader>>,
+    pub(crate) header_sub_tx: Option<HeaderSubSender>,
     pub(crate) peer_tracker_tx: watch::Sender<PeerTrackerInfo>,
 }
 
@@ -105,7 +266,7 @@ impl MockP2pHandle {
     /// Simulate a new header announced in the network.
     pub fn announce_new_head(&self, header: ExtendedHeader) {
         if let Some(ref tx) = self.header_sub_tx {
-            let _ = tx.try_send(header);
+            tx.try_send(header);
         }
     }
 
@@ -27,7 +27,6 @@ impl Token {
     }
 
     /// Returns if event is triggered or not.
-    #[allow(dead_code)]
     pub(crate) fn is_triggered(&self) -> bool {
         self.token.is_cancelled()
     }
@@ -4,19 +4,24 @@ use std::time::Duration;
 
 use celestia_types::consts::appconsts::AppVersion;
 use celestia_types::consts::HASH_SIZE;
-use celestia_types::fraud_proof::BadEncodingFraudProof;
 use celestia_types::hash::Hash;
 use celestia_types::test_utils::{corrupt_eds, generate_dummy_eds, ExtendedHeaderGenerator};
 use futures::StreamExt;
+use libp2p::identity::Keypair;
 use libp2p::swarm::NetworkBehaviour;
-use libp2p::{gossipsub, noise, ping, tcp, yamux, Multiaddr, SwarmBuilder};
+use libp2p::{identify, noise, ping, tcp, yamux, Multiaddr, PeerId, SwarmBuilder};
+use lumina_node::events::{DisconnectReason, NodeEvent};
+use lumina_node::node::{NodeError, ReorgInfo};
 use lumina_node::store::{InMemoryStore, Store};
 use lumina_node::test_utils::{
-    gen_filled_store, listening_test_node_builder, test_node_builder, ExtendedHeaderGeneratorExt,
+    gen_filled_store, listening_test_node_builder, spawn_befp_announcer, spawn_header_announcer,
+    spawn_raw_befp_announcer, test_node_builder, ExtendedHeaderGeneratorExt,
 };
 use rand::Rng;
-use tendermint_proto::Protobuf;
-use tokio::{select, spawn, sync::mpsc, time::sleep};
+use tokio::{
+    spawn,
+    time::{sleep, timeout},
+};
 
 use crate::utils::{fetch_bridge_info, new_connected_node};
 
@@ -83,6 +88,33 @@ async fn header_store_access() {
     }
 }
 
+#[tokio::test]
+async fn bandwidth_stats() {
+    let node1 = listening_test_node_builder().start().await.unwrap();
+    let node1_addrs = node1.listeners().await.unwrap();
+
+    let node2 = test_node_builder()
+        .bootnodes(node1_addrs)
+        .start()
+        .await
+        .unwrap();
+
+    node2.wait_connected().await.unwrap();
+    // Let identify and other protocols exchange some data, and the counters be
+    // sampled for the rates, which happens every second
+    sleep(Duration::from_millis(1500)).await;
+
+    let stats = node2.bandwidth_stats();
+    assert!(stats.total_inbound_bytes > 0);
+    assert!(stats.total_outbound_bytes > 0);
+    assert!(stats.inbound_rate_bps > 0.0);
+    assert!(stats.outbound_rate_bps > 0.0);
+
+    let stats1 = node1.bandwidth_stats();
+    assert!(stats1.total_inbound_bytes > 0);
+    assert!(stats1.total_outbound_bytes > 0);
+}
+
 #[tokio::test]
 async fn peer_discovery() {
     // Bridge node cannot connect to other nodes because it is behind Docker's NAT.
@@ -159,6 +191,136 @@ async fn peer_discovery() {
     assert_eq!(tracker_info.num_connected_trusted_peers, 1);
 }
 
+#[tokio::test]
+async fn configured_trusted_peer() {
+    let keypair = Keypair::generate_ed25519();
+    let trusted_peer_id = keypair.public().to_peer_id();
+
+    let (node1, mut events) = listening_test_node_builder()
+        .trusted_peers([trusted_peer_id])
+        .start_subscribed()
+        .await
+        .unwrap();
+
+    sleep(Duration::from_millis(300)).await;
+    let node1_addrs = node1.listeners().await.unwrap();
+
+    // trusted peer, connecting as a client
+    let node2 = test_node_builder()
+        .keypair(keypair)
+        .bootnodes(node1_addrs.clone())
+        .start()
+        .await
+        .unwrap();
+    node2.wait_connected().await.unwrap();
+
+    // untrusted peer
+    let node3 = test_node_builder()
+        .bootnodes(node1_addrs)
+        .start()
+        .await
+        .unwrap();
+    node3.wait_connected().await.unwrap();
+
+    let mut trusted = None;
+    let mut untrusted = None;
+
+    while trusted.is_none() || untrusted.is_none() {
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .expect("PeerConnected events not received")
+            .unwrap();
+
+        if let NodeEvent::PeerConnected {
+            id,
+            trusted: is_trusted,
+        } = event.event
+        {
+            if id == trusted_peer_id {
+                trusted = Some(is_trusted);
+            } else if id == *node3.local_peer_id() {
+                untrusted = Some(is_trusted);
+            }
+        }
+    }
+
+    assert_eq!(trusted, Some(true));
+    assert_eq!(untrusted, Some(false));
+    assert_eq!(node1.peer_tracker_info().num_connected_trusted_peers, 1);
+}
+
+#[tokio::test]
+async fn ready_to_serve_once_capable_peer_connects() {
+    let keypair = Keypair::generate_ed25519();
+    let capable_peer_id = keypair.public().to_peer_id();
+
+    let (node, mut events) = listening_test_node_builder()
+        .trusted_peers([capable_peer_id])
+        .start_subscribed()
+        .await
+        .unwrap();
+
+    sleep(Duration::from_millis(300)).await;
+    let node_addrs = node.listeners().await.unwrap();
+
+    // peer speaking neither header-ex nor shwap
+    let incapable_peer_id = spawn_identified_peer(node_addrs[0].clone());
+    loop {
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .expect("PeerConnected event not received")
+            .unwrap();
+
+        match event.event {
+            NodeEvent::PeerConnected { id, .. } if id == incapable_peer_id => break,
+            NodeEvent::ReadyToServe => panic!("ready without a capable peer"),
+            _ => (),
+        }
+    }
+    // let identify exchange the protocols
+    sleep(Duration::from_millis(500)).await;
+    assert!(!node.is_ready());
+
+    // trusted peer serving the head
+    let (store, _) = gen_filled_store(10).await;
+    let _capable = test_node_builder()
+        .keypair(keypair)
+        .store(store)
+        .bootnodes(node_addrs.clone())
+        .start()
+        .await
+        .unwrap();
+
+    let mut ready_events = 0;
+    while ready_events == 0 {
+        let event = tokio::time::timeout(Duration::from_secs(10), events.recv())
+            .await
+            .expect("ReadyToServe event not received")
+            .unwrap();
+
+        if let NodeEvent::ReadyToServe = event.event {
+            ready_events += 1;
+        }
+    }
+    assert!(node.is_ready());
+
+    // another capable peer doesn't announce it again
+    let other = test_node_builder()
+        .bootnodes(node_addrs)
+        .start()
+        .await
+        .unwrap();
+    other.wait_connected().await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    while let Ok(event) = events.try_recv() {
+        if let NodeEvent::ReadyToServe = event.event {
+            ready_events += 1;
+        }
+    }
+    assert_eq!(ready_events, 1);
+}
+
 #[tokio::test]
 async fn stops_services_when_network_is_compromised() {
     let mut gen = ExtendedHeaderGenerator::new();
@@ -201,15 +363,163 @@ async fn stops_services_when_network_is_compromised() {
     assert!(node.syncer_info().await.is_err());
 }
 
-fn spawn_befp_announcer(connect_to: Multiaddr) -> mpsc::Sender<BadEncodingFraudProof> {
+#[tokio::test]
+async fn bans_peer_announcing_malformed_fraud_proof() {
+    let (store, _) = gen_filled_store(64).await;
+    let node = listening_test_node_builder()
+        .store(store)
+        .start()
+        .await
+        .unwrap();
+    let mut events = node.event_subscriber();
+
+    sleep(Duration::from_millis(300)).await;
+    let listener_addr = node.listeners().await.unwrap()[0].clone();
+
+    let befp_announce_tx = spawn_raw_befp_announcer(listener_addr);
+    sleep(Duration::from_millis(300)).await;
+
+    befp_announce_tx.send(b"malformed".to_vec()).await.unwrap();
+
+    let reason = timeout(Duration::from_secs(5), async {
+        loop {
+            if let NodeEvent::PeerDisconnected { reason, .. } = events.recv().await.unwrap().event {
+                break reason;
+            }
+        }
+    })
+    .await
+    .expect("PeerDisconnected event not received");
+
+    assert_eq!(reason, DisconnectReason::Banned);
+    // a malformed proof doesn't compromise the network
+    assert!(!node.is_network_compromised());
+}
+
+#[tokio::test]
+async fn refuses_writes_when_network_is_compromised() {
+    let mut gen = ExtendedHeaderGenerator::new();
+    let store = InMemoryStore::new();
+
+    store.insert(gen.next_many_verified(64)).await.unwrap();
+
+    let mut eds = generate_dummy_eds(8, AppVersion::V2);
+    let (header, befp) = corrupt_eds(&mut gen, &mut eds);
+
+    store.insert(header).await.unwrap();
+
+    let node = listening_test_node_builder()
+        .store(store)
+        .start()
+        .await
+        .unwrap();
+
+    sleep(Duration::from_millis(300)).await;
+    let listener_addr = node.listeners().await.unwrap()[0].clone();
+
+    let befp_announce_tx = spawn_befp_announcer(listener_addr);
+    sleep(Duration::from_millis(300)).await;
+
+    assert!(!node.is_network_compromised());
+
+    befp_announce_tx.send(befp).await.unwrap();
+    sleep(Duration::from_millis(300)).await;
+
+    assert!(node.is_network_compromised());
+
+    // writes are refused
+    assert!(matches!(
+        node.insert_header(gen.next()).await.unwrap_err(),
+        NodeError::NetworkCompromised
+    ));
+    assert!(matches!(
+        node.sample_range(1..=2, false).await.unwrap_err(),
+        NodeError::NetworkCompromised
+    ));
+
+    // reads of the stored data and peer management are still allowed
+    assert_eq!(
+        node.get_local_head_header().await.unwrap().height().value(),
+        65
+    );
+    assert!(node.get_header_by_height(1).await.is_ok());
+    node.set_peer_trust(PeerId::random(), true).await.unwrap();
+    assert!(node.syncer_info().await.is_err());
+}
+
+#[tokio::test]
+async fn reorg_detected_from_header_sub() {
+    let (server_store, gen) = gen_filled_store(20).await;
+    let stored = server_store.get_by_height(10).await.unwrap();
+    let server = listening_test_node_builder()
+        .store(server_store)
+        .start()
+        .await
+        .unwrap();
+    sleep(Duration::from_millis(100)).await;
+
+    let node = listening_test_node_builder()
+        .bootnodes(server.listeners().await.unwrap())
+        .start()
+        .await
+        .unwrap();
+    let mut events = node.event_subscriber();
+
+    // header-sub is initialized once the node is synced with the server
+    timeout(Duration::from_secs(10), async {
+        while !node.syncer_info().await.unwrap().stored_headers.contains(1) {
+            sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(node.last_reorg().await.unwrap(), None);
+
+    let header_announce_tx = spawn_header_announcer(node.listeners().await.unwrap()[0].clone());
+    sleep(Duration::from_millis(500)).await;
+
+    // stored header is ignored, while the competing one is reported
+    header_announce_tx.send(stored.clone()).await.unwrap();
+    let conflicting = gen.another_of(&stored);
+    header_announce_tx.send(conflicting.clone()).await.unwrap();
+
+    let reorg = timeout(Duration::from_secs(5), async {
+        loop {
+            if let NodeEvent::ReorgDetected {
+                height,
+                old_hash,
+                new_hash,
+            } = events.recv().await.unwrap().event
+            {
+                break (height, old_hash, new_hash);
+            }
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(reorg, (10, stored.hash(), conflicting.hash()));
+
+    assert_eq!(
+        node.last_reorg().await.unwrap(),
+        Some(ReorgInfo {
+            height: 10,
+            old_hash: stored.hash(),
+            new_hash: conflicting.hash(),
+        })
+    );
+    // stored header is kept
+    assert_eq!(node.get_header_by_height(10).await.unwrap(), stored);
+}
+
+fn spawn_identified_peer(connect_to: Multiaddr) -> PeerId {
     #[derive(NetworkBehaviour)]
     struct Behaviour {
         ping: ping::Behaviour,
-        gossipsub: gossipsub::Behaviour,
+        identify: identify::Behaviour,
     }
 
-    // create a new libp2p node with gossipsub
-    let mut announcer = SwarmBuilder::with_new_identity()
+    // create a new libp2p node speaking only ping and identify
+    let mut peer = SwarmBuilder::with_new_identity()
         .with_tokio()
         .with_tcp(
             tcp::Config::default(),
@@ -219,41 +529,22 @@ fn spawn_befp_announcer(connect_to: Multiaddr) -> mpsc::Sender<BadEncodingFraudP
         .unwrap()
         .with_behaviour(|key| {
             let ping = ping::Behaviour::new(ping::Config::default());
+            let identify =
+                identify::Behaviour::new(identify::Config::new(String::new(), key.public()));
 
-            let config = gossipsub::ConfigBuilder::default().build().unwrap();
-            let message_authenticity = gossipsub::MessageAuthenticity::Signed(key.clone());
-            let gossipsub: gossipsub::Behaviour =
-                gossipsub::Behaviour::new(message_authenticity, config).unwrap();
-
-            Ok(Behaviour { ping, gossipsub })
+            Ok(Behaviour { ping, identify })
         })
         .unwrap()
         .build();
 
-    announcer.dial(connect_to).unwrap();
-
-    // subscribe to the fraud-sub topic
-    let topic = gossipsub::IdentTopic::new("/badencoding/fraud-sub/private/v0.0.1");
-    announcer
-        .behaviour_mut()
-        .gossipsub
-        .subscribe(&topic)
-        .unwrap();
-
-    // a channel for proof announcment
-    let (tx, mut rx) = mpsc::channel::<BadEncodingFraudProof>(8);
+    let peer_id = *peer.local_peer_id();
+    peer.dial(connect_to).unwrap();
 
     spawn(async move {
         loop {
-            select! {
-                _ = announcer.select_next_some() => (),
-                Some(proof) = rx.recv() => {
-                    let proof = proof.encode_vec();
-                    announcer.behaviour_mut().gossipsub.publish(topic.hash(), proof).unwrap();
-                }
-            }
+            peer.select_next_some().await;
         }
     });
 
-    tx
+    peer_id
 }
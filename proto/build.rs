@@ -28,6 +28,7 @@ static CUSTOM_TYPE_ATTRIBUTES: &[(&str, &str)] = &[
     (".celestia.core.v1.proof.Proof", SERIALIZED_DEFAULT),
     (".celestia.core.v1.proof.RowProof", SERIALIZED_DEFAULT),
     (".celestia.core.v1.proof.ShareProof", SERIALIZED_DEFAULT),
+    (".cosmos.auth.v1beta1.Params", SERIALIZED_DEFAULT),
     (".cosmos.base.abci.v1beta1.ABCIMessageLog", SERIALIZED_DEFAULT),
     (".cosmos.base.abci.v1beta1.Attribute", SERIALIZED_DEFAULT),
     (".cosmos.base.abci.v1beta1.StringEvent", SERIALIZED_DEFAULT),
@@ -117,13 +118,17 @@ const PROTO_FILES: &[&str] = &[
     "vendor/celestia/core/v1/proof/proof.proto",
     "vendor/cosmos/auth/v1beta1/auth.proto",
     "vendor/cosmos/auth/v1beta1/query.proto",
+    "vendor/cosmos/bank/v1beta1/bank.proto",
+    "vendor/cosmos/bank/v1beta1/query.proto",
     "vendor/cosmos/base/abci/v1beta1/abci.proto",
     "vendor/cosmos/base/node/v1beta1/query.proto",
     "vendor/cosmos/base/tendermint/v1beta1/query.proto",
     "vendor/cosmos/base/v1beta1/coin.proto",
     "vendor/cosmos/crypto/ed25519/keys.proto",
+    "vendor/cosmos/crypto/multisig/keys.proto",
     "vendor/cosmos/crypto/multisig/v1beta1/multisig.proto",
     "vendor/cosmos/crypto/secp256k1/keys.proto",
+    "vendor/cosmos/distribution/v1beta1/query.proto",
     "vendor/cosmos/staking/v1beta1/query.proto",
     "vendor/cosmos/tx/v1beta1/service.proto",
     "vendor/cosmos/tx/v1beta1/tx.proto",
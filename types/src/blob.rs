@@ -4,6 +4,7 @@ use std::iter;
 
 use serde::{Deserialize, Serialize};
 
+mod builder;
 mod commitment;
 mod msg_pay_for_blobs;
 
@@ -12,6 +13,7 @@ use crate::consts::appconsts::{subtree_root_threshold, AppVersion};
 use crate::nmt::Namespace;
 use crate::{bail_validation, Error, Result, Share};
 
+pub use self::builder::BlobBuilder;
 pub use self::commitment::Commitment;
 pub use self::msg_pay_for_blobs::MsgPayForBlobs;
 pub use celestia_proto::celestia::blob::v1::MsgPayForBlobs as RawMsgPayForBlobs;
@@ -48,7 +50,7 @@ pub struct Blob {
 }
 
 /// Params defines the parameters for the blob module.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BlobParams {
     /// Gas cost per blob byte
     pub gas_per_blob_byte: u32,
@@ -0,0 +1,235 @@
+use std::io::Cursor;
+
+use crate::blob::commitment::build_sparse_share_v0;
+use crate::blob::{Blob, BlobParams, Commitment};
+use crate::consts::appconsts::{
+    self, subtree_root_threshold, AppVersion, CONTINUATION_SPARSE_SHARE_CONTENT_SIZE,
+    FIRST_SPARSE_SHARE_CONTENT_SIZE,
+};
+use crate::nmt::{Namespace, NamespaceMerkleHasher, NamespacedHash, NamespacedSha2Hasher};
+use crate::{Error, Result, Share};
+
+/// Builder of a [`Blob`] from data arriving in chunks.
+///
+/// Creating a [`Blob`] with [`Blob::new`] requires the whole data up front and splits it into
+/// [`Share`]s at once to compute the [`Commitment`]. [`BlobBuilder`] instead hashes each share
+/// as soon as its data is complete, so only the data itself and the hashes of the shares are
+/// kept in memory. The first share holds the length of the whole data, so it's hashed only when
+/// the blob is finalized.
+///
+/// # Example
+///
+/// ```
+/// use celestia_types::blob::{BlobBuilder, BlobParams};
+/// use celestia_types::{AppVersion, Blob, nmt::Namespace};
+///
+/// let namespace = Namespace::new_v0(&[1, 2, 3, 4, 5]).unwrap();
+/// let params = BlobParams {
+///     gas_per_blob_byte: 8,
+///     gov_max_square_size: 64,
+/// };
+///
+/// let mut builder = BlobBuilder::new(namespace, AppVersion::V2);
+/// builder.push(b"some data ");
+/// builder.push(b"to store on blockchain");
+/// let blob = builder.finalize(&params).unwrap();
+///
+/// let expected = Blob::new(namespace, b"some data to store on blockchain".to_vec(), AppVersion::V2)
+///     .unwrap();
+/// assert_eq!(blob, expected);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BlobBuilder {
+    namespace: Namespace,
+    app_version: AppVersion,
+    data: Vec<u8>,
+    /// Hashes of the complete continuation shares.
+    continuation_hashes: Vec<NamespacedHash>,
+}
+
+impl BlobBuilder {
+    /// Create a new empty [`BlobBuilder`] for a blob within the [`Namespace`].
+    pub fn new(namespace: Namespace, app_version: AppVersion) -> Self {
+        BlobBuilder {
+            namespace,
+            app_version,
+            data: Vec::new(),
+            continuation_hashes: Vec::new(),
+        }
+    }
+
+    /// Append the chunk of data to the blob.
+    pub fn push(&mut self, chunk: &[u8]) {
+        self.data.extend_from_slice(chunk);
+
+        while self.data.len() >= self.continuation_share_start(self.continuation_hashes.len() + 1) {
+            let start = self.continuation_share_start(self.continuation_hashes.len());
+            // data bounds were checked above, so building the share can't fail
+            let share = self
+                .build_share(start)
+                .expect("Failed to build continuation share");
+            self.continuation_hashes.push(self.hash_share(&share));
+        }
+    }
+
+    /// Length of the data pushed so far.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if no data was pushed yet.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Finish the [`Blob`], computing its [`Commitment`] from the hashes of the shares.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the data length overflows [`u32`] or
+    /// the blob doesn't fit in the square of [`BlobParams::gov_max_square_size`].
+    pub fn finalize(self, params: &BlobParams) -> Result<Blob> {
+        let mut leaf_hashes = Vec::with_capacity(self.continuation_hashes.len() + 2);
+
+        if !self.data.is_empty() {
+            let first_share = self.build_share(0)?;
+            leaf_hashes.push(self.hash_share(&first_share));
+            leaf_hashes.extend_from_slice(&self.continuation_hashes);
+
+            let incomplete_start = self.continuation_share_start(self.continuation_hashes.len());
+            if self.data.len() > incomplete_start {
+                let last_share = self.build_share(incomplete_start)?;
+                leaf_hashes.push(self.hash_share(&last_share));
+            }
+        }
+
+        let shares = leaf_hashes.len() as u64;
+        let max_shares = params
+            .gov_max_square_size
+            .saturating_mul(params.gov_max_square_size);
+
+        if shares > max_shares {
+            return Err(Error::BlobExceedsMaxSquareSize(
+                shares,
+                params.gov_max_square_size,
+            ));
+        }
+
+        let commitment =
+            Commitment::from_leaf_hashes(&leaf_hashes, subtree_root_threshold(self.app_version));
+
+        Ok(Blob {
+            namespace: self.namespace,
+            data: self.data,
+            share_version: appconsts::SHARE_VERSION_ZERO,
+            commitment,
+            index: None,
+        })
+    }
+
+    /// Offset in the data of the continuation share with the given index,
+    /// counting from the first continuation share.
+    fn continuation_share_start(&self, index: usize) -> usize {
+        FIRST_SPARSE_SHARE_CONTENT_SIZE + index * CONTINUATION_SPARSE_SHARE_CONTENT_SIZE
+    }
+
+    fn build_share(&self, start: usize) -> Result<Share> {
+        let mut cursor = Cursor::new(&self.data[..]);
+        cursor.set_position(start as u64);
+        build_sparse_share_v0(self.namespace, &mut cursor)
+    }
+
+    fn hash_share(&self, share: &Share) -> NamespacedHash {
+        NamespacedSha2Hasher::with_ignore_max_ns(true)
+            .hash_leaf_with_namespace(share.as_ref(), self.namespace.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    const PARAMS: BlobParams = BlobParams {
+        gas_per_blob_byte: 8,
+        gov_max_square_size: 64,
+    };
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn build(namespace: Namespace, data: &[u8], chunk_size: usize) -> Blob {
+        let mut builder = BlobBuilder::new(namespace, AppVersion::V3);
+
+        for chunk in data.chunks(chunk_size) {
+            builder.push(chunk);
+        }
+
+        assert_eq!(builder.len(), data.len());
+        builder.finalize(&PARAMS).unwrap()
+    }
+
+    #[test]
+    fn chunked_commitment_matches_single_chunk() {
+        let namespace = Namespace::new_v0(&[1, 2, 3]).unwrap();
+
+        for len in [
+            1,
+            FIRST_SPARSE_SHARE_CONTENT_SIZE,
+            FIRST_SPARSE_SHARE_CONTENT_SIZE + 1,
+            FIRST_SPARSE_SHARE_CONTENT_SIZE + CONTINUATION_SPARSE_SHARE_CONTENT_SIZE,
+            100_000,
+        ] {
+            let data = data(len);
+            let expected = Blob::new(namespace, data.clone(), AppVersion::V3).unwrap();
+
+            let single = build(namespace, &data, data.len());
+            let several = build(namespace, &data, 1000);
+            let tiny = build(namespace, &data, 7);
+
+            assert_eq!(single.commitment, expected.commitment, "len {len}");
+            assert_eq!(several.commitment, single.commitment, "len {len}");
+            assert_eq!(tiny.commitment, single.commitment, "len {len}");
+            assert_eq!(tiny, expected);
+        }
+    }
+
+    #[test]
+    fn empty_blob() {
+        let namespace = Namespace::new_v0(&[1, 2, 3]).unwrap();
+        let builder = BlobBuilder::new(namespace, AppVersion::V2);
+        assert!(builder.is_empty());
+
+        let blob = builder.finalize(&PARAMS).unwrap();
+        let expected = Blob::new(namespace, Vec::new(), AppVersion::V2).unwrap();
+        assert_eq!(blob.commitment, expected.commitment);
+    }
+
+    #[test]
+    fn blob_exceeding_square_size() {
+        let namespace = Namespace::new_v0(&[1, 2, 3]).unwrap();
+        let params = BlobParams {
+            gas_per_blob_byte: 8,
+            gov_max_square_size: 2,
+        };
+
+        // 4 shares fit into the 2x2 square
+        let mut builder = BlobBuilder::new(namespace, AppVersion::V2);
+        builder.push(&data(
+            FIRST_SPARSE_SHARE_CONTENT_SIZE + 3 * CONTINUATION_SPARSE_SHARE_CONTENT_SIZE,
+        ));
+        builder.finalize(&params).unwrap();
+
+        let mut builder = BlobBuilder::new(namespace, AppVersion::V2);
+        builder.push(&data(
+            FIRST_SPARSE_SHARE_CONTENT_SIZE + 3 * CONTINUATION_SPARSE_SHARE_CONTENT_SIZE + 1,
+        ));
+        assert!(matches!(
+            builder.finalize(&params).unwrap_err(),
+            Error::BlobExceedsMaxSquareSize(5, 2)
+        ));
+    }
+}
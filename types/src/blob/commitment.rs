@@ -12,7 +12,10 @@ use tendermint::{crypto, merkle};
 use wasm_bindgen::prelude::*;
 
 use crate::consts::appconsts;
-use crate::nmt::{Namespace, NamespacedHashExt, NamespacedSha2Hasher, Nmt, RawNamespacedHash};
+use crate::nmt::{
+    MerkleHash, Namespace, NamespacedHash, NamespacedHashExt, NamespacedSha2Hasher, Nmt,
+    RawNamespacedHash,
+};
 use crate::{Error, Result};
 use crate::{InfoByte, Share};
 
@@ -79,6 +82,30 @@ impl Commitment {
         Self::from_shares(namespace, &shares, subtree_root_threshold)
     }
 
+    /// Generate the share commitment from the given blob data, following the commitment
+    /// rules of the numeric app version of the network, e.g. as read from its headers.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnsupportedAppVersion`] if the app version isn't implemented
+    /// by this crate.
+    pub fn from_blob_for_app_version(
+        namespace: Namespace,
+        blob_data: &[u8],
+        share_version: u8,
+        app_version: u64,
+    ) -> Result<Commitment> {
+        let app_version = appconsts::AppVersion::from_u64(app_version)
+            .ok_or(Error::UnsupportedAppVersion(app_version))?;
+
+        Self::from_blob(
+            namespace,
+            blob_data,
+            share_version,
+            appconsts::subtree_root_threshold(app_version),
+        )
+    }
+
     /// Generate the commitment from the given shares.
     pub fn from_shares(
         namespace: Namespace,
@@ -118,6 +145,28 @@ impl Commitment {
         Ok(Commitment { hash })
     }
 
+    /// Generate the commitment from the hashes of the blob's shares as [`Nmt`] leaves.
+    pub(crate) fn from_leaf_hashes(
+        mut leaf_hashes: &[NamespacedHash],
+        subtree_root_threshold: u64,
+    ) -> Commitment {
+        let subtree_width = subtree_width(leaf_hashes.len() as u64, subtree_root_threshold);
+        let tree_sizes = merkle_mountain_range_sizes(leaf_hashes.len() as u64, subtree_width);
+        let hasher = NamespacedSha2Hasher::with_ignore_max_ns(true);
+
+        let mut subtree_roots: Vec<RawNamespacedHash> = Vec::with_capacity(tree_sizes.len());
+
+        for size in tree_sizes {
+            let (leafs, rest) = leaf_hashes.split_at(size as usize);
+            subtree_roots.push(subtree_root(&hasher, leafs).to_array());
+            leaf_hashes = rest;
+        }
+
+        let hash = merkle::simple_hash_from_byte_vectors::<crypto::default::Sha256>(&subtree_roots);
+
+        Commitment { hash }
+    }
+
     /// Hash of the commitment
     pub fn hash(&self) -> &merkle::Hash {
         &self.hash
@@ -192,8 +241,21 @@ pub(crate) fn split_blob_to_shares(
     Ok(shares)
 }
 
+/// Root of the [`Nmt`] with the given leaf hashes, split the same way the tree does.
+fn subtree_root(hasher: &NamespacedSha2Hasher, leaf_hashes: &[NamespacedHash]) -> NamespacedHash {
+    match leaf_hashes {
+        [] => NamespacedSha2Hasher::EMPTY_ROOT,
+        [leaf] => leaf.clone(),
+        _ => {
+            let split = leaf_hashes.len().next_power_of_two() / 2;
+            let (left, right) = leaf_hashes.split_at(split);
+            hasher.hash_nodes(&subtree_root(hasher, left), &subtree_root(hasher, right))
+        }
+    }
+}
+
 /// Build a sparse share from a cursor over data
-fn build_sparse_share_v0(
+pub(crate) fn build_sparse_share_v0(
     namespace: Namespace,
     data: &mut Cursor<impl AsRef<[u8]>>,
 ) -> Result<Share> {
@@ -325,6 +387,36 @@ mod tests {
     #[cfg(target_arch = "wasm32")]
     use wasm_bindgen_test::wasm_bindgen_test as test;
 
+    #[test]
+    fn commitment_for_app_version() {
+        let namespace = Namespace::new_v0(&[1, 2, 3]).unwrap();
+        // large enough for the subtree width to depend on the threshold
+        let data = vec![7; 100 * appconsts::CONTINUATION_SPARSE_SHARE_CONTENT_SIZE];
+
+        let v2 = Commitment::from_blob_for_app_version(namespace, &data, 0, 2).unwrap();
+        let v3 = Commitment::from_blob_for_app_version(namespace, &data, 0, 3).unwrap();
+
+        // both versions share the subtree root threshold, so the commitments must match
+        // the ones computed with it, but not with any other threshold
+        assert_eq!(v2, v3);
+        assert_eq!(
+            v3,
+            Commitment::from_blob(
+                namespace,
+                &data,
+                0,
+                appconsts::subtree_root_threshold(appconsts::AppVersion::V3)
+            )
+            .unwrap()
+        );
+        assert_ne!(v3, Commitment::from_blob(namespace, &data, 0, 1).unwrap());
+
+        assert!(matches!(
+            Commitment::from_blob_for_app_version(namespace, &data, 0, 99).unwrap_err(),
+            Error::UnsupportedAppVersion(99)
+        ));
+    }
+
     #[test]
     fn test_single_sparse_share() {
         let namespace = Namespace::new(0, &[1, 1, 1, 1, 1, 1, 1, 1, 1, 1]).unwrap();
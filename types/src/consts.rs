@@ -63,6 +63,8 @@ pub mod appconsts {
         pub const TX_SIZE_COST_PER_BYTE: u64 = 10;
         /// Cost of each byte in blob (in units of gas).
         pub const GAS_PER_BLOB_BYTE: u64 = 8;
+        /// Maximum size of a transaction in bytes, including its blobs.
+        pub const MAX_TX_SIZE: u64 = 2_097_152;
     }
 
     // celestia-app/pkg/appconsts/versioned_consts.go
@@ -190,6 +192,14 @@ pub mod appconsts {
         }
     }
 
+    /// Maximum size of a transaction in bytes, including its blobs.
+    pub const fn max_tx_size(app_version: AppVersion) -> Option<u64> {
+        match app_version {
+            AppVersion::V1 | AppVersion::V2 => None,
+            AppVersion::V3 => Some(v3::MAX_TX_SIZE),
+        }
+    }
+
     // celestia-app/pkg/appconsts/global_consts
     mod global_consts {
         use crate::nmt::NS_SIZE;
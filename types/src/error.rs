@@ -244,6 +244,10 @@ pub enum Error {
     #[error("Blob too large")]
     BlobTooLarge,
 
+    /// Blob doesn't fit in the square of the maximum size.
+    #[error("Blob of {0} shares doesn't fit in the square of max size {1}")]
+    BlobExceedsMaxSquareSize(u64, u64),
+
     /// Invalid comittment length
     #[error("Invalid committment length")]
     InvalidComittmentLength,
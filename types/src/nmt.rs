@@ -254,6 +254,45 @@ impl Namespace {
         Ok(Namespace(nmt_rs::NamespaceId(bytes)))
     }
 
+    /// Create a new [`Namespace`] version `0` from a human-readable label, e.g. a name of the rollup.
+    ///
+    /// The UTF-8 bytes of the label are placed at the beginning of the 10 byte user-defined
+    /// suffix and padded with `0x00` bytes on the right, so the label reads from the start of the id.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the label is empty or longer than 10 bytes,
+    /// or if it's made of `\0` bytes so that the namespace falls in the reserved range.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use celestia_types::nmt::Namespace;
+    ///
+    /// let namespace = Namespace::from_label("my-rollup").unwrap();
+    /// assert_eq!(namespace.id_v0().unwrap(), b"my-rollup\0");
+    ///
+    /// // label doesn't fit the namespace id
+    /// Namespace::from_label("my-long-rollup").unwrap_err();
+    /// ```
+    pub fn from_label(label: &str) -> Result<Self> {
+        let label = label.as_bytes();
+
+        if label.is_empty() || label.len() > NS_ID_V0_SIZE {
+            return Err(Error::InvalidNamespaceSize);
+        }
+
+        let mut id = [0u8; NS_ID_V0_SIZE];
+        id[..label.len()].copy_from_slice(label);
+
+        let namespace = Namespace::const_v0(id);
+        if namespace.is_reserved() {
+            return Err(Error::InvalidNamespaceV0);
+        }
+
+        Ok(namespace)
+    }
+
     pub(crate) const fn new_unchecked(bytes: [u8; NS_SIZE]) -> Self {
         Namespace(nmt_rs::NamespaceId(bytes))
     }
@@ -637,6 +676,43 @@ mod tests {
         assert_eq!(nid, expected_nid);
     }
 
+    #[test]
+    fn namespace_from_label() {
+        let nid = Namespace::from_label("rollup").unwrap();
+        let expected_nid = Namespace(nmt_rs::NamespaceId([
+            0, // version
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, // prefix
+            b'r', b'o', b'l', b'l', b'u', b'p', 0, 0, 0, 0, // id with right padding
+        ]));
+        assert_eq!(nid, expected_nid);
+
+        let nid = Namespace::from_label("ten-bytes!").unwrap();
+        assert_eq!(nid, Namespace::const_v0(*b"ten-bytes!"));
+
+        // multi-byte characters are counted by their encoded length
+        let nid = Namespace::from_label("zażółć").unwrap();
+        assert_eq!(nid.id_v0().unwrap(), "zażółć".as_bytes());
+    }
+
+    #[test]
+    fn namespace_from_invalid_label() {
+        for label in ["", "eleven-byte", "zażółć!"] {
+            assert!(matches!(
+                Namespace::from_label(label).unwrap_err(),
+                Error::InvalidNamespaceSize
+            ));
+        }
+
+        // labels of the reserved namespaces
+        for label in ["\0", "\0\0\0\0\0\0\0\0\0\u{1}", "\0\0\0\0\0\0\0\0\0\u{7f}"] {
+            assert!(matches!(
+                Namespace::from_label(label).unwrap_err(),
+                Error::InvalidNamespaceV0
+            ));
+        }
+        assert!(Namespace::from_label("\0\0\0\0\0\0\0\0\u{1}").is_ok());
+    }
+
     #[test]
     fn namespace_id_const_v255() {
         let nid = Namespace::const_v255(0xab);
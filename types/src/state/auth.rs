@@ -1,8 +1,10 @@
 //! types related to accounts
 
 use celestia_proto::cosmos::crypto::ed25519::PubKey as Ed25519PubKey;
+use celestia_proto::cosmos::crypto::multisig::LegacyAminoPubKey;
 use celestia_proto::cosmos::crypto::secp256k1::PubKey as Secp256k1PubKey;
 use prost::Message;
+use serde::{Deserialize, Serialize};
 use tendermint::public_key::PublicKey;
 use tendermint_proto::google::protobuf::Any;
 use tendermint_proto::Protobuf;
@@ -16,6 +18,7 @@ pub use celestia_proto::cosmos::auth::v1beta1::Params as AuthParams;
 
 const COSMOS_ED25519_PUBKEY: &str = "/cosmos.crypto.ed25519.PubKey";
 const COSMOS_SECP256K1_PUBKEY: &str = "/cosmos.crypto.secp256k1.PubKey";
+const COSMOS_MULTISIG_PUBKEY: &str = "/cosmos.crypto.multisig.LegacyAminoPubKey";
 
 /// [`BaseAccount`] defines a base account type.
 ///
@@ -23,11 +26,12 @@ const COSMOS_SECP256K1_PUBKEY: &str = "/cosmos.crypto.secp256k1.PubKey";
 ///
 /// Any custom account type should extend this type for additional functionality
 /// (e.g. vesting).
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BaseAccount {
     /// Bech32 `AccountId` of this account.
     pub address: Address,
     /// Optional `PublicKey` associated with this account.
+    #[serde(with = "option_public_key")]
     pub pub_key: Option<PublicKey>,
     /// `account_number` is the account number of the account in state
     pub account_number: u64,
@@ -37,7 +41,7 @@ pub struct BaseAccount {
 }
 
 /// [`ModuleAccount`] defines an account for modules that holds coins on a pool.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ModuleAccount {
     /// [`BaseAccount`] specification of this module account.
     pub base_account: Option<BaseAccount>,
@@ -47,6 +51,31 @@ pub struct ModuleAccount {
     pub permissions: Vec<String>,
 }
 
+/// [`MultisigAccount`] is a [`BaseAccount`] with a multisig public key, whose transactions
+/// are signed by, at least, the threshold of its members.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultisigAccount {
+    /// Bech32 `AccountId` of this account.
+    pub address: Address,
+    /// Multisig public key associated with this account.
+    pub pub_key: MultisigPubKey,
+    /// `account_number` is the account number of the account in state
+    pub account_number: u64,
+    /// Sequence of the account, which describes the number of committed transactions signed by a
+    /// given address.
+    pub sequence: u64,
+}
+
+/// [`MultisigPubKey`] nests the public keys of the members of a multisig account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultisigPubKey {
+    /// Number of the members required to sign a transaction.
+    pub threshold: u32,
+    /// `PublicKey`s of the members, in the order they were given when creating the account.
+    #[serde(with = "public_keys")]
+    pub public_keys: Vec<PublicKey>,
+}
+
 impl From<BaseAccount> for RawBaseAccount {
     fn from(account: BaseAccount) -> Self {
         RawBaseAccount {
@@ -72,6 +101,56 @@ impl TryFrom<RawBaseAccount> for BaseAccount {
     }
 }
 
+impl From<MultisigAccount> for RawBaseAccount {
+    fn from(account: MultisigAccount) -> Self {
+        let pub_key = LegacyAminoPubKey {
+            threshold: account.pub_key.threshold,
+            public_keys: account
+                .pub_key
+                .public_keys
+                .into_iter()
+                .map(any_from_public_key)
+                .collect(),
+        };
+        RawBaseAccount {
+            address: account.address.to_string(),
+            pub_key: Some(Any {
+                type_url: COSMOS_MULTISIG_PUBKEY.to_string(),
+                value: pub_key.encode_to_vec(),
+            }),
+            account_number: account.account_number,
+            sequence: account.sequence,
+        }
+    }
+}
+
+impl TryFrom<RawBaseAccount> for MultisigAccount {
+    type Error = Error;
+
+    fn try_from(account: RawBaseAccount) -> Result<Self, Self::Error> {
+        let any = account.pub_key.ok_or(Error::InvalidPublicKey)?;
+        if any.type_url != COSMOS_MULTISIG_PUBKEY {
+            return Err(Error::InvalidPublicKeyType(any.type_url));
+        }
+
+        let pub_key = LegacyAminoPubKey::decode(&*any.value)?;
+        let public_keys = pub_key
+            .public_keys
+            .into_iter()
+            .map(public_key_from_any)
+            .collect::<Result<_, _>>()?;
+        Ok(MultisigAccount {
+            address: account.address.parse()?,
+            pub_key: MultisigPubKey {
+                threshold: pub_key.threshold,
+                public_keys,
+            },
+            account_number: account.account_number,
+            sequence: account.sequence,
+        })
+    }
+}
+
 impl From<ModuleAccount> for RawModuleAccount {
     fn from(account: ModuleAccount) -> Self {
         let base_account = account.base_account.map(BaseAccount::into);
@@ -132,6 +211,89 @@ fn any_from_public_key(key: PublicKey) -> Any {
     }
 }
 
+/// Serialize the optional [`PublicKey`] as its type and the key as a hexstring.
+mod option_public_key {
+    use celestia_proto::serializers::bytes::hexstring;
+    use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+    use tendermint::public_key::PublicKey;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(tag = "type", content = "key", rename_all = "snake_case")]
+    pub(super) enum SerdePublicKey {
+        Ed25519(#[serde(with = "hexstring")] Vec<u8>),
+        Secp256k1(#[serde(with = "hexstring")] Vec<u8>),
+    }
+
+    impl SerdePublicKey {
+        pub(super) fn from_public_key<E: ser::Error>(key: &PublicKey) -> Result<Self, E> {
+            match key {
+                PublicKey::Ed25519(_) => Ok(SerdePublicKey::Ed25519(key.to_bytes())),
+                PublicKey::Secp256k1(_) => Ok(SerdePublicKey::Secp256k1(key.to_bytes())),
+                _ => Err(E::custom("unexpected key type")),
+            }
+        }
+
+        pub(super) fn into_public_key<E: de::Error>(self) -> Result<PublicKey, E> {
+            match self {
+                SerdePublicKey::Ed25519(bytes) => PublicKey::from_raw_ed25519(&bytes),
+                SerdePublicKey::Secp256k1(bytes) => PublicKey::from_raw_secp256k1(&bytes),
+            }
+            .ok_or_else(|| E::custom("invalid public key"))
+        }
+    }
+
+    pub(super) fn serialize<S>(key: &Option<PublicKey>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let key = key
+            .as_ref()
+            .map(SerdePublicKey::from_public_key)
+            .transpose()?;
+        key.serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D>(deserializer: D) -> Result<Option<PublicKey>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<SerdePublicKey>::deserialize(deserializer)?
+            .map(SerdePublicKey::into_public_key)
+            .transpose()
+    }
+}
+
+/// Serialize the [`PublicKey`]s the same way as [`option_public_key`].
+mod public_keys {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use tendermint::public_key::PublicKey;
+
+    use super::option_public_key::SerdePublicKey;
+
+    pub(super) fn serialize<S>(keys: &[PublicKey], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let keys = keys
+            .iter()
+            .map(SerdePublicKey::from_public_key)
+            .collect::<Result<Vec<_>, _>>()?;
+        keys.serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D>(deserializer: D) -> Result<Vec<PublicKey>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<SerdePublicKey>::deserialize(deserializer)?
+            .into_iter()
+            .map(SerdePublicKey::into_public_key)
+            .collect()
+    }
+}
+
 impl Protobuf<RawBaseAccount> for BaseAccount {}
 
 impl Protobuf<RawModuleAccount> for ModuleAccount {}
+
+impl Protobuf<RawBaseAccount> for MultisigAccount {}